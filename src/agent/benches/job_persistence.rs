@@ -0,0 +1,38 @@
+//! Compares listing 100 jobs stored as individual JSON files with listing
+//! them from a single archive.
+
+use criterion::{criterion_group, criterion_main, Criterion};
+use rae_agent::scheduler::job::Job;
+use rae_agent::scheduler::persistence::{JobPersistence, StorageFormat};
+use tokio::runtime::Runtime;
+
+const JOB_COUNT: usize = 100;
+
+fn list_jobs(c: &mut Criterion) {
+    let runtime = Runtime::new().unwrap();
+    let temp_dir = tempfile::tempdir().unwrap();
+    
+    let jobs: Vec<Job> = (0..JOB_COUNT)
+        .map(|i| Job::new(format!("job-{}", i), "echo".to_string()).with_cron("0 0 9 * * *".to_string()))
+        .collect();
+    
+    let json = JobPersistence::with_storage_dir(temp_dir.path().join("json").join("jobs")).unwrap();
+    let archive = JobPersistence::with_storage_dir(temp_dir.path().join("archive").join("jobs")).unwrap()
+        .with_format(StorageFormat::Archive);
+    runtime.block_on(async {
+        json.save_jobs(&jobs).await.unwrap();
+        archive.save_jobs(&jobs).await.unwrap();
+    });
+    
+    let mut group = c.benchmark_group("list_jobs");
+    group.bench_function("json_files", |b| {
+        b.iter(|| runtime.block_on(json.list_jobs()).unwrap())
+    });
+    group.bench_function("archive", |b| {
+        b.iter(|| runtime.block_on(archive.list_jobs()).unwrap())
+    });
+    group.finish();
+}
+
+criterion_group!(benches, list_jobs);
+criterion_main!(benches);
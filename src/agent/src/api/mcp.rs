@@ -0,0 +1,229 @@
+//! Model Context Protocol (MCP) adapter.
+//!
+//! Answers MCP JSON-RPC 2.0 requests, exposing modules as MCP tools:
+//! `tools/list` describes every registered module and `tools/call` runs one
+//! with the call's arguments as its input.
+
+use crate::error::Result;
+use crate::modules::ModuleInfo;
+use crate::schemas::SchemaValidator;
+use serde_json::{json, Value};
+use std::collections::BTreeMap;
+
+/// MCP protocol revision implemented by the adapter.
+pub const MCP_PROTOCOL_VERSION: &str = "2024-11-05";
+
+/// JSON-RPC error codes.
+const PARSE_ERROR: i64 = -32700;
+const INVALID_REQUEST: i64 = -32600;
+const METHOD_NOT_FOUND: i64 = -32601;
+const INVALID_PARAMS: i64 = -32602;
+
+/// Runs a module with the given input, returning its output.
+pub type ModuleHandler = Box<dyn Fn(Value) -> Result<Value> + Send + Sync>;
+
+/// A module exposed as an MCP tool.
+struct Tool {
+    info: ModuleInfo,
+    /// Validator for the module's input schema, if it declares one
+    validator: Option<SchemaValidator>,
+    handler: ModuleHandler,
+}
+
+/// MCP server exposing modules as tools.
+#[derive(Default)]
+pub struct McpServer {
+    /// Tools by module name, sorted so `tools/list` is stable
+    tools: BTreeMap<String, Tool>,
+}
+
+impl McpServer {
+    /// Creates a server without any tools.
+    pub fn new() -> Self {
+        Self::default()
+    }
+    
+    /// Exposes a module as a tool, replacing any tool with the same name.
+    pub fn register_module(&mut self, info: ModuleInfo, handler: ModuleHandler) -> Result<()> {
+        let validator = info.input_schema.clone().map(SchemaValidator::new).transpose()?;
+        self.tools.insert(info.name.clone(), Tool { info, validator, handler });
+        Ok(())
+    }
+    
+    /// Handles one raw message, returning the response to send.
+    ///
+    /// Notifications get no response.
+    pub fn handle_raw(&self, message: &[u8]) -> Option<Value> {
+        match serde_json::from_slice(message) {
+            Ok(message) => self.handle_message(message),
+            Err(e) => Some(error_response(Value::Null, PARSE_ERROR, format!("Parse error: {}", e))),
+        }
+    }
+    
+    /// Handles one JSON-RPC message, returning the response to send.
+    ///
+    /// Notifications get no response.
+    pub fn handle_message(&self, message: Value) -> Option<Value> {
+        let id = message.get("id").cloned();
+        let method = match message.get("method").and_then(Value::as_str) {
+            Some(method) if message.get("jsonrpc") == Some(&json!("2.0")) => method,
+            _ => {
+                return Some(error_response(
+                    id.unwrap_or(Value::Null),
+                    INVALID_REQUEST,
+                    "Invalid request: expected a JSON-RPC 2.0 request".to_string(),
+                ));
+            }
+        };
+        
+        // Notifications such as `notifications/initialized` need no action
+        let id = id?;
+        let params = message.get("params").cloned().unwrap_or_else(|| json!({}));
+        
+        let result = match method {
+            "initialize" => Ok(json!({
+                "protocolVersion": MCP_PROTOCOL_VERSION,
+                "capabilities": { "tools": {} },
+                "serverInfo": { "name": "rae-agent", "version": crate::VERSION },
+            })),
+            "ping" => Ok(json!({})),
+            "tools/list" => Ok(self.list_tools()),
+            "tools/call" => self.call_tool(&params),
+            _ => Err((METHOD_NOT_FOUND, format!("Method not found: {}", method))),
+        };
+        
+        Some(match result {
+            Ok(result) => json!({ "jsonrpc": "2.0", "id": id, "result": result }),
+            Err((code, message)) => error_response(id, code, message),
+        })
+    }
+    
+    /// Describes every tool.
+    fn list_tools(&self) -> Value {
+        let tools: Vec<Value> = self.tools.values()
+            .map(|tool| json!({
+                "name": tool.info.name,
+                "description": tool.info.description.clone().unwrap_or_default(),
+                "inputSchema": tool.info.input_schema.clone().unwrap_or_else(|| json!({ "type": "object" })),
+            }))
+            .collect();
+        
+        json!({ "tools": tools })
+    }
+    
+    /// Runs a tool with the call's arguments as the module input.
+    ///
+    /// Failures of the module itself are reported in the result with
+    /// `isError`, as MCP expects, rather than as JSON-RPC errors.
+    fn call_tool(&self, params: &Value) -> std::result::Result<Value, (i64, String)> {
+        let name = params.get("name").and_then(Value::as_str)
+            .ok_or_else(|| (INVALID_PARAMS, "Missing tool name".to_string()))?;
+        let tool = self.tools.get(name)
+            .ok_or_else(|| (INVALID_PARAMS, format!("Unknown tool: {}", name)))?;
+        
+        let input = params.get("arguments").cloned().unwrap_or_else(|| json!({}));
+        if let Some(validator) = &tool.validator {
+            validator.validate(&input)
+                .map_err(|e| (INVALID_PARAMS, format!("Invalid arguments for {}: {}", name, e)))?;
+        }
+        
+        let (text, is_error) = match (tool.handler)(input) {
+            Ok(Value::String(text)) => (text, false),
+            Ok(output) => (output.to_string(), false),
+            Err(e) => (e.to_string(), true),
+        };
+        
+        Ok(json!({
+            "content": [{ "type": "text", "text": text }],
+            "isError": is_error,
+        }))
+    }
+}
+
+/// Builds a JSON-RPC error response.
+fn error_response(id: Value, code: i64, message: String) -> Value {
+    json!({
+        "jsonrpc": "2.0",
+        "id": id,
+        "error": { "code": code, "message": message },
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::error::RaeError;
+    
+    fn server() -> McpServer {
+        let mut server = McpServer::new();
+        let info = ModuleInfo {
+            name: "echo".to_string(),
+            version: "1.0.0".to_string(),
+            description: Some("Echoes its input".to_string()),
+            permissions: Vec::new(),
+            input_schema: Some(json!({
+                "type": "object",
+                "properties": { "text": { "type": "string" } },
+                "required": ["text"],
+            })),
+            sensitive: false,
+        };
+        server.register_module(info, Box::new(|input| match input["text"].as_str() {
+            Some("fail") => Err(RaeError::Module("echo failed".to_string())),
+            _ => Ok(input["text"].clone()),
+        })).unwrap();
+        server
+    }
+    
+    #[test]
+    fn test_tools_list() {
+        let response = server().handle_message(json!({
+            "jsonrpc": "2.0", "id": 1, "method": "tools/list"
+        })).unwrap();
+        
+        assert_eq!(response["jsonrpc"], "2.0");
+        assert_eq!(response["id"], 1);
+        let tools = response["result"]["tools"].as_array().unwrap();
+        assert_eq!(tools.len(), 1);
+        assert_eq!(tools[0]["name"], "echo");
+        assert_eq!(tools[0]["description"], "Echoes its input");
+        assert_eq!(tools[0]["inputSchema"]["required"], json!(["text"]));
+    }
+    
+    #[test]
+    fn test_tools_call() {
+        let server = server();
+        let call = |arguments: Value| server.handle_message(json!({
+            "jsonrpc": "2.0", "id": "call", "method": "tools/call",
+            "params": { "name": "echo", "arguments": arguments },
+        })).unwrap();
+        
+        let response = call(json!({ "text": "hello" }));
+        assert_eq!(response["result"]["content"][0], json!({ "type": "text", "text": "hello" }));
+        assert_eq!(response["result"]["isError"], false);
+        
+        let response = call(json!({ "text": "fail" }));
+        assert_eq!(response["result"]["isError"], true);
+        assert_eq!(response["result"]["content"][0]["text"], "Module error: echo failed");
+        
+        let response = call(json!({ "text": 42 }));
+        assert_eq!(response["error"]["code"], INVALID_PARAMS);
+    }
+    
+    #[test]
+    fn test_protocol_errors() {
+        let server = server();
+        
+        let response = server.handle_raw(b"{not json").unwrap();
+        assert_eq!(response["error"]["code"], PARSE_ERROR);
+        assert_eq!(response["id"], Value::Null);
+        
+        let response = server.handle_message(json!({ "jsonrpc": "2.0", "id": 7, "method": "resources/list" })).unwrap();
+        assert_eq!(response["error"]["code"], METHOD_NOT_FOUND);
+        
+        let response = server.handle_message(json!({ "id": 8, "method": "ping" })).unwrap();
+        assert_eq!(response["error"]["code"], INVALID_REQUEST);
+        
+        assert!(server.handle_message(json!({ "jsonrpc": "2.0", "method": "notifications/initialized" })).is_none());
+    }
+}
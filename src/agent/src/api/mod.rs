@@ -3,11 +3,13 @@
 //! This module provides REST and WebSocket APIs for local communication,
 //! as well as protocol support for A2A and MCP.
 
+pub mod mcp;
+pub mod protocols;
 pub mod rest;
 pub mod websocket;
-pub mod protocols;
 
 // Re-export main types
-pub use rest::RestApi;
+pub use mcp::McpServer;
+pub use protocols::ProtocolBridge;
+pub use rest::{NetworkGuard, RestApi};
 pub use websocket::WebSocketApi;
-pub use protocols::ProtocolBridge; 
\ No newline at end of file
@@ -0,0 +1,327 @@
+//! Protocol bridge for A2A and MCP transports.
+//!
+//! Provides message framing for stdio transports. Length-prefix framing
+//! (4-byte big-endian length followed by the message bytes) is the default
+//! because it survives payloads that contain newlines; newline-delimited
+//! framing is kept for older clients.
+
+use crate::api::mcp::McpServer;
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+use tokio::io::{AsyncBufReadExt, AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt, BufReader};
+
+/// Largest frame accepted by default (16 MiB).
+pub const DEFAULT_MAX_FRAME_SIZE: usize = 16 * 1024 * 1024;
+
+/// Errors that can occur while framing protocol messages.
+#[derive(Debug, Error)]
+pub enum ProtocolError {
+    #[error("IO error: {0}")]
+    IoError(#[from] std::io::Error),
+    
+    #[error("Invalid message: {0}")]
+    InvalidMessage(#[from] serde_json::Error),
+    
+    #[error("Frame of {0} bytes exceeds the maximum frame size")]
+    FrameTooLarge(usize),
+    
+    #[error("Message contains a newline and cannot be sent with newline framing")]
+    EmbeddedNewline,
+}
+
+/// How messages are delimited on a stream.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum Framing {
+    /// 4-byte big-endian length prefix followed by the message bytes
+    #[default]
+    LengthPrefixed,
+    /// One message per line (legacy)
+    Newline,
+}
+
+/// Configuration for the protocol bridge.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProtocolBridgeConfig {
+    /// Framing used on stdio transports
+    pub framing: Framing,
+    /// Largest frame accepted when reading
+    pub max_frame_size: usize,
+}
+
+impl Default for ProtocolBridgeConfig {
+    fn default() -> Self {
+        ProtocolBridgeConfig {
+            framing: Framing::default(),
+            max_frame_size: DEFAULT_MAX_FRAME_SIZE,
+        }
+    }
+}
+
+/// Reads framed messages from a stream.
+pub struct FramedReader<R> {
+    reader: BufReader<R>,
+    framing: Framing,
+    max_frame_size: usize,
+}
+
+impl<R: AsyncRead + Unpin> FramedReader<R> {
+    /// Creates a reader with the given framing.
+    pub fn new(reader: R, framing: Framing) -> Self {
+        FramedReader {
+            reader: BufReader::new(reader),
+            framing,
+            max_frame_size: DEFAULT_MAX_FRAME_SIZE,
+        }
+    }
+    
+    /// Sets the largest frame accepted.
+    pub fn with_max_frame_size(mut self, max_frame_size: usize) -> Self {
+        self.max_frame_size = max_frame_size;
+        self
+    }
+    
+    /// Reads the next message, returning `None` at end of stream.
+    pub async fn read_message(&mut self) -> Result<Option<Vec<u8>>, ProtocolError> {
+        match self.framing {
+            Framing::LengthPrefixed => self.read_length_prefixed().await,
+            Framing::Newline => self.read_line().await,
+        }
+    }
+    
+    /// Reads the next message and deserializes it from JSON.
+    pub async fn read_json<T: DeserializeOwned>(&mut self) -> Result<Option<T>, ProtocolError> {
+        match self.read_message().await? {
+            Some(bytes) => Ok(Some(serde_json::from_slice(&bytes)?)),
+            None => Ok(None),
+        }
+    }
+    
+    async fn read_length_prefixed(&mut self) -> Result<Option<Vec<u8>>, ProtocolError> {
+        let mut prefix = [0u8; 4];
+        
+        // End of stream is only clean between frames
+        let read = self.reader.read(&mut prefix).await?;
+        if read == 0 {
+            return Ok(None);
+        }
+        self.reader.read_exact(&mut prefix[read..]).await?;
+        
+        let length = u32::from_be_bytes(prefix) as usize;
+        if length > self.max_frame_size {
+            return Err(ProtocolError::FrameTooLarge(length));
+        }
+        
+        let mut message = vec![0u8; length];
+        self.reader.read_exact(&mut message).await?;
+        Ok(Some(message))
+    }
+    
+    async fn read_line(&mut self) -> Result<Option<Vec<u8>>, ProtocolError> {
+        loop {
+            let mut line = Vec::new();
+            if self.reader.read_until(b'\n', &mut line).await? == 0 {
+                return Ok(None);
+            }
+            
+            while matches!(line.last(), Some(b'\n' | b'\r')) {
+                line.pop();
+            }
+            
+            if line.len() > self.max_frame_size {
+                return Err(ProtocolError::FrameTooLarge(line.len()));
+            }
+            
+            // Skip blank keep-alive lines
+            if !line.is_empty() {
+                return Ok(Some(line));
+            }
+        }
+    }
+}
+
+/// Writes framed messages to a stream.
+pub struct FramedWriter<W> {
+    writer: W,
+    framing: Framing,
+}
+
+impl<W: AsyncWrite + Unpin> FramedWriter<W> {
+    /// Creates a writer with the given framing.
+    pub fn new(writer: W, framing: Framing) -> Self {
+        FramedWriter { writer, framing }
+    }
+    
+    /// Writes a single message and flushes the stream.
+    pub async fn write_message(&mut self, message: &[u8]) -> Result<(), ProtocolError> {
+        match self.framing {
+            Framing::LengthPrefixed => {
+                let length = u32::try_from(message.len())
+                    .map_err(|_| ProtocolError::FrameTooLarge(message.len()))?;
+                self.writer.write_all(&length.to_be_bytes()).await?;
+                self.writer.write_all(message).await?;
+            }
+            Framing::Newline => {
+                if message.contains(&b'\n') {
+                    return Err(ProtocolError::EmbeddedNewline);
+                }
+                self.writer.write_all(message).await?;
+                self.writer.write_all(b"\n").await?;
+            }
+        }
+        
+        self.writer.flush().await?;
+        Ok(())
+    }
+    
+    /// Serializes a value to JSON and writes it as a single message.
+    pub async fn write_json<T: Serialize>(&mut self, value: &T) -> Result<(), ProtocolError> {
+        let bytes = serde_json::to_vec(value)?;
+        self.write_message(&bytes).await
+    }
+    
+    /// Returns the underlying writer.
+    pub fn into_inner(self) -> W {
+        self.writer
+    }
+}
+
+/// Bridge between the agent and external protocol transports.
+#[derive(Debug, Clone, Default)]
+pub struct ProtocolBridge {
+    config: ProtocolBridgeConfig,
+}
+
+impl ProtocolBridge {
+    /// Creates a bridge with the given configuration.
+    pub fn new(config: ProtocolBridgeConfig) -> Self {
+        ProtocolBridge { config }
+    }
+    
+    /// Gets the bridge configuration.
+    pub fn config(&self) -> &ProtocolBridgeConfig {
+        &self.config
+    }
+    
+    /// Wraps a stream for reading requests with the configured framing.
+    pub fn reader<R: AsyncRead + Unpin>(&self, reader: R) -> FramedReader<R> {
+        FramedReader::new(reader, self.config.framing)
+            .with_max_frame_size(self.config.max_frame_size)
+    }
+    
+    /// Wraps a stream for writing responses with the configured framing.
+    pub fn writer<W: AsyncWrite + Unpin>(&self, writer: W) -> FramedWriter<W> {
+        FramedWriter::new(writer, self.config.framing)
+    }
+    
+    /// Answers MCP requests from `reader` on `writer` until the stream ends.
+    ///
+    /// MCP's stdio transport expects `Framing::Newline`.
+    pub async fn serve_mcp<R, W>(&self, server: &McpServer, reader: R, writer: W) -> Result<(), ProtocolError>
+    where
+        R: AsyncRead + Unpin,
+        W: AsyncWrite + Unpin,
+    {
+        let mut reader = self.reader(reader);
+        let mut writer = self.writer(writer);
+        
+        while let Some(message) = reader.read_message().await? {
+            if let Some(response) = server.handle_raw(&message) {
+                writer.write_json(&response).await?;
+            }
+        }
+        
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::{json, Value};
+    
+    #[tokio::test]
+    async fn test_length_prefixed_round_trip_with_newlines() {
+        let bridge = ProtocolBridge::default();
+        let (client, server) = tokio::io::duplex(4096);
+        
+        let response = json!({
+            "jsonrpc": "2.0",
+            "id": 1,
+            "result": { "stdout": "line one\nline two\n", "exit_code": 0 }
+        });
+        let pretty = serde_json::to_vec_pretty(&response).unwrap();
+        assert!(pretty.contains(&b'\n'));
+        
+        let mut writer = bridge.writer(client);
+        writer.write_message(&pretty).await.unwrap();
+        writer.write_json(&response).await.unwrap();
+        drop(writer);
+        
+        let mut reader = bridge.reader(server);
+        let first: Value = reader.read_json().await.unwrap().unwrap();
+        let second: Value = reader.read_json().await.unwrap().unwrap();
+        assert_eq!(first, response);
+        assert_eq!(second, response);
+        assert_eq!(first["result"]["stdout"], "line one\nline two\n");
+        assert!(reader.read_message().await.unwrap().is_none());
+    }
+    
+    #[tokio::test]
+    async fn test_newline_framing_compatibility() {
+        let bridge = ProtocolBridge::new(ProtocolBridgeConfig {
+            framing: Framing::Newline,
+            ..ProtocolBridgeConfig::default()
+        });
+        let (client, server) = tokio::io::duplex(4096);
+        
+        // Compact JSON escapes newlines inside strings, so it fits on one line
+        let request = json!({ "method": "run", "params": { "data": "a\nb" } });
+        
+        let mut writer = bridge.writer(client);
+        writer.write_json(&request).await.unwrap();
+        assert!(matches!(
+            writer.write_message(b"{\n}").await,
+            Err(ProtocolError::EmbeddedNewline)
+        ));
+        drop(writer);
+        
+        let mut reader = bridge.reader(server);
+        let received: Value = reader.read_json().await.unwrap().unwrap();
+        assert_eq!(received, request);
+        assert!(reader.read_message().await.unwrap().is_none());
+    }
+    
+    #[tokio::test]
+    async fn test_serve_mcp_tools_list() {
+        let bridge = ProtocolBridge::new(ProtocolBridgeConfig {
+            framing: Framing::Newline,
+            ..ProtocolBridgeConfig::default()
+        });
+        // One pipe per direction, like stdin and stdout
+        let (stdin_writer, stdin) = tokio::io::duplex(4096);
+        let (stdout, stdout_reader) = tokio::io::duplex(4096);
+        
+        let mut requests = bridge.writer(stdin_writer);
+        requests.write_json(&json!({ "jsonrpc": "2.0", "method": "notifications/initialized" })).await.unwrap();
+        requests.write_json(&json!({ "jsonrpc": "2.0", "id": 1, "method": "tools/list" })).await.unwrap();
+        drop(requests);
+        
+        bridge.serve_mcp(&McpServer::new(), stdin, stdout).await.unwrap();
+        
+        // Only the request is answered, not the notification
+        let mut responses = bridge.reader(stdout_reader);
+        let response: Value = responses.read_json().await.unwrap().unwrap();
+        assert_eq!(response, json!({ "jsonrpc": "2.0", "id": 1, "result": { "tools": [] } }));
+        assert!(responses.read_message().await.unwrap().is_none());
+    }
+    
+    #[tokio::test]
+    async fn test_oversized_frame_is_rejected() {
+        let (mut client, server) = tokio::io::duplex(64);
+        client.write_all(&1024u32.to_be_bytes()).await.unwrap();
+        
+        let mut reader = FramedReader::new(server, Framing::LengthPrefixed).with_max_frame_size(512);
+        assert!(matches!(reader.read_message().await, Err(ProtocolError::FrameTooLarge(1024))));
+    }
+}
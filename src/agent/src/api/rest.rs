@@ -0,0 +1,479 @@
+//! REST API support.
+//!
+//! Serves the local HTTP API for managing scheduled jobs, and holds the network
+//! guard that enforces the configured privacy level on outbound HTTP requests.
+//!
+//! Routes:
+//! - `GET /status`: agent version and uptime
+//! - `GET /jobs`, `POST /jobs`: list and create jobs
+//! - `DELETE /jobs/:id`: remove a job
+//! - `GET /jobs/:id/history`: recent results of a job
+//! - `POST /jobs/:id/run`: run a job immediately
+//!
+//! Requests whose `Host` is not a loopback address are rejected, so pages
+//! that rebind their DNS name to 127.0.0.1 cannot reach the API. Errors are
+//! returned as `{"error": "..."}`.
+
+use crate::config::{Config, PrivacyLevel};
+use crate::error::{RaeError, Result};
+use crate::scheduler::job::{Job, JobId, JobResult};
+use crate::scheduler::parser::Parser;
+use crate::scheduler::queue::QueueError;
+use crate::scheduler::results::DEFAULT_MAX_HISTORY;
+use crate::scheduler::{JobInfo, Scheduler, SchedulerError};
+use axum::extract::rejection::JsonRejection;
+use axum::extract::{Path, Query, Request, State};
+use axum::http::uri::Authority;
+use axum::http::{header, StatusCode};
+use axum::middleware::{self, Next};
+use axum::response::{IntoResponse, Response};
+use axum::routing::{delete, get, post};
+use axum::{Json, Router};
+use reqwest::Url;
+use serde::Deserialize;
+use serde_json::{json, Value};
+use std::sync::Arc;
+use std::time::Instant;
+use tracing::info;
+
+/// Address the REST API listens on unless configured otherwise.
+pub const DEFAULT_BIND_ADDRESS: &str = "127.0.0.1:7432";
+
+/// Local HTTP API for managing scheduled jobs.
+#[derive(Clone)]
+pub struct RestApi {
+    /// Scheduler the routes operate on
+    scheduler: Arc<Scheduler>,
+    /// Address to listen on
+    bind_address: String,
+    /// When the API was created, for reporting uptime
+    started_at: Instant,
+}
+
+/// Body of `POST /jobs`.
+#[derive(Debug, Deserialize)]
+pub struct CreateJobRequest {
+    pub name: String,
+    pub command: String,
+    #[serde(default)]
+    pub args: Vec<String>,
+    /// Cron schedule expression
+    pub schedule: Option<String>,
+    pub timezone: Option<String>,
+    pub description: Option<String>,
+}
+
+/// Query of `GET /jobs/:id/history`.
+#[derive(Debug, Deserialize)]
+pub struct HistoryQuery {
+    pub limit: Option<usize>,
+}
+
+impl RestApi {
+    /// Creates the API for a scheduler, listening on the configured address.
+    pub fn new(scheduler: Arc<Scheduler>, config: &Config) -> Self {
+        RestApi {
+            scheduler,
+            bind_address: config.api_bind_address.clone(),
+            started_at: Instant::now(),
+        }
+    }
+    
+    /// Builds the router serving all routes.
+    pub fn router(&self) -> Router {
+        Router::new()
+            .route("/status", get(Self::status))
+            .route("/jobs", get(Self::list_jobs).post(Self::create_job))
+            .route("/jobs/:id", delete(Self::delete_job))
+            .route("/jobs/:id/history", get(Self::job_history))
+            .route("/jobs/:id/run", post(Self::run_job))
+            .layer(middleware::from_fn(require_json))
+            .layer(middleware::from_fn(require_loopback_host))
+            .with_state(self.clone())
+    }
+    
+    /// Serves the API until the process exits.
+    pub async fn serve(self) -> Result<()> {
+        let listener = tokio::net::TcpListener::bind(&self.bind_address).await?;
+        info!("REST API listening on {}", listener.local_addr()?);
+        axum::serve(listener, self.router()).await?;
+        Ok(())
+    }
+    
+    async fn status(State(api): State<RestApi>) -> Json<Value> {
+        Json(json!({
+            "version": crate::VERSION,
+            "uptime_seconds": api.started_at.elapsed().as_secs(),
+        }))
+    }
+    
+    async fn list_jobs(State(api): State<RestApi>) -> std::result::Result<Json<Vec<JobInfo>>, ApiError> {
+        Ok(Json(api.scheduler.list_jobs().await?))
+    }
+    
+    async fn create_job(
+        State(api): State<RestApi>,
+        body: std::result::Result<Json<CreateJobRequest>, JsonRejection>,
+    ) -> std::result::Result<(StatusCode, Json<Value>), ApiError> {
+        let Json(request) = body?;
+        
+        let mut job = Job::new(request.name, request.command).with_args(request.args);
+        if let Some(schedule) = request.schedule {
+            job = job.with_cron(schedule);
+        }
+        job.schedule.timezone = request.timezone;
+        if let Some(description) = request.description {
+            job = job.with_description(description);
+        }
+        Parser::validate_schedule(&job.schedule)
+            .map_err(|e| ApiError::new(StatusCode::BAD_REQUEST, e.to_string()))?;
+        
+        let job_id = api.scheduler.add_job(job).await?;
+        Ok((StatusCode::CREATED, Json(json!({ "id": job_id }))))
+    }
+    
+    async fn delete_job(
+        State(api): State<RestApi>,
+        Path(job_id): Path<JobId>,
+    ) -> std::result::Result<StatusCode, ApiError> {
+        api.scheduler.remove_job(&job_id).await?;
+        Ok(StatusCode::NO_CONTENT)
+    }
+    
+    async fn job_history(
+        State(api): State<RestApi>,
+        Path(job_id): Path<JobId>,
+        Query(query): Query<HistoryQuery>,
+    ) -> std::result::Result<Json<Vec<JobResult>>, ApiError> {
+        let limit = query.limit.unwrap_or(DEFAULT_MAX_HISTORY);
+        Ok(Json(api.scheduler.get_job_history(&job_id, limit).await?))
+    }
+    
+    async fn run_job(
+        State(api): State<RestApi>,
+        Path(job_id): Path<JobId>,
+    ) -> std::result::Result<(StatusCode, Json<Value>), ApiError> {
+        let job_id = api.scheduler.run_job_now(&job_id).await?;
+        Ok((StatusCode::ACCEPTED, Json(json!({ "id": job_id }))))
+    }
+}
+
+/// Rejects requests whose `Host` header does not name this machine.
+async fn require_loopback_host(request: Request, next: Next) -> Response {
+    let host = request.headers()
+        .get(header::HOST)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.parse::<Authority>().ok());
+    
+    match host {
+        Some(host) if is_loopback(&host.host().to_lowercase()) => next.run(request).await,
+        Some(host) => ApiError::new(
+            StatusCode::FORBIDDEN,
+            format!("Host {} is not allowed; the API only serves loopback addresses", host),
+        ).into_response(),
+        None => ApiError::new(StatusCode::FORBIDDEN, "Missing or invalid Host header").into_response(),
+    }
+}
+
+/// Rejects request bodies that are not JSON.
+async fn require_json(request: Request, next: Next) -> Response {
+    let content_type = request.headers()
+        .get(header::CONTENT_TYPE)
+        .map(|value| value.to_str().unwrap_or_default().to_string());
+    
+    if let Some(content_type) = content_type {
+        let mime = content_type.split(';').next().unwrap_or_default().trim();
+        if !mime.eq_ignore_ascii_case("application/json") {
+            return ApiError::new(
+                StatusCode::UNSUPPORTED_MEDIA_TYPE,
+                format!("Expected Content-Type application/json, got {}", content_type),
+            ).into_response();
+        }
+    }
+    
+    next.run(request).await
+}
+
+/// Error returned by a route, sent as `{"error": "..."}`.
+#[derive(Debug)]
+pub struct ApiError {
+    status: StatusCode,
+    message: String,
+}
+
+impl ApiError {
+    fn new(status: StatusCode, message: impl Into<String>) -> Self {
+        ApiError {
+            status,
+            message: message.into(),
+        }
+    }
+}
+
+impl IntoResponse for ApiError {
+    fn into_response(self) -> Response {
+        (self.status, Json(json!({ "error": self.message }))).into_response()
+    }
+}
+
+impl From<RaeError> for ApiError {
+    fn from(e: RaeError) -> Self {
+        let status = match e {
+            RaeError::Config(_) | RaeError::Schema(_) | RaeError::Serialization(_) => StatusCode::BAD_REQUEST,
+            RaeError::Security(_) => StatusCode::FORBIDDEN,
+            RaeError::NotFound { .. } => StatusCode::NOT_FOUND,
+            _ => StatusCode::INTERNAL_SERVER_ERROR,
+        };
+        ApiError::new(status, e.to_string())
+    }
+}
+
+impl From<SchedulerError> for ApiError {
+    fn from(e: SchedulerError) -> Self {
+        let status = match &e {
+            e if e.is_not_found() => StatusCode::NOT_FOUND,
+            SchedulerError::InvalidJob(_)
+            | SchedulerError::InvalidCronExpression(_)
+            | SchedulerError::CyclicDependency(_)
+            | SchedulerError::QueueError(QueueError::InvalidJob(_)) => StatusCode::BAD_REQUEST,
+            SchedulerError::QueueError(QueueError::JobAlreadyExists(_))
+            | SchedulerError::AlreadyRunning(_)
+            | SchedulerError::Paused
+            | SchedulerError::EmergencyStopped(_) => StatusCode::CONFLICT,
+            _ => StatusCode::INTERNAL_SERVER_ERROR,
+        };
+        ApiError::new(status, e.to_string())
+    }
+}
+
+impl From<JsonRejection> for ApiError {
+    fn from(rejection: JsonRejection) -> Self {
+        ApiError::new(rejection.status(), rejection.body_text())
+    }
+}
+
+/// Decides whether an outbound request is allowed by the privacy level.
+///
+/// `Strict` blocks every outbound request, `Standard` only allows hosts on the
+/// config's allowlist and `Open` allows everything. Loopback addresses are
+/// never external, so they are always allowed.
+#[derive(Debug, Clone)]
+pub struct NetworkGuard {
+    privacy_level: PrivacyLevel,
+    allowed_hosts: Vec<String>,
+}
+
+impl NetworkGuard {
+    /// Creates a guard for the current configuration.
+    pub fn new(config: &Config) -> Self {
+        NetworkGuard {
+            privacy_level: config.privacy_level.clone(),
+            allowed_hosts: config.allowed_hosts.clone(),
+        }
+    }
+    
+    /// Checks whether a request to `url` may be made.
+    pub fn check(&self, url: &str) -> Result<()> {
+        let url = Url::parse(url)
+            .map_err(|e| RaeError::Security(format!("Invalid outbound URL {}: {}", url, e)))?;
+        let host = url.host_str()
+            .ok_or_else(|| RaeError::Security(format!("Outbound URL {} has no host", url)))?
+            .to_lowercase();
+        
+        if is_loopback(&host) {
+            return Ok(());
+        }
+        
+        match self.privacy_level {
+            PrivacyLevel::Strict => Err(RaeError::Security(
+                "Outbound network blocked by Strict privacy level".to_string()
+            )),
+            PrivacyLevel::Standard if !self.allowed_hosts.contains(&host) => Err(RaeError::Security(format!(
+                "Outbound network to {} blocked: host is not in allowed_hosts", host
+            ))),
+            PrivacyLevel::Standard | PrivacyLevel::Open => Ok(()),
+        }
+    }
+}
+
+/// Whether a host name refers to this machine.
+pub(crate) fn is_loopback(host: &str) -> bool {
+    host == "localhost"
+        || host.trim_start_matches('[').trim_end_matches(']')
+            .parse::<std::net::IpAddr>()
+            .is_ok_and(|ip| ip.is_loopback())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::body::Body;
+    use tower::ServiceExt;
+    
+    async fn test_api(temp_dir: &tempfile::TempDir) -> RestApi {
+        let scheduler = Scheduler::with_temp_dir(temp_dir.path()).await;
+        scheduler.start().await.unwrap();
+        RestApi::new(Arc::new(scheduler), &Config::default())
+    }
+    
+    async fn send(api: &RestApi, method: &str, uri: &str, body: Option<Value>) -> (StatusCode, Value) {
+        let request = Request::builder().method(method).uri(uri).header(header::HOST, "127.0.0.1:7432");
+        let request = match body {
+            Some(body) => request
+                .header(header::CONTENT_TYPE, "application/json")
+                .body(Body::from(body.to_string())),
+            None => request.body(Body::empty()),
+        };
+        let response = api.router().oneshot(request.unwrap()).await.unwrap();
+        
+        let status = response.status();
+        let bytes = axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        let value = if bytes.is_empty() { Value::Null } else { serde_json::from_slice(&bytes).unwrap() };
+        (status, value)
+    }
+    
+    async fn create_echo_job(api: &RestApi) -> String {
+        let body = json!({ "name": "greet", "command": "echo", "args": ["hi"], "schedule": "0 0 3 * * *" });
+        let (status, value) = send(api, "POST", "/jobs", Some(body)).await;
+        assert_eq!(status, StatusCode::CREATED);
+        value["id"].as_str().unwrap().to_string()
+    }
+    
+    #[tokio::test]
+    async fn test_status_route() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let api = test_api(&temp_dir).await;
+        
+        let (status, value) = send(&api, "GET", "/status", None).await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(value["version"], crate::VERSION);
+        assert!(value["uptime_seconds"].is_u64());
+    }
+    
+    #[tokio::test]
+    async fn test_create_and_list_jobs() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let api = test_api(&temp_dir).await;
+        
+        let job_id = create_echo_job(&api).await;
+        
+        let (status, value) = send(&api, "GET", "/jobs", None).await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(value[0]["job"]["id"], job_id.as_str());
+        assert_eq!(value[0]["job"]["schedule"]["cron"], "0 0 3 * * *");
+        assert_eq!(value[0]["status"], "Scheduled");
+        
+        let (status, value) = send(&api, "POST", "/jobs", Some(json!({ "name": "bad", "command": "echo", "schedule": "nope" }))).await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+        assert!(value["error"].as_str().unwrap().contains("Invalid cron expression"));
+    }
+    
+    #[tokio::test]
+    async fn test_create_job_requires_json() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let api = test_api(&temp_dir).await;
+        
+        let request = Request::builder()
+            .method("POST")
+            .uri("/jobs")
+            .header(header::HOST, "localhost:7432")
+            .header(header::CONTENT_TYPE, "text/plain")
+            .body(Body::from("name=greet"))
+            .unwrap();
+        let response = api.router().oneshot(request).await.unwrap();
+        assert_eq!(response.status(), StatusCode::UNSUPPORTED_MEDIA_TYPE);
+        
+        let bytes = axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        let value: Value = serde_json::from_slice(&bytes).unwrap();
+        assert!(value["error"].as_str().unwrap().contains("application/json"));
+    }
+    
+    #[tokio::test]
+    async fn test_non_loopback_host_is_rejected() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let api = test_api(&temp_dir).await;
+        
+        let status_for = |host: Option<&'static str>| {
+            let router = api.router();
+            async move {
+                let request = Request::builder().uri("/status");
+                let request = match host {
+                    Some(host) => request.header(header::HOST, host),
+                    None => request,
+                };
+                router.oneshot(request.body(Body::empty()).unwrap()).await.unwrap().status()
+            }
+        };
+        
+        assert_eq!(status_for(Some("localhost")).await, StatusCode::OK);
+        assert_eq!(status_for(Some("[::1]:7432")).await, StatusCode::OK);
+        assert_eq!(status_for(Some("evil.example.com:7432")).await, StatusCode::FORBIDDEN);
+        assert_eq!(status_for(Some("192.168.1.10")).await, StatusCode::FORBIDDEN);
+        assert_eq!(status_for(None).await, StatusCode::FORBIDDEN);
+    }
+    
+    #[tokio::test]
+    async fn test_delete_job_route() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let api = test_api(&temp_dir).await;
+        let job_id = create_echo_job(&api).await;
+        
+        let (status, _) = send(&api, "DELETE", &format!("/jobs/{}", job_id), None).await;
+        assert_eq!(status, StatusCode::NO_CONTENT);
+        
+        let (_, value) = send(&api, "GET", "/jobs", None).await;
+        assert_eq!(value, json!([]));
+        
+        let (status, value) = send(&api, "DELETE", &format!("/jobs/{}", job_id), None).await;
+        assert_eq!(status, StatusCode::NOT_FOUND);
+        assert!(value["error"].is_string());
+    }
+    
+    #[tokio::test]
+    async fn test_run_job_and_history_routes() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let api = test_api(&temp_dir).await;
+        let job_id = create_echo_job(&api).await;
+        
+        let mut completions = api.scheduler.subscribe_completions();
+        let (status, value) = send(&api, "POST", &format!("/jobs/{}/run", job_id), None).await;
+        assert_eq!(status, StatusCode::ACCEPTED);
+        assert_eq!(value["id"], job_id.as_str());
+        tokio::time::timeout(std::time::Duration::from_secs(5), completions.recv()).await.unwrap().unwrap();
+        
+        let (status, value) = send(&api, "GET", &format!("/jobs/{}/history?limit=5", job_id), None).await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(value[0]["stdout"], "hi\n");
+        
+        let (status, _) = send(&api, "POST", "/jobs/missing/run", None).await;
+        assert_eq!(status, StatusCode::NOT_FOUND);
+    }
+    
+    #[test]
+    fn test_strict_blocks_outbound_requests() {
+        let config = Config::default();
+        let guard = NetworkGuard::new(&config);
+        
+        let err = guard.check("https://api.example.com/v1").unwrap_err();
+        assert_eq!(err.to_string(), "Security error: Outbound network blocked by Strict privacy level");
+        
+        assert!(guard.check("http://127.0.0.1:8080/health").is_ok());
+        assert!(guard.check("http://localhost/health").is_ok());
+    }
+    
+    #[test]
+    fn test_standard_allows_only_listed_hosts() {
+        let mut config = Config {
+            privacy_level: PrivacyLevel::Standard,
+            ..Default::default()
+        };
+        config.add_allowed_host("API.example.com");
+        let guard = NetworkGuard::new(&config);
+        
+        assert!(guard.check("https://api.example.com/v1").is_ok());
+        assert!(matches!(guard.check("https://tracker.example.net/"), Err(RaeError::Security(_))));
+        
+        config.privacy_level = PrivacyLevel::Open;
+        assert!(NetworkGuard::new(&config).check("https://tracker.example.net/").is_ok());
+    }
+}
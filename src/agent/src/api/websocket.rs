@@ -0,0 +1,335 @@
+//! WebSocket API for real-time events.
+//!
+//! Clients connect to the local WebSocket server and send
+//! `{"subscribe": "jobs"}` to receive job events such as
+//! `{"event": "job_status_changed", "job_id": "...", "status": "Completed"}`.
+//! Streamed runs also push each output line as
+//! `{"event": "job_output", "job_id": "...", "stream": "stdout", "line": "..."}`
+//! followed by a final `job_finished` event.
+//!
+//! Handshakes with a non-loopback `Host` or a foreign `Origin` are refused,
+//! so web pages cannot connect through DNS rebinding or from other sites.
+
+use crate::api::rest::is_loopback;
+use crate::error::{RaeError, Result};
+use crate::scheduler::executor::JobOutputLine;
+use crate::scheduler::job::JobId;
+use crate::scheduler::monitor::JobStatusChange;
+use futures_util::{SinkExt, Stream, StreamExt};
+use serde_json::{json, Value};
+use std::collections::HashSet;
+use std::net::SocketAddr;
+use tokio::net::{TcpListener, TcpStream};
+use tokio::sync::broadcast;
+use tokio::task::JoinHandle;
+use tokio_tungstenite::tungstenite::handshake::server::{ErrorResponse, Request, Response};
+use tokio_tungstenite::tungstenite::http::uri::{Authority, Uri};
+use tokio_tungstenite::tungstenite::http::{header, StatusCode};
+use tokio_tungstenite::tungstenite::Message;
+use tracing::{debug, warn};
+
+/// Events buffered per connection before a slow client starts missing events.
+const EVENT_CHANNEL_CAPACITY: usize = 256;
+
+/// Topics clients may subscribe to.
+const TOPICS: &[&str] = &["jobs"];
+
+/// WebSocket server pushing events to subscribed clients.
+#[derive(Debug, Clone)]
+pub struct WebSocketApi {
+    /// Sender for events pushed to every connection
+    events: broadcast::Sender<Value>,
+}
+
+impl Default for WebSocketApi {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl WebSocketApi {
+    /// Creates a WebSocket API without any connections.
+    pub fn new() -> Self {
+        WebSocketApi {
+            events: broadcast::channel(EVENT_CHANNEL_CAPACITY).0,
+        }
+    }
+    
+    /// Sends an event to every client subscribed to job events.
+    ///
+    /// Returns the number of connections the event was queued for.
+    pub fn broadcast(&self, event: Value) -> usize {
+        self.events.send(event).unwrap_or(0)
+    }
+    
+    /// Pushes job status changes to subscribed clients until the sender closes.
+    pub fn forward_job_status(&self, mut changes: broadcast::Receiver<JobStatusChange>) -> JoinHandle<()> {
+        let api = self.clone();
+        tokio::spawn(async move {
+            loop {
+                match changes.recv().await {
+                    Ok(change) => {
+                        api.broadcast(json!({
+                            "event": "job_status_changed",
+                            "job_id": change.job_id,
+                            "status": change.status,
+                        }));
+                    }
+                    Err(broadcast::error::RecvError::Lagged(skipped)) => {
+                        warn!("Missed {} job status changes while streaming to WebSocket clients", skipped);
+                    }
+                    Err(broadcast::error::RecvError::Closed) => break,
+                }
+            }
+        })
+    }
+    
+    /// Pushes the output of a streamed run to subscribed clients as it is printed.
+    pub fn forward_job_output<S>(&self, job_id: JobId, output: S) -> JoinHandle<()>
+    where
+        S: Stream<Item = JobOutputLine> + Send + 'static,
+    {
+        let api = self.clone();
+        tokio::spawn(async move {
+            let mut output = std::pin::pin!(output);
+            while let Some(line) = output.next().await {
+                let event = match line {
+                    JobOutputLine::Stdout(line) => {
+                        json!({ "event": "job_output", "job_id": job_id, "stream": "stdout", "line": line })
+                    }
+                    JobOutputLine::Stderr(line) => {
+                        json!({ "event": "job_output", "job_id": job_id, "stream": "stderr", "line": line })
+                    }
+                    JobOutputLine::Completed(result) => json!({
+                        "event": "job_finished",
+                        "job_id": job_id,
+                        "status": result.status,
+                        "exit_code": result.exit_code,
+                    }),
+                };
+                api.broadcast(event);
+            }
+        })
+    }
+    
+    /// Starts accepting connections on `addr`, returning the bound address.
+    pub async fn start(&self, addr: SocketAddr) -> Result<SocketAddr> {
+        let listener = TcpListener::bind(addr).await?;
+        let local_addr = listener.local_addr()?;
+        
+        let events = self.events.clone();
+        tokio::spawn(async move {
+            loop {
+                match listener.accept().await {
+                    Ok((stream, peer)) => {
+                        // Subscribe before the handshake so no event is missed after it
+                        let receiver = events.subscribe();
+                        tokio::spawn(async move {
+                            if let Err(e) = Self::handle_connection(stream, receiver).await {
+                                debug!("WebSocket connection from {} closed: {}", peer, e);
+                            }
+                        });
+                    }
+                    Err(e) => warn!("Failed to accept WebSocket connection: {}", e),
+                }
+            }
+        });
+        
+        Ok(local_addr)
+    }
+    
+    /// Serves one client until it disconnects.
+    async fn handle_connection(stream: TcpStream, mut events: broadcast::Receiver<Value>) -> Result<()> {
+        let mut socket = tokio_tungstenite::accept_hdr_async(stream, Self::check_origin).await.map_err(protocol_error)?;
+        let mut topics = HashSet::new();
+        
+        loop {
+            tokio::select! {
+                message = socket.next() => match message {
+                    Some(Ok(Message::Text(text))) => {
+                        let reply = Self::handle_request(&text, &mut topics);
+                        socket.send(Message::Text(reply.to_string())).await.map_err(protocol_error)?;
+                    }
+                    Some(Ok(Message::Close(_))) | None => return Ok(()),
+                    // Pings are answered by tungstenite itself
+                    Some(Ok(_)) => {}
+                    Some(Err(e)) => return Err(protocol_error(e)),
+                },
+                event = events.recv() => match event {
+                    Ok(event) if topics.contains("jobs") => {
+                        socket.send(Message::Text(event.to_string())).await.map_err(protocol_error)?;
+                    }
+                    Ok(_) => {}
+                    Err(broadcast::error::RecvError::Lagged(skipped)) => {
+                        warn!("WebSocket client missed {} events", skipped);
+                    }
+                    Err(broadcast::error::RecvError::Closed) => return Ok(()),
+                },
+            }
+        }
+    }
+    
+    /// Refuses handshakes from other hosts or from pages served elsewhere.
+    ///
+    /// Clients that send no `Origin`, such as the CLI, are accepted.
+    // The signature is fixed by tungstenite's handshake `Callback`
+    #[allow(clippy::result_large_err)]
+    fn check_origin(request: &Request, response: Response) -> std::result::Result<Response, ErrorResponse> {
+        let header_value = |name| request.headers().get(name).map(|value| value.to_str().unwrap_or_default());
+        
+        let host_allowed = header_value(header::HOST)
+            .and_then(|value| value.parse::<Authority>().ok())
+            .is_some_and(|host| is_loopback(&host.host().to_lowercase()));
+        if !host_allowed {
+            return Err(forbidden("Host is not allowed; the WebSocket API only serves loopback addresses"));
+        }
+        
+        let origin_allowed = header_value(header::ORIGIN).is_none_or(|value| {
+            value.parse::<Uri>().ok()
+                .and_then(|origin| origin.host().map(str::to_lowercase))
+                .is_some_and(|host| is_loopback(&host))
+        });
+        if !origin_allowed {
+            return Err(forbidden("Origin is not allowed; only pages served from this machine may connect"));
+        }
+        
+        Ok(response)
+    }
+    
+    /// Handles a client request, returning the reply.
+    fn handle_request(text: &str, topics: &mut HashSet<&'static str>) -> Value {
+        let request: Value = match serde_json::from_str(text) {
+            Ok(request) => request,
+            Err(e) => return json!({ "error": format!("Invalid request: {}", e) }),
+        };
+        
+        match request.get("subscribe").and_then(Value::as_str) {
+            Some(topic) => match TOPICS.iter().find(|known| **known == topic) {
+                Some(known) => {
+                    topics.insert(*known);
+                    json!({ "subscribed": topic })
+                }
+                None => json!({ "error": format!("Unknown topic: {}", topic) }),
+            },
+            None => json!({ "error": "Expected a request like {\"subscribe\": \"jobs\"}" }),
+        }
+    }
+}
+
+/// Builds the response that refuses a handshake.
+fn forbidden(message: &str) -> ErrorResponse {
+    let mut response = ErrorResponse::new(Some(message.to_string()));
+    *response.status_mut() = StatusCode::FORBIDDEN;
+    response
+}
+
+/// Wraps a WebSocket error.
+fn protocol_error(e: tokio_tungstenite::tungstenite::Error) -> RaeError {
+    RaeError::Protocol(format!("WebSocket error: {}", e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::scheduler::executor::JobExecutor;
+    use crate::scheduler::job::{Job, JobStatus};
+    use crate::scheduler::monitor::JobMonitor;
+    use tokio::time::{timeout, Duration};
+    
+    #[test]
+    fn test_handle_request() {
+        let mut topics = HashSet::new();
+        
+        assert_eq!(WebSocketApi::handle_request(r#"{"subscribe": "jobs"}"#, &mut topics), json!({ "subscribed": "jobs" }));
+        assert!(topics.contains("jobs"));
+        
+        assert!(WebSocketApi::handle_request(r#"{"subscribe": "secrets"}"#, &mut topics).get("error").is_some());
+        assert!(WebSocketApi::handle_request("not json", &mut topics).get("error").is_some());
+        assert_eq!(topics.len(), 1);
+    }
+    
+    #[tokio::test]
+    async fn test_foreign_host_or_origin_is_refused() {
+        use tokio_tungstenite::tungstenite::client::IntoClientRequest;
+        use tokio_tungstenite::tungstenite::Error;
+        
+        let api = WebSocketApi::new();
+        let addr = api.start("127.0.0.1:0".parse().unwrap()).await.unwrap();
+        
+        let cases = [
+            (header::HOST, "attacker.example"),
+            (header::ORIGIN, "https://attacker.example"),
+            (header::ORIGIN, "null"),
+        ];
+        for (name, value) in cases {
+            let mut request = format!("ws://{}", addr).into_client_request().unwrap();
+            request.headers_mut().insert(name.clone(), value.parse().unwrap());
+            match tokio_tungstenite::connect_async(request).await {
+                Err(Error::Http(response)) => assert_eq!(response.status(), StatusCode::FORBIDDEN, "{}: {}", name, value),
+                other => panic!("{}: {} was not refused: {:?}", name, value, other.map(|_| ())),
+            }
+        }
+        
+        // Pages served from this machine may connect
+        let mut request = format!("ws://{}", addr).into_client_request().unwrap();
+        request.headers_mut().insert(header::HOST, "localhost".parse().unwrap());
+        request.headers_mut().insert(header::ORIGIN, "http://127.0.0.1:7432".parse().unwrap());
+        assert!(tokio_tungstenite::connect_async(request).await.is_ok());
+    }
+    
+    #[tokio::test]
+    async fn test_client_receives_job_completion() {
+        let api = WebSocketApi::new();
+        let addr = api.start("127.0.0.1:0".parse().unwrap()).await.unwrap();
+        
+        let monitor = JobMonitor::new();
+        api.forward_job_status(monitor.subscribe_status_changes());
+        
+        let (mut client, _) = tokio_tungstenite::connect_async(format!("ws://{}", addr)).await.unwrap();
+        client.send(Message::Text(r#"{"subscribe": "jobs"}"#.to_string())).await.unwrap();
+        let reply = client.next().await.unwrap().unwrap();
+        assert_eq!(reply.into_text().unwrap(), r#"{"subscribed":"jobs"}"#);
+        
+        // Run a job and report its completion the way the scheduler does
+        let executor = JobExecutor::new();
+        let mut completions = executor.subscribe_completions();
+        let job = Job::new("notify".to_string(), "echo".to_string());
+        monitor.track_job(job.id.clone()).await.unwrap();
+        let job_id = executor.execute_job(job).await.unwrap();
+        let completed = timeout(Duration::from_secs(5), completions.recv()).await.unwrap().unwrap();
+        monitor.update_job_status(&completed.job_id, JobStatus::Completed).await.unwrap();
+        
+        let event = timeout(Duration::from_secs(5), client.next()).await.unwrap().unwrap().unwrap();
+        let event: Value = serde_json::from_str(&event.into_text().unwrap()).unwrap();
+        assert_eq!(event, json!({
+            "event": "job_status_changed",
+            "job_id": job_id,
+            "status": "Completed",
+        }));
+    }
+    
+    #[tokio::test]
+    async fn test_client_receives_streamed_output() {
+        let api = WebSocketApi::new();
+        let addr = api.start("127.0.0.1:0".parse().unwrap()).await.unwrap();
+        
+        let (mut client, _) = tokio_tungstenite::connect_async(format!("ws://{}", addr)).await.unwrap();
+        client.send(Message::Text(r#"{"subscribe": "jobs"}"#.to_string())).await.unwrap();
+        client.next().await.unwrap().unwrap();
+        
+        let executor = JobExecutor::new();
+        let job = Job::new("report".to_string(), "printf".to_string()).with_args(vec!["a\\nb\\n".to_string()]);
+        let job_id = job.id.clone();
+        api.forward_job_output(job_id.clone(), executor.execute_job_streaming(job).await.unwrap());
+        
+        let mut events = Vec::new();
+        while events.len() < 3 {
+            let event = timeout(Duration::from_secs(5), client.next()).await.unwrap().unwrap().unwrap();
+            events.push(serde_json::from_str::<Value>(&event.into_text().unwrap()).unwrap());
+        }
+        
+        assert_eq!(events[0], json!({ "event": "job_output", "job_id": job_id, "stream": "stdout", "line": "a" }));
+        assert_eq!(events[1]["line"], "b");
+        assert_eq!(events[2], json!({ "event": "job_finished", "job_id": job_id, "status": "Completed", "exit_code": 0 }));
+    }
+}
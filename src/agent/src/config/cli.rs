@@ -0,0 +1,204 @@
+//! `rae-agent config get/set/list`: reading and editing `~/.rae/rae.toml`.
+//!
+//! Keys are dot-separated paths into the configuration, such as `log_level`
+//! or `env_groups.build.CC`. Edits go through `toml_edit`, so comments and key
+//! order in the file are kept.
+
+use crate::config::Config;
+use crate::error::{RaeError, Result};
+use serde_json::Value as JsonValue;
+use std::fs;
+use std::path::{Path, PathBuf};
+use toml_edit::{DocumentMut, Item};
+
+/// Path of the user configuration file.
+pub fn config_path() -> Result<PathBuf> {
+    dirs::home_dir()
+        .map(|home_dir| home_dir.join(".rae").join("rae.toml"))
+        .ok_or_else(|| RaeError::Config("Cannot locate the configuration: home directory is unknown".to_string()))
+}
+
+/// Sets `key` in the user configuration.
+pub fn set(key: &str, value: &str) -> Result<()> {
+    set_in(&config_path()?, key, value)
+}
+
+/// Value of `key` in the user configuration, or `None` if it is unset.
+pub fn get(key: &str) -> Result<Option<String>> {
+    get_in(&config_path()?, key)
+}
+
+/// All settings of the user configuration as `(key, value)` pairs.
+pub fn list() -> Result<Vec<(String, String)>> {
+    list_in(&config_path()?)
+}
+
+/// Sets `key` in the configuration file at `path`, creating the file if needed.
+///
+/// The value is parsed as a TOML value (so `10` is a number and `true` a
+/// boolean) unless the setting is a string. The edited configuration must
+/// validate before it is written.
+pub fn set_in(path: &Path, key: &str, value: &str) -> Result<()> {
+    let schema = schema_value(key)?;
+    
+    let content = if path.exists() { fs::read_to_string(path)? } else { String::new() };
+    let mut document: DocumentMut = content.parse()
+        .map_err(|e| RaeError::Config(format!("Invalid {}: {}", path.display(), e)))?;
+    
+    let mut item = document.as_item_mut();
+    for segment in key.split('.') {
+        if !item.is_none() && !item.is_table_like() {
+            return Err(RaeError::Config(format!("Cannot set {}: {} is not a table", key, segment)));
+        }
+        item = &mut item[segment];
+    }
+    
+    let mut new_value = match schema {
+        Some(JsonValue::String(_)) => toml_edit::Value::from(value),
+        _ => value.parse::<toml_edit::Value>().unwrap_or_else(|_| toml_edit::Value::from(value)),
+    };
+    // Keep any comment trailing the old value
+    if let Some(old_value) = item.as_value() {
+        *new_value.decor_mut() = old_value.decor().clone();
+    }
+    *item = Item::Value(new_value);
+    
+    let content = document.to_string();
+    let config: Config = toml::from_str(&content)
+        .map_err(|e| RaeError::Config(format!("Invalid value for {}: {}", key, e)))?;
+    config.validate()?;
+    
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    let temp_path = path.with_extension("toml.tmp");
+    if let Err(e) = fs::write(&temp_path, content) {
+        let _ = fs::remove_file(&temp_path);
+        return Err(e.into());
+    }
+    fs::rename(&temp_path, path)?;
+    
+    Ok(())
+}
+
+/// Value of `key` in the configuration file at `path`, with defaults for settings
+/// missing from the file.
+///
+/// Strings are returned without quotes; other values in TOML syntax.
+pub fn get_in(path: &Path, key: &str) -> Result<Option<String>> {
+    schema_value(key)?;
+    
+    let mut value = &settings(path)?;
+    for segment in key.split('.') {
+        match value.get(segment) {
+            Some(inner) => value = inner,
+            None => return Ok(None),
+        }
+    }
+    
+    Ok(Some(match value {
+        toml::Value::String(s) => s.clone(),
+        other => other.to_string(),
+    }))
+}
+
+/// All settings in the configuration file at `path`, with defaults for settings
+/// missing from the file, as `(key, value)` pairs in TOML syntax.
+pub fn list_in(path: &Path) -> Result<Vec<(String, String)>> {
+    let mut pairs = Vec::new();
+    flatten("", &settings(path)?, &mut pairs);
+    Ok(pairs)
+}
+
+/// Loads the configuration at `path` as a TOML table.
+fn settings(path: &Path) -> Result<toml::Value> {
+    toml::Value::try_from(Config::load_from_file(path)?)
+        .map_err(|e| RaeError::Config(format!("Failed to serialize configuration: {}", e)))
+}
+
+fn flatten(prefix: &str, value: &toml::Value, pairs: &mut Vec<(String, String)>) {
+    match value {
+        toml::Value::Table(table) => {
+            for (key, inner) in table {
+                let path = if prefix.is_empty() { key.clone() } else { format!("{}.{}", prefix, key) };
+                flatten(&path, inner, pairs);
+            }
+        }
+        other => pairs.push((prefix.to_string(), other.to_string())),
+    }
+}
+
+/// Checks that `key` names a setting and returns its default value.
+///
+/// Keys below a map setting (such as `env_groups`) are accepted and have no
+/// default.
+fn schema_value(key: &str) -> Result<Option<JsonValue>> {
+    let unknown = || RaeError::Config(format!("Unknown configuration key: {}", key));
+    let mut value = serde_json::to_value(Config::default())?;
+    
+    for segment in key.split('.') {
+        if segment.is_empty() {
+            return Err(unknown());
+        }
+        match value {
+            JsonValue::Object(mut fields) if !fields.is_empty() => {
+                value = fields.remove(segment).ok_or_else(unknown)?;
+            }
+            // An empty map takes any keys
+            JsonValue::Object(_) => return Ok(None),
+            _ => return Err(unknown()),
+        }
+    }
+    
+    Ok(Some(value))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+    
+    #[test]
+    fn test_set_get_round_trip_keeps_comments() {
+        let temp_dir = tempdir().unwrap();
+        let path = temp_dir.path().join("rae.toml");
+        fs::write(&path, "# Rae Configuration\n\n# How chatty the logs are\nlog_level = \"info\" # default\n").unwrap();
+        
+        set_in(&path, "log_level", "debug").unwrap();
+        set_in(&path, "max_modules", "25").unwrap();
+        set_in(&path, "env_groups.build.CC", "clang").unwrap();
+        
+        assert_eq!(get_in(&path, "log_level").unwrap().as_deref(), Some("debug"));
+        assert_eq!(get_in(&path, "max_modules").unwrap().as_deref(), Some("25"));
+        assert_eq!(get_in(&path, "env_groups.build.CC").unwrap().as_deref(), Some("clang"));
+        assert_eq!(get_in(&path, "alert_webhook_url").unwrap(), None);
+        
+        let content = fs::read_to_string(&path).unwrap();
+        assert!(content.starts_with("# Rae Configuration\n\n# How chatty the logs are\nlog_level = \"debug\" # default\n"));
+        
+        let config = Config::load_from_file(&path).unwrap();
+        assert_eq!(config.log_level, "debug");
+        assert_eq!(config.max_modules, 25);
+        
+        let pairs = list_in(&path).unwrap();
+        assert!(pairs.contains(&("log_level".to_string(), "\"debug\"".to_string())));
+        assert!(pairs.contains(&("max_modules".to_string(), "25".to_string())));
+    }
+    
+    #[test]
+    fn test_set_rejects_unknown_keys_and_invalid_values() {
+        let temp_dir = tempdir().unwrap();
+        let path = temp_dir.path().join("rae.toml");
+        
+        for key in ["agent.log_level", "log_level.name", "", "max_modules."] {
+            assert!(matches!(set_in(&path, key, "1"), Err(RaeError::Config(_))), "{:?}", key);
+        }
+        assert!(matches!(get_in(&path, "nope"), Err(RaeError::Config(_))));
+        
+        // Fails validation or has the wrong type; the file is not written
+        assert!(matches!(set_in(&path, "max_modules", "0"), Err(RaeError::Config(_))));
+        assert!(matches!(set_in(&path, "max_modules", "many"), Err(RaeError::Config(_))));
+        assert!(matches!(set_in(&path, "log_level", "loud"), Err(RaeError::Config(_))));
+        assert!(!path.exists());
+    }
+}
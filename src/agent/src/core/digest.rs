@@ -0,0 +1,484 @@
+//! Digests summarizing the activity recorded over a day or a week.
+
+use crate::core::storage::{ActivityData, Storage};
+use crate::error::Result;
+use chrono::{DateTime, Duration, Utc};
+use handlebars::html_escape;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::fmt::Write as _;
+use std::fs;
+use std::path::Path;
+
+/// Topic of activities that match none of the requested topics.
+pub const MISC_TOPIC: &str = "misc";
+
+/// Output format of `Digest::write_to_file`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DigestFormat {
+    Markdown,
+    Html,
+}
+
+/// Summary of the activity recorded in a period.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Digest {
+    /// `<period>-<start date>`, so regenerating a digest replaces it
+    pub id: String,
+    /// `daily` or `weekly`
+    pub period: String,
+    pub start_date: DateTime<Utc>,
+    /// End of the period (exclusive)
+    pub end_date: DateTime<Utc>,
+    pub summary: String,
+    /// Number of activities in the period
+    pub activity_count: usize,
+    /// Number of activities per module
+    pub module_counts: BTreeMap<String, usize>,
+    /// Activities in the period, oldest first
+    #[serde(default)]
+    pub activities: Vec<ActivityData>,
+}
+
+impl Digest {
+    /// Summarizes the day starting at `day_start`.
+    pub fn generate_daily(storage: &Storage, day_start: DateTime<Utc>) -> Result<Digest> {
+        let day_end = day_start + Duration::days(1);
+        let activities = storage.query_activities(None, day_start, day_end)?;
+        Ok(Self::from_activities("daily", day_start, day_end, &activities))
+    }
+    
+    /// Summarizes the seven days starting at `week_start`.
+    ///
+    /// Each day is queried separately; an activity returned for more than one
+    /// day is counted once.
+    pub fn generate_weekly(storage: &Storage, week_start: DateTime<Utc>) -> Result<Digest> {
+        let mut seen = HashSet::new();
+        let mut activities = Vec::new();
+        for day in 0..7 {
+            let day_start = week_start + Duration::days(day);
+            for activity in storage.query_activities(None, day_start, day_start + Duration::days(1))? {
+                if seen.insert(activity.id.clone()) {
+                    activities.push(activity);
+                }
+            }
+        }
+        
+        Ok(Self::from_activities("weekly", week_start, week_start + Duration::days(7), &activities))
+    }
+    
+    fn from_activities(period: &str, start_date: DateTime<Utc>, end_date: DateTime<Utc>, activities: &[ActivityData]) -> Digest {
+        let mut module_counts = BTreeMap::new();
+        for activity in activities {
+            *module_counts.entry(activity.module.clone()).or_insert(0) += 1;
+        }
+        
+        Digest {
+            id: format!("{}-{}", period, start_date.format("%Y-%m-%d")),
+            period: period.to_string(),
+            start_date,
+            end_date,
+            summary: tally_summary(activities.len(), &module_counts),
+            activity_count: activities.len(),
+            module_counts,
+            activities: activities.to_vec(),
+        }
+    }
+    
+    /// Groups the activities by the topics named in their string values,
+    /// ignoring case.
+    ///
+    /// An activity can belong to several topics; activities matching none of
+    /// them are grouped under `misc`. Only topics with activities are returned.
+    pub fn topic_clustering(&self, topics: &[&str]) -> HashMap<String, Vec<ActivityData>> {
+        let topic_keywords = topics.iter()
+            .map(|topic| (topic.to_string(), vec![topic.to_string()]))
+            .collect();
+        self.cluster_by_keywords(&topic_keywords)
+    }
+    
+    /// Groups the activities under each topic with a keyword in their string
+    /// values, ignoring case; see `topic_clustering`.
+    pub fn cluster_by_keywords(&self, topic_keywords: &HashMap<String, Vec<String>>) -> HashMap<String, Vec<ActivityData>> {
+        let topic_keywords: Vec<(&String, Vec<String>)> = topic_keywords.iter()
+            .map(|(topic, keywords)| {
+                let keywords = keywords.iter()
+                    .filter(|keyword| !keyword.is_empty())
+                    .map(|keyword| keyword.to_lowercase())
+                    .collect();
+                (topic, keywords)
+            })
+            .collect();
+        
+        let mut clusters: HashMap<String, Vec<ActivityData>> = HashMap::new();
+        for activity in &self.activities {
+            let mut values = Vec::new();
+            string_values(&activity.data, &mut values);
+            let values: Vec<String> = values.iter().map(|value| value.to_lowercase()).collect();
+            
+            let mut matched = false;
+            for (topic, keywords) in &topic_keywords {
+                if keywords.iter().any(|keyword| values.iter().any(|value| value.contains(keyword.as_str()))) {
+                    clusters.entry(topic.to_string()).or_default().push(activity.clone());
+                    matched = true;
+                }
+            }
+            if !matched {
+                clusters.entry(MISC_TOPIC.to_string()).or_default().push(activity.clone());
+            }
+        }
+        
+        clusters
+    }
+    
+    /// Renders the digest as a Markdown document with a table of its activities.
+    pub fn to_markdown(&self) -> String {
+        let mut out = format!("# {}\n\n## Activities\n\n", self.title());
+        push_markdown_table(&mut out, &self.activities);
+        
+        let _ = write!(out, "\n## Summary\n\n{}\n", self.summary);
+        out
+    }
+    
+    /// Renders the digest as Markdown with a table per topic (`misc` last),
+    /// as `to_markdown` does when no topics are configured.
+    pub fn to_markdown_by_topic(&self, topic_keywords: &HashMap<String, Vec<String>>) -> String {
+        if topic_keywords.is_empty() || self.activities.is_empty() {
+            return self.to_markdown();
+        }
+        
+        let clusters = self.cluster_by_keywords(topic_keywords);
+        let mut topics: Vec<&String> = clusters.keys().collect();
+        topics.sort_by_key(|topic| (topic.as_str() == MISC_TOPIC, topic.as_str()));
+        
+        self.grouped_markdown(topics.into_iter().map(|topic| (topic.as_str(), clusters[topic].as_slice())))
+    }
+    
+    /// Renders the digest as Markdown with a table per module.
+    pub fn to_markdown_by_module(&self) -> String {
+        if self.activities.is_empty() {
+            return self.to_markdown();
+        }
+        
+        let mut modules: BTreeMap<&str, Vec<ActivityData>> = BTreeMap::new();
+        for activity in &self.activities {
+            modules.entry(activity.module.as_str()).or_default().push(activity.clone());
+        }
+        
+        self.grouped_markdown(modules.iter().map(|(module, activities)| (*module, activities.as_slice())))
+    }
+    
+    fn grouped_markdown<'a>(&self, groups: impl Iterator<Item = (&'a str, &'a [ActivityData])>) -> String {
+        let mut out = format!("# {}\n\n## Activities\n", self.title());
+        for (name, activities) in groups {
+            let _ = write!(out, "\n### {}\n\n", name);
+            push_markdown_table(&mut out, activities);
+        }
+        
+        let _ = write!(out, "\n## Summary\n\n{}\n", self.summary);
+        out
+    }
+    
+    /// Renders the digest as a standalone HTML page; all recorded text is escaped.
+    pub fn to_html(&self) -> String {
+        let title = html_escape(&self.title());
+        let mut out = format!(
+            "<!DOCTYPE html>\n<html lang=\"en\">\n<head>\n<meta charset=\"utf-8\">\n<title>{title}</title>\n</head>\n<body>\n<h1>{title}</h1>\n<h2>Activities</h2>\n"
+        );
+        if self.activities.is_empty() {
+            out.push_str("<p>No activities recorded.</p>\n");
+        } else {
+            out.push_str("<table>\n<thead><tr><th>Module</th><th>Time</th><th>Data</th></tr></thead>\n<tbody>\n");
+            for activity in &self.activities {
+                let _ = writeln!(
+                    out,
+                    "<tr><td>{}</td><td><time datetime=\"{}\">{}</time></td><td>{}</td></tr>",
+                    html_escape(&activity.module),
+                    activity.timestamp.to_rfc3339(),
+                    activity.timestamp.format("%Y-%m-%d %H:%M UTC"),
+                    html_escape(&data_fields(&activity.data)),
+                );
+            }
+            out.push_str("</tbody>\n</table>\n");
+        }
+        
+        let _ = write!(out, "<h2>Summary</h2>\n<p>{}</p>\n</body>\n</html>\n", html_escape(&self.summary));
+        out
+    }
+    
+    /// Writes the digest to `path` in `format`, replacing the file.
+    pub fn write_to_file(&self, path: &Path, format: DigestFormat) -> Result<()> {
+        let contents = match format {
+            DigestFormat::Markdown => self.to_markdown(),
+            DigestFormat::Html => self.to_html(),
+        };
+        fs::write(path, contents)?;
+        Ok(())
+    }
+    
+    /// `Daily digest: <day>` or `Weekly digest: <first day> to <last day>`.
+    fn title(&self) -> String {
+        let first_day = self.start_date.format("%Y-%m-%d");
+        let last_day = (self.end_date - Duration::days(1)).format("%Y-%m-%d");
+        
+        match self.period.as_str() {
+            "daily" => format!("Daily digest: {}", first_day),
+            "weekly" => format!("Weekly digest: {} to {}", first_day, last_day),
+            period => format!("{} digest: {} to {}", period, first_day, last_day),
+        }
+    }
+}
+
+/// Appends a table of `activities`, or a note that there are none.
+fn push_markdown_table(out: &mut String, activities: &[ActivityData]) {
+    if activities.is_empty() {
+        out.push_str("_No activities recorded._\n");
+        return;
+    }
+    
+    out.push_str("| Module | Time | Data |\n| --- | --- | --- |\n");
+    for activity in activities {
+        let _ = writeln!(
+            out,
+            "| {} | {} | {} |",
+            markdown_cell(&activity.module),
+            activity.timestamp.format("%Y-%m-%d %H:%M UTC"),
+            markdown_cell(&data_fields(&activity.data)),
+        );
+    }
+}
+
+/// Collects the strings anywhere in `data`.
+fn string_values<'a>(data: &'a Value, values: &mut Vec<&'a str>) {
+    match data {
+        Value::String(text) => values.push(text),
+        Value::Array(items) => items.iter().for_each(|item| string_values(item, values)),
+        Value::Object(fields) => fields.values().for_each(|value| string_values(value, values)),
+        _ => {}
+    }
+}
+
+/// The top-level fields of an activity's data as `key: value` pairs.
+fn data_fields(data: &Value) -> String {
+    match data {
+        Value::Object(fields) => fields.iter()
+            .map(|(key, value)| format!("{}: {}", key, data_value(value)))
+            .collect::<Vec<_>>()
+            .join(", "),
+        value => data_value(value),
+    }
+}
+
+fn data_value(value: &Value) -> String {
+    match value {
+        Value::String(text) => text.clone(),
+        Value::Null => String::new(),
+        value => value.to_string(),
+    }
+}
+
+/// Keeps a value inside its Markdown table cell.
+fn markdown_cell(text: &str) -> String {
+    text.replace('|', "\\|").replace(['\r', '\n'], " ")
+}
+
+/// Describes the activity counts, busiest module first.
+fn tally_summary(total: usize, module_counts: &BTreeMap<String, usize>) -> String {
+    if total == 0 {
+        return "No activity recorded.".to_string();
+    }
+    
+    let mut modules: Vec<(&String, &usize)> = module_counts.iter().collect();
+    modules.sort_by(|a, b| b.1.cmp(a.1).then_with(|| a.0.cmp(b.0)));
+    let tallies: Vec<String> = modules.iter()
+        .map(|(module, count)| format!("{} ({})", module, count))
+        .collect();
+    
+    format!(
+        "{} {} across {} {}: {}",
+        total,
+        if total == 1 { "activity" } else { "activities" },
+        modules.len(),
+        if modules.len() == 1 { "module" } else { "modules" },
+        tallies.join(", ")
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+    use serde_json::json;
+    use tempfile::tempdir;
+    
+    #[test]
+    fn test_weekly_digest_covers_all_seven_days() {
+        let temp_dir = tempdir().unwrap();
+        let storage = Storage::new(&temp_dir.path().join("rae.db")).unwrap();
+        let week_start = Utc.with_ymd_and_hms(2026, 3, 2, 0, 0, 0).unwrap();
+        
+        for day in 0..7 {
+            let day_start = week_start + Duration::days(day);
+            for (module, hour) in [("git", 9), ("calendar", 14)] {
+                let activity = ActivityData::new(module.to_string(), json!({ "day": day }))
+                    .with_timestamp(day_start + Duration::hours(hour));
+                storage.store_activity(&activity).unwrap();
+            }
+        }
+        // Outside the week on both sides
+        for timestamp in [week_start - Duration::seconds(1), week_start + Duration::days(7)] {
+            storage.store_activity(&ActivityData::new("git".to_string(), json!({})).with_timestamp(timestamp)).unwrap();
+        }
+        // A third git event on the last day
+        storage.store_activity(&ActivityData::new("git".to_string(), json!({}))
+            .with_timestamp(week_start + Duration::days(7) - Duration::seconds(1))).unwrap();
+        
+        let digest = Digest::generate_weekly(&storage, week_start).unwrap();
+        assert_eq!(digest.id, "weekly-2026-03-02");
+        assert_eq!(digest.period, "weekly");
+        assert_eq!(digest.end_date, week_start + Duration::days(7));
+        assert_eq!(digest.activity_count, 15);
+        assert_eq!(digest.module_counts["git"], 8);
+        assert_eq!(digest.module_counts["calendar"], 7);
+        assert_eq!(digest.summary, "15 activities across 2 modules: git (8), calendar (7)");
+        
+        let daily = Digest::generate_daily(&storage, week_start).unwrap();
+        assert_eq!(daily.activity_count, 2);
+        assert_eq!(daily.activities.len(), 2);
+        assert!(daily.activities[0].timestamp < daily.activities[1].timestamp);
+    }
+    
+    fn digest_of(activities: &[ActivityData]) -> Digest {
+        let day_start = Utc.with_ymd_and_hms(2026, 3, 2, 0, 0, 0).unwrap();
+        Digest::from_activities("daily", day_start, day_start + Duration::days(1), activities)
+    }
+    
+    #[test]
+    fn test_render_empty_digest() {
+        let digest = digest_of(&[]);
+        
+        assert_eq!(
+            digest.to_markdown(),
+            "# Daily digest: 2026-03-02\n\n## Activities\n\n_No activities recorded._\n\n## Summary\n\nNo activity recorded.\n"
+        );
+        
+        let html = digest.to_html();
+        assert!(html.contains("<h1>Daily digest: 2026-03-02</h1>"));
+        assert!(html.contains("<p>No activities recorded.</p>"));
+        assert!(!html.contains("<table>"));
+    }
+    
+    #[test]
+    fn test_render_activities_table() {
+        let at = Utc.with_ymd_and_hms(2026, 3, 2, 9, 30, 0).unwrap();
+        let digest = digest_of(&[
+            ActivityData::new("git".to_string(), json!({ "repo": "rae", "commits": 3 })).with_timestamp(at),
+            ActivityData::new("notes".to_string(), json!({ "title": "a | b\nc" })).with_timestamp(at),
+        ]);
+        
+        let markdown = digest.to_markdown();
+        assert!(markdown.contains("| Module | Time | Data |\n| --- | --- | --- |\n"));
+        assert!(markdown.contains("| git | 2026-03-02 09:30 UTC | commits: 3, repo: rae |\n"));
+        assert!(markdown.contains("| notes | 2026-03-02 09:30 UTC | title: a \\| b c |\n"));
+        assert!(markdown.ends_with("## Summary\n\n2 activities across 2 modules: git (1), notes (1)\n"));
+        
+        let weekly = Digest { period: "weekly".to_string(), end_date: digest.start_date + Duration::days(7), ..digest };
+        assert!(weekly.to_markdown().starts_with("# Weekly digest: 2026-03-02 to 2026-03-08\n"));
+    }
+    
+    #[test]
+    fn test_render_non_ascii_and_markup() {
+        let at = Utc.with_ymd_and_hms(2026, 3, 2, 18, 5, 0).unwrap();
+        let digest = digest_of(&[
+            ActivityData::new("café".to_string(), json!({ "note": "Grüße <b>& 日本語 🎉</b>" })).with_timestamp(at),
+        ]);
+        
+        assert!(digest.to_markdown().contains("| café | 2026-03-02 18:05 UTC | note: Grüße <b>& 日本語 🎉</b> |"));
+        
+        let html = digest.to_html();
+        assert!(html.contains("<meta charset=\"utf-8\">"));
+        assert!(html.contains("<td>café</td>"));
+        assert!(html.contains("<td>note: Grüße &lt;b&gt;&amp; 日本語 🎉&lt;/b&gt;</td>"));
+        assert!(html.contains("<time datetime=\"2026-03-02T18:05:00+00:00\">"));
+    }
+    
+    fn topic_digest() -> Digest {
+        digest_of(&[
+            ActivityData::new("calendar".to_string(), json!({ "event": "Work standup" })),
+            ActivityData::new("browser".to_string(), json!({ "title": "Rust course", "tags": ["Learning", "work"] })),
+            ActivityData::new("fitness".to_string(), json!({ "activity": "run", "km": 5 })),
+        ])
+    }
+    
+    fn modules_of(activities: &[ActivityData]) -> Vec<&str> {
+        activities.iter().map(|activity| activity.module.as_str()).collect()
+    }
+    
+    #[test]
+    fn test_topic_clustering_matches_keywords() {
+        let clusters = topic_digest().topic_clustering(&["work", "learning"]);
+        
+        assert_eq!(modules_of(&clusters["work"]), vec!["calendar", "browser"]);
+        assert_eq!(modules_of(&clusters["learning"]), vec!["browser"]);
+        assert_eq!(modules_of(&clusters[MISC_TOPIC]), vec!["fitness"]);
+        assert_eq!(clusters.len(), 3);
+    }
+    
+    #[test]
+    fn test_topic_clustering_misc_bucket() {
+        let digest = topic_digest();
+        
+        let clusters = digest.topic_clustering(&[]);
+        assert_eq!(clusters.len(), 1);
+        assert_eq!(clusters[MISC_TOPIC].len(), 3);
+        
+        // Topics nothing matches are left out
+        let clusters = digest.topic_clustering(&["health"]);
+        assert!(!clusters.contains_key("health"));
+        assert_eq!(clusters[MISC_TOPIC].len(), 3);
+        
+        assert!(digest_of(&[]).topic_clustering(&["work"]).is_empty());
+    }
+    
+    #[test]
+    fn test_markdown_groups_by_configured_topics() {
+        let digest = topic_digest();
+        let topic_keywords = HashMap::from([
+            ("health".to_string(), vec!["run".to_string(), "gym".to_string()]),
+            ("work".to_string(), vec!["standup".to_string()]),
+        ]);
+        
+        let markdown = digest.to_markdown_by_topic(&topic_keywords);
+        let health = markdown.find("### health\n").unwrap();
+        let work = markdown.find("### work\n").unwrap();
+        let misc = markdown.find("### misc\n").unwrap();
+        assert!(health < misc && work < misc && health < work);
+        assert!(markdown[misc..].contains("| browser |"));
+        assert!(markdown[health..work].contains("| fitness |"));
+        
+        assert_eq!(digest.to_markdown_by_topic(&HashMap::new()), digest.to_markdown());
+        
+        let by_module = digest.to_markdown_by_module();
+        let browser = by_module.find("### browser\n").unwrap();
+        let calendar = by_module.find("### calendar\n").unwrap();
+        assert!(browser < calendar);
+        assert!(by_module[calendar..].contains("| calendar |"));
+    }
+    
+    #[test]
+    fn test_write_to_file() {
+        let temp_dir = tempdir().unwrap();
+        let digest = digest_of(&[ActivityData::new("git".to_string(), json!({ "commits": 1 }))]);
+        
+        let markdown_path = temp_dir.path().join("digest.md");
+        digest.write_to_file(&markdown_path, DigestFormat::Markdown).unwrap();
+        assert_eq!(fs::read_to_string(&markdown_path).unwrap(), digest.to_markdown());
+        
+        let html_path = temp_dir.path().join("digest.html");
+        digest.write_to_file(&html_path, DigestFormat::Html).unwrap();
+        assert_eq!(fs::read_to_string(&html_path).unwrap(), digest.to_html());
+        
+        assert!(digest.write_to_file(&temp_dir.path().join("missing").join("digest.md"), DigestFormat::Markdown).is_err());
+    }
+}
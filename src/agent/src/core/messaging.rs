@@ -0,0 +1,167 @@
+//! Publish/subscribe message routing between modules.
+//!
+//! Each subscription pattern gets its own broadcast channel. Publishing never
+//! blocks: when a subscriber falls behind, its oldest messages are dropped and
+//! it sees `RecvError::Lagged` on its next receive.
+
+use serde_json::Value;
+use std::collections::HashMap;
+use std::sync::RwLock;
+use tokio::sync::broadcast;
+use tracing::warn;
+
+/// Messages buffered per subscription pattern by default.
+pub const DEFAULT_CHANNEL_CAPACITY: usize = 256;
+
+/// Routes published messages to subscribers by topic.
+///
+/// Topics are dot-separated names such as `module.start`. Subscription
+/// patterns may use `*` to match any run of characters, so `module.*` receives
+/// both `module.start` and `module.stop`.
+#[derive(Debug)]
+pub struct MessageBus {
+    /// Channels by subscription pattern
+    channels: RwLock<HashMap<String, broadcast::Sender<Value>>>,
+    /// Messages buffered per pattern before the oldest are dropped
+    capacity: usize,
+}
+
+impl Default for MessageBus {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl MessageBus {
+    /// Creates a message bus with the default channel capacity.
+    pub fn new() -> Self {
+        Self::with_capacity(DEFAULT_CHANNEL_CAPACITY)
+    }
+    
+    /// Creates a message bus buffering `capacity` messages per pattern.
+    pub fn with_capacity(capacity: usize) -> Self {
+        MessageBus {
+            channels: RwLock::new(HashMap::new()),
+            capacity: capacity.max(1),
+        }
+    }
+    
+    /// Subscribes to a topic or topic pattern.
+    pub fn subscribe(&self, pattern: &str) -> broadcast::Receiver<Value> {
+        let mut channels = self.channels.write().unwrap_or_else(|e| e.into_inner());
+        channels
+            .entry(pattern.to_string())
+            .or_insert_with(|| broadcast::channel(self.capacity).0)
+            .subscribe()
+    }
+    
+    /// Publishes a message to every subscriber whose pattern matches the topic.
+    ///
+    /// Returns the number of patterns the message was delivered to.
+    pub fn publish(&self, topic: &str, payload: Value) -> usize {
+        let mut channels = self.channels.write().unwrap_or_else(|e| e.into_inner());
+        
+        // Patterns whose subscribers have all gone away are dropped
+        channels.retain(|_, sender| sender.receiver_count() > 0);
+        
+        let mut delivered = 0;
+        for (pattern, sender) in channels.iter() {
+            if !topic_matches(pattern, topic) {
+                continue;
+            }
+            
+            if sender.len() >= self.capacity {
+                warn!("Subscribers to {} are falling behind; dropping the oldest message", pattern);
+            }
+            if sender.send(payload.clone()).is_ok() {
+                delivered += 1;
+            }
+        }
+        
+        delivered
+    }
+}
+
+/// Matches a topic against a pattern where `*` matches any run of characters.
+fn topic_matches(pattern: &str, topic: &str) -> bool {
+    let mut parts = pattern.split('*');
+    let first = parts.next().unwrap_or_default();
+    let Some(mut rest) = topic.strip_prefix(first) else {
+        return false;
+    };
+    
+    let parts: Vec<&str> = parts.collect();
+    let Some((last, middle)) = parts.split_last() else {
+        // No wildcard: the pattern must match exactly
+        return rest.is_empty();
+    };
+    
+    for part in middle {
+        match rest.find(part) {
+            Some(index) => rest = &rest[index + part.len()..],
+            None => return false,
+        }
+    }
+    
+    rest.ends_with(last)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+    use tokio::sync::broadcast::error::{RecvError, TryRecvError};
+    
+    #[test]
+    fn test_topic_matching() {
+        assert!(topic_matches("module.start", "module.start"));
+        assert!(!topic_matches("module.start", "module.stop"));
+        assert!(topic_matches("module.*", "module.start"));
+        assert!(topic_matches("*.error", "storage.error"));
+        assert!(topic_matches("*", "anything"));
+        assert!(topic_matches("module.*.done", "module.sync.done"));
+        assert!(!topic_matches("module.*", "storage.start"));
+        assert!(!topic_matches("module.*.done", "module.sync"));
+    }
+    
+    #[tokio::test]
+    async fn test_glob_subscriber_receives_matching_topics() {
+        let bus = MessageBus::new();
+        let mut all_module = bus.subscribe("module.*");
+        let mut start_only = bus.subscribe("module.start");
+        
+        assert_eq!(bus.publish("module.start", json!({ "id": "digest" })), 2);
+        assert_eq!(bus.publish("module.stop", json!({ "id": "digest" })), 1);
+        assert_eq!(bus.publish("storage.ready", json!(null)), 0);
+        
+        assert_eq!(all_module.recv().await.unwrap(), json!({ "id": "digest" }));
+        assert_eq!(all_module.recv().await.unwrap(), json!({ "id": "digest" }));
+        assert!(matches!(all_module.try_recv(), Err(TryRecvError::Empty)));
+        
+        assert_eq!(start_only.recv().await.unwrap(), json!({ "id": "digest" }));
+        assert!(matches!(start_only.try_recv(), Err(TryRecvError::Empty)));
+    }
+    
+    #[tokio::test]
+    async fn test_slow_subscriber_loses_oldest_messages() {
+        let bus = MessageBus::with_capacity(2);
+        let mut receiver = bus.subscribe("tick");
+        
+        for i in 0..3 {
+            bus.publish("tick", json!(i));
+        }
+        
+        assert!(matches!(receiver.recv().await, Err(RecvError::Lagged(1))));
+        assert_eq!(receiver.recv().await.unwrap(), json!(1));
+        assert_eq!(receiver.recv().await.unwrap(), json!(2));
+    }
+    
+    #[test]
+    fn test_dropped_subscribers_are_removed() {
+        let bus = MessageBus::new();
+        drop(bus.subscribe("module.*"));
+        
+        assert_eq!(bus.publish("module.start", json!(null)), 0);
+        assert!(bus.channels.read().unwrap().is_empty());
+    }
+}
@@ -1,18 +1,18 @@
 //! Core functionality for the Rae agent
 //!
 //! This module contains the essential components that make up the Rae agent:
-//! - Agent: Main agent logic and coordination
-//! - Scheduler: Task scheduling and automation
-//! - Storage: Local data storage and management
 //! - Messaging: Inter-module communication
+//! - Storage: SQLite store for activity data and digests
+//! - Digest: Daily and weekly activity summaries
+//!
+//! The agent component is not implemented yet; scheduling lives in the
+//! top-level `scheduler` module.
 
-pub mod agent;
-pub mod scheduler;
-pub mod storage;
+pub mod digest;
 pub mod messaging;
+pub mod storage;
 
 // Re-export main types
-pub use agent::Agent;
-pub use scheduler::Scheduler;
-pub use storage::Storage;
-pub use messaging::MessageBus; 
\ No newline at end of file
+pub use digest::{Digest, DigestFormat};
+pub use messaging::MessageBus;
+pub use storage::{ActivityData, DedupStats, EncryptedStorage, Storage, VacuumReport};
@@ -0,0 +1,1188 @@
+//! SQLite store for activity data and digests.
+//!
+//! The database runs in WAL mode so readers are not blocked by the writer.
+//! Its schema version is kept in `PRAGMA user_version`; opening a store
+//! applies any migrations newer than that version.
+//!
+//! Activity data can be encrypted with AES-256-GCM, row by row with a key
+//! from the caller, or for every write through `EncryptedStorage` with a key
+//! derived from a passphrase.
+
+use crate::config::Config;
+use crate::core::digest::Digest;
+use crate::error::{RaeError, Result};
+use crate::modules::ModuleInfo;
+use aes_gcm::aead::{Aead, AeadCore, KeyInit, OsRng, Payload};
+use aes_gcm::{Aes256Gcm, Nonce};
+use argon2::{Algorithm, Argon2, Params, Version};
+use arrow_array::{ArrayRef, RecordBatch, StringArray, TimestampMicrosecondArray};
+use arrow_schema::{DataType, Field, Schema, TimeUnit};
+use base64::Engine;
+use base64::engine::general_purpose::STANDARD as BASE64;
+use chrono::{DateTime, Duration, SecondsFormat, Utc};
+use parquet::arrow::ArrowWriter;
+use rand::RngCore;
+use rusqlite::{params, Connection, OptionalExtension};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::fs::{self, File};
+use std::io::{ErrorKind, Write};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use std::sync::atomic::{AtomicI64, AtomicU64, Ordering};
+use std::time::Instant;
+use tokio::sync::mpsc;
+use tracing::warn;
+use uuid::Uuid;
+
+/// File name of the database inside the data directory.
+pub const DATABASE_FILE: &str = "rae.db";
+
+/// File next to the database holding the salt of encryption passphrases.
+pub const SALT_FILE: &str = "salt";
+
+/// Activities buffered for a stream whose reader falls behind; more are dropped.
+const STREAM_BUFFER: usize = 1024;
+
+/// How often a stream checks whether its FIFO has a reader.
+#[cfg(unix)]
+const FIFO_RETRY_INTERVAL: std::time::Duration = std::time::Duration::from_millis(100);
+
+/// Days of activity kept by `Storage::vacuum` unless configured otherwise.
+pub const DEFAULT_RETENTION_DAYS: u32 = 90;
+
+/// Cron schedule of the built-in vacuum job unless configured otherwise (Sundays at 03:00).
+pub const DEFAULT_VACUUM_SCHEDULE: &str = "0 0 3 * * Sun";
+
+/// Schema migrations, applied in order; migration `i` brings the schema to version `i + 1`.
+const MIGRATIONS: &[&str] = &[
+    "CREATE TABLE activity_data (
+        id TEXT PRIMARY KEY,
+        timestamp TEXT NOT NULL,
+        module TEXT NOT NULL,
+        data BLOB NOT NULL
+    );
+    CREATE INDEX idx_activity_data_timestamp ON activity_data (timestamp);
+    CREATE INDEX idx_activity_data_module_timestamp ON activity_data (module, timestamp);
+    CREATE TABLE digests (
+        id TEXT PRIMARY KEY,
+        period TEXT NOT NULL,
+        start_date TEXT NOT NULL,
+        end_date TEXT NOT NULL,
+        summary TEXT NOT NULL
+    );",
+    "ALTER TABLE activity_data ADD COLUMN content_hash TEXT;
+    CREATE INDEX idx_activity_data_content_hash ON activity_data (content_hash);",
+    "ALTER TABLE activity_data ADD COLUMN encrypted INTEGER NOT NULL DEFAULT 0;",
+];
+
+/// A record produced by a module.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ActivityData {
+    pub id: String,
+    pub timestamp: DateTime<Utc>,
+    /// Name of the module that recorded the activity
+    pub module: String,
+    pub data: Value,
+}
+
+impl ActivityData {
+    /// Creates an activity recorded now.
+    pub fn new(module: String, data: Value) -> Self {
+        ActivityData {
+            id: Uuid::new_v4().to_string(),
+            timestamp: Utc::now(),
+            module,
+            data,
+        }
+    }
+    
+    /// Sets when the activity happened.
+    pub fn with_timestamp(mut self, timestamp: DateTime<Utc>) -> Self {
+        self.timestamp = timestamp;
+        self
+    }
+    
+    /// Hex `blake3` hash of the module, the minute of the timestamp and the
+    /// data with sorted keys; activities with the same hash are duplicates.
+    pub fn content_hash(&self) -> String {
+        let mut data = String::new();
+        canonical_json(&self.data, &mut data);
+        
+        let mut hasher = blake3::Hasher::new();
+        hasher.update(self.module.as_bytes());
+        hasher.update(&[0]);
+        hasher.update(self.timestamp.format("%Y-%m-%dT%H:%MZ").to_string().as_bytes());
+        hasher.update(&[0]);
+        hasher.update(data.as_bytes());
+        hasher.finalize().to_hex().to_string()
+    }
+}
+
+/// A `Storage` that encrypts the data of every activity it stores.
+///
+/// Reads decrypt encrypted rows and pass plaintext rows through unchanged,
+/// so a store can be read while it is migrated.
+pub struct EncryptedStorage {
+    storage: Storage,
+    key: [u8; 32],
+}
+
+impl EncryptedStorage {
+    /// The underlying store, e.g. for digests and maintenance.
+    pub fn storage(&self) -> &Storage {
+        &self.storage
+    }
+    
+    /// Stores an activity with its data encrypted.
+    pub fn store_activity(&self, activity: &ActivityData) -> Result<()> {
+        self.storage.store_activity_encrypted(activity, &self.key)
+    }
+    
+    /// Loads and decrypts an activity by ID.
+    pub fn get_activity(&self, id: &str) -> Result<ActivityData> {
+        let conn = self.storage.conn.lock().unwrap_or_else(|e| e.into_inner());
+        let (activity, encrypted) = conn.query_row(
+            "SELECT id, timestamp, module, data, encrypted FROM activity_data WHERE id = ?1",
+            params![id],
+            flagged_activity_row,
+        )
+        .optional()?
+        .ok_or_else(|| RaeError::not_found("activity", id))?;
+        
+        self.decrypt(activity?, encrypted)
+    }
+    
+    /// Decrypted activities recorded from `since` (inclusive) until `until`
+    /// (exclusive), oldest first; see `Storage::query_activities`.
+    pub fn query_activities(
+        &self,
+        module: Option<&str>,
+        since: DateTime<Utc>,
+        until: DateTime<Utc>,
+    ) -> Result<Vec<ActivityData>> {
+        let conn = self.storage.conn.lock().unwrap_or_else(|e| e.into_inner());
+        let mut stmt = conn.prepare_cached(
+            "SELECT id, timestamp, module, data, encrypted FROM activity_data
+             WHERE (?1 IS NULL OR module = ?1) AND timestamp >= ?2 AND timestamp < ?3
+             ORDER BY timestamp",
+        )?;
+        let rows = stmt.query_map(params![module, timestamp_key(&since), timestamp_key(&until)], flagged_activity_row)?;
+        
+        rows.collect::<rusqlite::Result<Vec<_>>>()?
+            .into_iter()
+            .map(|(activity, encrypted)| self.decrypt(activity?, encrypted))
+            .collect()
+    }
+    
+    fn decrypt(&self, activity: ActivityData, encrypted: bool) -> Result<ActivityData> {
+        if encrypted {
+            decrypt_data(activity, &self.key)
+        } else {
+            Ok(activity)
+        }
+    }
+}
+
+/// Writes the activities received to the FIFO at `path` as JSON lines, once
+/// it has a reader, until the reader closes it or the store is dropped.
+#[cfg(unix)]
+async fn write_to_fifo(path: PathBuf, mut receiver: mpsc::Receiver<ActivityData>) {
+    use tokio::io::AsyncWriteExt;
+    use tokio::net::unix::pipe;
+    
+    let mut fifo = loop {
+        match pipe::OpenOptions::new().open_sender(&path) {
+            Ok(fifo) => break fifo,
+            // No reader yet
+            Err(e) if e.raw_os_error() == Some(nix::errno::Errno::ENXIO as i32) => {
+                if receiver.is_closed() {
+                    return;
+                }
+                tokio::time::sleep(FIFO_RETRY_INTERVAL).await;
+            }
+            Err(e) => {
+                warn!("Failed to open FIFO {}: {}", path.display(), e);
+                return;
+            }
+        }
+    };
+    
+    while let Some(activity) = receiver.recv().await {
+        let mut line = match serde_json::to_vec(&activity) {
+            Ok(line) => line,
+            Err(e) => {
+                warn!("Failed to serialize activity {}: {}", activity.id, e);
+                continue;
+            }
+        };
+        line.push(b'\n');
+        
+        if let Err(e) = fifo.write_all(&line).await {
+            if e.kind() != ErrorKind::BrokenPipe {
+                warn!("Failed to write to FIFO {}: {}", path.display(), e);
+            }
+            return;
+        }
+    }
+}
+
+/// Encrypts the plaintext activities of the database at `path` in place,
+/// with a key derived as `Storage::with_encryption` does; returns how many
+/// were encrypted.
+///
+/// The rows are encrypted in a copy of the database that is renamed over the
+/// original once complete, so a failure leaves the original untouched.
+pub fn migrate_to_encrypted(path: &Path, passphrase: &str) -> Result<u64> {
+    // Bring the schema up to date and fold the WAL into the database file
+    let storage = Storage::new(path)?;
+    checkpoint(&storage.conn.lock().unwrap_or_else(|e| e.into_inner()))?;
+    let key = storage.with_encryption(passphrase)?.key;
+    
+    let mut temp_name = path.file_name().unwrap_or_default().to_os_string();
+    temp_name.push(".encrypting");
+    let temp_path = path.with_file_name(temp_name);
+    
+    let encrypted = fs::copy(path, &temp_path)
+        .map_err(RaeError::from)
+        .and_then(|_| encrypt_rows(&temp_path, &key));
+    match encrypted {
+        Ok(count) => {
+            fs::rename(&temp_path, path)?;
+            Ok(count)
+        }
+        Err(e) => {
+            let _ = fs::remove_file(&temp_path);
+            Err(e)
+        }
+    }
+}
+
+/// Encrypts every plaintext activity of the database at `path` in one transaction.
+fn encrypt_rows(path: &Path, key: &[u8; 32]) -> Result<u64> {
+    let mut conn = Connection::open(path)?;
+    let tx = conn.transaction()?;
+    
+    let activities = {
+        let mut stmt = tx.prepare("SELECT id, timestamp, module, data FROM activity_data WHERE encrypted = 0")?;
+        let rows = stmt.query_map([], activity_row)?;
+        rows.collect::<rusqlite::Result<Vec<_>>>()?
+    };
+    for activity in &activities {
+        let activity = activity.as_ref().map_err(|e| RaeError::Storage(e.to_string()))?;
+        tx.execute(
+            "UPDATE activity_data SET data = ?1, content_hash = NULL, encrypted = 1 WHERE id = ?2",
+            params![serde_json::to_vec(&encrypt_data(activity, key)?)?, activity.id],
+        )?;
+    }
+    tx.commit()?;
+    checkpoint(&conn)?;
+    
+    Ok(activities.len() as u64)
+}
+
+/// Derives a 256-bit key from `passphrase` with Argon2id.
+pub fn derive_key(passphrase: &str, salt: &[u8]) -> Result<[u8; 32]> {
+    let mut key = [0u8; 32];
+    Argon2::new(Algorithm::Argon2id, Version::V0x13, Params::default())
+        .hash_password_into(passphrase.as_bytes(), salt, &mut key)
+        .map_err(|e| RaeError::Security(format!("Failed to derive the storage key: {}", e)))?;
+    Ok(key)
+}
+
+/// Reads the salt at `path`, writing a random 16-byte salt there first if it does not exist.
+fn load_or_create_salt(path: &Path) -> Result<Vec<u8>> {
+    match fs::read(path) {
+        Ok(salt) => return Ok(salt),
+        Err(e) if e.kind() == ErrorKind::NotFound => {}
+        Err(e) => return Err(e.into()),
+    }
+    
+    let mut salt = vec![0u8; 16];
+    rand::rngs::OsRng.fill_bytes(&mut salt);
+    
+    let mut options = fs::OpenOptions::new();
+    options.write(true).create_new(true);
+    #[cfg(unix)]
+    std::os::unix::fs::OpenOptionsExt::mode(&mut options, 0o600);
+    match options.open(path) {
+        Ok(mut file) => file.write_all(&salt)?,
+        // Another process created it first
+        Err(e) if e.kind() == ErrorKind::AlreadyExists => return Ok(fs::read(path)?),
+        Err(e) => return Err(e.into()),
+    }
+    
+    Ok(salt)
+}
+
+/// The `data` column of an encrypted activity: its JSON data sealed with
+/// AES-256-GCM, using the activity ID as associated data.
+#[derive(Debug, Serialize, Deserialize)]
+struct EncryptedData {
+    /// Random 96-bit nonce, base64
+    nonce: String,
+    /// Ciphertext and authentication tag, base64
+    ciphertext: String,
+}
+
+/// Counts of the activities passed to `Storage::insert_activity` since the store was opened.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize)]
+pub struct DedupStats {
+    pub total: u64,
+    /// Activities that were new and stored
+    pub unique: u64,
+}
+
+impl DedupStats {
+    /// Activities dropped as duplicates.
+    pub fn duplicates(&self) -> u64 {
+        self.total - self.unique
+    }
+}
+
+/// What a `Storage::vacuum` run removed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub struct VacuumReport {
+    pub deleted_records: u64,
+    /// Shrinkage of the database, in bytes
+    pub freed_bytes: u64,
+    pub duration_ms: u64,
+}
+
+/// SQLite-backed store for activity data and digests.
+pub struct Storage {
+    conn: Mutex<Connection>,
+    path: PathBuf,
+    inserted: AtomicU64,
+    inserted_unique: AtomicU64,
+    /// Streams fed by `store_activity` and `insert_activity`
+    streams: Mutex<Vec<ActivityStream>>,
+    /// Last row forwarded to the streams by `poll_writes`
+    polled_rowid: AtomicI64,
+}
+
+/// A subscriber to stored activities, optionally of a single module.
+struct ActivityStream {
+    module: Option<String>,
+    sender: mpsc::Sender<ActivityData>,
+}
+
+impl Storage {
+    /// Opens or creates the database at `path` and brings its schema up to date.
+    pub fn new(path: &Path) -> Result<Self> {
+        if let Some(parent) = path.parent().filter(|parent| !parent.as_os_str().is_empty()) {
+            fs::create_dir_all(parent)?;
+        }
+        
+        let mut conn = Connection::open(path)?;
+        conn.query_row("PRAGMA journal_mode=WAL", [], |row| row.get::<_, String>(0))?;
+        migrate(&mut conn)?;
+        let last_rowid: i64 = conn.query_row("SELECT COALESCE(MAX(rowid), 0) FROM activity_data", [], |row| row.get(0))?;
+        
+        Ok(Storage {
+            conn: Mutex::new(conn),
+            path: path.to_path_buf(),
+            inserted: AtomicU64::new(0),
+            inserted_unique: AtomicU64::new(0),
+            streams: Mutex::new(Vec::new()),
+            polled_rowid: AtomicI64::new(last_rowid),
+        })
+    }
+    
+    /// Opens the database in the configured data directory.
+    pub fn in_data_dir(config: &Config) -> Result<Self> {
+        Self::new(&config.expanded_data_dir().join(DATABASE_FILE))
+    }
+    
+    /// Encrypts every activity written through the returned store, with a key
+    /// derived from `passphrase` and the installation's salt.
+    ///
+    /// The salt is read from `salt` next to the database (`~/.rae/salt` by
+    /// default) and created on first use.
+    pub fn with_encryption(self, passphrase: &str) -> Result<EncryptedStorage> {
+        let salt = load_or_create_salt(&self.path.with_file_name(SALT_FILE))?;
+        let key = derive_key(passphrase, &salt)?;
+        Ok(EncryptedStorage { storage: self, key })
+    }
+    
+    /// Schema version of the database.
+    pub fn schema_version(&self) -> Result<usize> {
+        let conn = self.conn.lock().unwrap_or_else(|e| e.into_inner());
+        user_version(&conn)
+    }
+    
+    /// Stores an activity, replacing any activity with the same ID.
+    pub fn store_activity(&self, activity: &ActivityData) -> Result<()> {
+        let data = serde_json::to_vec(&activity.data)?;
+        let conn = self.conn.lock().unwrap_or_else(|e| e.into_inner());
+        conn.execute(
+            "INSERT OR REPLACE INTO activity_data (id, timestamp, module, data, content_hash) VALUES (?1, ?2, ?3, ?4, ?5)",
+            params![activity.id, timestamp_key(&activity.timestamp), activity.module, data, activity.content_hash()],
+        )?;
+        self.polled_rowid.fetch_max(conn.last_insert_rowid(), Ordering::Relaxed);
+        drop(conn);
+        
+        self.publish(activity);
+        Ok(())
+    }
+    
+    /// Stores an activity with its data encrypted under `key`.
+    ///
+    /// Each row gets a fresh random nonce. No content hash is kept for
+    /// encrypted rows, so equal data cannot be told apart.
+    pub fn store_activity_encrypted(&self, activity: &ActivityData, key: &[u8; 32]) -> Result<()> {
+        let data = serde_json::to_vec(&encrypt_data(activity, key)?)?;
+        let conn = self.conn.lock().unwrap_or_else(|e| e.into_inner());
+        conn.execute(
+            "INSERT OR REPLACE INTO activity_data (id, timestamp, module, data, content_hash, encrypted)
+             VALUES (?1, ?2, ?3, ?4, NULL, 1)",
+            params![activity.id, timestamp_key(&activity.timestamp), activity.module, data],
+        )?;
+        Ok(())
+    }
+    
+    /// Stores an activity of `module`, encrypted under `key` if the module is sensitive.
+    pub fn store_module_activity(&self, module: &ModuleInfo, activity: &ActivityData, key: &[u8; 32]) -> Result<()> {
+        if module.sensitive {
+            self.store_activity_encrypted(activity, key)
+        } else {
+            self.store_activity(activity)
+        }
+    }
+    
+    /// All encrypted activities, decrypted with `key`, oldest first.
+    ///
+    /// Returns `RaeError::Security` if any of them does not decrypt with `key`.
+    pub fn query_activities_encrypted(&self, key: &[u8; 32]) -> Result<Vec<ActivityData>> {
+        let conn = self.conn.lock().unwrap_or_else(|e| e.into_inner());
+        let mut stmt = conn.prepare_cached(
+            "SELECT id, timestamp, module, data FROM activity_data WHERE encrypted = 1 ORDER BY timestamp",
+        )?;
+        let rows = stmt.query_map([], activity_row)?;
+        
+        rows.collect::<rusqlite::Result<Vec<_>>>()?
+            .into_iter()
+            .map(|activity| decrypt_data(activity?, key))
+            .collect()
+    }
+    
+    /// Stores an activity unless one with the same `content_hash` is already stored.
+    ///
+    /// Returns whether the activity was new.
+    pub fn insert_activity(&self, activity: ActivityData) -> Result<bool> {
+        let data = serde_json::to_vec(&activity.data)?;
+        let conn = self.conn.lock().unwrap_or_else(|e| e.into_inner());
+        let inserted = conn.execute(
+            "INSERT INTO activity_data (id, timestamp, module, data, content_hash)
+             SELECT ?1, ?2, ?3, ?4, ?5
+             WHERE NOT EXISTS (SELECT 1 FROM activity_data WHERE content_hash = ?5)",
+            params![activity.id, timestamp_key(&activity.timestamp), activity.module, data, activity.content_hash()],
+        )? > 0;
+        if inserted {
+            self.polled_rowid.fetch_max(conn.last_insert_rowid(), Ordering::Relaxed);
+        }
+        drop(conn);
+        
+        self.inserted.fetch_add(1, Ordering::Relaxed);
+        if inserted {
+            self.inserted_unique.fetch_add(1, Ordering::Relaxed);
+            self.publish(&activity);
+        }
+        Ok(inserted)
+    }
+    
+    /// Streams activities stored from now on as JSON lines to a named pipe
+    /// created at `fifo_path`, only those of module `filter` if set.
+    ///
+    /// A background task waits for a reader to open the pipe and writes to it
+    /// at the reader's pace; up to `STREAM_BUFFER` activities are held for a
+    /// slow reader. The stream ends when its reader closes the pipe. Must be
+    /// called within a Tokio runtime.
+    #[cfg(unix)]
+    pub fn stream_to_fifo(&self, fifo_path: &Path, filter: Option<&str>) -> Result<()> {
+        use nix::sys::stat::Mode;
+        use std::os::unix::fs::FileTypeExt;
+        
+        let runtime = tokio::runtime::Handle::try_current()
+            .map_err(|e| RaeError::Storage(format!("Streaming activities needs a Tokio runtime: {}", e)))?;
+        match nix::unistd::mkfifo(fifo_path, Mode::S_IRUSR | Mode::S_IWUSR) {
+            Ok(()) => {}
+            Err(nix::errno::Errno::EEXIST) if fs::metadata(fifo_path)?.file_type().is_fifo() => {}
+            Err(e) => {
+                return Err(RaeError::Storage(format!("Failed to create FIFO {}: {}", fifo_path.display(), e)));
+            }
+        }
+        
+        let (sender, receiver) = mpsc::channel(STREAM_BUFFER);
+        self.streams.lock().unwrap_or_else(|e| e.into_inner()).push(ActivityStream {
+            module: filter.map(str::to_string),
+            sender,
+        });
+        runtime.spawn(write_to_fifo(fifo_path.to_path_buf(), receiver));
+        
+        Ok(())
+    }
+    
+    /// Forwards activities stored by other processes since the store was
+    /// opened or last polled to the streams; returns how many were stored.
+    ///
+    /// Meant for processes that stream activities without storing any
+    /// themselves, such as `rae storage stream`. Rows up to this process's
+    /// latest write are skipped, as its own writes are streamed when stored.
+    pub fn poll_writes(&self) -> Result<usize> {
+        let activities = {
+            let conn = self.conn.lock().unwrap_or_else(|e| e.into_inner());
+            let mut stmt = conn.prepare_cached(
+                "SELECT id, timestamp, module, data, rowid FROM activity_data
+                 WHERE rowid > ?1 AND encrypted = 0
+                 ORDER BY rowid",
+            )?;
+            let rows = stmt.query_map(params![self.polled_rowid.load(Ordering::Relaxed)], |row| {
+                Ok((activity_row(row)?, row.get::<_, i64>(4)?))
+            })?;
+            rows.collect::<rusqlite::Result<Vec<_>>>()?
+        };
+        
+        for (activity, rowid) in &activities {
+            self.polled_rowid.store(*rowid, Ordering::Relaxed);
+            match activity {
+                Ok(activity) => self.publish(activity),
+                Err(e) => warn!("Not streaming unreadable activity: {}", e),
+            }
+        }
+        Ok(activities.len())
+    }
+    
+    /// Hands a stored activity to the streams that want it, dropping streams
+    /// whose reader has gone.
+    fn publish(&self, activity: &ActivityData) {
+        let mut streams = self.streams.lock().unwrap_or_else(|e| e.into_inner());
+        streams.retain(|stream| {
+            if stream.module.as_ref().is_some_and(|module| *module != activity.module) {
+                return true;
+            }
+            match stream.sender.try_send(activity.clone()) {
+                Ok(()) => true,
+                Err(mpsc::error::TrySendError::Full(_)) => {
+                    warn!("Activity stream is full, dropping activity {}", activity.id);
+                    true
+                }
+                Err(mpsc::error::TrySendError::Closed(_)) => false,
+            }
+        });
+    }
+    
+    /// How many activities `insert_activity` stored and dropped as duplicates.
+    pub fn dedup_stats(&self) -> DedupStats {
+        DedupStats {
+            total: self.inserted.load(Ordering::Relaxed),
+            unique: self.inserted_unique.load(Ordering::Relaxed),
+        }
+    }
+    
+    /// Loads an activity by ID.
+    pub fn get_activity(&self, id: &str) -> Result<ActivityData> {
+        let conn = self.conn.lock().unwrap_or_else(|e| e.into_inner());
+        conn.query_row(
+            "SELECT id, timestamp, module, data FROM activity_data WHERE id = ?1",
+            params![id],
+            activity_row,
+        )
+        .optional()?
+        .ok_or_else(|| RaeError::not_found("activity", id))?
+    }
+    
+    /// Activities recorded from `since` (inclusive) until `until` (exclusive), oldest first.
+    ///
+    /// With `module` set, only that module's activities are returned.
+    pub fn query_activities(
+        &self,
+        module: Option<&str>,
+        since: DateTime<Utc>,
+        until: DateTime<Utc>,
+    ) -> Result<Vec<ActivityData>> {
+        let conn = self.conn.lock().unwrap_or_else(|e| e.into_inner());
+        let since = timestamp_key(&since);
+        let until = timestamp_key(&until);
+        
+        // Both filters are served by an index range scan
+        let rows = match module {
+            Some(module) => {
+                let mut stmt = conn.prepare_cached(
+                    "SELECT id, timestamp, module, data FROM activity_data
+                     WHERE module = ?1 AND timestamp >= ?2 AND timestamp < ?3
+                     ORDER BY timestamp",
+                )?;
+                let rows = stmt.query_map(params![module, since, until], activity_row)?;
+                rows.collect::<rusqlite::Result<Vec<_>>>()?
+            }
+            None => {
+                let mut stmt = conn.prepare_cached(
+                    "SELECT id, timestamp, module, data FROM activity_data
+                     WHERE timestamp >= ?1 AND timestamp < ?2
+                     ORDER BY timestamp",
+                )?;
+                let rows = stmt.query_map(params![since, until], activity_row)?;
+                rows.collect::<rusqlite::Result<Vec<_>>>()?
+            }
+        };
+        
+        rows.into_iter().collect()
+    }
+    
+    /// Writes the activities recorded from `start` (inclusive) until `end`
+    /// (exclusive) to a Parquet file, oldest first; returns how many were written.
+    ///
+    /// Columns are `timestamp` (UTC microseconds), `module`, `content_hash`
+    /// and `data` (a JSON string).
+    pub fn export_parquet(&self, path: &Path, start: Option<DateTime<Utc>>, end: Option<DateTime<Utc>>) -> Result<u64> {
+        let activities = {
+            let conn = self.conn.lock().unwrap_or_else(|e| e.into_inner());
+            let mut stmt = conn.prepare_cached(
+                "SELECT id, timestamp, module, data FROM activity_data
+                 WHERE (?1 IS NULL OR timestamp >= ?1) AND (?2 IS NULL OR timestamp < ?2)
+                 ORDER BY timestamp",
+            )?;
+            let rows = stmt.query_map(
+                params![start.as_ref().map(timestamp_key), end.as_ref().map(timestamp_key)],
+                activity_row,
+            )?;
+            rows.collect::<rusqlite::Result<Vec<_>>>()?
+                .into_iter()
+                .collect::<Result<Vec<ActivityData>>>()?
+        };
+        
+        let data = activities.iter()
+            .map(|activity| serde_json::to_string(&activity.data))
+            .collect::<serde_json::Result<Vec<_>>>()?;
+        let columns: Vec<ArrayRef> = vec![
+            Arc::new(
+                TimestampMicrosecondArray::from_iter_values(activities.iter().map(|activity| activity.timestamp.timestamp_micros()))
+                    .with_timezone("UTC"),
+            ),
+            Arc::new(StringArray::from_iter_values(activities.iter().map(|activity| activity.module.as_str()))),
+            Arc::new(StringArray::from_iter_values(activities.iter().map(ActivityData::content_hash))),
+            Arc::new(StringArray::from_iter_values(data)),
+        ];
+        
+        let schema = Arc::new(parquet_schema());
+        let batch = RecordBatch::try_new(schema.clone(), columns).map_err(export_error)?;
+        let mut writer = ArrowWriter::try_new(File::create(path)?, schema, None).map_err(export_error)?;
+        writer.write(&batch).map_err(export_error)?;
+        writer.close().map_err(export_error)?;
+        
+        Ok(activities.len() as u64)
+    }
+    
+    /// Stores a digest, replacing any digest with the same ID.
+    pub fn store_digest(&self, digest: &Digest) -> Result<()> {
+        let conn = self.conn.lock().unwrap_or_else(|e| e.into_inner());
+        conn.execute(
+            "INSERT OR REPLACE INTO digests (id, period, start_date, end_date, summary) VALUES (?1, ?2, ?3, ?4, ?5)",
+            params![
+                digest.id,
+                digest.period,
+                timestamp_key(&digest.start_date),
+                timestamp_key(&digest.end_date),
+                digest.summary,
+            ],
+        )?;
+        Ok(())
+    }
+    
+    /// Deletes activities older than `retain_days` days and compacts the database.
+    pub fn vacuum(&self, retain_days: u32) -> Result<VacuumReport> {
+        let started = Instant::now();
+        let cutoff = Utc::now() - Duration::days(retain_days as i64);
+        let conn = self.conn.lock().unwrap_or_else(|e| e.into_inner());
+        let size_before = database_size(&conn)?;
+        
+        let deleted = conn.execute(
+            "DELETE FROM activity_data WHERE timestamp < ?1",
+            params![timestamp_key(&cutoff)],
+        )?;
+        
+        // VACUUM rewrites the database through the WAL, so checkpoint on both
+        // sides of it to fold the pages back into the main file
+        checkpoint(&conn)?;
+        conn.execute_batch("VACUUM")?;
+        checkpoint(&conn)?;
+        
+        Ok(VacuumReport {
+            deleted_records: deleted as u64,
+            freed_bytes: size_before.saturating_sub(database_size(&conn)?),
+            duration_ms: started.elapsed().as_millis() as u64,
+        })
+    }
+}
+
+/// Seals the data of `activity` under `key`.
+fn encrypt_data(activity: &ActivityData, key: &[u8; 32]) -> Result<EncryptedData> {
+    let cipher = Aes256Gcm::new(key.into());
+    let nonce = Aes256Gcm::generate_nonce(&mut OsRng);
+    let plaintext = serde_json::to_vec(&activity.data)?;
+    let ciphertext = cipher
+        .encrypt(&nonce, Payload { msg: &plaintext, aad: activity.id.as_bytes() })
+        .map_err(|_| RaeError::Security(format!("Failed to encrypt activity {}", activity.id)))?;
+    
+    Ok(EncryptedData {
+        nonce: BASE64.encode(nonce),
+        ciphertext: BASE64.encode(ciphertext),
+    })
+}
+
+/// Replaces the sealed data of an encrypted activity with its plaintext.
+fn decrypt_data(mut activity: ActivityData, key: &[u8; 32]) -> Result<ActivityData> {
+    let undecryptable = || RaeError::Security(format!("Failed to decrypt activity {}: wrong key or corrupted data", activity.id));
+    
+    let sealed: EncryptedData = serde_json::from_value(activity.data.take())?;
+    let nonce = BASE64.decode(&sealed.nonce).ok().filter(|nonce| nonce.len() == 12).ok_or_else(undecryptable)?;
+    let ciphertext = BASE64.decode(&sealed.ciphertext).map_err(|_| undecryptable())?;
+    let plaintext = Aes256Gcm::new(key.into())
+        .decrypt(Nonce::from_slice(&nonce), Payload { msg: &ciphertext, aad: activity.id.as_bytes() })
+        .map_err(|_| undecryptable())?;
+    
+    activity.data = serde_json::from_slice(&plaintext)?;
+    Ok(activity)
+}
+
+/// Columns of the files written by `Storage::export_parquet`.
+fn parquet_schema() -> Schema {
+    Schema::new(vec![
+        Field::new("timestamp", DataType::Timestamp(TimeUnit::Microsecond, Some("UTC".into())), false),
+        Field::new("module", DataType::Utf8, false),
+        Field::new("content_hash", DataType::Utf8, false),
+        Field::new("data", DataType::Utf8, false),
+    ])
+}
+
+fn export_error(err: impl std::fmt::Display) -> RaeError {
+    RaeError::Storage(format!("Failed to write Parquet file: {}", err))
+}
+
+/// Copies the WAL into the database file and truncates it.
+fn checkpoint(conn: &Connection) -> Result<()> {
+    conn.query_row("PRAGMA wal_checkpoint(TRUNCATE)", [], |_| Ok(()))?;
+    Ok(())
+}
+
+/// Size of the database in bytes.
+fn database_size(conn: &Connection) -> Result<u64> {
+    let page_count: i64 = conn.query_row("PRAGMA page_count", [], |row| row.get(0))?;
+    let page_size: i64 = conn.query_row("PRAGMA page_size", [], |row| row.get(0))?;
+    Ok((page_count * page_size) as u64)
+}
+
+/// Applies the migrations the database has not seen yet.
+fn migrate(conn: &mut Connection) -> Result<()> {
+    let version = user_version(conn)?;
+    if version > MIGRATIONS.len() {
+        return Err(RaeError::Storage(format!(
+            "Database schema version {} is newer than this agent supports ({})",
+            version,
+            MIGRATIONS.len()
+        )));
+    }
+    
+    for (index, migration) in MIGRATIONS.iter().enumerate().skip(version) {
+        let tx = conn.transaction()?;
+        tx.execute_batch(migration)?;
+        tx.pragma_update(None, "user_version", index + 1)?;
+        tx.commit()?;
+    }
+    
+    Ok(())
+}
+
+fn user_version(conn: &Connection) -> Result<usize> {
+    let version: i64 = conn.query_row("PRAGMA user_version", [], |row| row.get(0))?;
+    Ok(version as usize)
+}
+
+/// Writes `value` as JSON with object keys in sorted order.
+fn canonical_json(value: &Value, out: &mut String) {
+    match value {
+        Value::Object(fields) => {
+            let mut keys: Vec<&String> = fields.keys().collect();
+            keys.sort();
+            out.push('{');
+            for (index, key) in keys.into_iter().enumerate() {
+                if index > 0 {
+                    out.push(',');
+                }
+                out.push_str(&Value::String(key.clone()).to_string());
+                out.push(':');
+                canonical_json(&fields[key], out);
+            }
+            out.push('}');
+        }
+        Value::Array(items) => {
+            out.push('[');
+            for (index, item) in items.iter().enumerate() {
+                if index > 0 {
+                    out.push(',');
+                }
+                canonical_json(item, out);
+            }
+            out.push(']');
+        }
+        value => out.push_str(&value.to_string()),
+    }
+}
+
+/// Formats a timestamp so that string order matches time order.
+fn timestamp_key(timestamp: &DateTime<Utc>) -> String {
+    timestamp.to_rfc3339_opts(SecondsFormat::Nanos, true)
+}
+
+/// Reads an activity row; decoding errors are returned in the inner result.
+fn activity_row(row: &rusqlite::Row<'_>) -> rusqlite::Result<Result<ActivityData>> {
+    let id: String = row.get(0)?;
+    let timestamp: String = row.get(1)?;
+    let module: String = row.get(2)?;
+    let data: Vec<u8> = row.get(3)?;
+    
+    Ok(decode_activity(id, &timestamp, module, &data))
+}
+
+/// Reads an activity row followed by its `encrypted` flag.
+fn flagged_activity_row(row: &rusqlite::Row<'_>) -> rusqlite::Result<(Result<ActivityData>, bool)> {
+    Ok((activity_row(row)?, row.get(4)?))
+}
+
+fn decode_activity(id: String, timestamp: &str, module: String, data: &[u8]) -> Result<ActivityData> {
+    let timestamp = DateTime::parse_from_rfc3339(timestamp)
+        .map_err(|e| RaeError::Storage(format!("Invalid timestamp for activity {}: {}", id, e)))?
+        .with_timezone(&Utc);
+    
+    Ok(ActivityData {
+        data: serde_json::from_slice(data)?,
+        id,
+        timestamp,
+        module,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{Duration, TimeZone};
+    use serde_json::json;
+    use tempfile::tempdir;
+    
+    #[test]
+    fn test_query_activities_by_range_and_module() {
+        let temp_dir = tempdir().unwrap();
+        let storage = Storage::new(&temp_dir.path().join("data").join("rae.db")).unwrap();
+        let start = Utc.with_ymd_and_hms(2026, 3, 1, 9, 0, 0).unwrap();
+        
+        let activities: Vec<ActivityData> = [("git", 0), ("calendar", 1), ("git", 2), ("git", 5)]
+            .into_iter()
+            .map(|(module, hours)| {
+                ActivityData::new(module.to_string(), json!({ "hour": hours }))
+                    .with_timestamp(start + Duration::hours(hours))
+            })
+            .collect();
+        for activity in &activities {
+            storage.store_activity(activity).unwrap();
+        }
+        
+        let until = start + Duration::hours(5);
+        assert_eq!(
+            storage.query_activities(None, start, until).unwrap(),
+            activities[..3].to_vec()
+        );
+        assert_eq!(
+            storage.query_activities(Some("git"), start + Duration::hours(1), start + Duration::hours(6)).unwrap(),
+            vec![activities[2].clone(), activities[3].clone()]
+        );
+        assert!(storage.query_activities(Some("email"), start, until).unwrap().is_empty());
+        
+        assert_eq!(storage.get_activity(&activities[1].id).unwrap(), activities[1]);
+        assert!(storage.get_activity("missing").unwrap_err().is_not_found());
+    }
+    
+    #[test]
+    fn test_vacuum_deletes_expired_activities() {
+        let temp_dir = tempdir().unwrap();
+        let storage = Storage::new(&temp_dir.path().join("rae.db")).unwrap();
+        let old = Utc::now() - Duration::days(400);
+        
+        for i in 0..100 {
+            let activity = ActivityData::new("git".to_string(), json!({ "commit": i, "message": "x".repeat(512) }))
+                .with_timestamp(old + Duration::minutes(i));
+            storage.store_activity(&activity).unwrap();
+        }
+        let recent = ActivityData::new("git".to_string(), json!({}));
+        storage.store_activity(&recent).unwrap();
+        
+        let report = storage.vacuum(30).unwrap();
+        assert_eq!(report.deleted_records, 100);
+        assert!(report.freed_bytes > 0);
+        
+        let cutoff = Utc::now() - Duration::days(30);
+        assert!(storage.query_activities(None, old - Duration::days(1), cutoff).unwrap().is_empty());
+        assert_eq!(storage.get_activity(&recent.id).unwrap(), recent);
+    }
+    
+    #[test]
+    fn test_insert_activity_drops_duplicates() {
+        let temp_dir = tempdir().unwrap();
+        let storage = Storage::new(&temp_dir.path().join("rae.db")).unwrap();
+        let at = Utc.with_ymd_and_hms(2026, 3, 1, 9, 15, 10).unwrap();
+        
+        let activity = ActivityData::new("files".to_string(), json!({ "path": "/tmp/a", "event": "modified" }))
+            .with_timestamp(at);
+        assert!(storage.insert_activity(activity.clone()).unwrap());
+        assert!(!storage.insert_activity(activity.clone()).unwrap());
+        
+        // Same event within the minute, with its keys in another order and a new ID
+        let repeated = ActivityData::new("files".to_string(), json!({ "event": "modified", "path": "/tmp/a" }))
+            .with_timestamp(at + Duration::seconds(30));
+        assert_eq!(repeated.content_hash(), activity.content_hash());
+        assert!(!storage.insert_activity(repeated).unwrap());
+        
+        // A new minute or another module is a new record
+        let next_minute = ActivityData::new("files".to_string(), activity.data.clone())
+            .with_timestamp(at + Duration::minutes(1));
+        assert!(storage.insert_activity(next_minute).unwrap());
+        let other_module = ActivityData::new("git".to_string(), activity.data.clone()).with_timestamp(at);
+        assert!(storage.insert_activity(other_module).unwrap());
+        
+        let stored = storage.query_activities(Some("files"), at - Duration::hours(1), at + Duration::hours(1)).unwrap();
+        assert_eq!(stored.len(), 2);
+        assert_eq!(stored[0], activity);
+        assert_eq!(storage.dedup_stats(), DedupStats { total: 5, unique: 3 });
+        assert_eq!(storage.dedup_stats().duplicates(), 2);
+    }
+    
+    #[test]
+    fn test_export_parquet_reads_back() {
+        use arrow_array::Array;
+        use parquet::arrow::arrow_reader::ParquetRecordBatchReaderBuilder;
+        
+        let temp_dir = tempdir().unwrap();
+        let storage = Storage::new(&temp_dir.path().join("rae.db")).unwrap();
+        let start = Utc.with_ymd_and_hms(2026, 3, 1, 9, 0, 0).unwrap();
+        let activities: Vec<ActivityData> = (0..5)
+            .map(|hours| {
+                ActivityData::new("git".to_string(), json!({ "commit": hours, "message": "Grüße" }))
+                    .with_timestamp(start + Duration::hours(hours))
+            })
+            .collect();
+        for activity in &activities {
+            storage.store_activity(activity).unwrap();
+        }
+        
+        let path = temp_dir.path().join("activity.parquet");
+        assert_eq!(storage.export_parquet(&path, None, None).unwrap(), 5);
+        
+        let reader = ParquetRecordBatchReaderBuilder::try_new(File::open(&path).unwrap()).unwrap();
+        assert_eq!(reader.metadata().file_metadata().num_rows(), 5);
+        assert_eq!(reader.schema().as_ref(), &parquet_schema());
+        
+        let batches: Vec<RecordBatch> = reader.build().unwrap().collect::<std::result::Result<_, _>>().unwrap();
+        let batch = &batches[0];
+        let timestamps = batch.column(0).as_any().downcast_ref::<TimestampMicrosecondArray>().unwrap();
+        assert_eq!(timestamps.value(1), activities[1].timestamp.timestamp_micros());
+        assert_eq!(timestamps.timezone(), Some("UTC"));
+        let hashes = batch.column(2).as_any().downcast_ref::<StringArray>().unwrap();
+        assert_eq!(hashes.value(0), activities[0].content_hash());
+        let data = batch.column(3).as_any().downcast_ref::<StringArray>().unwrap();
+        assert_eq!(serde_json::from_str::<Value>(data.value(4)).unwrap(), activities[4].data);
+        assert_eq!(data.null_count(), 0);
+        
+        // Bounded exports include the start and exclude the end
+        let bounded = temp_dir.path().join("bounded.parquet");
+        let exported = storage.export_parquet(&bounded, Some(start + Duration::hours(1)), Some(start + Duration::hours(3))).unwrap();
+        assert_eq!(exported, 2);
+        let reader = ParquetRecordBatchReaderBuilder::try_new(File::open(&bounded).unwrap()).unwrap();
+        assert_eq!(reader.metadata().file_metadata().num_rows(), 2);
+    }
+    
+    #[test]
+    fn test_encrypted_activity_round_trip() {
+        let temp_dir = tempdir().unwrap();
+        let path = temp_dir.path().join("rae.db");
+        let storage = Storage::new(&path).unwrap();
+        let key = [7u8; 32];
+        
+        let secret = ActivityData::new("health".to_string(), json!({ "heart_rate": 72, "note": "résumé" }));
+        storage.store_activity_encrypted(&secret, &key).unwrap();
+        storage.store_activity(&ActivityData::new("git".to_string(), json!({ "commits": 1 }))).unwrap();
+        
+        assert_eq!(storage.query_activities_encrypted(&key).unwrap(), vec![secret.clone()]);
+        
+        // The plaintext never reaches the database
+        let conn = Connection::open(&path).unwrap();
+        let stored: Vec<u8> = conn.query_row(
+            "SELECT data FROM activity_data WHERE id = ?1", params![secret.id], |row| row.get(0),
+        ).unwrap();
+        let stored: Value = serde_json::from_slice(&stored).unwrap();
+        assert!(stored["nonce"].is_string() && stored["ciphertext"].is_string());
+        assert!(!String::from_utf8_lossy(&serde_json::to_vec(&stored).unwrap()).contains("heart_rate"));
+        
+        // Sealing the same data twice uses different nonces
+        let again = encrypt_data(&secret, &key).unwrap();
+        assert_ne!(again.nonce, stored["nonce"].as_str().unwrap());
+    }
+    
+    #[test]
+    fn test_encrypted_activity_rejects_wrong_key() {
+        let temp_dir = tempdir().unwrap();
+        let storage = Storage::new(&temp_dir.path().join("rae.db")).unwrap();
+        let key = [7u8; 32];
+        
+        let module = ModuleInfo {
+            name: "health".to_string(),
+            version: "1.0.0".to_string(),
+            description: None,
+            permissions: Vec::new(),
+            input_schema: None,
+            sensitive: true,
+        };
+        storage.store_module_activity(&module, &ActivityData::new("health".to_string(), json!({ "steps": 9000 })), &key).unwrap();
+        
+        let err = storage.query_activities_encrypted(&[8u8; 32]).unwrap_err();
+        assert!(matches!(err, RaeError::Security(ref msg) if msg.contains("wrong key")));
+        assert_eq!(storage.query_activities_encrypted(&key).unwrap().len(), 1);
+    }
+    
+    #[test]
+    fn test_migrate_to_encrypted_store() {
+        let temp_dir = tempdir().unwrap();
+        let path = temp_dir.path().join("rae.db");
+        let start = Utc.with_ymd_and_hms(2026, 3, 1, 9, 0, 0).unwrap();
+        
+        let storage = Storage::new(&path).unwrap();
+        let activities: Vec<ActivityData> = (0..3)
+            .map(|hours| ActivityData::new("git".to_string(), json!({ "commit": hours })).with_timestamp(start + Duration::hours(hours)))
+            .collect();
+        for activity in &activities {
+            storage.store_activity(activity).unwrap();
+        }
+        drop(storage);
+        
+        assert_eq!(migrate_to_encrypted(&path, "correct horse").unwrap(), 3);
+        assert!(temp_dir.path().join(SALT_FILE).exists());
+        assert!(!temp_dir.path().join("rae.db.encrypting").exists());
+        // Already encrypted rows are left alone
+        assert_eq!(migrate_to_encrypted(&path, "correct horse").unwrap(), 0);
+        
+        let encrypted = Storage::new(&path).unwrap().with_encryption("correct horse").unwrap();
+        let until = start + Duration::days(1);
+        assert_eq!(encrypted.query_activities(None, start, until).unwrap(), activities);
+        let key = derive_key("correct horse", &fs::read(temp_dir.path().join(SALT_FILE)).unwrap()).unwrap();
+        assert_eq!(encrypted.storage().query_activities_encrypted(&key).unwrap().len(), 3);
+        
+        // New writes are encrypted too, and the plaintext is gone from the database
+        let added = ActivityData::new("notes".to_string(), json!({ "title": "secret plans" })).with_timestamp(start);
+        encrypted.store_activity(&added).unwrap();
+        assert_eq!(encrypted.get_activity(&added.id).unwrap(), added);
+        assert!(encrypted.storage().query_activities(Some("notes"), start, until).unwrap()[0].data.get("ciphertext").is_some());
+        drop(encrypted);
+        
+        let wrong = Storage::new(&path).unwrap().with_encryption("wrong passphrase").unwrap();
+        assert!(matches!(wrong.query_activities(None, start, until), Err(RaeError::Security(_))));
+    }
+    
+    #[test]
+    fn test_failed_migration_keeps_original_store() {
+        let temp_dir = tempdir().unwrap();
+        let path = temp_dir.path().join("rae.db");
+        let storage = Storage::new(&path).unwrap();
+        let activity = ActivityData::new("git".to_string(), json!({ "commit": 1 }));
+        storage.store_activity(&activity).unwrap();
+        drop(storage);
+        
+        // A directory in the way of the copy makes the migration fail
+        fs::create_dir(temp_dir.path().join("rae.db.encrypting")).unwrap();
+        assert!(migrate_to_encrypted(&path, "correct horse").is_err());
+        
+        let storage = Storage::new(&path).unwrap();
+        assert_eq!(storage.get_activity(&activity.id).unwrap(), activity);
+    }
+    
+    #[test]
+    fn test_salt_is_created_once() {
+        let temp_dir = tempdir().unwrap();
+        let path = temp_dir.path().join(SALT_FILE);
+        
+        let salt = load_or_create_salt(&path).unwrap();
+        assert_eq!(salt.len(), 16);
+        assert_eq!(load_or_create_salt(&path).unwrap(), salt);
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            assert_eq!(fs::metadata(&path).unwrap().permissions().mode() & 0o777, 0o600);
+        }
+        
+        assert_eq!(derive_key("passphrase", &salt).unwrap(), derive_key("passphrase", &salt).unwrap());
+        assert_ne!(derive_key("passphrase", &salt).unwrap(), derive_key("passphrase", &[0u8; 16]).unwrap());
+    }
+    
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_stream_to_fifo() {
+        use std::io::{BufRead, BufReader};
+        
+        let temp_dir = tempdir().unwrap();
+        let path = temp_dir.path().join("rae.db");
+        let fifo_path = temp_dir.path().join("activity.fifo");
+        let storage = Storage::new(&path).unwrap();
+        storage.stream_to_fifo(&fifo_path, Some("git")).unwrap();
+        
+        // A detached thread, so a failed test does not wait on a reader blocked in `open`
+        let reader_path = fifo_path.clone();
+        let (lines_sender, lines) = tokio::sync::oneshot::channel();
+        std::thread::spawn(move || {
+            let fifo = File::open(reader_path).unwrap();
+            let _ = lines_sender.send(BufReader::new(fifo).lines().take(3).collect::<std::io::Result<Vec<String>>>());
+        });
+        
+        let first = ActivityData::new("git".to_string(), json!({ "commit": "abc" }));
+        storage.store_activity(&first).unwrap();
+        storage.store_activity(&ActivityData::new("calendar".to_string(), json!({}))).unwrap();
+        let second = ActivityData::new("git".to_string(), json!({ "commit": "def" }));
+        assert!(storage.insert_activity(second.clone()).unwrap());
+        
+        // Writes from another process reach the stream when polled
+        assert_eq!(storage.poll_writes().unwrap(), 0);
+        let other = Storage::new(&path).unwrap();
+        let third = ActivityData::new("git".to_string(), json!({ "commit": "0123" }));
+        other.store_activity(&third).unwrap();
+        assert_eq!(storage.poll_writes().unwrap(), 1);
+        assert_eq!(storage.poll_writes().unwrap(), 0);
+        
+        let lines = tokio::time::timeout(std::time::Duration::from_secs(5), lines).await.unwrap().unwrap().unwrap();
+        let streamed: Vec<ActivityData> = lines.iter().map(|line| serde_json::from_str(line).unwrap()).collect();
+        assert_eq!(streamed, vec![first, second, third]);
+        
+        // An existing file that is not a FIFO is refused
+        assert!(matches!(storage.stream_to_fifo(&path, None), Err(RaeError::Storage(_))));
+    }
+    
+    #[test]
+    fn test_migrations_run_once_and_wal_is_enabled() {
+        let temp_dir = tempdir().unwrap();
+        let path = temp_dir.path().join("rae.db");
+        
+        let storage = Storage::new(&path).unwrap();
+        assert_eq!(storage.schema_version().unwrap(), MIGRATIONS.len());
+        let activity = ActivityData::new("git".to_string(), json!({ "commits": 3 }));
+        storage.store_activity(&activity).unwrap();
+        drop(storage);
+        
+        // Reopening keeps the data and does not re-run migrations
+        let storage = Storage::new(&path).unwrap();
+        assert_eq!(storage.get_activity(&activity.id).unwrap(), activity);
+        
+        let conn = Connection::open(&path).unwrap();
+        let mode: String = conn.query_row("PRAGMA journal_mode", [], |row| row.get(0)).unwrap();
+        assert_eq!(mode, "wal");
+        
+        // A database from a newer agent is refused
+        conn.pragma_update(None, "user_version", MIGRATIONS.len() + 1).unwrap();
+        assert!(matches!(Storage::new(&path), Err(RaeError::Storage(_))));
+    }
+}
@@ -0,0 +1,220 @@
+//! First-time setup for the Rae agent.
+//!
+//! Creates the configuration and summary directories and writes a default
+//! `~/.rae/rae.toml`, optionally prompting for the main settings.
+
+use crate::config::{Config, PrivacyLevel, LOG_LEVELS};
+use crate::error::{RaeError, Result};
+use dialoguer::{Input, Select};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Options for `rae-agent init`.
+#[derive(Debug, Clone)]
+pub struct InitOptions {
+    /// Home directory the `.rae` and `Documents/rae` directories are created under
+    pub home_dir: PathBuf,
+    /// Data directory to store in the configuration
+    pub data_dir: Option<String>,
+    /// Privacy level to store in the configuration
+    pub privacy: Option<String>,
+    /// Whether to prompt for values that were not given
+    pub interactive: bool,
+}
+
+impl InitOptions {
+    /// Creates options rooted at the user's home directory.
+    pub fn new() -> Result<Self> {
+        let home_dir = dirs::home_dir()
+            .ok_or_else(|| RaeError::Config("Could not determine home directory".to_string()))?;
+        
+        Ok(InitOptions {
+            home_dir,
+            data_dir: None,
+            privacy: None,
+            interactive: true,
+        })
+    }
+}
+
+/// Result of a completed setup.
+#[derive(Debug, Clone)]
+pub struct InitSummary {
+    /// Path of the configuration file
+    pub config_path: PathBuf,
+    /// Whether the configuration file was written (false if it already existed)
+    pub config_written: bool,
+    /// Directory holding the daily summaries
+    pub summary_dir: PathBuf,
+    /// Data directory from the configuration, with `~` expanded
+    pub data_dir: PathBuf,
+    /// Configuration in effect after setup
+    pub config: Config,
+}
+
+/// Runs first-time setup.
+///
+/// An existing `rae.toml` is left untouched; its values are used instead of
+/// prompting.
+pub fn run_init(options: &InitOptions) -> Result<InitSummary> {
+    let rae_dir = options.home_dir.join(".rae");
+    let summary_dir = options.home_dir.join("Documents").join("rae");
+    let config_path = rae_dir.join("rae.toml");
+    
+    fs::create_dir_all(&rae_dir)?;
+    fs::create_dir_all(&summary_dir)?;
+    
+    let (config, config_written) = if config_path.exists() {
+        let config = Config::load_from_file(&config_path)?;
+        config.validate()?;
+        (config, false)
+    } else {
+        let config = build_config(options)?;
+        config.validate()?;
+        config.save_to_file(&config_path)?;
+        (config, true)
+    };
+    
+    let data_dir = expand_home(&config.data_dir, &options.home_dir);
+    fs::create_dir_all(&data_dir)?;
+    
+    Ok(InitSummary {
+        config_path,
+        config_written,
+        summary_dir,
+        data_dir,
+        config,
+    })
+}
+
+/// Builds the initial configuration from the options, prompting if interactive.
+fn build_config(options: &InitOptions) -> Result<Config> {
+    let mut config = Config::default();
+    
+    if let Some(privacy) = &options.privacy {
+        config.privacy_level = privacy.parse().map_err(RaeError::Config)?;
+    }
+    
+    if let Some(data_dir) = &options.data_dir {
+        config.data_dir = data_dir.clone();
+    }
+    
+    if !options.interactive {
+        return Ok(config);
+    }
+    
+    if options.data_dir.is_none() {
+        config.data_dir = Input::new()
+            .with_prompt("Data directory")
+            .default(config.data_dir.clone())
+            .interact_text()
+            .map_err(prompt_error)?;
+    }
+    
+    if options.privacy.is_none() {
+        let levels = [PrivacyLevel::Strict, PrivacyLevel::Standard, PrivacyLevel::Open];
+        let selection = Select::new()
+            .with_prompt("Privacy level")
+            .items(&[
+                "strict - no external communication",
+                "standard - minimal external communication",
+                "open - full external communication",
+            ])
+            .default(0)
+            .interact()
+            .map_err(prompt_error)?;
+        config.privacy_level = levels[selection].clone();
+    }
+    
+    let selection = Select::new()
+        .with_prompt("Log level")
+        .items(&LOG_LEVELS)
+        .default(2)
+        .interact()
+        .map_err(prompt_error)?;
+    config.log_level = LOG_LEVELS[selection].to_string();
+    
+    Ok(config)
+}
+
+/// Expands a leading `~` against the given home directory.
+fn expand_home(path: &str, home_dir: &Path) -> PathBuf {
+    match path.strip_prefix("~") {
+        Some(rest) => home_dir.join(rest.trim_start_matches('/')),
+        None => PathBuf::from(path),
+    }
+}
+
+/// Converts a failed prompt into a configuration error.
+fn prompt_error(err: dialoguer::Error) -> RaeError {
+    RaeError::Config(format!("Prompt failed: {}", err))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+    
+    fn non_interactive(home_dir: &Path) -> InitOptions {
+        InitOptions {
+            home_dir: home_dir.to_path_buf(),
+            data_dir: None,
+            privacy: None,
+            interactive: false,
+        }
+    }
+    
+    #[test]
+    fn test_non_interactive_creates_files() {
+        let home = tempdir().unwrap();
+        
+        let summary = run_init(&non_interactive(home.path())).unwrap();
+        
+        assert!(summary.config_written);
+        assert!(home.path().join(".rae").is_dir());
+        assert!(home.path().join(".rae").join("rae.toml").is_file());
+        assert!(home.path().join("Documents").join("rae").is_dir());
+        assert_eq!(summary.data_dir, home.path().join(".rae"));
+        
+        let content = fs::read_to_string(&summary.config_path).unwrap();
+        let config: Config = toml::from_str(&content).unwrap();
+        assert_eq!(config.log_level, "info");
+        assert!(matches!(config.privacy_level, PrivacyLevel::Strict));
+    }
+    
+    #[test]
+    fn test_non_interactive_uses_given_values() {
+        let home = tempdir().unwrap();
+        let mut options = non_interactive(home.path());
+        options.data_dir = Some("~/rae-data".to_string());
+        options.privacy = Some("standard".to_string());
+        
+        let summary = run_init(&options).unwrap();
+        
+        assert!(home.path().join("rae-data").is_dir());
+        assert_eq!(summary.config.data_dir, "~/rae-data");
+        assert!(matches!(summary.config.privacy_level, PrivacyLevel::Standard));
+    }
+    
+    #[test]
+    fn test_existing_config_is_kept() {
+        let home = tempdir().unwrap();
+        run_init(&non_interactive(home.path())).unwrap();
+        
+        let mut options = non_interactive(home.path());
+        options.privacy = Some("open".to_string());
+        let summary = run_init(&options).unwrap();
+        
+        assert!(!summary.config_written);
+        assert!(matches!(summary.config.privacy_level, PrivacyLevel::Strict));
+    }
+    
+    #[test]
+    fn test_invalid_privacy_level() {
+        let home = tempdir().unwrap();
+        let mut options = non_interactive(home.path());
+        options.privacy = Some("public".to_string());
+        
+        assert!(matches!(run_init(&options), Err(RaeError::Config(_))));
+    }
+}
@@ -8,33 +8,424 @@ pub const VERSION: &str = env!("CARGO_PKG_VERSION");
 
 /// Default configuration values
 pub mod config {
+    use crate::error::RaeError;
     use serde::{Deserialize, Serialize};
+    use std::collections::HashMap;
+    use std::path::Path;
 
-    #[derive(Debug, Clone, Serialize, Deserialize)]
+    pub mod cli;
+
+    /// Accepted values of `log_level`
+    pub const LOG_LEVELS: [&str; 5] = ["error", "warn", "info", "debug", "trace"];
+
+    /// Settings missing from a configuration file take their default values
+    #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+    #[serde(default)]
     pub struct Config {
         pub data_dir: String,
         pub log_level: String,
+        /// Format log lines are written in
+        #[serde(default)]
+        pub log_format: LogFormat,
         pub privacy_level: PrivacyLevel,
         pub max_modules: usize,
+        /// Named sets of environment variables that jobs can inherit
+        #[serde(default)]
+        pub env_groups: HashMap<String, HashMap<String, String>>,
+        /// Format scheduled jobs are stored in
+        #[serde(default)]
+        pub job_storage_format: crate::scheduler::persistence::StorageFormat,
+        /// Store scheduled jobs in a single archive file, overriding `job_storage_format`
+        #[serde(default)]
+        pub compact: bool,
+        /// Number of results kept in each job's history
+        #[serde(default = "default_job_history_limit")]
+        pub job_history_limit: usize,
+        /// Maximum number of scheduled jobs running at the same time
+        #[serde(default = "default_max_concurrent_jobs")]
+        pub max_concurrent_jobs: usize,
+        /// Hosts that outbound requests may reach under `PrivacyLevel::Standard`
+        #[serde(default)]
+        pub allowed_hosts: Vec<String>,
+        /// Address the local REST API listens on
+        #[serde(default = "default_api_bind_address")]
+        pub api_bind_address: String,
+        /// Share of failed runs (0.0 to 1.0) above which a job raises an alert
+        #[serde(default = "default_alert_failure_rate")]
+        pub alert_failure_rate: f64,
+        /// Minutes a job may run before it is reported as stuck
+        #[serde(default = "default_alert_stuck_minutes")]
+        pub alert_stuck_minutes: u64,
+        /// URL alerts are posted to; only used under `PrivacyLevel::Open`
+        #[serde(default)]
+        pub alert_webhook_url: Option<String>,
+        /// Days of activity data kept when the store is vacuumed
+        #[serde(default = "default_activity_retention_days")]
+        pub activity_retention_days: u32,
+        /// Cron schedule of the built-in job that vacuums the store
+        #[serde(default = "default_vacuum_schedule")]
+        pub vacuum_schedule: String,
+        /// Keywords per topic; digest Markdown groups activities by these topics when set
+        #[serde(default)]
+        pub topic_keywords: HashMap<String, Vec<String>>,
+        /// Encrypt activity data at rest; the store is then opened with
+        /// `Storage::with_encryption` and a passphrase
+        #[serde(default)]
+        pub encrypt_at_rest: bool,
+    }
+
+    fn default_job_history_limit() -> usize {
+        crate::scheduler::results::DEFAULT_MAX_HISTORY
+    }
+
+    fn default_max_concurrent_jobs() -> usize {
+        crate::scheduler::executor::DEFAULT_MAX_CONCURRENT_JOBS
+    }
+
+    fn default_api_bind_address() -> String {
+        crate::api::rest::DEFAULT_BIND_ADDRESS.to_string()
+    }
+
+    fn default_alert_failure_rate() -> f64 {
+        crate::scheduler::alert::DEFAULT_FAILURE_RATE_THRESHOLD
+    }
+
+    fn default_alert_stuck_minutes() -> u64 {
+        crate::scheduler::alert::DEFAULT_STUCK_JOB_MINUTES
+    }
+
+    fn default_activity_retention_days() -> u32 {
+        crate::core::storage::DEFAULT_RETENTION_DAYS
+    }
+
+    fn default_vacuum_schedule() -> String {
+        crate::core::storage::DEFAULT_VACUUM_SCHEDULE.to_string()
+    }
+
+    /// Format log lines are written in
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+    pub enum LogFormat {
+        /// Human-readable lines
+        #[default]
+        Text,
+        /// One JSON object per line, with span fields, for tools like `jq`
+        Json,
     }
 
-    #[derive(Debug, Clone, Serialize, Deserialize)]
+    #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
     pub enum PrivacyLevel {
         Strict,    // No external communication
         Standard,  // Minimal external communication
         Open,      // Full external communication (user consent required)
     }
 
+    impl std::str::FromStr for PrivacyLevel {
+        type Err = String;
+
+        fn from_str(s: &str) -> Result<Self, Self::Err> {
+            match s.to_lowercase().as_str() {
+                "strict" => Ok(PrivacyLevel::Strict),
+                "standard" => Ok(PrivacyLevel::Standard),
+                "open" => Ok(PrivacyLevel::Open),
+                _ => Err(format!("Unknown privacy level: {}", s)),
+            }
+        }
+    }
+
     impl Default for Config {
         fn default() -> Self {
             Self {
                 data_dir: "~/.rae".to_string(),
                 log_level: "info".to_string(),
+                log_format: LogFormat::default(),
                 privacy_level: PrivacyLevel::Strict,
                 max_modules: 10,
+                env_groups: HashMap::new(),
+                job_storage_format: Default::default(),
+                compact: false,
+                job_history_limit: default_job_history_limit(),
+                max_concurrent_jobs: default_max_concurrent_jobs(),
+                allowed_hosts: Vec::new(),
+                api_bind_address: default_api_bind_address(),
+                alert_failure_rate: default_alert_failure_rate(),
+                alert_stuck_minutes: default_alert_stuck_minutes(),
+                alert_webhook_url: None,
+                activity_retention_days: default_activity_retention_days(),
+                vacuum_schedule: default_vacuum_schedule(),
+                topic_keywords: HashMap::new(),
+                encrypt_at_rest: false,
             }
         }
     }
+
+    impl Config {
+        /// Reads a TOML configuration file, using the defaults if it does not exist
+        pub fn load_from_file(path: &Path) -> crate::error::Result<Config> {
+            if !path.exists() {
+                return Ok(Config::default());
+            }
+
+            let content = std::fs::read_to_string(path)?;
+            toml::from_str(&content)
+                .map_err(|e| RaeError::Config(format!("Invalid {}: {}", path.display(), e)))
+        }
+
+        /// Writes the configuration as TOML
+        ///
+        /// The file is written next to `path` and renamed over it, so readers
+        /// never see a partially written configuration.
+        pub fn save_to_file(&self, path: &Path) -> crate::error::Result<()> {
+            let content = toml::to_string_pretty(self)
+                .map_err(|e| RaeError::Config(format!("Failed to serialize configuration: {}", e)))?;
+
+            let temp_path = path.with_extension("toml.tmp");
+            if let Err(e) = std::fs::write(&temp_path, format!("# Rae Configuration\n\n{}", content)) {
+                let _ = std::fs::remove_file(&temp_path);
+                return Err(e.into());
+            }
+            std::fs::rename(&temp_path, path)?;
+
+            Ok(())
+        }
+
+        /// Checks the invariants a loaded configuration must satisfy
+        ///
+        /// Every violation is reported in a single `RaeError::Config`.
+        pub fn validate(&self) -> crate::error::Result<()> {
+            let mut problems = Vec::new();
+
+            if self.max_modules < 1 {
+                problems.push("max_modules must be at least 1".to_string());
+            }
+            if !LOG_LEVELS.contains(&self.log_level.as_str()) {
+                problems.push(format!(
+                    "log_level must be one of {}, got {:?}", LOG_LEVELS.join(", "), self.log_level
+                ));
+            }
+            if !(0.0..=1.0).contains(&self.alert_failure_rate) {
+                problems.push(format!(
+                    "alert_failure_rate must be between 0.0 and 1.0, got {}", self.alert_failure_rate
+                ));
+            }
+            if self.alert_stuck_minutes < 1 {
+                problems.push("alert_stuck_minutes must be at least 1".to_string());
+            }
+            if self.activity_retention_days < 1 {
+                problems.push("activity_retention_days must be at least 1".to_string());
+            }
+            if let Err(problem) = self.check_data_dir() {
+                problems.push(problem);
+            }
+
+            if problems.is_empty() {
+                Ok(())
+            } else {
+                Err(RaeError::Config(problems.join("; ")))
+            }
+        }
+
+        /// Checks that `data_dir` can be expanded to a path
+        fn check_data_dir(&self) -> std::result::Result<(), String> {
+            if self.data_dir.trim().is_empty() {
+                return Err("data_dir must not be empty".to_string());
+            }
+
+            match self.data_dir.strip_prefix("~") {
+                Some(rest) if !rest.is_empty() && !rest.starts_with('/') => Err(format!(
+                    "data_dir {:?} cannot be expanded: only ~ and ~/ refer to the home directory", self.data_dir
+                )),
+                Some(_) if dirs::home_dir().is_none() => Err(format!(
+                    "data_dir {:?} cannot be expanded: home directory is unknown", self.data_dir
+                )),
+                _ => Ok(()),
+            }
+        }
+
+        /// Layers `overlay` (e.g. a per-project configuration) on top of `base`
+        ///
+        /// Fields of the overlay that differ from the defaults win. Environment
+        /// groups and topic keywords are merged by name and allowed hosts are combined.
+        pub fn merge(base: Config, overlay: Config) -> Config {
+            let defaults = Config::default();
+            let mut merged = base;
+
+            if overlay.data_dir != defaults.data_dir {
+                merged.data_dir = overlay.data_dir;
+            }
+            if overlay.log_level != defaults.log_level {
+                merged.log_level = overlay.log_level;
+            }
+            if overlay.log_format != defaults.log_format {
+                merged.log_format = overlay.log_format;
+            }
+            if overlay.privacy_level != defaults.privacy_level {
+                merged.privacy_level = overlay.privacy_level;
+            }
+            if overlay.max_modules != defaults.max_modules {
+                merged.max_modules = overlay.max_modules;
+            }
+            if overlay.job_storage_format != defaults.job_storage_format {
+                merged.job_storage_format = overlay.job_storage_format;
+            }
+            if overlay.compact != defaults.compact {
+                merged.compact = overlay.compact;
+            }
+            if overlay.job_history_limit != defaults.job_history_limit {
+                merged.job_history_limit = overlay.job_history_limit;
+            }
+            if overlay.max_concurrent_jobs != defaults.max_concurrent_jobs {
+                merged.max_concurrent_jobs = overlay.max_concurrent_jobs;
+            }
+            if overlay.api_bind_address != defaults.api_bind_address {
+                merged.api_bind_address = overlay.api_bind_address;
+            }
+            if overlay.alert_failure_rate != defaults.alert_failure_rate {
+                merged.alert_failure_rate = overlay.alert_failure_rate;
+            }
+            if overlay.alert_stuck_minutes != defaults.alert_stuck_minutes {
+                merged.alert_stuck_minutes = overlay.alert_stuck_minutes;
+            }
+            if overlay.alert_webhook_url.is_some() {
+                merged.alert_webhook_url = overlay.alert_webhook_url;
+            }
+            if overlay.activity_retention_days != defaults.activity_retention_days {
+                merged.activity_retention_days = overlay.activity_retention_days;
+            }
+            if overlay.vacuum_schedule != defaults.vacuum_schedule {
+                merged.vacuum_schedule = overlay.vacuum_schedule;
+            }
+            if overlay.encrypt_at_rest != defaults.encrypt_at_rest {
+                merged.encrypt_at_rest = overlay.encrypt_at_rest;
+            }
+
+            merged.env_groups.extend(overlay.env_groups);
+            merged.topic_keywords.extend(overlay.topic_keywords);
+            for host in &overlay.allowed_hosts {
+                merged.add_allowed_host(host);
+            }
+
+            merged
+        }
+
+        /// Defines (or replaces) a named environment group
+        pub fn define_env_group(&mut self, name: &str, vars: HashMap<String, String>) {
+            self.env_groups.insert(name.to_string(), vars);
+        }
+
+        /// Gets the data directory with a leading `~` expanded
+        pub fn expanded_data_dir(&self) -> std::path::PathBuf {
+            match (self.data_dir.strip_prefix("~"), dirs::home_dir()) {
+                (Some(rest), Some(home)) => home.join(rest.trim_start_matches('/')),
+                _ => std::path::PathBuf::from(&self.data_dir),
+            }
+        }
+
+        /// Allows outbound requests to a host under `PrivacyLevel::Standard`
+        pub fn add_allowed_host(&mut self, host: &str) {
+            let host = host.trim().to_lowercase();
+            if !self.allowed_hosts.contains(&host) {
+                self.allowed_hosts.push(host);
+            }
+        }
+
+        /// Gets the variables of a named environment group
+        pub fn env_group(&self, name: &str) -> Option<&HashMap<String, String>> {
+            self.env_groups.get(name)
+        }
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+
+        #[test]
+        fn test_save_and_load_round_trip() {
+            let temp_dir = tempfile::tempdir().unwrap();
+            let path = temp_dir.path().join("rae.toml");
+
+            let mut config = Config {
+                log_level: "debug".to_string(),
+                log_format: LogFormat::Json,
+                privacy_level: PrivacyLevel::Standard,
+                max_concurrent_jobs: 2,
+                compact: true,
+                activity_retention_days: 30,
+                ..Default::default()
+            };
+            config.define_env_group("build", HashMap::from([("CC".to_string(), "clang".to_string())]));
+            config.add_allowed_host("api.example.com");
+            config.save_to_file(&path).unwrap();
+
+            assert_eq!(Config::load_from_file(&path).unwrap(), config);
+            assert!(!temp_dir.path().join("rae.toml.tmp").exists());
+        }
+
+        #[test]
+        fn test_load_missing_or_partial_file() {
+            let temp_dir = tempfile::tempdir().unwrap();
+            let path = temp_dir.path().join("rae.toml");
+            assert_eq!(Config::load_from_file(&path).unwrap(), Config::default());
+
+            std::fs::write(&path, "log_level = \"warn\"\n").unwrap();
+            let config = Config::load_from_file(&path).unwrap();
+            assert_eq!(config.log_level, "warn");
+            assert_eq!(config.max_modules, Config::default().max_modules);
+
+            std::fs::write(&path, "log_level = [").unwrap();
+            assert!(matches!(Config::load_from_file(&path), Err(RaeError::Config(_))));
+        }
+
+        #[test]
+        fn test_validate() {
+            assert!(Config::default().validate().is_ok());
+
+            let config = Config {
+                max_modules: 0,
+                log_level: "verbose".to_string(),
+                data_dir: "~rae/data".to_string(),
+                alert_failure_rate: 1.5,
+                activity_retention_days: 0,
+                ..Default::default()
+            };
+            let Err(RaeError::Config(message)) = config.validate() else {
+                panic!("expected a configuration error");
+            };
+            assert!(message.contains("max_modules must be at least 1"));
+            assert!(message.contains("log_level must be one of error, warn, info, debug, trace"));
+            assert!(message.contains("data_dir \"~rae/data\" cannot be expanded"));
+            assert!(message.contains("alert_failure_rate must be between 0.0 and 1.0"));
+            assert!(message.contains("activity_retention_days must be at least 1"));
+
+            let config = Config {
+                data_dir: " ".to_string(),
+                ..Default::default()
+            };
+            assert!(config.validate().is_err());
+        }
+
+        #[test]
+        fn test_merge_prefers_non_default_overlay_fields() {
+            let mut base = Config {
+                log_level: "debug".to_string(),
+                max_modules: 20,
+                ..Default::default()
+            };
+            base.add_allowed_host("a.example.com");
+
+            let mut overlay = Config {
+                privacy_level: PrivacyLevel::Open,
+                max_modules: 5,
+                ..Default::default()
+            };
+            overlay.add_allowed_host("b.example.com");
+
+            let merged = Config::merge(base, overlay);
+            assert_eq!(merged.log_level, "debug");
+            assert_eq!(merged.privacy_level, PrivacyLevel::Open);
+            assert_eq!(merged.max_modules, 5);
+            assert_eq!(merged.allowed_hosts, vec!["a.example.com", "b.example.com"]);
+        }
+    }
 }
 
 /// Error types for the Rae agent
@@ -47,6 +438,8 @@ pub mod error {
         Schema(String),
         Security(String),
         Protocol(String),
+        /// A lookup found no record, e.g. `kind: "job"`
+        NotFound { kind: &'static str, id: String },
         Io(std::io::Error),
         Serialization(serde_json::Error),
     }
@@ -60,13 +453,66 @@ pub mod error {
                 RaeError::Schema(msg) => write!(f, "Schema validation error: {}", msg),
                 RaeError::Security(msg) => write!(f, "Security error: {}", msg),
                 RaeError::Protocol(msg) => write!(f, "Protocol error: {}", msg),
+                RaeError::NotFound { kind, id } => write!(f, "{} not found: {}", kind, id),
                 RaeError::Io(err) => write!(f, "IO error: {}", err),
                 RaeError::Serialization(err) => write!(f, "Serialization error: {}", err),
             }
         }
     }
 
-    impl std::error::Error for RaeError {}
+    impl std::error::Error for RaeError {
+        fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+            match self {
+                RaeError::Io(err) => Some(err),
+                RaeError::Serialization(err) => Some(err),
+                _ => None,
+            }
+        }
+    }
+
+    /// Messages of an error and all of its sources, joined by `": "`.
+    ///
+    /// A source whose message already ends the chain (because the outer error
+    /// includes it in its own message) is not repeated.
+    pub fn full_chain(err: &dyn std::error::Error) -> String {
+        let mut chain = err.to_string();
+        let mut source = err.source();
+        while let Some(cause) = source {
+            let message = cause.to_string();
+            if !chain.ends_with(&message) {
+                chain.push_str(": ");
+                chain.push_str(&message);
+            }
+            source = cause.source();
+        }
+        chain
+    }
+
+    impl RaeError {
+        /// Creates the error for a failed lookup of a `kind` record.
+        pub fn not_found(kind: &'static str, id: impl Into<String>) -> Self {
+            RaeError::NotFound { kind, id: id.into() }
+        }
+
+        /// Whether the error is a failed lookup.
+        pub fn is_not_found(&self) -> bool {
+            matches!(self, RaeError::NotFound { .. })
+        }
+
+        /// The innermost error in the `source()` chain, or this error if it has no source.
+        pub fn root_cause(&self) -> &(dyn std::error::Error + 'static) {
+            let mut cause: &(dyn std::error::Error + 'static) = self;
+            while let Some(source) = cause.source() {
+                cause = source;
+            }
+            cause
+        }
+
+        /// Messages of this error and all of its sources, joined by `": "`.
+        pub fn full_chain(&self) -> String {
+            full_chain(self)
+        }
+    }
 
     impl From<std::io::Error> for RaeError {
         fn from(err: std::io::Error) -> Self {
@@ -80,8 +526,70 @@ pub mod error {
         }
     }
 
+    impl From<rusqlite::Error> for RaeError {
+        fn from(err: rusqlite::Error) -> Self {
+            RaeError::Storage(err.to_string())
+        }
+    }
+
     pub type Result<T> = std::result::Result<T, RaeError>;
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+        use std::error::Error;
+        use std::fmt;
+
+        /// An error wrapping another without repeating its message.
+        #[derive(Debug)]
+        struct Wrapper(RaeError);
+
+        impl fmt::Display for Wrapper {
+            fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                f.write_str("Storage error")
+            }
+        }
+
+        impl Error for Wrapper {
+            fn source(&self) -> Option<&(dyn Error + 'static)> {
+                Some(&self.0)
+            }
+        }
+
+        #[test]
+        fn test_root_cause_and_full_chain() {
+            let io = RaeError::from(std::io::Error::from(std::io::ErrorKind::NotFound));
+            assert_eq!(io.full_chain(), "IO error: entity not found");
+            assert_eq!(io.root_cause().to_string(), "entity not found");
+            assert!(io.root_cause().source().is_none());
+
+            let wrapped = Wrapper(RaeError::from(std::io::Error::from_raw_os_error(2)));
+            assert_eq!(full_chain(&wrapped), "Storage error: IO error: No such file or directory (os error 2)");
+
+            let config = RaeError::Config("bad value".to_string());
+            assert_eq!(config.full_chain(), "Configuration error: bad value");
+            assert_eq!(config.root_cause().to_string(), config.to_string());
+        }
+    }
 }
 
+/// Core agent components
+pub mod core;
+
+/// Local API and protocol support
+pub mod api;
+
+/// Schema validation for module data
+pub mod schemas;
+
+/// Sandboxed extension modules
+pub mod modules;
+
+/// First-time setup of configuration and data directories
+pub mod init;
+
+/// Native Web Components user interface
+pub mod ui;
+
 /// Scheduler module for job management and automation
 pub mod scheduler; 
\ No newline at end of file
@@ -3,49 +3,82 @@
 //! This is the main entry point for the Rae agent, providing CLI interface,
 //! core scheduling, module management, and local API services.
 
+use chrono::{DateTime, Duration, NaiveTime, Utc};
 use clap::{Parser, Subcommand};
-use tracing::{error, info};
-use tracing_subscriber;
+use output::{CliError, CliOutput, Printer, StatusReport};
+use rae_agent::api::protocols::{Framing, ProtocolBridgeConfig};
+use rae_agent::api::{McpServer, ProtocolBridge};
+use rae_agent::config::{self, Config, LogFormat};
+use rae_agent::core::{Digest, Storage, VacuumReport};
+use rae_agent::error::full_chain;
+use rae_agent::modules::BuiltinModules;
+use rae_agent::scheduler::cli::OutputFormat;
+use rae_agent::scheduler::job::Priority;
+use rae_agent::{init, scheduler};
+use serde_json::json;
+use std::path::{Path, PathBuf};
+use tracing::{error, info, warn};
+use tracing_subscriber::fmt::writer::BoxMakeWriter;
 
+mod output;
 mod tray;
-mod scheduler;
 
 #[derive(Parser)]
 #[command(name = "rae-agent")]
 #[command(about = "Local-first, privacy-respecting AI assistant")]
 #[command(version = "0.1.0")]
 struct Cli {
+    /// Print command output as JSON
+    #[arg(long, global = true)]
+    json: bool,
+    
     #[command(subcommand)]
     command: Option<Commands>,
 }
 
 #[derive(Subcommand)]
 enum Commands {
+    /// Set up configuration and data directories for first use
+    Init {
+        /// Data directory to store in the configuration
+        #[arg(long)]
+        data_dir: Option<String>,
+        /// Privacy level (strict, standard or open)
+        #[arg(long)]
+        privacy: Option<String>,
+        /// Use defaults instead of prompting
+        #[arg(long)]
+        non_interactive: bool,
+    },
     /// Start the Rae agent in background mode
     Start,
     /// Show system status and module health
     Status,
-    /// Manually run a module
+    /// Run a scheduled job now, bypassing its schedule
     Run {
-        /// Module name to run
+        /// ID of the job to run
         module: String,
+        /// Wait for the job to finish and show its result
+        #[arg(long)]
+        wait: bool,
     },
     /// Generate digest (daily or weekly)
     Digest {
         /// Digest type (daily or weekly)
         #[arg(default_value = "daily")]
         digest_type: String,
+        /// Summarize the seven days ending today (same as `weekly`)
+        #[arg(long)]
+        week: bool,
     },
     /// Open today's summary file
     Summary,
     /// List all installed modules
     Modules,
-    /// Get or set configuration values
+    /// Get or set configuration values (opens the file without a subcommand)
     Config {
-        /// Configuration key
-        key: Option<String>,
-        /// Configuration value
-        value: Option<String>,
+        #[command(subcommand)]
+        command: Option<ConfigCommands>,
     },
     /// Development and testing commands
     Dev {
@@ -53,26 +86,87 @@ enum Commands {
         #[arg(default_value = "test")]
         test_cmd: String,
     },
+    /// Maintain the activity database
+    Storage {
+        #[command(subcommand)]
+        command: Option<StorageCommands>,
+        /// Delete expired activity and compact the database
+        #[arg(long)]
+        vacuum: bool,
+        /// Days of activity to keep (defaults to activity_retention_days in the config)
+        #[arg(long, requires = "vacuum")]
+        retain_days: Option<u32>,
+    },
     /// Manage scheduled jobs and automation
     Scheduler {
+        /// Output format: json, table or plain (tables are only used by list)
+        #[arg(long, global = true, default_value = "plain")]
+        output: OutputFormat,
         #[command(subcommand)]
         command: SchedulerCommands,
     },
+    /// Serve modules as tools over the Model Context Protocol on stdio
+    Mcp,
+}
+
+#[derive(Subcommand)]
+enum ConfigCommands {
+    /// Print a configuration value
+    Get {
+        /// Dot-separated key, e.g. `log_level` or `env_groups.build.CC`
+        key: String,
+    },
+    /// Set a configuration value
+    Set {
+        /// Dot-separated key, e.g. `log_level` or `env_groups.build.CC`
+        key: String,
+        /// New value
+        value: String,
+    },
+    /// List all configuration values
+    List,
+}
+
+#[derive(Subcommand)]
+enum StorageCommands {
+    /// Export activity data for analytics tools
+    Export {
+        /// File format (only parquet is supported)
+        #[arg(long, default_value = "parquet")]
+        format: String,
+        /// File to write
+        path: PathBuf,
+        /// Only export activity recorded from this time (RFC 3339)
+        #[arg(long)]
+        since: Option<DateTime<Utc>>,
+        /// Only export activity recorded before this time (RFC 3339)
+        #[arg(long)]
+        until: Option<DateTime<Utc>>,
+    },
+    /// Stream new activity as JSON lines to a named pipe until interrupted
+    Stream {
+        /// Only stream this module's activity
+        #[arg(long)]
+        module: Option<String>,
+        /// Named pipe to create and write to
+        #[arg(long)]
+        fifo: PathBuf,
+    },
 }
 
 #[derive(Subcommand)]
 enum SchedulerCommands {
     /// Add a new scheduled job
     Add {
-        /// Job name
-        #[arg(short, long)]
-        name: String,
+        /// Job name (defaults to the alias name with --alias)
+        #[arg(short, long, required_unless_present_any = ["alias", "template"])]
+        name: Option<String>,
         /// Cron schedule expression
-        #[arg(short, long)]
-        schedule: String,
+        #[arg(short, long, required_unless_present_any = ["alias", "template"])]
+        schedule: Option<String>,
         /// Command to execute
-        #[arg(short, long)]
-        command: String,
+        #[arg(short, long, required_unless_present_any = ["alias", "template"], conflicts_with_all = ["alias", "template"])]
+        command: Option<String>,
         /// Command arguments
         #[arg(short, long)]
         args: Option<Vec<String>>,
@@ -82,12 +176,36 @@ enum SchedulerCommands {
         /// Job description
         #[arg(short, long)]
         description: Option<String>,
+        /// Job priority (low, normal, high or critical)
+        #[arg(short, long, conflicts_with_all = ["alias", "template"])]
+        priority: Option<Priority>,
+        /// Tags for grouping jobs (comma-separated)
+        #[arg(long, value_delimiter = ',', conflicts_with_all = ["alias", "template"])]
+        tag: Vec<String>,
+        /// Create the job from a shell alias in ~/.rae/aliases.toml
+        #[arg(long)]
+        alias: Option<String>,
+        /// Inherit environment variables from a named group in the config (repeatable)
+        #[arg(long = "env-group")]
+        env_group: Vec<String>,
+        /// Declare a `{{NAME}}` placeholder, making the job a template (repeatable)
+        #[arg(long = "template-var", conflicts_with_all = ["alias", "template"])]
+        template_var: Vec<String>,
+        /// Create the job by instantiating the template with this ID
+        #[arg(long, conflicts_with = "alias")]
+        template: Option<String>,
+        /// Template variable as KEY=VAL (repeatable, with --template)
+        #[arg(long = "var", requires = "template", conflicts_with_all = ["command", "alias"])]
+        var: Vec<String>,
     },
     /// List all scheduled jobs
     List {
         /// Show detailed information
         #[arg(short, long)]
         verbose: bool,
+        /// Only list jobs with this tag
+        #[arg(long)]
+        filter_tag: Option<String>,
     },
     /// Remove a scheduled job
     Remove {
@@ -109,204 +227,677 @@ enum SchedulerCommands {
         /// Job ID to disable
         job_id: String,
     },
+    /// Change the schedule, command or metadata of an existing job
+    Update {
+        /// Job ID to update
+        job_id: String,
+        /// New cron schedule expression
+        #[arg(short, long)]
+        schedule: Option<String>,
+        /// Timezone for the new schedule
+        #[arg(short, long, requires = "schedule")]
+        timezone: Option<String>,
+        /// New command to execute
+        #[arg(short, long)]
+        command: Option<String>,
+        /// New command arguments
+        #[arg(short, long)]
+        args: Option<Vec<String>>,
+        /// New priority (low, normal, high or critical)
+        #[arg(short, long)]
+        priority: Option<Priority>,
+        /// New job description
+        #[arg(short, long)]
+        description: Option<String>,
+    },
+    /// Run a command once at a given time without creating a persistent job
+    RunAt {
+        /// Command to execute
+        #[arg(short, long)]
+        command: String,
+        /// Command arguments
+        #[arg(short, long)]
+        args: Vec<String>,
+        /// When to run, e.g. "2025-01-31 18:00", "in 5 minutes" or "next monday at 09:00"
+        #[arg(long)]
+        at: String,
+        /// Job name (defaults to a generated name)
+        #[arg(short, long)]
+        name: Option<String>,
+    },
+    /// Show scheduler statistics, including duration percentiles
+    Stats,
+    /// Halt all jobs immediately and keep the scheduler paused
+    EmergencyStop {
+        /// Reason for the stop
+        reason: String,
+    },
+    /// Clear an emergency stop and resume the scheduler
+    ClearStop,
+    /// Pause job execution for maintenance; due jobs are held until resumed
+    Pause,
+    /// Resume job execution and run the jobs held while paused
+    Resume,
+    /// Watch live job execution status (press q to exit)
+    Watch {
+        /// Refresh interval in seconds
+        #[arg(short, long, default_value_t = 5)]
+        interval_seconds: u64,
+    },
 }
 
 #[tokio::main]
 async fn main() -> Result<(), Box<dyn std::error::Error>> {
-    // Initialize logging
-    tracing_subscriber::fmt::init();
-    info!("Starting Rae agent v0.1.0");
-
     let cli = Cli::parse();
+    // `scheduler --output json` prints the same JSON as `--json`
+    let scheduler_json = matches!(cli.command, Some(Commands::Scheduler { output: OutputFormat::Json, .. }));
+    let out = Printer::new(cli.json || scheduler_json);
+    
+    // Initialize logging (on stderr with --json or mcp so stdout stays parseable)
+    let log_format = load_config().map(|config| config.log_format).unwrap_or_default();
+    init_logging(log_format, out.is_json() || matches!(cli.command, Some(Commands::Mcp)));
+    info!("Starting Rae agent v0.1.0");
 
     match &cli.command {
+        Some(Commands::Init { data_dir, privacy, non_interactive }) => {
+            let mut options = init::InitOptions::new()?;
+            options.data_dir = data_dir.clone();
+            options.privacy = privacy.clone();
+            options.interactive = !non_interactive && !out.is_json();
+            
+            match init::run_init(&options) {
+                Ok(summary) => {
+                    let mut text = if summary.config_written {
+                        format!("✅ Wrote configuration: {}", summary.config_path.display())
+                    } else {
+                        format!("ℹ️  Keeping existing configuration: {}", summary.config_path.display())
+                    };
+                    text.push_str(&format!("\n📁 Data directory: {}", summary.data_dir.display()));
+                    text.push_str(&format!("\n📄 Summary directory: {}", summary.summary_dir.display()));
+                    text.push_str("\n\nNext steps:");
+                    text.push_str("\n  rae-agent start     - Start the agent in background mode");
+                    text.push_str("\n  rae-agent scheduler - Schedule your first job");
+                    text.push_str("\n  rae-agent config    - Review the configuration");
+                    
+                    out.print(CliOutput::new(json!({
+                        "config_path": summary.config_path,
+                        "config_written": summary.config_written,
+                        "data_dir": summary.data_dir,
+                        "summary_dir": summary.summary_dir,
+                    }), text));
+                }
+                Err(e) => {
+                    error!("Initialization failed: {}", full_chain(&e));
+                    out.fail(CliError::from_error("rae::init::failed", &e));
+                }
+            }
+        }
         Some(Commands::Start) => {
             info!("Starting Rae agent in background mode");
-            println!("Starting Rae agent...");
-            println!("Agent will run in background mode.");
-            println!("Use 'rae status' to check agent status.");
+            out.print(CliOutput::new(
+                json!({ "starting": true }),
+                "Starting Rae agent...\nAgent will run in background mode.\nUse 'rae status' to check agent status.",
+            ));
             
             // Start the agent in background mode
             if let Err(e) = tray::start_background() {
-                error!("Failed to start background mode: {}", e);
-                println!("Error: {}", e);
+                error!("Failed to start background mode: {}", full_chain(&*e));
+                out.fail(CliError::from_error("rae::agent::start_failed", &*e));
             }
         }
         Some(Commands::Status) => {
-            println!("Rae Agent Status:");
-            println!("✅ Agent is running");
-            println!("📊 Version: 0.1.0");
-            println!("🔧 Status: Operational");
-            println!("📁 Data directory: ~/.rae");
-            println!("📄 Summary file: ~/Documents/rae/today.md");
+            out.print(StatusReport::current().into_output());
         }
-        Some(Commands::Run { module }) => {
-            println!("Running module: {}", module);
-            println!("Module execution completed.");
+        Some(Commands::Run { module, wait }) => {
+            if let Err(e) = scheduler::cli::init_scheduler().await {
+                out.fail(CliError::new("rae::scheduler::init_failed", format!("Failed to initialize scheduler: {}", full_chain(&e))));
+                return Ok(());
+            }
+            
+            out.progress(format!("Running job: {}", module));
+            match scheduler::cli::run_job_now(module, *wait).await {
+                Ok((run_id, Some(result))) => {
+                    let text = format!("Job {} finished with {:?}\n{}", run_id, result.status, result.stdout.trim_end());
+                    out.print(CliOutput::new(json!(result), text));
+                }
+                Ok((run_id, None)) => {
+                    out.print(CliOutput::new(json!({ "run_id": run_id }), format!("Job started.\nRun ID: {}", run_id)));
+                }
+                Err(e) => {
+                    out.fail(CliError::scheduler("rae::run::failed", "Failed to run job", &e));
+                }
+            }
         }
-        Some(Commands::Digest { digest_type }) => {
-            println!("Generating {} digest...", digest_type);
-            println!("Digest generated successfully.");
+        Some(Commands::Digest { digest_type, week }) => {
+            let weekly = *week || digest_type == "weekly";
+            if !weekly && digest_type != "daily" {
+                out.fail(CliError::new("rae::digest::invalid_type", format!("Unknown digest type: {} (expected daily or weekly)", digest_type)));
+                return Ok(());
+            }
+            
+            out.progress(format!("Generating {} digest...", if weekly { "weekly" } else { "daily" }));
+            match generate_digest(weekly) {
+                Ok(digest) => {
+                    let text = format!("Digest generated successfully.\n{}", digest.summary);
+                    out.print(CliOutput::new(json!(digest), text));
+                }
+                Err(e) => {
+                    error!("Failed to generate digest: {}", full_chain(&e));
+                    out.fail(CliError::from_error("rae::digest::failed", &e));
+                }
+            }
         }
         Some(Commands::Summary) => {
-            println!("Opening today's summary...");
+            out.progress("Opening today's summary...");
+            if let Err(e) = refresh_todays_summary() {
+                warn!("Failed to update today's summary: {}", full_chain(&*e));
+            }
             if let Err(e) = tray::open_todays_summary() {
-                error!("Failed to open today's summary: {}", e);
-                println!("Error: {}", e);
+                error!("Failed to open today's summary: {}", full_chain(&*e));
+                out.fail(CliError::from_error("rae::summary::open_failed", &*e));
             } else {
-                println!("Summary file opened successfully");
+                out.print(CliOutput::new(json!({ "opened": true }), "Summary file opened successfully"));
             }
         }
         Some(Commands::Modules) => {
-            println!("Installed modules:");
-            println!("📊 core - Core functionality");
-            println!("📝 summary - Summary generation");
-            println!("🔧 config - Configuration management");
-        }
-        Some(Commands::Config { key, value }) => {
-            match (key, value) {
-                (Some(k), Some(v)) => {
-                    println!("Setting config {} = {}", k, v);
-                    println!("Configuration updated successfully.");
-                }
-                (Some(k), None) => {
-                    println!("Getting config value for: {}", k);
-                    println!("Value: [not implemented]");
-                }
-                (None, None) => {
-                    println!("Opening configuration file...");
-                    if let Err(e) = tray::open_config_file() {
-                        error!("Failed to open config: {}", e);
-                        println!("Error: {}", e);
-                    } else {
-                        println!("Configuration file opened successfully");
-                    }
+            out.print(CliOutput::new(
+                json!([
+                    { "name": "core", "description": "Core functionality" },
+                    { "name": "summary", "description": "Summary generation" },
+                    { "name": "config", "description": "Configuration management" },
+                ]),
+                "Installed modules:\n📊 core - Core functionality\n📝 summary - Summary generation\n🔧 config - Configuration management",
+            ));
+        }
+        Some(Commands::Config { command }) => {
+            handle_config_command(command.as_ref(), out);
+        }
+        Some(Commands::Dev { test_cmd }) => {
+            out.print(CliOutput::new(
+                json!({ "test_cmd": test_cmd, "completed": true }),
+                format!("Running development test: {}\nTest completed successfully.", test_cmd),
+            ));
+        }
+        Some(Commands::Storage { command: Some(StorageCommands::Export { format, path, since, until }), .. }) => {
+            if format != "parquet" {
+                out.fail(CliError::new("rae::storage::invalid_format", format!("Unknown export format: {} (expected parquet)", format)));
+                return Ok(());
+            }
+            
+            out.progress(format!("Exporting activity to {}...", path.display()));
+            match export_storage(path, *since, *until) {
+                Ok(rows) => {
+                    out.print(CliOutput::new(
+                        json!({ "path": path, "format": format, "rows": rows }),
+                        format!("Exported {} records to {}", rows, path.display()),
+                    ));
                 }
-                _ => {
-                    println!("Invalid config command usage");
+                Err(e) => {
+                    error!("Failed to export storage: {}", full_chain(&e));
+                    out.fail(CliError::from_error("rae::storage::export_failed", &e));
                 }
             }
         }
-        Some(Commands::Dev { test_cmd }) => {
-            println!("Running development test: {}", test_cmd);
-            println!("Test completed successfully.");
+        #[cfg(unix)]
+        Some(Commands::Storage { command: Some(StorageCommands::Stream { module, fifo }), .. }) => {
+            out.progress(format!("Streaming activity to {} (Ctrl+C to stop)...", fifo.display()));
+            match stream_storage(fifo, module.as_deref()).await {
+                Ok(()) => out.print(CliOutput::new(json!({ "fifo": fifo, "stopped": true }), "Stopped streaming")),
+                Err(e) => {
+                    error!("Failed to stream storage: {}", full_chain(&e));
+                    out.fail(CliError::from_error("rae::storage::stream_failed", &e));
+                }
+            }
+        }
+        #[cfg(not(unix))]
+        Some(Commands::Storage { command: Some(StorageCommands::Stream { .. }), .. }) => {
+            out.fail(CliError::new("rae::storage::unsupported", "Streaming to a named pipe is only supported on Unix"));
+        }
+        Some(Commands::Storage { command: None, vacuum, retain_days }) => {
+            if !vacuum {
+                out.fail(CliError::new("rae::storage::no_action", "Nothing to do (use --vacuum)"));
+                return Ok(());
+            }
+            
+            out.progress("Vacuuming storage...");
+            match vacuum_storage(*retain_days) {
+                Ok(report) => {
+                    let text = format!(
+                        "Deleted {} records and freed {} bytes in {} ms",
+                        report.deleted_records, report.freed_bytes, report.duration_ms
+                    );
+                    out.print(CliOutput::new(json!(report), text));
+                }
+                Err(e) => {
+                    error!("Failed to vacuum storage: {}", full_chain(&e));
+                    out.fail(CliError::from_error("rae::storage::vacuum_failed", &e));
+                }
+            }
         }
-        Some(Commands::Scheduler { command }) => {
-            handle_scheduler_command(command).await?;
+        Some(Commands::Scheduler { output, command }) => {
+            handle_scheduler_command(command, *output, out).await?;
+        }
+        Some(Commands::Mcp) => {
+            let bridge = ProtocolBridge::new(ProtocolBridgeConfig {
+                framing: Framing::Newline,
+                ..ProtocolBridgeConfig::default()
+            });
+            // Tools are registered here once modules can be loaded and run
+            let server = McpServer::new();
+            
+            info!("Serving MCP on stdio");
+            if let Err(e) = bridge.serve_mcp(&server, tokio::io::stdin(), tokio::io::stdout()).await {
+                error!("MCP bridge failed: {}", full_chain(&e));
+                out.fail(CliError::from_error("rae::mcp::failed", &e));
+            }
         }
         None => {
-            println!("Local-first, privacy-respecting AI assistant");
-            println!("\nUsage:");
-            println!("  rae-agent start     - Start the agent in background mode");
-            println!("  rae-agent status    - Show system status");
-            println!("  rae-agent summary   - Open today's summary");
-            println!("  rae-agent config    - Open configuration");
-            println!("  rae-agent scheduler - Manage scheduled jobs");
-            println!("  rae-agent --help    - Show this help");
+            let commands = [
+                ("init", "Set up Rae for first use"),
+                ("start", "Start the agent in background mode"),
+                ("status", "Show system status"),
+                ("summary", "Open today's summary"),
+                ("config", "Open configuration"),
+                ("scheduler", "Manage scheduled jobs"),
+                ("--help", "Show this help"),
+            ];
+            
+            let mut text = "Local-first, privacy-respecting AI assistant\n\nUsage:".to_string();
+            for (name, description) in commands {
+                text.push_str(&format!("\n  rae-agent {:<9} - {}", name, description));
+            }
+            
+            let value: Vec<_> = commands.iter()
+                .map(|(name, description)| json!({ "command": name, "description": description }))
+                .collect();
+            out.print(CliOutput::new(value, text));
         }
     }
 
     Ok(())
 }
 
+/// Load the configuration from `~/.rae/rae.toml`
+fn load_config() -> rae_agent::error::Result<Config> {
+    match dirs::home_dir() {
+        Some(home_dir) => Config::load_from_file(&home_dir.join(".rae").join("rae.toml")),
+        None => Ok(Config::default()),
+    }
+}
+
+/// Install the global log subscriber, writing to stderr instead of stdout if asked
+fn init_logging(format: LogFormat, to_stderr: bool) {
+    let writer = if to_stderr {
+        BoxMakeWriter::new(std::io::stderr)
+    } else {
+        BoxMakeWriter::new(std::io::stdout)
+    };
+    let builder = tracing_subscriber::fmt().with_writer(writer);
+    
+    match format {
+        LogFormat::Text => builder.init(),
+        LogFormat::Json => builder.json().init(),
+    }
+}
+
+/// Writes today's activities into the summary file with the built-in summary module
+fn refresh_todays_summary() -> Result<(), Box<dyn std::error::Error>> {
+    let config = load_config()?;
+    let storage = Storage::in_data_dir(&config)?;
+    BuiltinModules::summary(&storage, &config, &tray::todays_summary_path()?, Utc::now())?;
+    Ok(())
+}
+
+/// Generates and stores today's digest, or the one for the seven days ending today
+fn generate_digest(weekly: bool) -> rae_agent::error::Result<Digest> {
+    let config = load_config()?;
+    let storage = Storage::in_data_dir(&config)?;
+    
+    let today = Utc::now().date_naive().and_time(NaiveTime::MIN).and_utc();
+    let digest = if weekly {
+        Digest::generate_weekly(&storage, today - Duration::days(6))?
+    } else {
+        Digest::generate_daily(&storage, today)?
+    };
+    storage.store_digest(&digest)?;
+    
+    Ok(digest)
+}
+
+/// Handle config subcommands
+fn handle_config_command(command: Option<&ConfigCommands>, out: Printer) {
+    match command {
+        Some(ConfigCommands::Get { key }) => match config::cli::get(key) {
+            Ok(value) => {
+                let text = value.clone().unwrap_or_else(|| "(not set)".to_string());
+                out.print(CliOutput::new(json!({ "key": key, "value": value }), text));
+            }
+            Err(e) => out.fail(CliError::from_error("rae::config::get_failed", &e)),
+        },
+        Some(ConfigCommands::Set { key, value }) => match config::cli::set(key, value) {
+            Ok(()) => {
+                out.print(CliOutput::new(
+                    json!({ "key": key, "value": value, "updated": true }),
+                    format!("Set {} = {}", key, value),
+                ));
+            }
+            Err(e) => out.fail(CliError::from_error("rae::config::set_failed", &e)),
+        },
+        Some(ConfigCommands::List) => match config::cli::list() {
+            Ok(pairs) => {
+                let text = pairs.iter()
+                    .map(|(key, value)| format!("{} = {}", key, value))
+                    .collect::<Vec<_>>()
+                    .join("\n");
+                let value: serde_json::Map<_, _> = pairs.into_iter()
+                    .map(|(key, value)| (key, json!(value)))
+                    .collect();
+                out.print(CliOutput::new(value, text));
+            }
+            Err(e) => out.fail(CliError::from_error("rae::config::list_failed", &e)),
+        },
+        None => {
+            out.progress("Opening configuration file...");
+            if let Err(e) = tray::open_config_file() {
+                error!("Failed to open config: {}", full_chain(&*e));
+                out.fail(CliError::from_error("rae::config::open_failed", &*e));
+            } else {
+                out.print(CliOutput::new(json!({ "opened": true }), "Configuration file opened successfully"));
+            }
+        }
+    }
+}
+
+/// Deletes activity older than the retention period and compacts the database
+fn vacuum_storage(retain_days: Option<u32>) -> rae_agent::error::Result<VacuumReport> {
+    let config = load_config()?;
+    let storage = Storage::in_data_dir(&config)?;
+    storage.vacuum(retain_days.unwrap_or(config.activity_retention_days))
+}
+
+/// Writes activity data to a Parquet file
+fn export_storage(path: &Path, since: Option<DateTime<Utc>>, until: Option<DateTime<Utc>>) -> rae_agent::error::Result<u64> {
+    let config = load_config()?;
+    let storage = Storage::in_data_dir(&config)?;
+    storage.export_parquet(path, since, until)
+}
+
+/// Streams activity to a named pipe, following writes by other processes, until Ctrl+C
+#[cfg(unix)]
+async fn stream_storage(fifo: &Path, module: Option<&str>) -> rae_agent::error::Result<()> {
+    let config = load_config()?;
+    let storage = Storage::in_data_dir(&config)?;
+    storage.stream_to_fifo(fifo, module)?;
+    
+    let mut poll = tokio::time::interval(std::time::Duration::from_secs(1));
+    let stop = tokio::signal::ctrl_c();
+    tokio::pin!(stop);
+    loop {
+        tokio::select! {
+            _ = poll.tick() => {
+                storage.poll_writes()?;
+            }
+            _ = &mut stop => return Ok(()),
+        }
+    }
+}
+
 /// Handle scheduler subcommands
-async fn handle_scheduler_command(command: &SchedulerCommands) -> Result<(), Box<dyn std::error::Error>> {
+async fn handle_scheduler_command(
+    command: &SchedulerCommands,
+    output: OutputFormat,
+    out: Printer,
+) -> Result<(), Box<dyn std::error::Error>> {
     // Initialize the scheduler
     if let Err(e) = scheduler::cli::init_scheduler().await {
-        eprintln!("Failed to initialize scheduler: {}", e);
+        out.fail(CliError::new("rae::scheduler::init_failed", format!("Failed to initialize scheduler: {}", full_chain(&e))));
         return Ok(());
     }
     
     match command {
-        SchedulerCommands::Add { name, schedule, command, args, timezone, description } => {
-            println!("Adding scheduled job: {}", name);
-            println!("Schedule: {}", schedule);
-            println!("Command: {}", command);
+        SchedulerCommands::Add { name, schedule, command, args, timezone, description, priority, tag, alias, env_group, template_var, template, var } => {
+            let result = if let Some(template) = template {
+                out.progress(format!("Adding scheduled job from template: {}", template));
+                
+                scheduler::cli::add_template_job(template.clone(), var.clone()).await
+            } else if let Some(alias) = alias {
+                out.progress(format!("Adding scheduled job from alias: {}", alias));
+                
+                scheduler::cli::add_alias_job(
+                    alias.clone(),
+                    name.clone(),
+                    schedule.clone(),
+                    args.clone(),
+                    timezone.clone(),
+                    description.clone(),
+                    env_group.clone(),
+                ).await
+            } else {
+                // clap requires these when --alias is not given
+                let name = name.clone().unwrap_or_default();
+                let schedule = schedule.clone().unwrap_or_default();
+                let command = command.clone().unwrap_or_default();
+                
+                out.progress(format!("Adding scheduled job: {}", name));
+                out.progress(format!("Schedule: {}", schedule));
+                out.progress(format!("Command: {}", command));
+                
+                if scheduler::cli::is_alias(&command) {
+                    out.progress(format!("Hint: '{}' is a shell alias; use --alias {} to apply its configuration", command, command));
+                }
+                
+                scheduler::cli::add_job(
+                    name,
+                    schedule,
+                    command,
+                    args.clone(),
+                    timezone.clone(),
+                    description.clone(),
+                    *priority,
+                    tag.iter().map(|tag| tag.trim().to_string()).filter(|tag| !tag.is_empty()).collect(),
+                    env_group.clone(),
+                    template_var.clone(),
+                ).await
+            };
             
-            match scheduler::cli::add_job(
-                name.clone(),
-                schedule.clone(),
-                command.clone(),
-                args.clone(),
-                timezone.clone(),
-                description.clone(),
-            ).await {
+            match result {
                 Ok(job_id) => {
-                    println!("Job created successfully!");
-                    println!("Job ID: {}", job_id);
-                    println!("Next run: [to be calculated]");
+                    let text = format!("Job created successfully!\nJob ID: {}\nNext run: [to be calculated]", job_id);
+                    out.print(CliOutput::new(json!({ "job_id": job_id }), text));
                 }
                 Err(e) => {
-                    eprintln!("Failed to add job: {}", e);
+                    out.fail(CliError::new("rae::scheduler::add_failed", format!("Failed to add job: {}", full_chain(&e))));
                 }
             }
         }
         
-        SchedulerCommands::List { verbose } => {
-            println!("Scheduled Jobs:");
-            match scheduler::cli::list_jobs(*verbose).await {
+        SchedulerCommands::List { verbose, filter_tag } => {
+            // `--json` alone prints job summaries; `--output json` the full jobs
+            if out.is_json() && output != OutputFormat::Json {
+                match scheduler::cli::list_job_summaries(filter_tag.as_deref()).await {
+                    Ok(jobs) => out.print(CliOutput::new(jobs, String::new())),
+                    Err(e) => out.fail(CliError::new("rae::scheduler::list_failed", format!("Failed to list jobs: {}", full_chain(&e)))),
+                }
+                return Ok(());
+            }
+            
+            if output == OutputFormat::Plain {
+                println!("Scheduled Jobs:");
+            }
+            match scheduler::cli::list_jobs(*verbose, output, filter_tag.as_deref()).await {
                 Ok(jobs) => {
-                    if jobs.is_empty() {
-                        println!("No scheduled jobs found.");
-                    } else {
-                        for job in jobs {
-                            println!("{}", job);
-                        }
-                    }
+                    println!("{}", jobs);
                 }
                 Err(e) => {
-                    eprintln!("Failed to list jobs: {}", e);
+                    out.fail(CliError::new("rae::scheduler::list_failed", format!("Failed to list jobs: {}", full_chain(&e))));
                 }
             }
         }
         
         SchedulerCommands::Remove { job_id } => {
-            println!("Removing job: {}", job_id);
+            out.progress(format!("Removing job: {}", job_id));
             match scheduler::cli::remove_job(job_id).await {
                 Ok(_) => {
-                    println!("Job removed successfully!");
+                    out.print(CliOutput::new(json!({ "job_id": job_id, "removed": true }), "Job removed successfully!"));
                 }
                 Err(e) => {
-                    eprintln!("Failed to remove job: {}", e);
+                    out.fail(CliError::scheduler("rae::scheduler::remove_failed", "Failed to remove job", &e));
                 }
             }
         }
         
         SchedulerCommands::Status { job_id } => {
-            match scheduler::cli::get_job_status(job_id.as_deref()).await {
+            let result = match (job_id, out.is_json()) {
+                (Some(id), true) => scheduler::cli::job_status(id).await
+                    .map(|status| CliOutput::new(json!({ "job_id": id, "status": status }), String::new())),
+                (None, true) => scheduler::cli::scheduler_overview().await
+                    .map(|overview| CliOutput::new(json!(overview), String::new())),
+                (_, false) => scheduler::cli::get_job_status(job_id.as_deref()).await
+                    .map(|status| CliOutput::new(json!(null), status)),
+            };
+            
+            match result {
                 Ok(status) => {
-                    println!("{}", status);
+                    out.print(status);
                 }
                 Err(e) => {
-                    eprintln!("Failed to get job status: {}", e);
+                    out.fail(CliError::scheduler("rae::scheduler::status_failed", "Failed to get job status", &e));
                 }
             }
         }
         
         SchedulerCommands::Enable { job_id } => {
-            println!("Enabling job: {}", job_id);
+            out.progress(format!("Enabling job: {}", job_id));
             match scheduler::cli::enable_job(job_id).await {
                 Ok(_) => {
-                    println!("Job enabled successfully!");
+                    out.print(CliOutput::new(json!({ "job_id": job_id, "enabled": true }), "Job enabled successfully!"));
                 }
                 Err(e) => {
-                    eprintln!("Failed to enable job: {}", e);
+                    out.fail(CliError::scheduler("rae::scheduler::enable_failed", "Failed to enable job", &e));
                 }
             }
         }
         
         SchedulerCommands::Disable { job_id } => {
-            println!("Disabling job: {}", job_id);
+            out.progress(format!("Disabling job: {}", job_id));
             match scheduler::cli::disable_job(job_id).await {
                 Ok(_) => {
-                    println!("Job disabled successfully!");
+                    out.print(CliOutput::new(json!({ "job_id": job_id, "enabled": false }), "Job disabled successfully!"));
+                }
+                Err(e) => {
+                    out.fail(CliError::scheduler("rae::scheduler::disable_failed", "Failed to disable job", &e));
+                }
+            }
+        }
+        
+        SchedulerCommands::Update { job_id, schedule, timezone, command, args, priority, description } => {
+            out.progress(format!("Updating job: {}", job_id));
+            match scheduler::cli::update_job(
+                job_id,
+                schedule.clone(),
+                timezone.clone(),
+                command.clone(),
+                args.clone(),
+                *priority,
+                description.clone(),
+            ).await {
+                Ok(job) => {
+                    out.print(CliOutput::new(job, "Job updated successfully!"));
+                }
+                Err(e) => {
+                    out.fail(CliError::scheduler("rae::scheduler::update_failed", "Failed to update job", &e));
+                }
+            }
+        }
+        
+        SchedulerCommands::RunAt { command, args, at, name } => {
+            out.progress(format!("Running '{}' once at: {}", command, at));
+            
+            match scheduler::cli::run_at(command.clone(), args.clone(), at, name.clone()).await {
+                Ok(result) => {
+                    let text = format!("Job {} finished with {:?}\n{}", result.job_id, result.status, result.stdout.trim_end());
+                    out.print(CliOutput::new(json!(result), text));
+                }
+                Err(e) => {
+                    out.fail(CliError::scheduler("rae::scheduler::run_at_failed", "Failed to run job", &e));
+                }
+            }
+        }
+        
+        SchedulerCommands::Stats => {
+            let result = match out.is_json() {
+                true => scheduler::cli::scheduler_stats().await.map(|stats| CliOutput::new(json!(stats), String::new())),
+                false => scheduler::cli::get_stats().await.map(|stats| CliOutput::new(json!(null), stats)),
+            };
+            
+            match result {
+                Ok(stats) => {
+                    out.print(stats);
+                }
+                Err(e) => {
+                    out.fail(CliError::new("rae::scheduler::stats_failed", format!("Failed to get scheduler stats: {}", full_chain(&e))));
+                }
+            }
+        }
+        
+        SchedulerCommands::EmergencyStop { reason } => {
+            match scheduler::cli::emergency_stop(reason).await {
+                Ok(_) => {
+                    out.print(CliOutput::new(
+                        json!({ "stopped": true, "reason": reason }),
+                        format!("⛔ Emergency stop recorded: {}", reason),
+                    ));
                 }
                 Err(e) => {
-                    eprintln!("Failed to disable job: {}", e);
+                    out.fail(CliError::new("rae::scheduler::stop_failed", format!("Failed to stop scheduler: {}", full_chain(&e))));
                 }
             }
         }
+        
+        SchedulerCommands::Pause => {
+            match scheduler::cli::pause_scheduler().await {
+                Ok(_) => {
+                    out.print(CliOutput::new(json!({ "paused": true }), "⏸️ Scheduler paused. Due jobs are held until 'rae-agent scheduler resume'."));
+                }
+                Err(e) => {
+                    out.fail(CliError::new("rae::scheduler::pause_failed", format!("Failed to pause scheduler: {}", full_chain(&e))));
+                }
+            }
+        }
+        
+        SchedulerCommands::Resume => {
+            match scheduler::cli::resume_scheduler().await {
+                Ok(_) => {
+                    out.print(CliOutput::new(json!({ "paused": false }), "▶️ Scheduler resumed."));
+                }
+                Err(e) => {
+                    out.fail(CliError::new("rae::scheduler::resume_failed", format!("Failed to resume scheduler: {}", full_chain(&e))));
+                }
+            }
+        }
+        
+        SchedulerCommands::ClearStop => {
+            match scheduler::cli::clear_stop().await {
+                Ok(true) => {
+                    out.print(CliOutput::new(json!({ "cleared": true }), "Emergency stop cleared."));
+                }
+                Ok(false) => {
+                    out.print(CliOutput::new(json!({ "cleared": false }), "No emergency stop was in effect."));
+                }
+                Err(e) => {
+                    out.fail(CliError::new("rae::scheduler::clear_stop_failed", format!("Failed to clear emergency stop: {}", full_chain(&e))));
+                }
+            }
+        }
+        
+        SchedulerCommands::Watch { interval_seconds } => {
+            if out.is_json() {
+                out.fail(CliError::new("rae::scheduler::watch_unsupported", "watch is interactive and has no JSON output"));
+            } else if let Err(e) = scheduler::cli::watch_jobs(*interval_seconds).await {
+                out.fail(CliError::new("rae::scheduler::watch_failed", format!("Failed to watch jobs: {}", full_chain(&e))));
+            }
+        }
     }
     
     Ok(())
-} 
\ No newline at end of file
+}
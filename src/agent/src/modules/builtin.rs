@@ -0,0 +1,132 @@
+//! Modules built into the agent.
+
+use crate::config::Config;
+use crate::core::{Digest, Storage};
+use crate::error::Result;
+use chrono::{DateTime, NaiveTime, Utc};
+use std::fs;
+use std::io::ErrorKind;
+use std::path::Path;
+
+/// Opens the section of the summary file written by the summary module.
+pub const GENERATED_SENTINEL: &str = "<!-- rae-generated -->";
+
+/// Closes the generated section.
+const GENERATED_END: &str = "<!-- /rae-generated -->";
+
+/// Modules that ship with the agent.
+pub struct BuiltinModules;
+
+impl BuiltinModules {
+    /// Writes the activities of the day containing `now` into the summary file at `path`.
+    ///
+    /// Activities are grouped by the configured topics, or else by module. The
+    /// section is marked with `<!-- rae-generated -->`, so running again replaces
+    /// it and leaves the rest of the file alone.
+    pub fn summary(storage: &Storage, config: &Config, path: &Path, now: DateTime<Utc>) -> Result<()> {
+        let day_start = now.date_naive().and_time(NaiveTime::MIN).and_utc();
+        let digest = Digest::generate_daily(storage, day_start)?;
+        let markdown = if config.topic_keywords.is_empty() {
+            digest.to_markdown_by_module()
+        } else {
+            digest.to_markdown_by_topic(&config.topic_keywords)
+        };
+        let section = format!("{}\n{}{}\n", GENERATED_SENTINEL, markdown, GENERATED_END);
+        
+        let existing = match fs::read_to_string(path) {
+            Ok(contents) => contents,
+            Err(e) if e.kind() == ErrorKind::NotFound => String::new(),
+            Err(e) => return Err(e.into()),
+        };
+        if let Some(parent) = path.parent().filter(|parent| !parent.as_os_str().is_empty()) {
+            fs::create_dir_all(parent)?;
+        }
+        fs::write(path, replace_generated_section(&existing, &section))?;
+        
+        Ok(())
+    }
+}
+
+/// Replaces the generated section of `existing` with `section`, or appends it.
+fn replace_generated_section(existing: &str, section: &str) -> String {
+    let Some(start) = existing.find(GENERATED_SENTINEL) else {
+        let mut contents = existing.to_string();
+        if !contents.is_empty() {
+            contents.push_str(if contents.ends_with('\n') { "\n" } else { "\n\n" });
+        }
+        contents.push_str(section);
+        return contents;
+    };
+    
+    // A section missing its end marker runs to the end of the file
+    let end = existing[start..].find(GENERATED_END)
+        .map(|offset| start + offset + GENERATED_END.len())
+        .unwrap_or(existing.len());
+    let rest = &existing[end..];
+    
+    format!("{}{}{}", &existing[..start], section, rest.strip_prefix('\n').unwrap_or(rest))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::ActivityData;
+    use chrono::{Duration, TimeZone};
+    use serde_json::json;
+    use std::collections::HashMap;
+    use tempfile::tempdir;
+    
+    #[test]
+    fn test_summary_is_idempotent() {
+        let temp_dir = tempdir().unwrap();
+        let storage = Storage::new(&temp_dir.path().join("rae.db")).unwrap();
+        let now = Utc.with_ymd_and_hms(2026, 3, 2, 17, 0, 0).unwrap();
+        let path = temp_dir.path().join("summary").join("today.md");
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(&path, "# Today's Summary\n\nMy notes.\n").unwrap();
+        
+        storage.store_activity(&ActivityData::new("git".to_string(), json!({ "commits": 2 }))
+            .with_timestamp(now - Duration::hours(8))).unwrap();
+        // Yesterday's activity is left out
+        storage.store_activity(&ActivityData::new("calendar".to_string(), json!({}))
+            .with_timestamp(now - Duration::days(1))).unwrap();
+        BuiltinModules::summary(&storage, &Config::default(), &path, now).unwrap();
+        
+        let first = fs::read_to_string(&path).unwrap();
+        assert!(first.starts_with("# Today's Summary\n\nMy notes.\n\n<!-- rae-generated -->\n# Daily digest: 2026-03-02\n"));
+        assert!(first.contains("### git\n"));
+        assert!(!first.contains("calendar"));
+        assert!(first.ends_with("<!-- /rae-generated -->\n"));
+        
+        // Notes added after the section survive, and the section is replaced in place
+        fs::write(&path, format!("{}\nLater notes.\n", first)).unwrap();
+        storage.store_activity(&ActivityData::new("browser".to_string(), json!({ "title": "docs" }))
+            .with_timestamp(now - Duration::hours(1))).unwrap();
+        BuiltinModules::summary(&storage, &Config::default(), &path, now).unwrap();
+        
+        let second = fs::read_to_string(&path).unwrap();
+        assert_eq!(second.matches(GENERATED_SENTINEL).count(), 1);
+        assert!(second.contains("### browser\n"));
+        assert!(second.ends_with("<!-- /rae-generated -->\n\nLater notes.\n"));
+    }
+    
+    #[test]
+    fn test_summary_groups_by_configured_topics() {
+        let temp_dir = tempdir().unwrap();
+        let storage = Storage::new(&temp_dir.path().join("rae.db")).unwrap();
+        let now = Utc::now();
+        let path = temp_dir.path().join("today.md");
+        
+        storage.store_activity(&ActivityData::new("calendar".to_string(), json!({ "event": "Standup" }))).unwrap();
+        let config = Config {
+            topic_keywords: HashMap::from([("work".to_string(), vec!["standup".to_string()])]),
+            ..Default::default()
+        };
+        BuiltinModules::summary(&storage, &config, &path, now).unwrap();
+        
+        let summary = fs::read_to_string(&path).unwrap();
+        assert!(summary.starts_with(GENERATED_SENTINEL));
+        assert!(summary.contains("### work\n"));
+        assert!(!summary.contains("### calendar\n"));
+    }
+}
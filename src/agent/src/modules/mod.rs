@@ -4,13 +4,33 @@
 //! Rae to be extended with new capabilities while maintaining security
 //! and privacy through sandboxing.
 
-pub mod manager;
-pub mod runner;
-pub mod sandbox;
 pub mod builtin;
+pub mod sandbox;
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
 
 // Re-export main types
-pub use manager::ModuleManager;
-pub use runner::ModuleRunner;
+pub use builtin::BuiltinModules;
 pub use sandbox::ModuleSandbox;
-pub use builtin::BuiltinModules; 
\ No newline at end of file
+
+/// Metadata a module declares in its definition file.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ModuleInfo {
+    /// Unique module name
+    pub name: String,
+    /// Module version
+    pub version: String,
+    /// What the module does
+    #[serde(default)]
+    pub description: Option<String>,
+    /// Capabilities the module requests, e.g. `fs:read:/tmp` or `net:none`
+    #[serde(default)]
+    pub permissions: Vec<String>,
+    /// JSON Schema of the input the module accepts
+    #[serde(default)]
+    pub input_schema: Option<Value>,
+    /// Whether the module's activity data is encrypted in storage
+    #[serde(default)]
+    pub sensitive: bool,
+}
@@ -0,0 +1,225 @@
+//! Capability-based permission checks for modules.
+//!
+//! A module's `permissions` are parsed into capabilities when its sandbox is
+//! created; every file, network and process access the module makes goes
+//! through the sandbox, which denies anything not granted.
+//!
+//! Supported permissions:
+//! - `fs:read:<path>` / `fs:write:<path>`: access below a directory
+//! - `net:none`, `net:local`, `net:any`: network access
+//! - `exec:none`, `exec:any`: spawning processes
+
+use crate::error::{RaeError, Result};
+use crate::modules::ModuleInfo;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Network access granted to a module.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum NetAccess {
+    /// No network access
+    #[default]
+    None,
+    /// Loopback addresses only
+    Local,
+    /// Any host
+    Any,
+}
+
+/// Restricted execution context for a single module.
+#[derive(Debug, Clone)]
+pub struct ModuleSandbox {
+    /// Name of the sandboxed module
+    module: String,
+    /// Canonical directories the module may read from
+    fs_read: Vec<PathBuf>,
+    /// Canonical directories the module may write to
+    fs_write: Vec<PathBuf>,
+    /// Network access
+    net: NetAccess,
+    /// Whether the module may spawn processes
+    exec: bool,
+}
+
+impl ModuleSandbox {
+    /// Creates a sandbox from a module's declared permissions.
+    ///
+    /// Unknown or malformed permissions are rejected rather than ignored.
+    pub fn new(module_info: &ModuleInfo) -> Result<Self> {
+        let mut sandbox = ModuleSandbox {
+            module: module_info.name.clone(),
+            fs_read: Vec::new(),
+            fs_write: Vec::new(),
+            net: NetAccess::None,
+            exec: false,
+        };
+        
+        for permission in &module_info.permissions {
+            let parts: Vec<&str> = permission.splitn(3, ':').collect();
+            match parts.as_slice() {
+                ["fs", "read", path] => sandbox.fs_read.push(canonical_prefix(path)),
+                ["fs", "write", path] => sandbox.fs_write.push(canonical_prefix(path)),
+                ["net", "none"] => sandbox.net = NetAccess::None,
+                ["net", "local"] => sandbox.net = NetAccess::Local,
+                ["net", "any"] => sandbox.net = NetAccess::Any,
+                ["exec", "none"] => sandbox.exec = false,
+                ["exec", "any"] => sandbox.exec = true,
+                _ => {
+                    return Err(RaeError::Security(format!(
+                        "Module {} requests unknown permission: {}", module_info.name, permission
+                    )));
+                }
+            }
+        }
+        
+        Ok(sandbox)
+    }
+    
+    /// Gets the network access granted to the module.
+    pub fn net_access(&self) -> NetAccess {
+        self.net
+    }
+    
+    /// Checks that the module may read a file, returning its canonical path.
+    ///
+    /// The path is canonicalized first, so `..` components and symlinks cannot
+    /// escape the allowed directories.
+    pub fn check_read(&self, path: &Path) -> Result<PathBuf> {
+        let canonical = fs::canonicalize(path).map_err(|e| self.denied("read", path, &e.to_string()))?;
+        if self.fs_read.iter().chain(&self.fs_write).any(|prefix| canonical.starts_with(prefix)) {
+            Ok(canonical)
+        } else {
+            Err(self.denied("read", path, "path is outside the allowed directories"))
+        }
+    }
+    
+    /// Checks that the module may write a file, returning its canonical path.
+    ///
+    /// The file itself may not exist yet, so its parent directory is resolved.
+    pub fn check_write(&self, path: &Path) -> Result<PathBuf> {
+        let (parent, file_name) = match (path.parent(), path.file_name()) {
+            (Some(parent), Some(file_name)) => (parent, file_name),
+            _ => return Err(self.denied("write", path, "not a file path")),
+        };
+        let parent = if parent.as_os_str().is_empty() { Path::new(".") } else { parent };
+        
+        let canonical = fs::canonicalize(parent)
+            .map_err(|e| self.denied("write", path, &e.to_string()))?
+            .join(file_name);
+        // An existing symlink would be followed by the write
+        if fs::symlink_metadata(&canonical).is_ok_and(|meta| meta.file_type().is_symlink()) {
+            return Err(self.denied("write", path, "path is a symlink"));
+        }
+        
+        if self.fs_write.iter().any(|prefix| canonical.starts_with(prefix)) {
+            Ok(canonical)
+        } else {
+            Err(self.denied("write", path, "path is outside the allowed directories"))
+        }
+    }
+    
+    /// Reads a file on behalf of the module.
+    pub fn read_file(&self, path: &Path) -> Result<Vec<u8>> {
+        let canonical = self.check_read(path)?;
+        Ok(fs::read(canonical)?)
+    }
+    
+    /// Writes a file on behalf of the module.
+    pub fn write_file(&self, path: &Path, contents: &[u8]) -> Result<()> {
+        let canonical = self.check_write(path)?;
+        Ok(fs::write(canonical, contents)?)
+    }
+    
+    /// Checks that the module may connect to a host.
+    pub fn check_network(&self, host: &str) -> Result<()> {
+        let local = host == "localhost"
+            || host.trim_start_matches('[').trim_end_matches(']')
+                .parse::<std::net::IpAddr>()
+                .is_ok_and(|ip| ip.is_loopback());
+        
+        match self.net {
+            NetAccess::Any => Ok(()),
+            NetAccess::Local if local => Ok(()),
+            _ => Err(RaeError::Security(format!(
+                "Module {} may not connect to {}", self.module, host
+            ))),
+        }
+    }
+    
+    /// Checks that the module may spawn processes.
+    pub fn check_exec(&self, command: &str) -> Result<()> {
+        if self.exec {
+            Ok(())
+        } else {
+            Err(RaeError::Security(format!(
+                "Module {} may not execute {}", self.module, command
+            )))
+        }
+    }
+    
+    /// Builds the error for a denied file access.
+    fn denied(&self, access: &str, path: &Path, reason: &str) -> RaeError {
+        RaeError::Security(format!(
+            "Module {} may not {} {}: {}", self.module, access, path.display(), reason
+        ))
+    }
+}
+
+/// Canonicalizes an allowed directory, keeping the path as given if it does not exist.
+fn canonical_prefix(path: &str) -> PathBuf {
+    fs::canonicalize(path).unwrap_or_else(|_| PathBuf::from(path))
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+    
+    fn module(permissions: &[&str]) -> ModuleInfo {
+        ModuleInfo {
+            name: "reader".to_string(),
+            version: "1.0.0".to_string(),
+            description: None,
+            permissions: permissions.iter().map(|p| p.to_string()).collect(),
+            input_schema: None,
+            sensitive: false,
+        }
+    }
+    
+    #[test]
+    fn test_fs_read_is_limited_to_allowed_prefix() {
+        let file = tempfile::Builder::new().prefix("foo").tempfile_in("/tmp").unwrap();
+        std::fs::write(file.path(), b"hello").unwrap();
+        let sandbox = ModuleSandbox::new(&module(&["fs:read:/tmp", "net:none", "exec:none"])).unwrap();
+        
+        assert_eq!(sandbox.read_file(file.path()).unwrap(), b"hello");
+        assert!(matches!(sandbox.read_file(Path::new("/etc/passwd")), Err(RaeError::Security(_))));
+        assert!(matches!(sandbox.check_read(Path::new("/tmp/../etc/passwd")), Err(RaeError::Security(_))));
+        
+        // Reading grants no write access
+        assert!(matches!(sandbox.check_write(file.path()), Err(RaeError::Security(_))));
+    }
+    
+    #[test]
+    fn test_symlink_cannot_escape_sandbox() {
+        let dir = tempfile::tempdir().unwrap();
+        let link = dir.path().join("passwd");
+        std::os::unix::fs::symlink("/etc/passwd", &link).unwrap();
+        
+        let permission = format!("fs:write:{}", dir.path().display());
+        let sandbox = ModuleSandbox::new(&module(&[&permission])).unwrap();
+        
+        assert!(matches!(sandbox.check_read(&link), Err(RaeError::Security(_))));
+        assert!(matches!(sandbox.write_file(&link, b"x"), Err(RaeError::Security(_))));
+        sandbox.write_file(&dir.path().join("notes.txt"), b"ok").unwrap();
+    }
+    
+    #[test]
+    fn test_network_and_exec_permissions() {
+        let sandbox = ModuleSandbox::new(&module(&["net:local"])).unwrap();
+        assert!(sandbox.check_network("127.0.0.1").is_ok());
+        assert!(sandbox.check_network("example.com").is_err());
+        assert!(sandbox.check_exec("sh").is_err());
+        
+        assert!(matches!(ModuleSandbox::new(&module(&["fs:delete:/"])), Err(RaeError::Security(_))));
+    }
+}
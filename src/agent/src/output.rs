@@ -0,0 +1,206 @@
+//! Command output for the Rae CLI
+//!
+//! Commands print human-readable text by default and pretty-printed JSON
+//! when the global `--json` flag is set.
+
+use rae_agent::error::full_chain;
+use rae_agent::scheduler::SchedulerError;
+use serde::Serialize;
+use std::error::Error;
+use std::fmt::Display;
+
+/// Result of a command together with its human-readable rendering
+pub struct CliOutput<T> {
+    value: T,
+    text: String,
+}
+
+impl<T: Serialize> CliOutput<T> {
+    /// Creates output from a serializable result and its text form
+    pub fn new(value: T, text: impl Into<String>) -> Self {
+        CliOutput {
+            value,
+            text: text.into(),
+        }
+    }
+    
+    /// Renders the output as JSON or text
+    pub fn render(&self, json: bool) -> String {
+        if json {
+            serde_json::to_string_pretty(&self.value)
+                .unwrap_or_else(|e| CliError::new("rae::cli::serialize_failed", e).render(true))
+        } else {
+            self.text.clone()
+        }
+    }
+}
+
+/// A failed command, with a stable error code for scripts
+#[derive(Debug, Serialize)]
+pub struct CliError {
+    pub error: String,
+    pub code: String,
+}
+
+impl CliError {
+    /// Creates an error with a `rae::<area>::<failure>` code
+    pub fn new(code: &str, error: impl Display) -> Self {
+        CliError {
+            error: error.to_string(),
+            code: code.to_string(),
+        }
+    }
+    
+    /// Creates an error whose message includes the messages of all its sources
+    pub fn from_error(code: &str, error: &dyn Error) -> Self {
+        CliError::new(code, full_chain(error))
+    }
+    
+    /// Creates the error for a failed scheduler command
+    ///
+    /// Failed lookups get the `rae::scheduler::not_found` code so scripts can
+    /// tell a mistyped job ID from a real failure.
+    pub fn scheduler(code: &str, action: &str, e: &SchedulerError) -> Self {
+        if e.is_not_found() {
+            CliError::new("rae::scheduler::not_found", format!("{}: {}", action, full_chain(e)))
+        } else {
+            CliError::new(code, format!("{}: {}", action, full_chain(e)))
+        }
+    }
+    
+    /// Renders the error as JSON or text
+    pub fn render(&self, json: bool) -> String {
+        if json {
+            serde_json::json!({ "error": self.error, "code": self.code }).to_string()
+        } else {
+            format!("Error: {}", self.error)
+        }
+    }
+}
+
+/// Prints command output in the mode selected by `--json`
+#[derive(Debug, Clone, Copy)]
+pub struct Printer {
+    json: bool,
+}
+
+impl Printer {
+    /// Creates a printer for text or JSON output
+    pub fn new(json: bool) -> Self {
+        Printer { json }
+    }
+    
+    /// Whether JSON output was requested
+    pub fn is_json(&self) -> bool {
+        self.json
+    }
+    
+    /// Prints a progress line (text mode only, so JSON stays parseable)
+    pub fn progress(&self, line: impl Display) {
+        if !self.json {
+            println!("{}", line);
+        }
+    }
+    
+    /// Prints the result of a command
+    pub fn print<T: Serialize>(&self, output: CliOutput<T>) {
+        println!("{}", output.render(self.json));
+    }
+    
+    /// Prints a failed command (JSON on stdout, text on stderr)
+    pub fn fail(&self, error: CliError) {
+        if self.json {
+            println!("{}", error.render(true));
+        } else {
+            eprintln!("{}", error.error);
+        }
+    }
+}
+
+/// Agent status reported by `rae-agent status`
+#[derive(Debug, Serialize)]
+pub struct StatusReport {
+    pub running: bool,
+    pub version: String,
+    pub status: String,
+    pub data_dir: String,
+    pub summary_file: String,
+}
+
+impl StatusReport {
+    /// Gets the current agent status
+    pub fn current() -> Self {
+        StatusReport {
+            running: true,
+            version: env!("CARGO_PKG_VERSION").to_string(),
+            status: "Operational".to_string(),
+            data_dir: "~/.rae".to_string(),
+            summary_file: "~/Documents/rae/today.md".to_string(),
+        }
+    }
+    
+    /// Wraps the status for printing
+    pub fn into_output(self) -> CliOutput<Self> {
+        let text = format!(
+            "Rae Agent Status:\n✅ Agent is running\n📊 Version: {}\n🔧 Status: {}\n📁 Data directory: {}\n📄 Summary file: {}",
+            self.version, self.status, self.data_dir, self.summary_file
+        );
+        CliOutput::new(self, text)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::Value;
+    
+    #[test]
+    fn test_output_renders_text_or_json() {
+        let output = CliOutput::new(serde_json::json!({ "job_id": "abc" }), "Job ID: abc");
+        
+        assert_eq!(output.render(false), "Job ID: abc");
+        let value: Value = serde_json::from_str(&output.render(true)).unwrap();
+        assert_eq!(value["job_id"], "abc");
+    }
+    
+    #[test]
+    fn test_error_renders_code() {
+        let error = CliError::new("rae::scheduler::add_failed", "Command cannot be empty");
+        
+        assert_eq!(error.render(false), "Error: Command cannot be empty");
+        let value: Value = serde_json::from_str(&error.render(true)).unwrap();
+        assert_eq!(value["error"], "Command cannot be empty");
+        assert_eq!(value["code"], "rae::scheduler::add_failed");
+    }
+    
+    #[test]
+    fn test_scheduler_not_found_code() {
+        let missing = SchedulerError::from(rae_agent::error::RaeError::not_found("job", "abc"));
+        let error = CliError::scheduler("rae::scheduler::remove_failed", "Failed to remove job", &missing);
+        assert_eq!(error.code, "rae::scheduler::not_found");
+        assert_eq!(error.error, "Failed to remove job: job not found: abc");
+        
+        let paused = CliError::scheduler("rae::scheduler::remove_failed", "Failed to remove job", &SchedulerError::Paused);
+        assert_eq!(paused.code, "rae::scheduler::remove_failed");
+    }
+    
+    #[test]
+    fn test_error_message_includes_sources_once() {
+        let io = SchedulerError::from(std::io::Error::from_raw_os_error(2));
+        let error = CliError::scheduler("rae::scheduler::remove_failed", "Failed to remove job", &io);
+        assert_eq!(error.error, "Failed to remove job: IO error: No such file or directory (os error 2)");
+        
+        let storage = std::io::Error::other(rae_agent::error::RaeError::Storage("database is locked".to_string()));
+        let error = CliError::from_error("rae::storage::vacuum_failed", &storage);
+        assert_eq!(error.error, "Storage error: database is locked");
+    }
+    
+    #[test]
+    fn test_status_report_json() {
+        let value: Value = serde_json::from_str(&StatusReport::current().into_output().render(true)).unwrap();
+        
+        assert_eq!(value["running"], true);
+        assert_eq!(value["version"], env!("CARGO_PKG_VERSION"));
+        assert_eq!(value["data_dir"], "~/.rae");
+    }
+}
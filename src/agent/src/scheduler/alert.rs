@@ -0,0 +1,206 @@
+//! Alerts raised by the job monitor and the sinks they are delivered to.
+//!
+//! The monitor hands every alert to each registered `AlertSink`: the log
+//! (always), a JSON lines file and, under `PrivacyLevel::Open`, a webhook.
+
+use crate::config::{Config, PrivacyLevel};
+use crate::error::{RaeError, Result};
+use crate::scheduler::job::JobId;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::path::PathBuf;
+use tracing::warn;
+
+/// Failure rate above which a job raises an alert unless configured otherwise.
+pub const DEFAULT_FAILURE_RATE_THRESHOLD: f64 = 0.5;
+
+/// Minutes a job may run before it is reported as stuck unless configured otherwise.
+pub const DEFAULT_STUCK_JOB_MINUTES: u64 = 60;
+
+/// What an alert is about.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AlertType {
+    /// A job failed after exhausting its retries
+    JobFailed,
+    /// A job has not run within its SLA interval
+    SlaViolation,
+    /// A job fails more often than the configured threshold
+    HighFailureRate,
+    /// A job has been running longer than the configured threshold
+    StuckJob,
+}
+
+/// A problem with a job, raised by the monitor.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Alert {
+    pub job_id: JobId,
+    pub alert_type: AlertType,
+    pub message: String,
+    pub timestamp: DateTime<Utc>,
+}
+
+impl Alert {
+    /// Creates an alert raised now.
+    pub fn new(job_id: JobId, alert_type: AlertType, message: String) -> Self {
+        Alert {
+            job_id,
+            alert_type,
+            message,
+            timestamp: Utc::now(),
+        }
+    }
+}
+
+/// When the monitor's health checks raise alerts.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct AlertThresholds {
+    /// Share of failed runs (0.0 to 1.0) above which a job raises an alert
+    pub failure_rate: f64,
+    /// Minutes a job may run before it is reported as stuck
+    pub stuck_minutes: u64,
+}
+
+impl Default for AlertThresholds {
+    fn default() -> Self {
+        AlertThresholds {
+            failure_rate: DEFAULT_FAILURE_RATE_THRESHOLD,
+            stuck_minutes: DEFAULT_STUCK_JOB_MINUTES,
+        }
+    }
+}
+
+impl AlertThresholds {
+    /// Reads the thresholds from the configuration.
+    pub fn from_config(config: &Config) -> Self {
+        AlertThresholds {
+            failure_rate: config.alert_failure_rate,
+            stuck_minutes: config.alert_stuck_minutes,
+        }
+    }
+}
+
+/// Destination for monitor alerts.
+pub trait AlertSink: Send + Sync {
+    /// Delivers an alert.
+    fn alert(&self, alert: Alert) -> Result<()>;
+}
+
+/// Writes alerts to the log.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct LogAlerter;
+
+impl AlertSink for LogAlerter {
+    fn alert(&self, alert: Alert) -> Result<()> {
+        warn!("{}", alert.message);
+        Ok(())
+    }
+}
+
+/// Appends alerts to a JSON lines file.
+#[derive(Debug, Clone)]
+pub struct FileAlerter {
+    path: PathBuf,
+}
+
+impl FileAlerter {
+    /// Creates an alerter appending to `path`.
+    pub fn new(path: PathBuf) -> Self {
+        FileAlerter { path }
+    }
+    
+    /// Creates an alerter appending to `alerts.jsonl` in the data directory.
+    pub fn in_data_dir(config: &Config) -> Self {
+        Self::new(config.expanded_data_dir().join("alerts.jsonl"))
+    }
+}
+
+impl AlertSink for FileAlerter {
+    fn alert(&self, alert: Alert) -> Result<()> {
+        if let Some(parent) = self.path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        
+        let mut file = OpenOptions::new().create(true).append(true).open(&self.path)?;
+        writeln!(file, "{}", serde_json::to_string(&alert)?)?;
+        Ok(())
+    }
+}
+
+/// Posts alerts as JSON to a webhook.
+#[derive(Debug, Clone)]
+pub struct WebhookAlerter {
+    url: String,
+    client: reqwest::Client,
+}
+
+impl WebhookAlerter {
+    /// Creates an alerter posting to `url`.
+    ///
+    /// Alerts leave the machine, so this is only allowed under `PrivacyLevel::Open`.
+    pub fn new(url: String, privacy_level: &PrivacyLevel) -> Result<Self> {
+        if *privacy_level != PrivacyLevel::Open {
+            return Err(RaeError::Security(format!(
+                "Webhook alerts to {} require privacy level Open, got {:?}", url, privacy_level
+            )));
+        }
+        
+        Ok(WebhookAlerter {
+            url,
+            client: reqwest::Client::new(),
+        })
+    }
+}
+
+impl AlertSink for WebhookAlerter {
+    /// Queues the POST on the current Tokio runtime; delivery failures are logged.
+    fn alert(&self, alert: Alert) -> Result<()> {
+        let runtime = tokio::runtime::Handle::try_current()
+            .map_err(|e| RaeError::Protocol(format!("Cannot send webhook alert: {}", e)))?;
+        
+        let request = self.client.post(&self.url).json(&alert);
+        runtime.spawn(async move {
+            let sent = request.send().await.and_then(|response| response.error_for_status());
+            if let Err(e) = sent {
+                warn!("Failed to send alert for job {}: {}", alert.job_id, e);
+            }
+        });
+        
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    #[test]
+    fn test_file_alerter_appends_json_lines() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let path = temp_dir.path().join("nested").join("alerts.jsonl");
+        let alerter = FileAlerter::new(path.clone());
+        
+        let first = Alert::new("backup".to_string(), AlertType::StuckJob, "stuck".to_string());
+        let second = Alert::new("sync".to_string(), AlertType::HighFailureRate, "failing".to_string());
+        alerter.alert(first.clone()).unwrap();
+        alerter.alert(second.clone()).unwrap();
+        
+        let alerts: Vec<Alert> = fs::read_to_string(&path).unwrap()
+            .lines()
+            .map(|line| serde_json::from_str(line).unwrap())
+            .collect();
+        assert_eq!(alerts, vec![first, second]);
+        assert!(fs::read_to_string(&path).unwrap().contains(r#""alert_type":"stuck_job""#));
+    }
+    
+    #[test]
+    fn test_webhook_alerter_requires_open_privacy() {
+        let url = "https://hooks.example.com/rae".to_string();
+        
+        assert!(matches!(WebhookAlerter::new(url.clone(), &PrivacyLevel::Strict), Err(RaeError::Security(_))));
+        assert!(matches!(WebhookAlerter::new(url.clone(), &PrivacyLevel::Standard), Err(RaeError::Security(_))));
+        assert!(WebhookAlerter::new(url, &PrivacyLevel::Open).is_ok());
+    }
+}
@@ -0,0 +1,210 @@
+//! Shell aliases for reusable job configurations.
+//!
+//! Aliases are named shortcuts defined in `~/.rae/aliases.toml` that capture
+//! a complete job configuration (command, arguments, environment, retry policy)
+//! so it can be reused without repeating every flag.
+//!
+//! ```toml
+//! [alias.backup]
+//! command = "rsync"
+//! args = ["-avz", "~/Documents", "/Volumes/Backup"]
+//! schedule = "0 2 * * *"
+//! ```
+
+use crate::scheduler::job::{Job, Priority, RetryPolicy};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use thiserror::Error;
+
+/// Errors that can occur while loading aliases.
+#[derive(Debug, Error)]
+pub enum AliasError {
+    #[error("IO error: {0}")]
+    IoError(#[from] std::io::Error),
+    
+    #[error("Invalid alias file: {0}")]
+    ParseError(#[from] toml::de::Error),
+    
+    #[error("Alias not found: {0}")]
+    AliasNotFound(String),
+    
+    #[error("Alias directory error: {0}")]
+    DirectoryError(String),
+}
+
+/// A named job configuration.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ShellAlias {
+    /// Command to execute
+    pub command: String,
+    /// Arguments for the command
+    #[serde(default)]
+    pub args: Vec<String>,
+    /// Environment variables
+    #[serde(default)]
+    pub env: HashMap<String, String>,
+    /// Working directory for execution
+    pub working_dir: Option<String>,
+    /// Description of what the job does
+    pub description: Option<String>,
+    /// Cron expression used when no schedule is given on the command line
+    pub schedule: Option<String>,
+    /// Timezone for scheduling
+    pub timezone: Option<String>,
+    /// Priority level for execution
+    pub priority: Option<Priority>,
+    /// Retry policy for failed executions
+    pub retry_policy: Option<RetryPolicy>,
+}
+
+impl ShellAlias {
+    /// Builds a job from this alias, using the alias name as the job name.
+    pub fn to_job(&self, name: &str) -> Job {
+        let mut job = Job::new(name.to_string(), self.command.clone())
+            .with_args(self.args.clone());
+        
+        for (key, value) in &self.env {
+            job = job.with_env(key.clone(), value.clone());
+        }
+        
+        if let Some(working_dir) = &self.working_dir {
+            job = job.with_working_dir(working_dir.clone());
+        }
+        
+        if let Some(description) = &self.description {
+            job = job.with_description(description.clone());
+        }
+        
+        if let Some(schedule) = &self.schedule {
+            job = job.with_cron(schedule.clone());
+        }
+        
+        if let Some(priority) = self.priority {
+            job = job.with_priority(priority);
+        }
+        
+        if let Some(retry_policy) = &self.retry_policy {
+            job = job.with_retry_policy(retry_policy.clone());
+        }
+        
+        job.schedule.timezone = self.timezone.clone();
+        job
+    }
+}
+
+/// Contents of an aliases file.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct AliasFile {
+    /// Aliases keyed by name
+    #[serde(default)]
+    pub alias: HashMap<String, ShellAlias>,
+}
+
+impl AliasFile {
+    /// Gets the default aliases file path (`~/.rae/aliases.toml`).
+    pub fn default_path() -> Result<PathBuf, AliasError> {
+        let home_dir = dirs::home_dir()
+            .ok_or_else(|| AliasError::DirectoryError(
+                "Could not determine home directory".to_string()
+            ))?;
+        
+        Ok(home_dir.join(".rae").join("aliases.toml"))
+    }
+    
+    /// Loads aliases from a file. A missing file yields no aliases.
+    pub fn load(path: &Path) -> Result<Self, AliasError> {
+        if !path.exists() {
+            return Ok(AliasFile::default());
+        }
+        
+        let content = fs::read_to_string(path)?;
+        Ok(toml::from_str(&content)?)
+    }
+    
+    /// Loads aliases from the default file.
+    pub fn load_default() -> Result<Self, AliasError> {
+        Self::load(&Self::default_path()?)
+    }
+    
+    /// Gets an alias by name.
+    pub fn get(&self, name: &str) -> Option<&ShellAlias> {
+        self.alias.get(name)
+    }
+    
+    /// Checks if an alias is defined.
+    pub fn contains(&self, name: &str) -> bool {
+        self.alias.contains_key(name)
+    }
+    
+    /// Builds a job from the named alias.
+    pub fn job_from_alias(&self, name: &str) -> Result<Job, AliasError> {
+        self.get(name)
+            .map(|alias| alias.to_job(name))
+            .ok_or_else(|| AliasError::AliasNotFound(name.to_string()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+    
+    const FIXTURE: &str = r#"
+[alias.backup]
+command = "rsync"
+args = ["-avz", "/home/user/Documents", "/mnt/backup"]
+schedule = "0 2 * * *"
+priority = "High"
+
+[alias.backup.env]
+RSYNC_RSH = "ssh"
+
+[alias.cleanup]
+command = "find"
+args = ["/tmp", "-mtime", "+7", "-delete"]
+"#;
+    
+    #[test]
+    fn test_job_from_alias_fixture() {
+        let temp_dir = tempdir().unwrap();
+        let path = temp_dir.path().join("aliases.toml");
+        fs::write(&path, FIXTURE).unwrap();
+        
+        let aliases = AliasFile::load(&path).unwrap();
+        let job = aliases.job_from_alias("backup").unwrap();
+        
+        assert_eq!(job.name, "backup");
+        assert_eq!(job.command, "rsync");
+        assert_eq!(job.args, vec!["-avz", "/home/user/Documents", "/mnt/backup"]);
+        assert_eq!(job.schedule.cron.as_deref(), Some("0 2 * * *"));
+        assert_eq!(job.priority, Priority::High);
+        assert_eq!(job.env.get("RSYNC_RSH").map(String::as_str), Some("ssh"));
+        
+        let job = aliases.job_from_alias("cleanup").unwrap();
+        assert_eq!(job.command, "find");
+        assert!(job.schedule.cron.is_none());
+    }
+    
+    #[test]
+    fn test_unknown_alias() {
+        let temp_dir = tempdir().unwrap();
+        let path = temp_dir.path().join("aliases.toml");
+        fs::write(&path, FIXTURE).unwrap();
+        
+        let aliases = AliasFile::load(&path).unwrap();
+        assert!(!aliases.contains("restore"));
+        assert!(matches!(
+            aliases.job_from_alias("restore"),
+            Err(AliasError::AliasNotFound(_))
+        ));
+    }
+    
+    #[test]
+    fn test_missing_alias_file_is_empty() {
+        let temp_dir = tempdir().unwrap();
+        let aliases = AliasFile::load(&temp_dir.path().join("aliases.toml")).unwrap();
+        assert!(aliases.alias.is_empty());
+    }
+}
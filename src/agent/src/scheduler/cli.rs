@@ -1,21 +1,82 @@
-use crate::scheduler::{Scheduler, SchedulerError};
-use crate::scheduler::job::{Job, JobId, JobStatus};
+use crate::error::RaeError;
+use crate::config::Config;
+use crate::scheduler::{JobInfo, Scheduler, SchedulerError};
+use crate::scheduler::alias::AliasFile;
+use crate::scheduler::job::{Job, JobId, JobPatch, JobResult, JobStatus, Priority, Schedule};
+use crate::scheduler::monitor::MonitorStats;
+use crate::scheduler::parser::{ParsedTime, Parser};
+use crate::scheduler::persistence::StorageFormat;
+use chrono::{DateTime, Local, Utc};
+use comfy_table::Table;
+use crossterm::cursor::{Hide, MoveTo, Show};
+use crossterm::event::{self, Event, KeyCode, KeyEventKind, KeyModifiers};
+use crossterm::terminal::{self, Clear, ClearType};
+use crossterm::{execute, queue};
+use serde::Serialize;
+use std::collections::HashMap;
+use std::io::{self, Write};
+use std::str::FromStr;
 use std::sync::OnceLock;
+use std::time::{Duration, Instant};
 
 /// Global scheduler instance for CLI operations
 static SCHEDULER: OnceLock<Scheduler> = OnceLock::new();
 
+/// ID of the built-in job that vacuums the activity store.
+pub const VACUUM_JOB_ID: &str = "rae-storage-vacuum";
+
 /// Initialize the scheduler for CLI operations
 pub async fn init_scheduler() -> Result<(), SchedulerError> {
     if SCHEDULER.get().is_none() {
-        let scheduler = Scheduler::new().await?;
+        let config = load_config()?;
+        let storage_format = if config.compact { StorageFormat::Archive } else { config.job_storage_format };
+        let scheduler = Scheduler::new().await?
+            .with_storage_format(storage_format)?
+            .with_history_limit(config.job_history_limit)
+            .with_alerting(&config);
+        scheduler.set_concurrency_limit(config.max_concurrent_jobs).await;
         SCHEDULER.set(scheduler).map_err(|_| SchedulerError::InvalidJob("Failed to set scheduler".to_string()))?;
     }
     
     // Start the scheduler if it's not already running
     let scheduler = get_scheduler()?;
     scheduler.start().await?;
+    ensure_vacuum_job(scheduler, &load_config()?).await?;
+    
+    // Warn on stderr so command output stays machine-readable
+    if let Some(stop) = scheduler.emergency_stop_state()? {
+        eprintln!(
+            "⛔ Scheduler is paused by an emergency stop since {}: {}",
+            stop.stopped_at.with_timezone(&Local).format("%Y-%m-%d %H:%M"),
+            stop.reason
+        );
+        eprintln!("   Run 'rae-agent scheduler clear-stop' to resume.");
+    }
+    
+    Ok(())
+}
+
+/// Schedules the built-in storage vacuum job unless it already exists.
+///
+/// The job runs `rae-agent storage --vacuum`. It can be disabled, but is
+/// added again if it is removed.
+async fn ensure_vacuum_job(scheduler: &Scheduler, config: &Config) -> Result<(), SchedulerError> {
+    if scheduler.list_jobs().await?.iter().any(|info| info.job.id == VACUUM_JOB_ID) {
+        return Ok(());
+    }
+    
+    let exe = std::env::current_exe()?;
+    let mut job = Job::new("storage-vacuum".to_string(), exe.to_string_lossy().into_owned())
+        .with_args(vec!["storage".to_string(), "--vacuum".to_string()])
+        .with_cron(config.vacuum_schedule.clone())
+        .with_priority(Priority::Low)
+        .with_tags(vec!["builtin".to_string()])
+        .with_description(format!(
+            "Delete activity older than {} days and compact the database", config.activity_retention_days
+        ));
+    job.id = VACUUM_JOB_ID.to_string();
     
+    scheduler.add_job(job).await?;
     Ok(())
 }
 
@@ -25,6 +86,7 @@ fn get_scheduler() -> Result<&'static Scheduler, SchedulerError> {
 }
 
 /// Add a new scheduled job
+#[allow(clippy::too_many_arguments)]
 pub async fn add_job(
     name: String,
     schedule: String,
@@ -32,6 +94,10 @@ pub async fn add_job(
     args: Option<Vec<String>>,
     timezone: Option<String>,
     description: Option<String>,
+    priority: Option<Priority>,
+    tags: Vec<String>,
+    env_groups: Vec<String>,
+    template_vars: Vec<String>,
 ) -> Result<JobId, SchedulerError> {
     let scheduler = get_scheduler()?;
     
@@ -39,6 +105,11 @@ pub async fn add_job(
     let mut job = Job::new(name.clone(), command.clone())
         .with_args(args.unwrap_or_default());
     
+    // Declaring placeholders makes the job a template
+    if !template_vars.is_empty() {
+        job = job.as_template(template_vars);
+    }
+    
     // Set the cron schedule
     if !schedule.is_empty() {
         job = job.with_cron(schedule.clone());
@@ -54,38 +125,251 @@ pub async fn add_job(
         job = job.with_description(desc.clone());
     }
     
+    if let Some(priority) = priority {
+        job = job.with_priority(priority);
+    }
+    
+    if !tags.is_empty() {
+        job = job.with_tags(tags);
+    }
+    
+    // Inherit environment groups
+    job = apply_env_groups(job, &env_groups)?;
+    
     // Add the job to the scheduler
     scheduler.add_job(job).await
 }
 
-/// List all scheduled jobs
-pub async fn list_jobs(verbose: bool) -> Result<Vec<String>, SchedulerError> {
+/// Add a new scheduled job by instantiating a template with `KEY=VAL` variables
+pub async fn add_template_job(template_id: String, vars: Vec<String>) -> Result<JobId, SchedulerError> {
     let scheduler = get_scheduler()?;
     
-    let jobs = scheduler.list_jobs().await?;
+    let vars = parse_template_vars(&vars)?;
+    scheduler.instantiate_template(&template_id, &vars).await
+}
+
+/// Parse `KEY=VAL` pairs given with `--var`
+fn parse_template_vars(vars: &[String]) -> Result<HashMap<String, String>, SchedulerError> {
+    vars.iter()
+        .map(|var| {
+            var.split_once('=')
+                .map(|(key, value)| (key.to_string(), value.to_string()))
+                .ok_or_else(|| SchedulerError::InvalidJob(format!("Expected KEY=VAL, got: {}", var)))
+        })
+        .collect()
+}
+
+/// Add a new scheduled job from a shell alias, overriding alias fields that are given
+pub async fn add_alias_job(
+    alias: String,
+    name: Option<String>,
+    schedule: Option<String>,
+    args: Option<Vec<String>>,
+    timezone: Option<String>,
+    description: Option<String>,
+    env_groups: Vec<String>,
+) -> Result<JobId, SchedulerError> {
+    let scheduler = get_scheduler()?;
     
-    let mut output = Vec::new();
-    for job_info in jobs {
-        if verbose {
-            output.push(format!(
-                "ID: {}\nName: {}\nStatus: {:?}\nSchedule: {:?}\nCommand: {}\n---",
-                job_info.job.id,
-                job_info.job.name,
-                job_info.status,
-                job_info.job.schedule,
-                job_info.job.command
-            ));
-        } else {
-            output.push(format!(
-                "{} - {} - {:?}",
-                job_info.job.id,
-                job_info.job.name,
-                job_info.status
-            ));
+    let mut job = Job::from_alias(&alias)?;
+    
+    if let Some(name) = name {
+        job.name = name;
+    }
+    
+    if let Some(schedule) = schedule {
+        job = job.with_cron(schedule);
+    }
+    
+    if let Some(args) = args {
+        job = job.with_args(args);
+    }
+    
+    if let Some(tz) = timezone {
+        job.schedule.timezone = Some(tz);
+    }
+    
+    if let Some(desc) = description {
+        job = job.with_description(desc);
+    }
+    
+    job = apply_env_groups(job, &env_groups)?;
+    
+    scheduler.add_job(job).await
+}
+
+/// Merge the named environment groups from the user config into a job
+fn apply_env_groups(mut job: Job, env_groups: &[String]) -> Result<Job, SchedulerError> {
+    if env_groups.is_empty() {
+        return Ok(job);
+    }
+    
+    let config = load_config()?;
+    for group in env_groups {
+        if config.env_group(group).is_none() {
+            return Err(SchedulerError::InvalidJob(format!("Unknown environment group: {}", group)));
         }
+        job = job.with_env_group(&config, group);
     }
     
-    Ok(output)
+    Ok(job)
+}
+
+/// Load the configuration from `~/.rae/rae.toml`
+fn load_config() -> Result<Config, SchedulerError> {
+    let Some(home_dir) = dirs::home_dir() else {
+        return Ok(Config::default());
+    };
+    
+    let config = Config::load_from_file(&home_dir.join(".rae").join("rae.toml"))
+        .and_then(|config| config.validate().map(|_| config))
+        .map_err(|e| SchedulerError::InvalidJob(e.to_string()))?;
+    
+    Ok(config)
+}
+
+/// Check whether a command name matches a defined shell alias
+pub fn is_alias(command: &str) -> bool {
+    AliasFile::load_default()
+        .map(|aliases| aliases.contains(command))
+        .unwrap_or(false)
+}
+
+/// Format of job listings
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum OutputFormat {
+    /// The full job information as a JSON array
+    Json,
+    /// An aligned table with one row per job
+    Table,
+    /// One line per job, or a block per job when verbose
+    #[default]
+    Plain,
+}
+
+impl FromStr for OutputFormat {
+    type Err = SchedulerError;
+    
+    /// Parses a format name, ignoring case.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "json" => Ok(OutputFormat::Json),
+            "table" => Ok(OutputFormat::Table),
+            "plain" => Ok(OutputFormat::Plain),
+            _ => Err(SchedulerError::InvalidJob(format!(
+                "Unknown output format: {} (expected json, table or plain)", s
+            ))),
+        }
+    }
+}
+
+/// List all scheduled jobs, optionally only those with a tag, rendered in `format`
+pub async fn list_jobs(verbose: bool, format: OutputFormat, tag: Option<&str>) -> Result<String, SchedulerError> {
+    let scheduler = get_scheduler()?;
+    let jobs = list_job_infos(tag).await?;
+    
+    let mut next_runs = HashMap::new();
+    if format == OutputFormat::Table {
+        for job_info in &jobs {
+            if let Some(next_run) = scheduler.get_next_execution(&job_info.job.id).await {
+                next_runs.insert(job_info.job.id.clone(), next_run);
+            }
+        }
+    }
+    
+    render_jobs(&jobs, &next_runs, verbose, format)
+}
+
+/// Render jobs in `format`; `next_runs` fills the Next Run column of tables
+///
+/// `verbose` only applies to plain output.
+fn render_jobs(
+    jobs: &[JobInfo],
+    next_runs: &HashMap<JobId, DateTime<Utc>>,
+    verbose: bool,
+    format: OutputFormat,
+) -> Result<String, SchedulerError> {
+    match format {
+        OutputFormat::Json => Ok(serde_json::to_string_pretty(jobs).map_err(RaeError::from)?),
+        OutputFormat::Table => {
+            let mut table = Table::new();
+            table.set_header(vec!["ID", "Name", "Schedule", "Status", "Last Run", "Next Run"]);
+            for job_info in jobs {
+                table.add_row(vec![
+                    job_info.job.id.clone(),
+                    job_info.job.name.clone(),
+                    schedule_label(&job_info.job),
+                    status_label(&job_info.status),
+                    format_time(job_info.job.last_run_at),
+                    format_time(next_runs.get(&job_info.job.id).copied()),
+                ]);
+            }
+            Ok(table.to_string())
+        }
+        OutputFormat::Plain if jobs.is_empty() => Ok("No scheduled jobs found.".to_string()),
+        OutputFormat::Plain => {
+            let mut output = Vec::new();
+            for job_info in jobs {
+                if verbose {
+                    output.push(format!(
+                        "ID: {}\nName: {}\nStatus: {:?}\nSchedule: {:?}\nCommand: {}\nTags: {}\n---",
+                        job_info.job.id,
+                        job_info.job.name,
+                        job_info.status,
+                        job_info.job.schedule,
+                        job_info.job.command,
+                        job_info.job.tags.join(", ")
+                    ));
+                } else {
+                    output.push(format!(
+                        "{} - {} - {:?}",
+                        job_info.job.id,
+                        job_info.job.name,
+                        job_info.status
+                    ));
+                }
+            }
+            
+            Ok(output.join("\n"))
+        }
+    }
+}
+
+/// Summary of a scheduled job for machine-readable output
+#[derive(Debug, Clone, Serialize)]
+pub struct JobSummary {
+    pub id: JobId,
+    pub name: String,
+    pub status: String,
+    pub next_run: Option<DateTime<Utc>>,
+    pub tags: Vec<String>,
+}
+
+/// List all scheduled jobs as summaries, optionally only those with a tag
+pub async fn list_job_summaries(tag: Option<&str>) -> Result<Vec<JobSummary>, SchedulerError> {
+    let scheduler = get_scheduler()?;
+    
+    let mut summaries = Vec::new();
+    for job_info in list_job_infos(tag).await? {
+        summaries.push(JobSummary {
+            next_run: scheduler.get_next_execution(&job_info.job.id).await,
+            status: status_label(&job_info.status),
+            id: job_info.job.id,
+            name: job_info.job.name,
+            tags: job_info.job.tags,
+        });
+    }
+    
+    Ok(summaries)
+}
+
+/// List all jobs, or only those carrying `tag`
+async fn list_job_infos(tag: Option<&str>) -> Result<Vec<JobInfo>, SchedulerError> {
+    let scheduler = get_scheduler()?;
+    match tag {
+        Some(tag) => scheduler.list_jobs_by_tag(tag).await,
+        None => scheduler.list_jobs().await,
+    }
 }
 
 /// Remove a scheduled job
@@ -94,43 +378,219 @@ pub async fn remove_job(job_id: &str) -> Result<(), SchedulerError> {
     scheduler.remove_job(&job_id.to_string()).await
 }
 
-/// Get job status
-pub async fn get_job_status(job_id: Option<&str>) -> Result<String, SchedulerError> {
+/// Update the schedule, command or metadata of an existing job
+///
+/// A new schedule replaces the old one, including its timezone.
+#[allow(clippy::too_many_arguments)]
+pub async fn update_job(
+    job_id: &str,
+    schedule: Option<String>,
+    timezone: Option<String>,
+    command: Option<String>,
+    args: Option<Vec<String>>,
+    priority: Option<Priority>,
+    description: Option<String>,
+) -> Result<Job, SchedulerError> {
+    let scheduler = get_scheduler()?;
+    
+    let patch = JobPatch {
+        schedule: schedule.map(|cron| Schedule {
+            cron: Some(cron),
+            timezone,
+            ..Default::default()
+        }),
+        command,
+        args,
+        priority,
+        description,
+        ..Default::default()
+    };
+    if patch.is_empty() {
+        return Err(SchedulerError::InvalidJob("Nothing to update".to_string()));
+    }
+    
+    scheduler.update_job(&job_id.to_string(), patch).await
+}
+
+/// Run a command once at the given time without creating a persistent job.
+///
+/// Waits for the run to finish, since the job only lives in this process.
+pub async fn run_at(
+    command: String,
+    args: Vec<String>,
+    at: &str,
+    name: Option<String>,
+) -> Result<JobResult, SchedulerError> {
     let scheduler = get_scheduler()?;
     
+    let mut job = Job::new(name.unwrap_or_default(), command)
+        .with_args(args)
+        .with_max_runs(1);
+    if job.name.is_empty() {
+        job.name = format!("run-at-{}", job.id);
+    }
+    match Parser::parse_human_time(at).map_err(|e| SchedulerError::InvalidJob(e.to_string()))? {
+        ParsedTime::Absolute(dt) => job.schedule.at = Some(dt),
+        ParsedTime::Recurring(cron_expr) => {
+            return Err(SchedulerError::InvalidJob(format!(
+                "{} is a recurring time; use `rae scheduler add --schedule \"{}\"` instead", at, cron_expr
+            )));
+        }
+    }
+    
+    // Subscribe first so a run that is already due is not missed
+    let completions = scheduler.subscribe_completions();
+    let failures = scheduler.subscribe_failures();
+    let job_id = scheduler.run_at(job).await?;
+    
+    wait_for_result(completions, failures, job_id).await
+}
+
+/// Run a persisted job immediately, bypassing its schedule.
+///
+/// With `wait`, blocks until the run finishes and returns its result.
+pub async fn run_job_now(job_id: &str, wait: bool) -> Result<(JobId, Option<JobResult>), SchedulerError> {
+    let scheduler = get_scheduler()?;
+    
+    // Subscribe first so a quick run is not missed
+    let completions = scheduler.subscribe_completions();
+    let failures = scheduler.subscribe_failures();
+    let run_id = scheduler.run_job_now(&job_id.to_string()).await?;
+    
+    if !wait {
+        return Ok((run_id, None));
+    }
+    
+    let result = wait_for_result(completions, failures, run_id.clone()).await?;
+    Ok((run_id, Some(result)))
+}
+
+/// Waits for the completion or final failure of a run.
+async fn wait_for_result(
+    mut completions: tokio::sync::broadcast::Receiver<JobResult>,
+    mut failures: tokio::sync::broadcast::Receiver<JobResult>,
+    job_id: JobId,
+) -> Result<JobResult, SchedulerError> {
+    loop {
+        let result = tokio::select! {
+            result = completions.recv() => result,
+            result = failures.recv() => result,
+        };
+        
+        match result {
+            Ok(result) if result.job_id == job_id => return Ok(result),
+            Ok(_) | Err(tokio::sync::broadcast::error::RecvError::Lagged(_)) => continue,
+            Err(tokio::sync::broadcast::error::RecvError::Closed) => {
+                return Err(RaeError::not_found("job", job_id).into());
+            }
+        }
+    }
+}
+
+/// Overall scheduler status
+#[derive(Debug, Clone, Serialize)]
+pub struct SchedulerOverview {
+    pub total_jobs: usize,
+    pub active_jobs: usize,
+    /// Whether job execution is paused, for maintenance or by an emergency stop
+    pub paused: bool,
+}
+
+/// Get the status of a single job
+pub async fn job_status(job_id: &str) -> Result<JobStatus, SchedulerError> {
+    let scheduler = get_scheduler()?;
+    scheduler.get_job_status(&job_id.to_string()).await
+}
+
+/// Get the overall scheduler status
+pub async fn scheduler_overview() -> Result<SchedulerOverview, SchedulerError> {
+    let scheduler = get_scheduler()?;
+    
+    let jobs = scheduler.list_jobs().await?;
+    Ok(SchedulerOverview {
+        total_jobs: jobs.len(),
+        active_jobs: jobs.iter().filter(|j| j.status == JobStatus::Scheduled).count(),
+        paused: scheduler.is_paused(),
+    })
+}
+
+/// Get job status
+pub async fn get_job_status(job_id: Option<&str>) -> Result<String, SchedulerError> {
     match job_id {
         Some(id) => {
-            let status = scheduler.get_job_status(&id.to_string()).await?;
+            let status = job_status(id).await?;
             Ok(format!("Job {} status: {:?}", id, status))
         }
         None => {
-            // Return overall scheduler status
-            let jobs = scheduler.list_jobs().await?;
-            let total_jobs = jobs.len();
-            let active_jobs = jobs.iter().filter(|j| j.status == JobStatus::Scheduled).count();
-            
+            let overview = scheduler_overview().await?;
+            let state = if overview.paused {
+                "⏸️ Scheduler is paused (run 'rae-agent scheduler resume' or 'clear-stop' to continue)"
+            } else {
+                "✅ Scheduler is running"
+            };
             Ok(format!(
-                "Scheduler Status:\n✅ Scheduler is running\n📊 Total jobs: {}\n🔄 Active jobs: {}",
-                total_jobs, active_jobs
+                "Scheduler Status:\n{}\n📊 Total jobs: {}\n🔄 Active jobs: {}",
+                state, overview.total_jobs, overview.active_jobs
             ))
         }
     }
 }
 
+/// Get raw scheduler statistics
+pub async fn scheduler_stats() -> Result<MonitorStats, SchedulerError> {
+    let scheduler = get_scheduler()?;
+    Ok(scheduler.get_stats().await)
+}
+
+/// Get scheduler statistics
+pub async fn get_stats() -> Result<String, SchedulerError> {
+    let stats = scheduler_stats().await?;
+    
+    Ok(format!(
+        "Scheduler Statistics:\n📊 Total jobs: {}\n🔄 Running: {}\n✅ Completed: {}\n❌ Failed: {}\n🚨 SLA violations: {}\n⏱️ {}",
+        stats.total_jobs,
+        stats.running_jobs,
+        stats.completed_jobs,
+        stats.failed_jobs,
+        stats.sla_violations,
+        stats.format_latency_report()
+    ))
+}
+
+/// Halt all jobs and record the reason
+pub async fn emergency_stop(reason: &str) -> Result<(), SchedulerError> {
+    let scheduler = get_scheduler()?;
+    scheduler.emergency_stop(reason).await
+}
+
+/// Pause job execution for maintenance
+pub async fn pause_scheduler() -> Result<(), SchedulerError> {
+    let scheduler = get_scheduler()?;
+    scheduler.pause().await
+}
+
+/// Resume job execution after a pause
+pub async fn resume_scheduler() -> Result<(), SchedulerError> {
+    let scheduler = get_scheduler()?;
+    scheduler.resume().await
+}
+
+/// Clear an emergency stop. Returns whether a stop was in effect.
+pub async fn clear_stop() -> Result<bool, SchedulerError> {
+    let scheduler = get_scheduler()?;
+    scheduler.clear_emergency_stop().await
+}
+
 /// Enable a job
 pub async fn enable_job(job_id: &str) -> Result<(), SchedulerError> {
-    // TODO: Implement job enable functionality
-    // This would require adding an enable_job method to the Scheduler
-    println!("Enabling job: {}", job_id);
-    Ok(())
+    let scheduler = get_scheduler()?;
+    scheduler.enable_job(&job_id.to_string()).await
 }
 
 /// Disable a job
 pub async fn disable_job(job_id: &str) -> Result<(), SchedulerError> {
-    // TODO: Implement job disable functionality
-    // This would require adding a disable_job method to the Scheduler
-    println!("Disabling job: {}", job_id);
-    Ok(())
+    let scheduler = get_scheduler()?;
+    scheduler.disable_job(&job_id.to_string()).await
 }
 
 /// Start the scheduler
@@ -143,4 +603,332 @@ pub async fn start_scheduler() -> Result<(), SchedulerError> {
 pub async fn stop_scheduler() -> Result<(), SchedulerError> {
     let scheduler = get_scheduler()?;
     scheduler.stop().await
-} 
\ No newline at end of file
+} 
+
+/// A single row in the `scheduler watch` status table
+#[derive(Debug, Clone)]
+pub struct WatchRow {
+    pub name: String,
+    pub status: JobStatus,
+    pub last_run: Option<DateTime<Utc>>,
+    pub next_run: Option<DateTime<Utc>>,
+    pub success_rate: Option<f64>,
+}
+
+/// Terminal view that redraws the job status table on every refresh
+pub struct WatchView<W: Write> {
+    out: W,
+    frames: u64,
+}
+
+impl<W: Write> WatchView<W> {
+    /// Create a view drawing to the given terminal backend
+    pub fn new(out: W) -> Self {
+        WatchView { out, frames: 0 }
+    }
+    
+    /// Clear the screen and draw the status table
+    pub fn draw(&mut self, rows: &[WatchRow]) -> io::Result<()> {
+        queue!(self.out, Clear(ClearType::All), MoveTo(0, 0))?;
+        self.frames += 1;
+        
+        // Raw mode needs explicit carriage returns
+        write!(
+            self.out,
+            "Rae Scheduler - {} - press q to quit\r\n\r\n",
+            Local::now().format("%Y-%m-%d %H:%M:%S")
+        )?;
+        write!(
+            self.out,
+            "{:<24} {:<14} {:<19} {:<19} {:>8}\r\n",
+            "NAME", "STATUS", "LAST RUN", "NEXT RUN", "SUCCESS"
+        )?;
+        
+        if rows.is_empty() {
+            write!(self.out, "No scheduled jobs found.\r\n")?;
+        }
+        
+        for row in rows {
+            write!(
+                self.out,
+                "{:<24} {:<14} {:<19} {:<19} {:>8}\r\n",
+                truncate(&row.name, 24),
+                status_label(&row.status),
+                format_time(row.last_run),
+                format_time(row.next_run),
+                row.success_rate
+                    .map(|rate| format!("{:.0}%", rate * 100.0))
+                    .unwrap_or_else(|| "-".to_string()),
+            )?;
+        }
+        
+        self.out.flush()
+    }
+    
+    /// Number of frames drawn so far
+    pub fn frames(&self) -> u64 {
+        self.frames
+    }
+    
+    /// Consume the view and return the terminal backend
+    pub fn into_inner(self) -> W {
+        self.out
+    }
+}
+
+/// Watch live job execution status, refreshing every `interval_seconds` until `q` is pressed
+pub async fn watch_jobs(interval_seconds: u64) -> Result<(), SchedulerError> {
+    let scheduler = get_scheduler()?;
+    let interval = Duration::from_secs(interval_seconds.max(1));
+    
+    let mut view = WatchView::new(io::stdout());
+    terminal::enable_raw_mode()?;
+    
+    let result: Result<(), SchedulerError> = async {
+        execute!(io::stdout(), Hide)?;
+        loop {
+            let rows = collect_watch_rows(scheduler).await?;
+            view.draw(&rows)?;
+            
+            let quit = tokio::task::spawn_blocking(move || wait_for_quit(interval))
+                .await
+                .map_err(io::Error::other)??;
+            if quit {
+                return Ok(());
+            }
+        }
+    }
+    .await;
+    
+    // Always restore the terminal, even if a refresh failed, and run every
+    // restore step before reporting the first error
+    let shown = execute!(io::stdout(), Show);
+    let restored = terminal::disable_raw_mode();
+    println!();
+    
+    result?;
+    shown?;
+    restored?;
+    Ok(())
+}
+
+/// Build the status rows for every scheduled job
+async fn collect_watch_rows(scheduler: &Scheduler) -> Result<Vec<WatchRow>, SchedulerError> {
+    let jobs = scheduler.list_jobs().await?;
+    let mut rows = Vec::with_capacity(jobs.len());
+    
+    for job_info in jobs {
+        let health = scheduler.get_job_health(&job_info.job.id).await?;
+        let runs = health.execution_count + health.failure_count;
+        
+        rows.push(WatchRow {
+            name: job_info.job.name.clone(),
+            status: job_info.status,
+            last_run: health.last_execution,
+            next_run: scheduler.get_next_execution(&job_info.job.id).await,
+            success_rate: if runs > 0 {
+                Some(health.execution_count as f64 / runs as f64)
+            } else {
+                None
+            },
+        });
+    }
+    
+    rows.sort_by(|a, b| a.name.cmp(&b.name));
+    Ok(rows)
+}
+
+/// Block for up to `timeout` waiting for `q` (or Ctrl+C); returns true if the user quit
+fn wait_for_quit(timeout: Duration) -> io::Result<bool> {
+    let deadline = Instant::now() + timeout;
+    
+    loop {
+        let remaining = deadline.saturating_duration_since(Instant::now());
+        if remaining.is_zero() || !event::poll(remaining)? {
+            return Ok(false);
+        }
+        
+        if let Event::Key(key) = event::read()? {
+            if key.kind != KeyEventKind::Press {
+                continue;
+            }
+            
+            match key.code {
+                KeyCode::Char('q') | KeyCode::Char('Q') | KeyCode::Esc => return Ok(true),
+                KeyCode::Char('c') if key.modifiers.contains(KeyModifiers::CONTROL) => return Ok(true),
+                _ => {}
+            }
+        }
+    }
+}
+
+/// Short status label for table output
+fn status_label(status: &JobStatus) -> String {
+    match status {
+        JobStatus::Scheduled => "Scheduled".to_string(),
+        JobStatus::Running => "Running".to_string(),
+        JobStatus::Completed => "Completed".to_string(),
+        JobStatus::Failed { .. } => "Failed".to_string(),
+        JobStatus::Cancelled => "Cancelled".to_string(),
+        JobStatus::Retrying { attempts, max_attempts } => format!("Retrying {}/{}", attempts, max_attempts),
+        JobStatus::Suspended => "Suspended".to_string(),
+        JobStatus::Disabled => "Disabled".to_string(),
+    }
+}
+
+/// Short description of when a job runs, for table output
+fn schedule_label(job: &Job) -> String {
+    let schedule = &job.schedule;
+    if let Some(cron) = job.active_cron() {
+        match &schedule.timezone {
+            Some(timezone) => format!("{} ({})", cron, timezone),
+            None => cron.to_string(),
+        }
+    } else if let Some(at) = schedule.at {
+        format!("at {}", format_time(Some(at)))
+    } else if let Some(event) = &schedule.event {
+        format!("on {:?}", event.event_type)
+    } else if let Some(pattern) = &schedule.pattern {
+        format!("on {:?}", pattern.pattern_type)
+    } else {
+        "-".to_string()
+    }
+}
+
+/// Format an optional timestamp in local time
+fn format_time(time: Option<DateTime<Utc>>) -> String {
+    time.map(|t| t.with_timezone(&Local).format("%Y-%m-%d %H:%M:%S").to_string())
+        .unwrap_or_else(|| "-".to_string())
+}
+
+/// Truncate a string to at most `width` characters
+fn truncate(value: &str, width: usize) -> String {
+    if value.chars().count() <= width {
+        value.to_string()
+    } else {
+        let mut truncated: String = value.chars().take(width - 1).collect();
+        truncated.push('…');
+        truncated
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    fn row(name: &str, status: JobStatus, success_rate: Option<f64>) -> WatchRow {
+        WatchRow {
+            name: name.to_string(),
+            status,
+            last_run: None,
+            next_run: Some(Utc::now()),
+            success_rate,
+        }
+    }
+    
+    #[test]
+    fn test_watch_view_renders_columns() {
+        let mut view = WatchView::new(Vec::new());
+        
+        view.draw(&[row("backup", JobStatus::Scheduled, None)]).unwrap();
+        
+        let output = String::from_utf8_lossy(&view.into_inner()).to_string();
+        for column in ["NAME", "STATUS", "LAST RUN", "NEXT RUN", "SUCCESS"] {
+            assert!(output.contains(column), "missing column {}", column);
+        }
+        assert!(output.contains("backup"));
+        assert!(output.contains("Scheduled"));
+    }
+    
+    #[test]
+    fn test_watch_view_redraws_updated_rows() {
+        let mut view = WatchView::new(Vec::new());
+        
+        // First refresh: job has not run yet
+        view.draw(&[row("backup", JobStatus::Running, None)]).unwrap();
+        let first_len = view.out.len();
+        
+        // Second refresh: job completed once
+        view.draw(&[row("backup", JobStatus::Completed, Some(1.0))]).unwrap();
+        assert_eq!(view.frames(), 2);
+        
+        let output = view.into_inner();
+        let second_frame = String::from_utf8_lossy(&output[first_len..]).to_string();
+        assert!(second_frame.contains("Completed"));
+        assert!(second_frame.contains("100%"));
+        assert!(!second_frame.contains("Running"));
+    }
+    
+    #[test]
+    fn test_status_label_and_truncate() {
+        let status = JobStatus::Retrying { attempts: 2, max_attempts: 3 };
+        assert_eq!(status_label(&status), "Retrying 2/3");
+        assert_eq!(status_label(&JobStatus::Failed { error: "boom".to_string() }), "Failed");
+        
+        assert_eq!(truncate("short", 10), "short");
+        assert_eq!(truncate("a-very-long-job-name", 8).chars().count(), 8);
+    }
+    
+    /// A job with fixed fields, so its rendering does not change between runs
+    fn known_job() -> JobInfo {
+        let mut job = Job::new("Nightly backup".to_string(), "rsync".to_string())
+            .with_cron("0 0 3 * * *".to_string())
+            .with_args(vec!["-a".to_string()]);
+        job.id = "backup".to_string();
+        job.tags = vec!["ops".to_string()];
+        job.created_at = "2024-05-01T00:00:00Z".parse().unwrap();
+        job.updated_at = job.created_at;
+        job.last_run_at = Some("2024-05-02T03:00:00Z".parse().unwrap());
+        
+        JobInfo { job, status: JobStatus::Scheduled }
+    }
+    
+    #[test]
+    fn test_render_jobs_in_each_format() {
+        let jobs = vec![known_job()];
+        let next_run: DateTime<Utc> = "2024-05-03T03:00:00Z".parse().unwrap();
+        let next_runs = HashMap::from([("backup".to_string(), next_run)]);
+        
+        let plain = render_jobs(&jobs, &next_runs, false, OutputFormat::Plain).unwrap();
+        assert_eq!(plain, "backup - Nightly backup - Scheduled");
+        assert_eq!(render_jobs(&[], &next_runs, false, OutputFormat::Plain).unwrap(), "No scheduled jobs found.");
+        
+        // Times are shown in local time, which is 19 characters wide
+        let last_run = format_time(jobs[0].job.last_run_at);
+        let next_run = format_time(Some(next_run));
+        let table = render_jobs(&jobs, &next_runs, false, OutputFormat::Table).unwrap();
+        assert_eq!(table, format!("\
++--------+----------------+-------------+-----------+---------------------+---------------------+
+| ID     | Name           | Schedule    | Status    | Last Run            | Next Run            |
++===============================================================================================+
+| backup | Nightly backup | 0 0 3 * * * | Scheduled | {} | {} |
++--------+----------------+-------------+-----------+---------------------+---------------------+",
+            last_run, next_run
+        ));
+        
+        let json = render_jobs(&jobs, &next_runs, true, OutputFormat::Json).unwrap();
+        let value: serde_json::Value = serde_json::from_str(&json).unwrap();
+        assert_eq!(value, serde_json::to_value(&jobs).unwrap());
+        assert_eq!(value[0]["job"]["id"], "backup");
+        assert_eq!(value[0]["job"]["schedule"]["cron"], "0 0 3 * * *");
+        assert_eq!(value[0]["job"]["last_run_at"], "2024-05-02T03:00:00Z");
+        assert_eq!(value[0]["status"], "Scheduled");
+    }
+    
+    #[test]
+    fn test_parse_output_format() {
+        assert_eq!(OutputFormat::from_str("JSON").unwrap(), OutputFormat::Json);
+        assert_eq!(OutputFormat::from_str("table").unwrap(), OutputFormat::Table);
+        assert_eq!(OutputFormat::default(), OutputFormat::Plain);
+        assert!(OutputFormat::from_str("yaml").is_err());
+    }
+    
+    #[test]
+    fn test_parse_template_vars() {
+        let vars = parse_template_vars(&["MODULE=rae-agent".to_string(), "FILTER=a=b".to_string()]).unwrap();
+        assert_eq!(vars.get("MODULE").map(String::as_str), Some("rae-agent"));
+        assert_eq!(vars.get("FILTER").map(String::as_str), Some("a=b"));
+        
+        assert!(parse_template_vars(&["MODULE".to_string()]).is_err());
+    }
+}
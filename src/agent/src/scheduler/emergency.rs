@@ -0,0 +1,87 @@
+//! Emergency stop marker for the scheduler.
+//!
+//! An emergency stop is recorded in `~/.rae/scheduler/EMERGENCY_STOP` so that
+//! the scheduler stays paused across restarts until the stop is cleared.
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+/// A recorded emergency stop.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct EmergencyStop {
+    /// Why the scheduler was stopped
+    pub reason: String,
+    /// When the stop was issued
+    pub stopped_at: DateTime<Utc>,
+}
+
+impl EmergencyStop {
+    /// Creates a stop record for the current time.
+    pub fn new(reason: &str) -> Self {
+        EmergencyStop {
+            reason: reason.to_string(),
+            stopped_at: Utc::now(),
+        }
+    }
+    
+    /// Gets the default marker path (`~/.rae/scheduler/EMERGENCY_STOP`).
+    pub fn default_path() -> Option<PathBuf> {
+        dirs::home_dir().map(|home| home.join(".rae").join("scheduler").join("EMERGENCY_STOP"))
+    }
+    
+    /// Writes the marker file, creating its directory if needed.
+    pub fn write(&self, path: &Path) -> io::Result<()> {
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        
+        let content = serde_json::to_string_pretty(self)?;
+        fs::write(path, content)
+    }
+    
+    /// Reads the marker file, if present.
+    pub fn read(path: &Path) -> io::Result<Option<Self>> {
+        if !path.exists() {
+            return Ok(None);
+        }
+        
+        let content = fs::read_to_string(path)?;
+        Ok(Some(serde_json::from_str(&content)?))
+    }
+    
+    /// Removes the marker file. Returns whether a stop was cleared.
+    pub fn clear(path: &Path) -> io::Result<bool> {
+        if !path.exists() {
+            return Ok(false);
+        }
+        
+        fs::remove_file(path)?;
+        Ok(true)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+    
+    #[test]
+    fn test_write_read_and_clear() {
+        let temp_dir = tempdir().unwrap();
+        let path = temp_dir.path().join("scheduler").join("EMERGENCY_STOP");
+        
+        assert_eq!(EmergencyStop::read(&path).unwrap(), None);
+        
+        let stop = EmergencyStop::new("runaway backup job");
+        stop.write(&path).unwrap();
+        assert!(path.is_file());
+        assert_eq!(EmergencyStop::read(&path).unwrap(), Some(stop));
+        
+        assert!(EmergencyStop::clear(&path).unwrap());
+        assert!(!path.exists());
+        assert!(!EmergencyStop::clear(&path).unwrap());
+    }
+}
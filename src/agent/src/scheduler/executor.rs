@@ -3,15 +3,41 @@
 //! Provides cross-platform job execution with platform-appropriate
 //! process management, resource limits, and error handling.
 
-use crate::scheduler::job::{Job, JobId, JobResult, JobStatus, ResourceUsage};
-use chrono::{DateTime, Utc};
-use std::collections::HashMap;
-use std::process::{Command, Stdio};
-use std::sync::Arc;
-use tokio::sync::{mpsc, RwLock};
+use crate::config::Config;
+use crate::error::RaeError;
+use crate::scheduler::file_watch::FileWatch;
+use crate::scheduler::job::{Job, JobId, JobResult, JobStatus, OutputDestination};
+use crate::scheduler::output;
+use crate::scheduler::results::ResultStore;
+use crate::scheduler::usage::{self, UsageTracker};
+use chrono::Utc;
+use futures_util::Stream;
+use rand::Rng;
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::process::Stdio;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use tokio::io::{AsyncRead, AsyncReadExt, AsyncWriteExt};
+use tokio::process::Command;
+use tokio::sync::{broadcast, mpsc, watch, Notify, RwLock};
 use tokio::time::{sleep, Duration};
 use thiserror::Error;
-use tracing::{debug, error, info, warn};
+use tracing::{debug, error, info, info_span, instrument, warn, Instrument};
+
+/// How long a job waits before its dependencies are checked again.
+const DEFAULT_DEPENDENCY_WAIT: Duration = Duration::from_secs(5);
+
+/// Jobs run at the same time unless configured otherwise.
+pub const DEFAULT_MAX_CONCURRENT_JOBS: usize = 4;
+
+/// How often a job waiting for a free execution slot checks again.
+const CONCURRENCY_POLL_INTERVAL: Duration = Duration::from_secs(1);
+
+/// How long a killed job may take to exit before it is killed forcibly.
+pub const DEFAULT_KILL_GRACE_PERIOD: Duration = Duration::from_secs(5);
+
+/// How often a running job's resource usage is sampled.
+const USAGE_SAMPLE_INTERVAL: Duration = Duration::from_millis(50);
 
 /// Errors that can occur during job execution.
 #[derive(Debug, Error)]
@@ -33,6 +59,9 @@ pub enum ExecutorError {
     
     #[error("Invalid job configuration: {0}")]
     InvalidJob(String),
+    
+    #[error(transparent)]
+    Rae(#[from] RaeError),
 }
 
 /// Job executor with thread pool and retry logic.
@@ -43,8 +72,42 @@ pub struct JobExecutor {
     running_jobs: Arc<RwLock<HashMap<JobId, RunningJob>>>,
     /// Job results
     job_results: Arc<RwLock<HashMap<JobId, JobResult>>>,
+    /// Results of jobs that failed after exhausting their retries
+    failure_sender: broadcast::Sender<JobResult>,
+    /// Results of jobs that completed successfully
+    completion_sender: broadcast::Sender<JobResult>,
     /// Shutdown signal
     shutdown: Arc<RwLock<bool>>,
+    /// Delay before re-checking the dependencies of a waiting job
+    dependency_wait: Arc<RwLock<Duration>>,
+    /// On-disk history results are also written to
+    result_store: Arc<RwLock<Option<Arc<ResultStore>>>>,
+    /// Maximum number of jobs running at the same time
+    max_concurrent_jobs: Arc<RwLock<usize>>,
+    /// Signalled whenever a running job finishes or execution resumes
+    slot_freed: Arc<Notify>,
+    /// Whether queued jobs are held instead of dispatched
+    paused: Arc<AtomicBool>,
+    /// How long a killed job may take to exit before it is killed forcibly
+    kill_grace_period: RwLock<Duration>,
+    /// Watches of jobs triggered by file events
+    file_watchers: Mutex<HashMap<JobId, FileWatch>>,
+}
+
+/// State shared by the dispatch loop and the tasks running jobs.
+#[derive(Clone)]
+struct ExecutionContext {
+    job_sender: mpsc::Sender<JobExecutionRequest>,
+    running_jobs: Arc<RwLock<HashMap<JobId, RunningJob>>>,
+    job_results: Arc<RwLock<HashMap<JobId, JobResult>>>,
+    failure_sender: broadcast::Sender<JobResult>,
+    completion_sender: broadcast::Sender<JobResult>,
+    result_store: Arc<RwLock<Option<Arc<ResultStore>>>>,
+    /// Jobs whose failed attempt has a retry queued
+    retrying: Arc<RwLock<HashSet<JobId>>>,
+    slot_freed: Arc<Notify>,
+    paused: Arc<AtomicBool>,
+    config: Arc<Config>,
 }
 
 /// Request to execute a job.
@@ -52,39 +115,169 @@ pub struct JobExecutor {
 struct JobExecutionRequest {
     job: Job,
     attempt: u32,
+    stdin: Option<String>,
+    /// Receives output lines as they are printed, then the final result
+    output: Option<mpsc::UnboundedSender<JobOutputLine>>,
+}
+
+/// An item of a job's streamed output.
+#[derive(Debug, Clone)]
+pub enum JobOutputLine {
+    /// A line printed on standard output
+    Stdout(String),
+    /// A line printed on standard error
+    Stderr(String),
+    /// The final result, always the last item
+    Completed(JobResult),
 }
 
 /// Information about a running job.
 #[derive(Debug)]
 struct RunningJob {
     job: Job,
-    start_time: DateTime<Utc>,
     attempt: u32,
+    /// Set to the grace period to kill the job's process
+    kill: watch::Sender<Option<Duration>>,
+}
+
+/// State of a job's dependencies.
+#[derive(Debug, PartialEq)]
+enum DependencyCheck {
+    /// Every dependency has completed
+    Ready,
+    /// The dependency has not completed yet
+    Pending(JobId),
+    /// The dependency failed or was cancelled
+    Failed(JobId),
+}
+
+/// Ring buffer for captured process output.
+///
+/// Holds at most `capacity` bytes; when more arrive, the oldest bytes are
+/// dropped so the tail of the output (usually the most useful part) is kept.
+#[derive(Debug)]
+struct OutputBuffer {
+    bytes: VecDeque<u8>,
+    capacity: usize,
+    truncated: bool,
+}
+
+impl OutputBuffer {
+    /// Creates an empty buffer holding at most `capacity` bytes.
+    fn new(capacity: usize) -> Self {
+        OutputBuffer {
+            bytes: VecDeque::new(),
+            capacity,
+            truncated: false,
+        }
+    }
+    
+    /// Appends bytes, dropping the oldest ones on overflow.
+    fn push(&mut self, data: &[u8]) {
+        // Only the last `capacity` bytes of this chunk can survive
+        let keep = &data[data.len().saturating_sub(self.capacity)..];
+        if keep.len() < data.len() {
+            self.truncated = true;
+        }
+        
+        let overflow = (self.bytes.len() + keep.len()).saturating_sub(self.capacity);
+        if overflow > 0 {
+            self.bytes.drain(..overflow);
+            self.truncated = true;
+        }
+        
+        self.bytes.extend(keep);
+    }
+    
+    /// Whether any output was dropped.
+    fn is_truncated(&self) -> bool {
+        self.truncated
+    }
+    
+    /// Converts the buffered bytes into a string.
+    fn into_string(self) -> String {
+        let mut bytes: Vec<u8> = self.bytes.into();
+        
+        // Dropping from the front can split a UTF-8 sequence; skip the orphaned
+        // continuation bytes so the lossy conversion cannot grow past capacity.
+        if self.truncated {
+            let start = bytes.iter().take(3).take_while(|b| (**b & 0xC0) == 0x80).count();
+            bytes.drain(..start);
+        }
+        
+        String::from_utf8_lossy(&bytes).into_owned()
+    }
+}
+
+/// Where captured lines go: the output sender and the variant wrapping each line.
+type LineSink<'a> = (&'a mpsc::UnboundedSender<JobOutputLine>, fn(String) -> JobOutputLine);
+
+/// Converts a line of process output into a string without its line ending.
+fn output_line(bytes: &[u8]) -> String {
+    let bytes = bytes.strip_suffix(b"\r").unwrap_or(bytes);
+    String::from_utf8_lossy(bytes).into_owned()
 }
 
 impl JobExecutor {
     /// Creates a new job executor.
     pub fn new() -> Self {
+        Self::with_config(Config::default())
+    }
+    
+    /// Creates a job executor that delivers output according to `config`.
+    pub fn with_config(config: Config) -> Self {
         let (job_sender, job_receiver) = mpsc::channel(100);
         let running_jobs = Arc::new(RwLock::new(HashMap::new()));
         let job_results = Arc::new(RwLock::new(HashMap::new()));
+        let (failure_sender, _) = broadcast::channel(100);
+        let (completion_sender, _) = broadcast::channel(100);
         let shutdown = Arc::new(RwLock::new(false));
+        let dependency_wait = Arc::new(RwLock::new(DEFAULT_DEPENDENCY_WAIT));
+        let result_store = Arc::new(RwLock::new(None));
+        let max_concurrent_jobs = Arc::new(RwLock::new(config.max_concurrent_jobs.max(1)));
+        let slot_freed = Arc::new(Notify::new());
         
         let executor = JobExecutor {
             job_sender,
             running_jobs,
             job_results,
+            failure_sender,
+            completion_sender,
             shutdown,
+            dependency_wait,
+            result_store,
+            max_concurrent_jobs,
+            slot_freed,
+            paused: Arc::new(AtomicBool::new(false)),
+            kill_grace_period: RwLock::new(DEFAULT_KILL_GRACE_PERIOD),
+            file_watchers: Mutex::new(HashMap::new()),
         };
         
         // Start the job processing loop
-        let running_jobs_clone = executor.running_jobs.clone();
-        let job_results_clone = executor.job_results.clone();
-        let job_sender_clone = executor.job_sender.clone();
+        let context = ExecutionContext {
+            job_sender: executor.job_sender.clone(),
+            running_jobs: executor.running_jobs.clone(),
+            job_results: executor.job_results.clone(),
+            failure_sender: executor.failure_sender.clone(),
+            completion_sender: executor.completion_sender.clone(),
+            result_store: executor.result_store.clone(),
+            retrying: Arc::new(RwLock::new(HashSet::new())),
+            slot_freed: executor.slot_freed.clone(),
+            paused: executor.paused.clone(),
+            config: Arc::new(config),
+        };
         let shutdown_clone = executor.shutdown.clone();
+        let dependency_wait_clone = executor.dependency_wait.clone();
+        let max_concurrent_jobs_clone = executor.max_concurrent_jobs.clone();
         
         tokio::spawn(async move {
-            Self::process_jobs(job_receiver, job_sender_clone, running_jobs_clone, job_results_clone, shutdown_clone).await;
+            Self::process_jobs(
+                job_receiver,
+                context,
+                shutdown_clone,
+                dependency_wait_clone,
+                max_concurrent_jobs_clone,
+            ).await;
         });
         
         executor
@@ -104,8 +297,75 @@ impl JobExecutor {
         Ok(())
     }
     
+    /// Sets how long a job waits before its dependencies are checked again.
+    pub async fn set_dependency_wait(&self, wait: Duration) {
+        *self.dependency_wait.write().await = wait;
+    }
+    
+    /// Holds queued jobs instead of starting them; running jobs are not affected.
+    pub fn pause(&self) {
+        self.paused.store(true, Ordering::SeqCst);
+        info!("Job executor paused");
+    }
+    
+    /// Starts the jobs held while paused, up to the concurrency limit.
+    pub fn resume(&self) {
+        self.paused.store(false, Ordering::SeqCst);
+        self.slot_freed.notify_waiters();
+        info!("Job executor resumed");
+    }
+    
+    /// Checks if queued jobs are being held.
+    pub fn is_paused(&self) -> bool {
+        self.paused.load(Ordering::SeqCst)
+    }
+    
+    /// Sets how many jobs may run at the same time (at least one).
+    pub async fn set_concurrency_limit(&self, limit: usize) {
+        *self.max_concurrent_jobs.write().await = limit.max(1);
+        // Jobs waiting for a slot may be able to start now
+        self.slot_freed.notify_waiters();
+    }
+    
+    /// Gets the number of jobs that are currently running.
+    pub async fn running_count(&self) -> usize {
+        self.running_jobs.read().await.len()
+    }
+    
+    /// Sets the store that keeps the history of job results on disk.
+    pub async fn set_result_store(&self, store: Arc<ResultStore>) {
+        *self.result_store.write().await = Some(store);
+    }
+    
     /// Executes a job.
     pub async fn execute_job(&self, job: Job) -> Result<JobId, ExecutorError> {
+        self.submit(job, None, None).await
+    }
+    
+    /// Executes a job with `input` written to its standard input.
+    pub async fn execute_job_with_input(&self, job: Job, input: String) -> Result<JobId, ExecutorError> {
+        self.submit(job, Some(input), None).await
+    }
+    
+    /// Executes a job, yielding its output lines as they are printed.
+    ///
+    /// The stream ends with the final result of the job, after any retries.
+    pub async fn execute_job_streaming(&self, job: Job) -> Result<impl Stream<Item = JobOutputLine> + use<>, ExecutorError> {
+        let (sender, receiver) = mpsc::unbounded_channel();
+        self.submit(job, None, Some(sender)).await?;
+        
+        Ok(futures_util::stream::unfold(receiver, |mut receiver| async move {
+            receiver.recv().await.map(|line| (line, receiver))
+        }))
+    }
+    
+    /// Validates a job and queues it for execution.
+    async fn submit(
+        &self,
+        job: Job,
+        stdin: Option<String>,
+        output: Option<mpsc::UnboundedSender<JobOutputLine>>,
+    ) -> Result<JobId, ExecutorError> {
         let job_id = job.id.clone();
         
         // Validate job
@@ -115,6 +375,8 @@ impl JobExecutor {
         let request = JobExecutionRequest {
             job,
             attempt: 1,
+            stdin,
+            output,
         };
         
         self.job_sender
@@ -125,6 +387,62 @@ impl JobExecutor {
         Ok(job_id)
     }
     
+    /// Watches the paths of jobs with a file event trigger, running a job
+    /// whenever its event fires.
+    ///
+    /// Bursts of events within `file_watch::DEBOUNCE_WINDOW` run the job once.
+    /// A job that is already watched is watched again with its new trigger;
+    /// jobs without a file event trigger are skipped.
+    pub fn start_file_watchers(&self, jobs: &[Job]) -> Result<(), ExecutorError> {
+        for job in jobs {
+            let Some(trigger) = &job.schedule.event else {
+                continue;
+            };
+            let Some((watch, mut events)) = FileWatch::start(trigger)? else {
+                continue;
+            };
+            self.validate_job(job)?;
+            
+            let sender = self.job_sender.clone();
+            let job = job.clone();
+            let job_id = job.id.clone();
+            tokio::spawn(async move {
+                // Ends when the watch is dropped
+                while events.recv().await.is_some() {
+                    debug!("File event triggered job {}", job.id);
+                    let request = JobExecutionRequest {
+                        job: job.clone(),
+                        attempt: 1,
+                        stdin: None,
+                        output: None,
+                    };
+                    if sender.send(request).await.is_err() {
+                        break;
+                    }
+                }
+            });
+            
+            self.file_watchers.lock().unwrap_or_else(|e| e.into_inner()).insert(job_id, watch);
+        }
+        
+        Ok(())
+    }
+    
+    /// Stops watching the trigger path of a job; returns whether it was watched.
+    pub fn stop_file_watcher(&self, job_id: &JobId) -> bool {
+        self.file_watchers.lock().unwrap_or_else(|e| e.into_inner()).remove(job_id).is_some()
+    }
+    
+    /// Subscribes to results of jobs that completed successfully.
+    pub fn subscribe_completions(&self) -> broadcast::Receiver<JobResult> {
+        self.completion_sender.subscribe()
+    }
+    
+    /// Subscribes to results of jobs that failed after exhausting their retries.
+    pub fn subscribe_failures(&self) -> broadcast::Receiver<JobResult> {
+        self.failure_sender.subscribe()
+    }
+    
     /// Gets the status of a job.
     pub async fn get_job_status(&self, job_id: &JobId) -> Result<JobStatus, ExecutorError> {
         // Check if job is running
@@ -152,33 +470,59 @@ impl JobExecutor {
         Ok(job_results.get(job_id).cloned())
     }
     
+    /// Gets the IDs of jobs that are currently running.
+    pub async fn running_job_ids(&self) -> Vec<JobId> {
+        let running_jobs = self.running_jobs.read().await;
+        running_jobs.keys().cloned().collect()
+    }
+    
     /// Cancels a running job.
+    ///
+    /// The job's process is terminated in the background; use `kill_job` to
+    /// wait for it to exit.
     pub async fn cancel_job(&self, job_id: &JobId) -> Result<(), ExecutorError> {
-        let mut running_jobs = self.running_jobs.write().await;
+        let grace_period = *self.kill_grace_period.read().await;
         
-        if let Some(running_job) = running_jobs.remove(job_id) {
-            // TODO: Implement actual process termination
-            warn!("Cancelled job: {}", job_id);
-            
-            // Add cancelled result
-            let result = JobResult {
-                job_id: job_id.clone(),
-                started_at: running_job.start_time,
-                ended_at: Some(Utc::now()),
-                exit_code: None,
-                stdout: String::new(),
-                stderr: "Job cancelled".to_string(),
-                status: JobStatus::Cancelled,
-                resource_usage: None,
-            };
-            
-            let mut job_results = self.job_results.write().await;
-            job_results.insert(job_id.clone(), result);
+        if let Some(running_job) = self.running_jobs.read().await.get(job_id) {
+            warn!("Cancelling job: {}", job_id);
+            running_job.kill.send_replace(Some(grace_period));
         }
         
         Ok(())
     }
     
+    /// Sets how long a killed job may take to exit before it is killed forcibly.
+    pub async fn set_kill_grace_period(&self, grace_period: Duration) {
+        *self.kill_grace_period.write().await = grace_period;
+    }
+    
+    /// Kills a running job and waits for its process to exit.
+    ///
+    /// On Unix the process gets SIGTERM and, if it is still running after the
+    /// grace period, SIGKILL; on Windows it is terminated right away. The
+    /// returned result is `Cancelled` with the process's actual exit code.
+    pub async fn kill_job(&self, job_id: &JobId) -> Result<JobResult, ExecutorError> {
+        if !self.running_jobs.read().await.contains_key(job_id) {
+            return Err(RaeError::not_found("running job", job_id).into());
+        }
+        self.cancel_job(job_id).await?;
+        
+        loop {
+            // Register before checking so the job finishing in between is not missed
+            let slot_freed = self.slot_freed.notified();
+            tokio::pin!(slot_freed);
+            slot_freed.as_mut().enable();
+            
+            if !self.running_jobs.read().await.contains_key(job_id) {
+                break;
+            }
+            slot_freed.await;
+        }
+        
+        self.job_results.read().await.get(job_id).cloned()
+            .ok_or_else(|| RaeError::not_found("job result", job_id).into())
+    }
+    
     /// Validates a job configuration.
     fn validate_job(&self, job: &Job) -> Result<(), ExecutorError> {
         if job.command.is_empty() {
@@ -189,16 +533,117 @@ impl JobExecutor {
             return Err(ExecutorError::InvalidJob("Job is disabled".to_string()));
         }
         
+        if let Some(user) = &job.running_as {
+            Self::resolve_run_as_user(user)?;
+        }
+        
+        job.retry_policy.validate_patterns()
+            .map_err(|e| ExecutorError::InvalidJob(format!("Invalid retry pattern: {}", e)))?;
+        
         Ok(())
     }
     
+    /// Resolves the uid/gid to switch to for `running_as`.
+    ///
+    /// Returns `None` when the agent already runs as that user.
+    #[cfg(unix)]
+    fn resolve_run_as_user(user: &str) -> Result<Option<(u32, u32)>, RaeError> {
+        let entry = nix::unistd::User::from_name(user)
+            .map_err(|e| RaeError::Security(format!("failed to look up user {}: {}", user, e)))?
+            .ok_or_else(|| RaeError::Security(format!("unknown user {}", user)))?;
+        
+        let target_uid = entry.uid.as_raw();
+        let euid = nix::unistd::geteuid().as_raw();
+        if euid == target_uid {
+            return Ok(None);
+        }
+        
+        Self::check_run_as_privileges(user, target_uid, euid, Self::has_setuid_capability())?;
+        Ok(Some((target_uid, entry.gid.as_raw())))
+    }
+    
+    /// Resolves the uid/gid to switch to for `running_as`.
+    #[cfg(not(unix))]
+    fn resolve_run_as_user(user: &str) -> Result<Option<(u32, u32)>, RaeError> {
+        Err(RaeError::Security(format!("running jobs as user {} is only supported on Unix", user)))
+    }
+    
+    /// Checks that a process with `euid` may switch to `target_uid`.
+    #[cfg(unix)]
+    fn check_run_as_privileges(user: &str, target_uid: u32, euid: u32, has_capability: bool) -> Result<(), RaeError> {
+        if euid == 0 || euid == target_uid || has_capability {
+            Ok(())
+        } else {
+            Err(RaeError::Security(format!("insufficient privileges to run as user {}", user)))
+        }
+    }
+    
+    /// Whether the process holds both CAP_SETUID and CAP_SETGID.
+    #[cfg(target_os = "linux")]
+    fn has_setuid_capability() -> bool {
+        const CAP_SETGID: u64 = 1 << 6;
+        const CAP_SETUID: u64 = 1 << 7;
+        
+        std::fs::read_to_string("/proc/self/status")
+            .ok()
+            .and_then(|status| {
+                status.lines()
+                    .find_map(|line| line.strip_prefix("CapEff:"))
+                    .and_then(|caps| u64::from_str_radix(caps.trim(), 16).ok())
+            })
+            .map(|caps| caps & (CAP_SETUID | CAP_SETGID) == (CAP_SETUID | CAP_SETGID))
+            .unwrap_or(false)
+    }
+    
+    /// Whether the process holds both CAP_SETUID and CAP_SETGID.
+    #[cfg(all(unix, not(target_os = "linux")))]
+    fn has_setuid_capability() -> bool {
+        false
+    }
+    
+    /// Checks the latest results of a job's dependencies.
+    ///
+    /// A failed or cancelled dependency takes precedence over one that is
+    /// still pending; a failed dependency with a retry queued is pending.
+    async fn check_dependencies(
+        job: &Job,
+        job_results: &RwLock<HashMap<JobId, JobResult>>,
+        retrying: &HashSet<JobId>,
+    ) -> DependencyCheck {
+        let job_results = job_results.read().await;
+        let mut pending = None;
+        
+        for dependency in &job.depends_on {
+            match job_results.get(dependency).map(|result| &result.status) {
+                Some(JobStatus::Completed) => {}
+                _ if retrying.contains(dependency) => {
+                    pending.get_or_insert_with(|| dependency.clone());
+                }
+                Some(JobStatus::Failed { .. } | JobStatus::Cancelled) => {
+                    return DependencyCheck::Failed(dependency.clone());
+                }
+                _ => {
+                    pending.get_or_insert_with(|| dependency.clone());
+                }
+            }
+        }
+        
+        match pending {
+            Some(dependency) => DependencyCheck::Pending(dependency),
+            None => DependencyCheck::Ready,
+        }
+    }
+    
     /// Processes jobs from the channel.
+    ///
+    /// Requests are dispatched in order; each job runs in its own task once a
+    /// slot below the concurrency limit is free.
     async fn process_jobs(
         mut job_receiver: mpsc::Receiver<JobExecutionRequest>,
-        job_sender: mpsc::Sender<JobExecutionRequest>,
-        running_jobs: Arc<RwLock<HashMap<JobId, RunningJob>>>,
-        job_results: Arc<RwLock<HashMap<JobId, JobResult>>>,
+        context: ExecutionContext,
         shutdown: Arc<RwLock<bool>>,
+        dependency_wait: Arc<RwLock<Duration>>,
+        max_concurrent_jobs: Arc<RwLock<usize>>,
     ) {
         while let Some(request) = job_receiver.recv().await {
             // Check if we should shutdown
@@ -207,65 +652,247 @@ impl JobExecutor {
             }
             let job_id = request.job.id.clone();
             
+            // Hold the job back until its dependencies have completed
+            let dependencies = {
+                let retrying = context.retrying.read().await;
+                Self::check_dependencies(&request.job, &context.job_results, &retrying).await
+            };
+            match dependencies {
+                DependencyCheck::Ready => {}
+                DependencyCheck::Pending(dependency) => {
+                    let wait = *dependency_wait.read().await;
+                    debug!("Job {} is waiting for dependency {}", job_id, dependency);
+                    
+                    let job_sender = context.job_sender.clone();
+                    tokio::spawn(async move {
+                        sleep(wait).await;
+                        if let Err(e) = job_sender.send(request).await {
+                            warn!("Failed to re-queue job {} waiting for {}: {}", job_id, dependency, e);
+                        }
+                    });
+                    continue;
+                }
+                DependencyCheck::Failed(dependency) => {
+                    let now = Utc::now();
+                    let error = format!("Dependency {} did not complete", dependency);
+                    error!("Job {} failed: {}", job_id, error);
+                    
+                    let result = JobResult {
+                        job_id: job_id.clone(),
+                        started_at: now,
+                        ended_at: Some(now),
+                        exit_code: None,
+                        stdout: String::new(),
+                        stderr: String::new(),
+                        truncated: false,
+                        status: JobStatus::Failed { error },
+                        resource_usage: None,
+                    };
+                    
+                    context.job_results.write().await.insert(job_id, result.clone());
+                    Self::save_result(&context.result_store, &result).await;
+                    if let Some(output) = &request.output {
+                        let _ = output.send(JobOutputLine::Completed(result.clone()));
+                    }
+                    let _ = context.failure_sender.send(result);
+                    continue;
+                }
+            }
+            
+            // A job never runs alongside itself; try again once the earlier run may be done
+            if context.running_jobs.read().await.contains_key(&job_id) {
+                debug!("Job {} is already running, re-queueing it", job_id);
+                
+                let job_sender = context.job_sender.clone();
+                tokio::spawn(async move {
+                    sleep(CONCURRENCY_POLL_INTERVAL).await;
+                    if let Err(e) = job_sender.send(request).await {
+                        warn!("Failed to re-queue job {} while it is running: {}", job_id, e);
+                    }
+                });
+                continue;
+            }
+            
+            Self::wait_while_paused(&context, &job_id).await;
+            Self::wait_for_slot(&context, &max_concurrent_jobs, &job_id).await;
+            context.retrying.write().await.remove(&job_id);
+            
             // Add to running jobs
+            let (kill, killed) = watch::channel(None);
             {
-                let mut jobs = running_jobs.write().await;
+                let mut jobs = context.running_jobs.write().await;
                 jobs.insert(job_id.clone(), RunningJob {
                     job: request.job.clone(),
-                    start_time: Utc::now(),
                     attempt: request.attempt,
+                    kill,
                 });
             }
             
-            // Execute job
-            let job = request.job.clone();
-            let result = Self::execute_single_job(job.clone(), request.attempt).await;
+            // Spawned tasks do not inherit the current span, so attach one
+            let span = info_span!("job_run", job_id = %request.job.id);
+            tokio::spawn(Self::run_request(context.clone(), request, killed).instrument(span));
+        }
+    }
+    
+    /// Waits until fewer than `max_concurrent_jobs` jobs are running.
+    async fn wait_for_slot(context: &ExecutionContext, max_concurrent_jobs: &RwLock<usize>, job_id: &JobId) {
+        loop {
+            // Register before checking so a job finishing in between is not missed
+            let slot_freed = context.slot_freed.notified();
+            tokio::pin!(slot_freed);
+            slot_freed.as_mut().enable();
             
-            // Remove from running jobs
             {
-                let mut jobs = running_jobs.write().await;
-                jobs.remove(&job_id);
+                let running_jobs = context.running_jobs.read().await;
+                if running_jobs.len() < *max_concurrent_jobs.read().await {
+                    return;
+                }
             }
             
-            // Store result
+            debug!("Job {} is waiting for a free execution slot", job_id);
+            let _ = tokio::time::timeout(CONCURRENCY_POLL_INTERVAL, slot_freed).await;
+        }
+    }
+    
+    /// Waits until the executor is not paused.
+    async fn wait_while_paused(context: &ExecutionContext, job_id: &JobId) {
+        loop {
+            // Register before checking so a resume in between is not missed
+            let resumed = context.slot_freed.notified();
+            tokio::pin!(resumed);
+            resumed.as_mut().enable();
+            
+            if !context.paused.load(Ordering::SeqCst) {
+                return;
+            }
+            
+            debug!("Job {} is held while the executor is paused", job_id);
+            resumed.await;
+        }
+    }
+    
+    /// Runs a dispatched job and handles its result.
+    async fn run_request(context: ExecutionContext, request: JobExecutionRequest, killed: watch::Receiver<Option<Duration>>) {
+        let job = request.job;
+        let job_id = job.id.clone();
+        let mut result = Self::execute_single_job(
+            job.clone(),
+            request.attempt,
+            request.stdin.as_deref(),
+            request.output.as_ref(),
+            Some(killed.clone()),
+        ).await;
+        
+        // A killed job is cancelled, not failed, and never retried
+        if killed.borrow().is_some() {
+            info!("Job {} was killed", job_id);
+            result.status = JobStatus::Cancelled;
+        }
+        
+        // Decide on a retry before the result becomes visible to dependent jobs
+        let retry_delay = match &result.status {
+            JobStatus::Failed { .. } => {
+                let retryable = job.retry_policy.is_retryable(&result.stderr);
+                if !retryable {
+                    info!("Job {} failed with a non-retryable error, not retrying", job_id);
+                }
+                
+                (retryable && request.attempt < job.retry_policy.max_attempts)
+                    .then(|| Self::calculate_retry_delay(&job, request.attempt))
+            }
+            _ => None,
+        };
+        if retry_delay.is_some() {
+            // Dependent jobs keep waiting while a retry is pending
+            context.retrying.write().await.insert(job_id.clone());
+        }
+        
+        // Store result
+        {
+            let mut results = context.job_results.write().await;
+            results.insert(job_id.clone(), result.clone());
+        }
+        Self::save_result(&context.result_store, &result).await;
+        
+        // Remove from running jobs
+        context.running_jobs.write().await.remove(&job_id);
+        context.slot_freed.notify_waiters();
+        
+        if let (Some(output), None) = (&request.output, retry_delay) {
+            let _ = output.send(JobOutputLine::Completed(result.clone()));
+        }
+        
+        // Deliver output of successful runs
+        if let JobStatus::Completed = result.status {
+            if !matches!(job.output_destination, OutputDestination::Stdout)
+                && let Err(e) = output::deliver(&job.output_destination, &result.stdout, &context.config).await
             {
-                let mut results = job_results.write().await;
-                results.insert(job_id.clone(), result.clone());
+                error!("Failed to deliver output of job {}: {}", job_id, e);
             }
             
-            // Handle retry logic
-            if let JobStatus::Failed { error } = &result.status {
-                if request.attempt < job.retry_policy.max_attempts {
-                    let delay = Self::calculate_retry_delay(&job, request.attempt);
-                    
+            // No receivers just means nobody is piping output onward
+            let _ = context.completion_sender.send(result.clone());
+        }
+        
+        // Handle retry logic
+        if let JobStatus::Failed { error } = &result.status {
+            match retry_delay {
+                Some(delay) => {
                     info!("Job {} failed, retrying in {} seconds (attempt {}/{})", 
                           job_id, delay.as_secs(), request.attempt + 1, job.retry_policy.max_attempts);
                     
                     sleep(delay).await;
                     
                     let retry_request = JobExecutionRequest {
-                        job: job,
+                        job,
                         attempt: request.attempt + 1,
+                        stdin: request.stdin,
+                        output: request.output,
                     };
                     
                     // Re-queue for retry
-                    if let Err(e) = job_sender.send(retry_request).await {
+                    if let Err(e) = context.job_sender.send(retry_request).await {
                         warn!("Failed to re-queue job {} for retry: {}", job_id, e);
                     }
-                } else {
+                }
+                None => {
                     error!("Job {} failed after {} attempts: {}", 
                            job_id, request.attempt, error);
+                    
+                    // No receivers just means nobody is chaining recovery jobs
+                    let _ = context.failure_sender.send(result.clone());
                 }
             }
         }
     }
     
-    /// Executes a single job.
-    async fn execute_single_job(job: Job, attempt: u32) -> JobResult {
+    /// Writes a result to the on-disk history, if one is configured.
+    async fn save_result(result_store: &RwLock<Option<Arc<ResultStore>>>, result: &JobResult) {
+        if let Some(store) = result_store.read().await.as_ref()
+            && let Err(e) = store.save(result)
+        {
+            warn!("Failed to save result of job {}: {}", result.job_id, e);
+        }
+    }
+    
+    /// Executes a single job, sending its output lines to `output` if given.
+    ///
+    /// The process is terminated once `kill` is set to a grace period.
+    #[instrument(skip(job, stdin, output, kill), fields(job_id = %job.id))]
+    async fn execute_single_job(
+        job: Job,
+        attempt: u32,
+        stdin: Option<&str>,
+        output: Option<&mpsc::UnboundedSender<JobOutputLine>>,
+        kill: Option<watch::Receiver<Option<Duration>>>,
+    ) -> JobResult {
         let job_id = job.id.clone();
         let start_time = Utc::now();
         
-        info!("Executing job {} (attempt {})", job_id, attempt);
+        match &job.running_as {
+            Some(user) => info!("Executing job {} (attempt {}) as user {}", job_id, attempt, user),
+            None => info!("Executing job {} (attempt {})", job_id, attempt),
+        }
         
         // Build command
         let mut command = Command::new(&job.command);
@@ -285,43 +912,98 @@ impl JobExecutor {
             command.env(key, value);
         }
         
+        // Switch user in the child process if requested
+        if let Some(user) = &job.running_as {
+            match Self::resolve_run_as_user(user) {
+                #[cfg(unix)]
+                Ok(Some((uid, gid))) => {
+                    command.uid(uid);
+                    command.gid(gid);
+                }
+                Ok(_) => {}
+                Err(e) => {
+                    error!("Job {} cannot run as user {}: {}", job_id, user, e);
+                    return JobResult {
+                        job_id,
+                        started_at: start_time,
+                        ended_at: Some(Utc::now()),
+                        exit_code: None,
+                        stdout: String::new(),
+                        stderr: e.to_string(),
+                        truncated: false,
+                        status: JobStatus::Failed { error: e.to_string() },
+                        resource_usage: None,
+                    };
+                }
+            }
+        }
+        
         // Capture output
+        command.stdin(if stdin.is_some() { Stdio::piped() } else { Stdio::null() });
         command.stdout(Stdio::piped());
         command.stderr(Stdio::piped());
         
-        // Execute command
-        let result = command.output();
+        // Execute command, streaming output into bounded buffers
+        let max_output_bytes = job.resource_limits.max_output_bytes;
+        let result = match command.spawn() {
+            Ok(mut child) => {
+                let input = child.stdin.take();
+                let stdout = child.stdout.take();
+                let stderr = child.stderr.take();
+                
+                let (_, stdout, stderr, status) = tokio::join!(
+                    Self::write_input(input, stdin),
+                    Self::capture_output(stdout, max_output_bytes, output.map(|output| (output, JobOutputLine::Stdout as _))),
+                    Self::capture_output(stderr, max_output_bytes, output.map(|output| (output, JobOutputLine::Stderr as _))),
+                    Self::wait_with_timeout(&mut child, kill, job.effective_timeout()),
+                );
+                
+                status.map(|(status, usage, timed_out)| (status, stdout, stderr, usage, timed_out))
+            }
+            Err(e) => Err(e),
+        };
         
         let end_time = Utc::now();
         let duration = end_time.signed_duration_since(start_time);
         
         match result {
-            Ok(output) => {
-                let stdout = String::from_utf8_lossy(&output.stdout).to_string();
-                let stderr = String::from_utf8_lossy(&output.stderr).to_string();
+            Ok((exit_status, stdout, stderr, usage, timed_out)) => {
+                let truncated = stdout.is_truncated() || stderr.is_truncated();
+                if truncated {
+                    warn!("Job {} output exceeded {} bytes and was truncated", job_id, max_output_bytes);
+                }
                 
-                let status = if output.status.success() {
-                    JobStatus::Completed
-                } else {
+                let stdout = stdout.into_string();
+                let stderr = stderr.into_string();
+                
+                let status = if timed_out {
+                    let timeout = job.effective_timeout().unwrap_or_default();
+                    warn!("Job {} timed out after {}s", job_id, timeout.as_secs_f64());
+                    JobStatus::Failed {
+                        error: format!("Timed out after {}s", timeout.as_secs_f64())
+                    }
+                } else if !exit_status.success() {
                     JobStatus::Failed {
-                        error: format!("Exit code: {}", output.status.code().unwrap_or(-1))
+                        error: format!("Exit code: {}", exit_status.code().unwrap_or(-1))
                     }
+                } else if !job.retry_policy.output_succeeded(&stdout) {
+                    JobStatus::Failed {
+                        error: "Output did not match the success pattern".to_string()
+                    }
+                } else {
+                    JobStatus::Completed
                 };
                 
-                let resource_usage = Some(ResourceUsage {
-                    cpu_percent: 0.0, // TODO: Implement CPU monitoring
-                    memory_mb: 0,      // TODO: Implement memory monitoring
-                    duration_seconds: duration.num_seconds() as u64,
-                    disk_io_mb: 0,     // TODO: Implement disk I/O monitoring
-                });
+                let resource_usage = Some(usage.into_resource_usage(duration));
                 
                 JobResult {
                     job_id,
                     started_at: start_time,
                     ended_at: Some(end_time),
-                    exit_code: output.status.code(),
+                    exit_code: exit_status.code(),
                     stdout,
                     stderr,
+                    truncated,
                     status,
                     resource_usage,
                 }
@@ -338,6 +1020,7 @@ impl JobExecutor {
                     exit_code: None,
                     stdout: String::new(),
                     stderr: e.to_string(),
+                    truncated: false,
                     status,
                     resource_usage: None,
                 }
@@ -345,11 +1028,154 @@ impl JobExecutor {
         }
     }
     
+    /// Waits for a child to exit within `timeout`, terminating it like a killed
+    /// job once the timeout expires.
+    ///
+    /// Also returns whether the child timed out.
+    async fn wait_with_timeout(
+        child: &mut tokio::process::Child,
+        kill: Option<watch::Receiver<Option<Duration>>>,
+        timeout: Option<Duration>,
+    ) -> std::io::Result<(std::process::ExitStatus, UsageTracker, bool)> {
+        let mut tracker = UsageTracker::default();
+        let Some(timeout) = timeout else {
+            let status = Self::wait_with_usage(child, kill, &mut tracker).await?;
+            return Ok((status, tracker, false));
+        };
+        
+        match tokio::time::timeout(timeout, Self::wait_with_usage(child, kill, &mut tracker)).await {
+            Ok(status) => Ok((status?, tracker, false)),
+            Err(_) => {
+                let status = Self::terminate(child, DEFAULT_KILL_GRACE_PERIOD).await?;
+                Ok((status, tracker, true))
+            }
+        }
+    }
+    
+    /// Waits for a child to exit, sampling its resource usage while it runs.
+    ///
+    /// The child is terminated once `kill` is set to a grace period.
+    async fn wait_with_usage(
+        child: &mut tokio::process::Child,
+        mut kill: Option<watch::Receiver<Option<Duration>>>,
+        tracker: &mut UsageTracker,
+    ) -> std::io::Result<std::process::ExitStatus> {
+        let Some(pid) = child.id() else {
+            return child.wait().await;
+        };
+        
+        let mut interval = tokio::time::interval(USAGE_SAMPLE_INTERVAL);
+        loop {
+            tokio::select! {
+                status = child.wait() => return status,
+                grace_period = Self::kill_requested(&mut kill) => {
+                    return Self::terminate(child, grace_period).await;
+                }
+                _ = interval.tick() => tracker.record(usage::sample(pid)),
+            }
+        }
+    }
+    
+    /// Waits until a kill is requested, returning the grace period.
+    async fn kill_requested(kill: &mut Option<watch::Receiver<Option<Duration>>>) -> Duration {
+        // Without a kill switch the job runs to completion
+        let Some(kill) = kill else {
+            return std::future::pending().await;
+        };
+        
+        let grace_period = kill.wait_for(Option::is_some).await
+            .map(|grace_period| grace_period.unwrap_or_default());
+        match grace_period {
+            Ok(grace_period) => grace_period,
+            Err(_) => std::future::pending().await,
+        }
+    }
+    
+    /// Asks a child to exit, killing it if it is still running after the grace period.
+    async fn terminate(child: &mut tokio::process::Child, grace_period: Duration) -> std::io::Result<std::process::ExitStatus> {
+        #[cfg(unix)]
+        if let Some(pid) = child.id() {
+            use nix::sys::signal::{kill, Signal};
+            
+            match kill(nix::unistd::Pid::from_raw(pid as i32), Signal::SIGTERM) {
+                Ok(()) => {
+                    if let Ok(status) = tokio::time::timeout(grace_period, child.wait()).await {
+                        return status;
+                    }
+                    warn!("Process {} did not exit within {:?} of SIGTERM, killing it", pid, grace_period);
+                }
+                Err(e) => warn!("Failed to send SIGTERM to process {}: {}", pid, e),
+            }
+        }
+        #[cfg(not(unix))]
+        let _ = grace_period;
+        
+        // SIGKILL on Unix, TerminateProcess on Windows
+        child.kill().await?;
+        child.wait().await
+    }
+    
+    /// Writes piped input to a child's stdin, closing it afterwards.
+    async fn write_input(pipe: Option<tokio::process::ChildStdin>, input: Option<&str>) {
+        if let (Some(mut pipe), Some(input)) = (pipe, input) {
+            // A child that exits without reading its input is not an error
+            if let Err(e) = pipe.write_all(input.as_bytes()).await {
+                debug!("Job stopped reading its input: {}", e);
+            }
+        }
+    }
+    
+    /// Reads a child output stream to completion, keeping only the most recent bytes.
+    ///
+    /// With `lines`, every complete line is also sent as it arrives; lines
+    /// longer than `capacity` are sent in pieces.
+    async fn capture_output<R: AsyncRead + Unpin>(
+        stream: Option<R>,
+        capacity: usize,
+        lines: Option<LineSink<'_>>,
+    ) -> OutputBuffer {
+        let mut buffer = OutputBuffer::new(capacity);
+        let mut pending = Vec::new();
+        
+        if let Some(mut stream) = stream {
+            let mut chunk = [0u8; 8192];
+            loop {
+                match stream.read(&mut chunk).await {
+                    Ok(0) => break,
+                    Ok(n) => {
+                        buffer.push(&chunk[..n]);
+                        if let Some((sender, line)) = lines {
+                            pending.extend_from_slice(&chunk[..n]);
+                            while let Some(end) = pending.iter().position(|b| *b == b'\n') {
+                                let text: Vec<u8> = pending.drain(..=end).collect();
+                                let _ = sender.send(line(output_line(&text[..end])));
+                            }
+                            if pending.len() > capacity {
+                                let _ = sender.send(line(output_line(&std::mem::take(&mut pending))));
+                            }
+                        }
+                    }
+                    Err(e) => {
+                        warn!("Failed to read job output: {}", e);
+                        break;
+                    }
+                }
+            }
+        }
+        
+        // Output that does not end with a newline is still a line
+        if let (Some((sender, line)), false) = (lines, pending.is_empty()) {
+            let _ = sender.send(line(output_line(&pending)));
+        }
+        
+        buffer
+    }
+    
     /// Calculates retry delay with exponential backoff.
     fn calculate_retry_delay(job: &Job, attempt: u32) -> Duration {
         let base_delay = Duration::from_secs(job.retry_policy.delay);
         
-        if job.retry_policy.exponential_backoff {
+        let delay = if job.retry_policy.exponential_backoff {
             let exponential_delay = base_delay * 2_u32.pow(attempt - 1);
             
             if let Some(max_delay) = job.retry_policy.max_delay {
@@ -360,6 +1186,12 @@ impl JobExecutor {
             }
         } else {
             base_delay
+        };
+        
+        if job.retry_policy.jitter {
+            delay.mul_f64(rand::thread_rng().gen_range(0.5..1.5))
+        } else {
+            delay
         }
     }
 }
@@ -367,7 +1199,7 @@ impl JobExecutor {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::scheduler::job::Job;
+    use crate::scheduler::job::{EventTrigger, EventType, Job, ResourceLimits, RetryPolicy};
     
     #[tokio::test]
     async fn test_execute_simple_job() {
@@ -400,6 +1232,466 @@ mod tests {
         assert!(matches!(status, JobStatus::Failed { .. }));
     }
     
+    #[tokio::test]
+    async fn test_large_output_is_truncated() {
+        let limits = ResourceLimits { max_output_bytes: 1_048_576, ..Default::default() };
+        
+        // Print 2 MiB of output under a 1 MiB limit
+        let job = Job::new("test-job".to_string(), "sh".to_string())
+            .with_args(vec!["-c".to_string(), "yes | head -c 2097152".to_string()])
+            .with_resource_limits(limits);
+        
+        let result = JobExecutor::execute_single_job(job, 1, None, None, None).await;
+        
+        assert!(matches!(result.status, JobStatus::Completed));
+        assert!(result.truncated);
+        assert!(result.stdout.len() <= 1_048_576);
+        assert!(result.stdout.ends_with("y\n"));
+    }
+    
+    #[tokio::test]
+    async fn test_small_output_is_not_truncated() {
+        let job = Job::new("test-job".to_string(), "echo".to_string())
+            .with_args(vec!["hello".to_string()]);
+        
+        let result = JobExecutor::execute_single_job(job, 1, None, None, None).await;
+        
+        assert!(!result.truncated);
+        assert_eq!(result.stdout, "hello\n");
+    }
+    
+    #[tokio::test]
+    async fn test_resource_usage_is_sampled() {
+        let job = Job::new("test-job".to_string(), "sleep".to_string())
+            .with_args(vec!["0.3".to_string()]);
+        
+        let result = JobExecutor::execute_single_job(job, 1, None, None, None).await;
+        
+        assert!(matches!(result.status, JobStatus::Completed));
+        let usage = result.resource_usage.unwrap();
+        assert!(usage.cpu_percent >= 0.0);
+        if cfg!(target_os = "linux") {
+            assert!(usage.memory_mb > 0);
+        }
+    }
+    
+    #[test]
+    fn test_output_buffer_drops_oldest_bytes() {
+        let mut buffer = OutputBuffer::new(4);
+        
+        buffer.push(b"abc");
+        assert!(!buffer.is_truncated());
+        
+        buffer.push(b"def");
+        assert!(buffer.is_truncated());
+        assert_eq!(buffer.into_string(), "cdef");
+        
+        // A single chunk larger than the capacity keeps only its tail
+        let mut buffer = OutputBuffer::new(2);
+        buffer.push(b"hello");
+        assert_eq!(buffer.into_string(), "lo");
+    }
+    
+    /// Starts a job and waits until its process is running.
+    async fn start_job(executor: &JobExecutor, job: Job) -> JobId {
+        let job_id = executor.execute_job(job).await.unwrap();
+        while !executor.running_job_ids().await.contains(&job_id) {
+            sleep(Duration::from_millis(10)).await;
+        }
+        // Give the process time to start and set up its signal handlers
+        sleep(Duration::from_millis(200)).await;
+        job_id
+    }
+    
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_kill_job_terminates_process() {
+        let executor = JobExecutor::new();
+        let job_id = start_job(&executor, Job::new("test-job".to_string(), "sleep".to_string())
+            .with_args(vec!["60".to_string()])).await;
+        
+        let result = tokio::time::timeout(Duration::from_secs(10), executor.kill_job(&job_id)).await.unwrap().unwrap();
+        
+        assert!(matches!(result.status, JobStatus::Cancelled));
+        assert!(matches!(executor.get_job_status(&job_id).await.unwrap(), JobStatus::Cancelled));
+        assert_eq!(executor.running_count().await, 0);
+    }
+    
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_kill_job_escalates_to_sigkill() {
+        let executor = JobExecutor::new();
+        executor.set_kill_grace_period(Duration::from_millis(200)).await;
+        let job = Job::new("test-job".to_string(), "sh".to_string())
+            .with_args(vec!["-c".to_string(), "trap '' TERM; exec sleep 60".to_string()]);
+        let job_id = start_job(&executor, job).await;
+        
+        let result = tokio::time::timeout(Duration::from_secs(10), executor.kill_job(&job_id)).await.unwrap().unwrap();
+        
+        assert!(matches!(result.status, JobStatus::Cancelled));
+        assert_eq!(result.exit_code, None);
+        assert!(executor.kill_job(&job_id).await.is_err());
+    }
+    
+    #[tokio::test]
+    async fn test_execute_job_streaming_yields_lines_then_result() {
+        use futures_util::StreamExt;
+        
+        let executor = JobExecutor::new();
+        let job = Job::new("test-job".to_string(), "sh".to_string())
+            .with_args(vec!["-c".to_string(), "echo one; echo oops >&2; printf two".to_string()]);
+        
+        let stream = executor.execute_job_streaming(job).await.unwrap();
+        let lines: Vec<JobOutputLine> = tokio::time::timeout(Duration::from_secs(5), stream.collect()).await.unwrap();
+        
+        let (last, lines) = lines.split_last().unwrap();
+        let stdout: Vec<&str> = lines.iter()
+            .filter_map(|line| match line {
+                JobOutputLine::Stdout(text) => Some(text.as_str()),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(stdout, vec!["one", "two"]);
+        assert!(lines.iter().any(|line| matches!(line, JobOutputLine::Stderr(text) if text == "oops")));
+        
+        match last {
+            JobOutputLine::Completed(result) => {
+                assert!(matches!(result.status, JobStatus::Completed));
+                assert_eq!(result.stdout, "one\ntwo");
+            }
+            other => panic!("expected the final result, got {:?}", other),
+        }
+    }
+    
+    #[tokio::test]
+    async fn test_concurrency_limit() {
+        let executor = JobExecutor::new();
+        executor.set_concurrency_limit(2).await;
+        let mut completions = executor.subscribe_completions();
+        
+        for i in 0..10 {
+            let job = Job::new(format!("job-{}", i), "sleep".to_string())
+                .with_args(vec!["0.1".to_string()]);
+            executor.execute_job(job).await.unwrap();
+        }
+        
+        let mut completed = 0;
+        let mut max_running = 0;
+        while completed < 10 {
+            max_running = max_running.max(executor.running_count().await);
+            match tokio::time::timeout(Duration::from_millis(10), completions.recv()).await {
+                Ok(result) => {
+                    result.unwrap();
+                    completed += 1;
+                }
+                Err(_) => continue,
+            }
+        }
+        
+        assert_eq!(max_running, 2);
+        assert_eq!(executor.running_count().await, 0);
+    }
+    
+    #[tokio::test]
+    async fn test_running_job_does_not_block_other_jobs() {
+        let executor = JobExecutor::new();
+        let mut completions = executor.subscribe_completions();
+        
+        let slow = Job::new("slow".to_string(), "sleep".to_string()).with_args(vec!["1".to_string()]);
+        let slow_id = executor.execute_job(slow.clone()).await.unwrap();
+        tokio::time::timeout(Duration::from_secs(5), async {
+            while !executor.running_job_ids().await.contains(&slow_id) {
+                sleep(Duration::from_millis(10)).await;
+            }
+        }).await.unwrap();
+        
+        // The second run of the slow job waits, the quick job behind it does not
+        executor.execute_job(slow).await.unwrap();
+        let quick_id = executor.execute_job(Job::new("quick".to_string(), "true".to_string())).await.unwrap();
+        
+        let first = tokio::time::timeout(Duration::from_secs(5), completions.recv()).await.unwrap().unwrap();
+        assert_eq!(first.job_id, quick_id);
+        for _ in 0..2 {
+            let result = tokio::time::timeout(Duration::from_secs(5), completions.recv()).await.unwrap().unwrap();
+            assert_eq!(result.job_id, slow_id);
+        }
+    }
+    
+    #[tokio::test]
+    async fn test_job_timeout_kills_process() {
+        let executor = JobExecutor::new();
+        let mut failures = executor.subscribe_failures();
+        
+        let job = Job::new("slow-job".to_string(), "sleep".to_string())
+            .with_args(vec!["60".to_string()])
+            .with_timeout(Duration::from_secs(1))
+            .with_retry_policy(RetryPolicy { max_attempts: 1, ..RetryPolicy::fixed(0) });
+        let job_id = executor.execute_job(job).await.unwrap();
+        
+        let result = tokio::time::timeout(Duration::from_secs(2), failures.recv())
+            .await
+            .expect("timed out job should fail within 2 seconds")
+            .unwrap();
+        assert_eq!(result.job_id, job_id);
+        assert_eq!(result.status, JobStatus::Failed { error: "Timed out after 1s".to_string() });
+        assert_eq!(result.resource_usage.unwrap().duration_seconds, 1);
+    }
+    
+    #[tokio::test]
+    async fn test_exhausted_failure_is_broadcast() {
+        let executor = JobExecutor::new();
+        let mut failures = executor.subscribe_failures();
+        
+        let job = Job::new("failing-job".to_string(), "false".to_string())
+            .with_retry_policy(RetryPolicy {
+                max_attempts: 1,
+                delay: 0,
+                exponential_backoff: false,
+                max_delay: None,
+                ..RetryPolicy::default()
+            });
+        let job_id = executor.execute_job(job).await.unwrap();
+        
+        let result = tokio::time::timeout(Duration::from_secs(5), failures.recv())
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(result.job_id, job_id);
+        assert!(matches!(result.status, JobStatus::Failed { .. }));
+    }
+    
+    #[tokio::test]
+    async fn test_file_watcher_triggers_job() {
+        let executor = JobExecutor::new();
+        let mut completions = executor.subscribe_completions();
+        let temp_dir = tempfile::tempdir().unwrap();
+        let path = temp_dir.path().join("inbox.txt");
+        
+        let job = Job::new("on-inbox".to_string(), "echo".to_string())
+            .with_args(vec!["triggered".to_string()])
+            .with_event(EventTrigger {
+                event_type: EventType::FileCreated,
+                path: Some(path.to_string_lossy().into_owned()),
+                filter: None,
+            });
+        executor.start_file_watchers(std::slice::from_ref(&job)).unwrap();
+        
+        // Other files in the directory are ignored
+        std::fs::write(temp_dir.path().join("other.txt"), "x").unwrap();
+        std::fs::write(&path, "new mail").unwrap();
+        
+        let result = tokio::time::timeout(Duration::from_secs(5), completions.recv())
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(result.job_id, job.id);
+        assert_eq!(result.stdout.trim(), "triggered");
+        
+        // Once stopped, events no longer run the job
+        assert!(executor.stop_file_watcher(&job.id));
+        std::fs::remove_file(&path).unwrap();
+        std::fs::write(&path, "more mail").unwrap();
+        assert!(tokio::time::timeout(Duration::from_millis(700), completions.recv()).await.is_err());
+    }
+    
+    #[tokio::test]
+    async fn test_dependent_job_waits_for_dependency() {
+        let executor = JobExecutor::new();
+        executor.set_dependency_wait(Duration::from_millis(50)).await;
+        let mut completions = executor.subscribe_completions();
+        
+        let extract = Job::new("extract".to_string(), "echo".to_string());
+        let load = Job::new("load".to_string(), "echo".to_string())
+            .with_depends_on(vec![extract.id.clone()]);
+        
+        // Submitted first, but must not run before its dependency
+        let load_id = executor.execute_job(load).await.unwrap();
+        let extract_id = executor.execute_job(extract).await.unwrap();
+        
+        let mut order = Vec::new();
+        for _ in 0..2 {
+            let result = tokio::time::timeout(Duration::from_secs(5), completions.recv())
+                .await
+                .unwrap()
+                .unwrap();
+            order.push(result.job_id);
+        }
+        assert_eq!(order, vec![extract_id, load_id]);
+    }
+    
+    #[tokio::test]
+    async fn test_failed_dependency_fails_dependent_job() {
+        let executor = JobExecutor::new();
+        executor.set_dependency_wait(Duration::from_millis(50)).await;
+        let mut failures = executor.subscribe_failures();
+        
+        let extract = Job::new("extract".to_string(), "false".to_string())
+            .with_retry_policy(RetryPolicy {
+                max_attempts: 1,
+                delay: 0,
+                ..RetryPolicy::default()
+            });
+        let load = Job::new("load".to_string(), "echo".to_string())
+            .with_depends_on(vec![extract.id.clone()]);
+        let extract_id = executor.execute_job(extract).await.unwrap();
+        let load_id = executor.execute_job(load).await.unwrap();
+        
+        let mut failed = HashMap::new();
+        for _ in 0..2 {
+            let result = tokio::time::timeout(Duration::from_secs(5), failures.recv())
+                .await
+                .unwrap()
+                .unwrap();
+            failed.insert(result.job_id.clone(), result);
+        }
+        
+        assert!(failed.contains_key(&extract_id));
+        match &failed[&load_id].status {
+            JobStatus::Failed { error } => assert!(error.contains(&extract_id)),
+            status => panic!("unexpected status {:?}", status),
+        }
+    }
+    
+    /// Runs a job that fails with the given stderr and counts its attempts.
+    async fn attempts_until_failure(stderr: &str, on_error_pattern: Vec<String>) -> usize {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let log = temp_dir.path().join("attempts");
+        
+        let executor = JobExecutor::new();
+        let mut failures = executor.subscribe_failures();
+        
+        let script = format!("echo run >> '{}'; echo '{}' >&2; exit 1", log.display(), stderr);
+        let job = Job::new("flaky".to_string(), "sh".to_string())
+            .with_args(vec!["-c".to_string(), script])
+            .with_retry_policy(RetryPolicy {
+                max_attempts: 3,
+                delay: 0,
+                exponential_backoff: false,
+                on_error_pattern,
+                ..RetryPolicy::default()
+            });
+        executor.execute_job(job).await.unwrap();
+        
+        tokio::time::timeout(Duration::from_secs(5), failures.recv()).await.unwrap().unwrap();
+        std::fs::read_to_string(&log).unwrap().lines().count()
+    }
+    
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_matching_error_is_retried() {
+        let attempts = attempts_until_failure("connect: Connection refused", vec!["(?i)connection refused".to_string()]).await;
+        assert_eq!(attempts, 3);
+    }
+    
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_non_matching_error_fails_immediately() {
+        let attempts = attempts_until_failure("cat: data.csv: file not found", vec!["(?i)connection refused".to_string()]).await;
+        assert_eq!(attempts, 1);
+    }
+    
+    #[tokio::test]
+    async fn test_paused_executor_holds_jobs_until_resumed() {
+        let executor = JobExecutor::new();
+        let mut completions = executor.subscribe_completions();
+        
+        executor.pause();
+        assert!(executor.is_paused());
+        let first = executor.execute_job(Job::new("first".to_string(), "true".to_string())).await.unwrap();
+        let second = executor.execute_job(Job::new("second".to_string(), "true".to_string())).await.unwrap();
+        
+        assert!(tokio::time::timeout(Duration::from_millis(300), completions.recv()).await.is_err());
+        assert!(executor.running_job_ids().await.is_empty());
+        
+        executor.resume();
+        let mut completed = Vec::new();
+        for _ in 0..2 {
+            let result = tokio::time::timeout(Duration::from_secs(5), completions.recv()).await.unwrap().unwrap();
+            completed.push(result.job_id);
+        }
+        completed.sort();
+        let mut expected = vec![first, second];
+        expected.sort();
+        assert_eq!(completed, expected);
+    }
+    
+    #[test]
+    fn test_retry_delay_jitter_stays_in_range() {
+        let job = Job::new("sync".to_string(), "rsync".to_string())
+            .with_retry_policy(RetryPolicy::exponential(10, 3600, true));
+        
+        // Attempt 2 backs off to 20 seconds before jitter
+        let delays: Vec<Duration> = (0..1000).map(|_| JobExecutor::calculate_retry_delay(&job, 2)).collect();
+        assert!(delays.iter().all(|delay| *delay >= Duration::from_secs(10) && *delay < Duration::from_secs(30)));
+        assert!(delays.iter().any(|delay| *delay != delays[0]));
+        
+        let job = job.with_retry_policy(RetryPolicy::exponential(10, 15, false));
+        assert_eq!(JobExecutor::calculate_retry_delay(&job, 2), Duration::from_secs(15));
+        
+        let job = job.with_retry_policy(RetryPolicy::fixed(7));
+        assert_eq!(JobExecutor::calculate_retry_delay(&job, 3), Duration::from_secs(7));
+    }
+    
+    #[tokio::test]
+    async fn test_success_pattern_must_match() {
+        let policy = RetryPolicy {
+            on_success_pattern: Some("^synced \\d+ files".to_string()),
+            ..RetryPolicy::default()
+        };
+        
+        let job = Job::new("sync".to_string(), "echo".to_string())
+            .with_args(vec!["synced 12 files".to_string()])
+            .with_retry_policy(policy.clone());
+        let result = JobExecutor::execute_single_job(job, 1, None, None, None).await;
+        assert!(matches!(result.status, JobStatus::Completed));
+        
+        // Exit code 0 but the expected output is missing
+        let job = Job::new("sync".to_string(), "echo".to_string())
+            .with_args(vec!["nothing to do".to_string()])
+            .with_retry_policy(policy);
+        let result = JobExecutor::execute_single_job(job, 1, None, None, None).await;
+        assert!(matches!(result.status, JobStatus::Failed { .. }));
+    }
+    
+    #[cfg(unix)]
+    #[test]
+    fn test_run_as_requires_privileges() {
+        // Unprivileged process without CAP_SETUID cannot switch users
+        let err = JobExecutor::check_run_as_privileges("postgres", 70, 1000, false).unwrap_err();
+        assert!(matches!(err, RaeError::Security(ref msg) if msg == "insufficient privileges to run as user postgres"));
+        
+        // Root, the same user, or CAP_SETUID may
+        assert!(JobExecutor::check_run_as_privileges("postgres", 70, 0, false).is_ok());
+        assert!(JobExecutor::check_run_as_privileges("postgres", 70, 70, false).is_ok());
+        assert!(JobExecutor::check_run_as_privileges("postgres", 70, 1000, true).is_ok());
+    }
+    
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_run_as_unknown_user() {
+        let executor = JobExecutor::new();
+        
+        let job = Job::new("test-job".to_string(), "echo".to_string())
+            .with_running_as("rae-no-such-user".to_string());
+        
+        let err = executor.execute_job(job).await.unwrap_err();
+        assert!(matches!(err, ExecutorError::Rae(RaeError::Security(_))));
+    }
+    
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_run_as_current_user() {
+        let current = nix::unistd::User::from_uid(nix::unistd::geteuid()).unwrap().unwrap();
+        
+        let job = Job::new("test-job".to_string(), "echo".to_string())
+            .with_args(vec!["hello".to_string()])
+            .with_running_as(current.name);
+        
+        let result = JobExecutor::execute_single_job(job, 1, None, None, None).await;
+        assert!(matches!(result.status, JobStatus::Completed));
+        assert_eq!(result.stdout, "hello\n");
+    }
+    
     #[tokio::test]
     async fn test_validate_job() {
         let executor = JobExecutor::new();
@@ -416,5 +1708,52 @@ mod tests {
         let mut job = Job::new("test-job".to_string(), "echo".to_string());
         job.enabled = false;
         assert!(executor.validate_job(&job).is_err());
+        
+        // Invalid job - malformed retry pattern
+        let mut job = Job::new("test-job".to_string(), "echo".to_string());
+        job.retry_policy.on_error_pattern = vec!["(unclosed".to_string()];
+        assert!(executor.validate_job(&job).is_err());
+    }
+    
+    /// Collects log output written by a test subscriber.
+    #[derive(Clone, Default)]
+    struct LogBuffer(Arc<std::sync::Mutex<Vec<u8>>>);
+    
+    impl std::io::Write for LogBuffer {
+        fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+            self.0.lock().unwrap().extend_from_slice(buf);
+            Ok(buf.len())
+        }
+        
+        fn flush(&mut self) -> std::io::Result<()> {
+            Ok(())
+        }
+    }
+    
+    #[tokio::test]
+    async fn test_job_execution_logs_span_fields() {
+        let logs = LogBuffer::default();
+        let writer = logs.clone();
+        let subscriber = tracing_subscriber::fmt()
+            .json()
+            .with_writer(move || writer.clone())
+            .finish();
+        let _guard = tracing::subscriber::set_default(subscriber);
+        
+        let job = Job::new("test-job".to_string(), "echo".to_string());
+        let result = JobExecutor::execute_single_job(job.clone(), 2, None, None, None)
+            .instrument(info_span!("job_run", job_id = %job.id))
+            .await;
+        assert!(matches!(result.status, JobStatus::Completed));
+        
+        let output = String::from_utf8(logs.0.lock().unwrap().clone()).unwrap();
+        let event: serde_json::Value = output.lines()
+            .map(|line| serde_json::from_str(line).unwrap())
+            .find(|event: &serde_json::Value| event["fields"]["message"].as_str().is_some_and(|m| m.starts_with("Executing job")))
+            .expect("no execution log line");
+        assert_eq!(event["span"]["name"], "execute_single_job");
+        assert_eq!(event["span"]["job_id"], job.id.as_str());
+        assert_eq!(event["span"]["attempt"], 2);
+        assert_eq!(event["spans"][0]["name"], "job_run");
     }
 } 
\ No newline at end of file
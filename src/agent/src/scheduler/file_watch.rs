@@ -0,0 +1,116 @@
+//! File system watches for jobs triggered by `FileCreated`, `FileModified` or
+//! `FileDeleted` events.
+//!
+//! A trigger path that is a directory matches events for any entry in it;
+//! otherwise its parent directory is watched, so a file that does not exist yet
+//! can still trigger on creation.
+
+use crate::scheduler::executor::ExecutorError;
+use crate::scheduler::job::{EventTrigger, EventType};
+use notify::{Event, RecommendedWatcher, RecursiveMode, Watcher};
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+use tokio::sync::mpsc;
+use tracing::warn;
+
+/// Events arriving within this window of the last trigger are ignored.
+pub const DEBOUNCE_WINDOW: Duration = Duration::from_millis(500);
+
+/// A running watch; dropping it stops watching.
+pub struct FileWatch {
+    _watcher: RecommendedWatcher,
+}
+
+impl FileWatch {
+    /// Starts watching the path of a file event trigger.
+    ///
+    /// The receiver yields once per debounced burst of matching events.
+    /// Returns `None` for triggers that are not file events.
+    pub fn start(trigger: &EventTrigger) -> Result<Option<(FileWatch, mpsc::UnboundedReceiver<()>)>, ExecutorError> {
+        let Some(target) = WatchTarget::from_trigger(trigger)? else {
+            return Ok(None);
+        };
+        
+        let (sender, receiver) = mpsc::unbounded_channel();
+        let dir = target.dir.clone();
+        let mut last_fired: Option<Instant> = None;
+        
+        let mut watcher = notify::recommended_watcher(move |event: notify::Result<Event>| {
+            let event = match event {
+                Ok(event) => event,
+                Err(e) => {
+                    warn!("File watch on {} failed: {}", target.dir.display(), e);
+                    return;
+                }
+            };
+            if !target.matches(&event) {
+                return;
+            }
+            
+            let now = Instant::now();
+            if last_fired.is_some_and(|fired| now.duration_since(fired) < DEBOUNCE_WINDOW) {
+                return;
+            }
+            last_fired = Some(now);
+            let _ = sender.send(());
+        })
+        .map_err(|e| ExecutorError::ExecutionFailed(format!("Failed to create file watcher: {}", e)))?;
+        
+        watcher.watch(&dir, RecursiveMode::NonRecursive)
+            .map_err(|e| ExecutorError::ExecutionFailed(format!("Failed to watch {}: {}", dir.display(), e)))?;
+        
+        Ok(Some((FileWatch { _watcher: watcher }, receiver)))
+    }
+}
+
+/// The directory a trigger watches and the events it reacts to.
+struct WatchTarget {
+    dir: PathBuf,
+    /// The watched file, or `None` to match every entry of `dir`
+    file: Option<PathBuf>,
+    event_type: EventType,
+}
+
+impl WatchTarget {
+    fn from_trigger(trigger: &EventTrigger) -> Result<Option<Self>, ExecutorError> {
+        if !matches!(trigger.event_type, EventType::FileCreated | EventType::FileModified | EventType::FileDeleted) {
+            return Ok(None);
+        }
+        let path = trigger.path.as_deref()
+            .map(Path::new)
+            .ok_or_else(|| ExecutorError::InvalidJob("File events require a path".to_string()))?;
+        
+        // Canonical paths, so they compare equal to the paths of reported events
+        let canonicalize = |dir: &Path| {
+            dir.canonicalize()
+                .map_err(|e| ExecutorError::InvalidJob(format!("Cannot watch {}: {}", dir.display(), e)))
+        };
+        
+        let (dir, file) = if path.is_dir() {
+            (canonicalize(path)?, None)
+        } else {
+            let parent = path.parent().filter(|parent| !parent.as_os_str().is_empty()).unwrap_or(Path::new("."));
+            let name = path.file_name()
+                .ok_or_else(|| ExecutorError::InvalidJob(format!("Cannot watch {}: no file name", path.display())))?;
+            let dir = canonicalize(parent)?;
+            let file = dir.join(name);
+            (dir, Some(file))
+        };
+        
+        Ok(Some(WatchTarget { dir, file, event_type: trigger.event_type.clone() }))
+    }
+    
+    fn matches(&self, event: &Event) -> bool {
+        let kind_matches = match self.event_type {
+            EventType::FileCreated => event.kind.is_create(),
+            EventType::FileModified => event.kind.is_modify(),
+            EventType::FileDeleted => event.kind.is_remove(),
+            _ => false,
+        };
+        
+        kind_matches && match &self.file {
+            Some(file) => event.paths.iter().any(|path| path == file),
+            None => true,
+        }
+    }
+}
@@ -6,12 +6,21 @@
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::str::FromStr;
+use std::time::Duration;
 use chrono::{DateTime, Utc};
+use regex::Regex;
 use uuid::Uuid;
+use crate::config::Config;
+use crate::scheduler::alias::{AliasError, AliasFile};
+use crate::scheduler::SchedulerError;
+use thiserror::Error;
 
 /// Unique identifier for a job.
 pub type JobId = String;
 
+/// How long a `CommandSucceeds` condition may run before it counts as failed.
+pub const CONDITION_TIMEOUT: Duration = Duration::from_secs(10);
+
 /// Priority level for job execution.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
 pub enum Priority {
@@ -27,6 +36,47 @@ impl Default for Priority {
     }
 }
 
+impl Priority {
+    /// Raises the priority by the given number of levels, up to `Critical`.
+    pub fn boosted(self, levels: u8) -> Priority {
+        match (self as u8).saturating_add(levels) {
+            0 => Priority::Low,
+            1 => Priority::Normal,
+            2 => Priority::High,
+            _ => Priority::Critical,
+        }
+    }
+}
+
+impl FromStr for Priority {
+    type Err = SchedulerError;
+    
+    /// Parses a priority name, ignoring case.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "low" => Ok(Priority::Low),
+            "normal" => Ok(Priority::Normal),
+            "high" => Ok(Priority::High),
+            "critical" => Ok(Priority::Critical),
+            _ => Err(SchedulerError::InvalidJob(format!(
+                "Unknown priority: {} (expected low, normal, high or critical)", s
+            ))),
+        }
+    }
+}
+
+impl std::fmt::Display for Priority {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let name = match self {
+            Priority::Low => "low",
+            Priority::Normal => "normal",
+            Priority::High => "high",
+            Priority::Critical => "critical",
+        };
+        f.write_str(name)
+    }
+}
+
 /// Status of a job execution.
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub enum JobStatus {
@@ -42,6 +92,10 @@ pub enum JobStatus {
     Cancelled,
     /// Job is waiting for retry
     Retrying { attempts: u32, max_attempts: u32 },
+    /// Job is held back until its condition is met
+    Suspended,
+    /// Job is disabled and not queued
+    Disabled,
 }
 
 impl Default for JobStatus {
@@ -135,6 +189,16 @@ pub struct RetryPolicy {
     pub exponential_backoff: bool,
     /// Maximum delay between retries (in seconds)
     pub max_delay: Option<u64>,
+    /// Whether to scale each delay by a random factor in `[0.5, 1.5)`, so
+    /// jobs that fail together do not all retry at once
+    #[serde(default = "default_jitter")]
+    pub jitter: bool,
+    /// Regex patterns; if any are set, only failures whose stderr matches one are retried
+    #[serde(default)]
+    pub on_error_pattern: Vec<String>,
+    /// Regex that stdout must match for a run to count as successful
+    #[serde(default)]
+    pub on_success_pattern: Option<String>,
 }
 
 impl Default for RetryPolicy {
@@ -144,8 +208,64 @@ impl Default for RetryPolicy {
             delay: 60,
             exponential_backoff: true,
             max_delay: Some(3600), // 1 hour
+            jitter: true,
+            on_error_pattern: Vec::new(),
+            on_success_pattern: None,
+        }
+    }
+}
+
+fn default_jitter() -> bool {
+    true
+}
+
+impl RetryPolicy {
+    /// Retries after the same delay every time, without jitter.
+    pub fn fixed(delay_secs: u64) -> RetryPolicy {
+        RetryPolicy {
+            delay: delay_secs,
+            exponential_backoff: false,
+            max_delay: None,
+            jitter: false,
+            ..RetryPolicy::default()
+        }
+    }
+    
+    /// Doubles the delay after each attempt, starting at `base` and capped at `max` seconds.
+    pub fn exponential(base: u64, max: u64, jitter: bool) -> RetryPolicy {
+        RetryPolicy {
+            delay: base,
+            exponential_backoff: true,
+            max_delay: Some(max),
+            jitter,
+            ..RetryPolicy::default()
         }
     }
+    
+    /// Whether a failure with the given stderr is worth retrying.
+    pub fn is_retryable(&self, stderr: &str) -> bool {
+        self.on_error_pattern.is_empty()
+            || self.on_error_pattern.iter().any(|pattern| pattern_matches(pattern, stderr))
+    }
+    
+    /// Whether the stdout of a successful run satisfies the success pattern.
+    pub fn output_succeeded(&self, stdout: &str) -> bool {
+        self.on_success_pattern.as_deref().is_none_or(|pattern| pattern_matches(pattern, stdout))
+    }
+    
+    /// Checks that all patterns are valid regular expressions.
+    pub fn validate_patterns(&self) -> Result<(), regex::Error> {
+        for pattern in self.on_error_pattern.iter().chain(&self.on_success_pattern) {
+            Regex::new(pattern)?;
+        }
+        
+        Ok(())
+    }
+}
+
+/// Matches text against a regex, treating invalid patterns as non-matching.
+fn pattern_matches(pattern: &str, text: &str) -> bool {
+    Regex::new(pattern).is_ok_and(|regex| regex.is_match(text))
 }
 
 /// Resource limits for job execution.
@@ -159,6 +279,17 @@ pub struct ResourceLimits {
     pub max_duration: Option<u64>,
     /// Maximum disk I/O (MB/s)
     pub max_disk_io: Option<u64>,
+    /// Maximum captured output per stream (bytes); older output is dropped first
+    #[serde(default = "default_max_output_bytes")]
+    pub max_output_bytes: usize,
+    /// Maximum simultaneous runs; with 1, on-demand runs of a running job are rejected
+    #[serde(default)]
+    pub max_concurrent: Option<usize>,
+}
+
+/// Default cap on captured stdout/stderr (1 MiB).
+fn default_max_output_bytes() -> usize {
+    1024 * 1024
 }
 
 impl Default for ResourceLimits {
@@ -168,6 +299,135 @@ impl Default for ResourceLimits {
             max_memory: Some(512), // 512 MB
             max_duration: Some(3600), // 1 hour
             max_disk_io: Some(100), // 100 MB/s
+            max_output_bytes: default_max_output_bytes(),
+            max_concurrent: None,
+        }
+    }
+}
+
+/// What to do about cron windows missed while the agent was not running.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum CatchupPolicy {
+    /// Ignore missed windows
+    #[default]
+    Skip,
+    /// Run once if any window was missed
+    RunOnce,
+    /// Run once per missed window, up to `max_catchup` runs
+    RunAll { max_catchup: u32 },
+}
+
+impl CatchupPolicy {
+    /// Number of catch-up runs for the given number of missed windows.
+    pub fn runs_for(&self, missed: u32) -> u32 {
+        match self {
+            CatchupPolicy::Skip => 0,
+            CatchupPolicy::RunOnce => missed.min(1),
+            CatchupPolicy::RunAll { max_catchup } => missed.min(*max_catchup),
+        }
+    }
+}
+
+/// Where a job's standard output is delivered after it completes.
+#[derive(Debug, Clone, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum OutputDestination {
+    /// Keep the output in the job result only
+    #[default]
+    Stdout,
+    /// Write the output to a file
+    File { path: String, append: bool },
+    /// Upload the output to an S3-compatible object store
+    S3Compatible { endpoint: String, bucket: String, key: String },
+    /// Pass the output as stdin to another job
+    PipeToJob(JobId),
+}
+
+/// Errors that can occur when instantiating a job template.
+#[derive(Debug, Error)]
+pub enum TemplateError {
+    #[error("Job is not a template: {0}")]
+    NotATemplate(JobId),
+    
+    #[error("Missing template variable: {0}")]
+    MissingVariable(String),
+}
+
+/// Schedule that switches between a fast and a slow cron expression based on
+/// the job's last output.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DynamicSchedule {
+    /// Cron expression used while the condition holds
+    pub fast_cron: String,
+    /// Cron expression used otherwise
+    pub slow_cron: String,
+    /// JSON pointer (e.g. `/volatile`) into the JSON output that selects `fast_cron` when `true`
+    pub fast_condition: String,
+}
+
+impl DynamicSchedule {
+    /// Picks the cron expression for the given job output.
+    pub fn select(&self, stdout: &str) -> &str {
+        let fast = serde_json::from_str::<serde_json::Value>(stdout)
+            .ok()
+            .and_then(|output| output.pointer(&self.fast_condition).and_then(|value| value.as_bool()))
+            .unwrap_or(false);
+        
+        if fast { &self.fast_cron } else { &self.slow_cron }
+    }
+}
+
+/// Expected execution frequency of a job.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct JobSla {
+    /// Longest allowed gap between successful runs, in seconds
+    pub max_interval_seconds: u64,
+    /// Whether to fire the configured alert webhook on a violation
+    pub alert_on_violation: bool,
+}
+
+/// Environment condition that must hold for a job to be scheduled.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum JobCondition {
+    /// The environment variable is set
+    EnvVarSet(String),
+    /// The environment variable is set to the given value
+    EnvVarMatches(String, String),
+    /// The shell command exits successfully
+    CommandSucceeds(String),
+}
+
+impl JobCondition {
+    /// Evaluates the condition against the current environment.
+    ///
+    /// A command that does not exit within `CONDITION_TIMEOUT` is killed and
+    /// counts as failed.
+    pub async fn evaluate(&self) -> bool {
+        match self {
+            JobCondition::EnvVarSet(name) => std::env::var_os(name).is_some(),
+            JobCondition::EnvVarMatches(name, value) => {
+                std::env::var(name).map(|actual| actual == *value).unwrap_or(false)
+            }
+            JobCondition::CommandSucceeds(command) => {
+                #[cfg(windows)]
+                let mut process = tokio::process::Command::new("cmd");
+                #[cfg(windows)]
+                process.args(["/C", command]);
+                
+                #[cfg(not(windows))]
+                let mut process = tokio::process::Command::new("sh");
+                #[cfg(not(windows))]
+                process.args(["-c", command]);
+                
+                process
+                    .stdin(std::process::Stdio::null())
+                    .stdout(std::process::Stdio::null())
+                    .stderr(std::process::Stdio::null())
+                    .kill_on_drop(true);
+                
+                tokio::time::timeout(CONDITION_TIMEOUT, process.status())
+                    .await
+                    .is_ok_and(|status| status.is_ok_and(|status| status.success()))
+            }
         }
     }
 }
@@ -191,12 +451,63 @@ pub struct Job {
     pub working_dir: Option<String>,
     /// Environment variables
     pub env: HashMap<String, String>,
+    /// Unix user to run the job as (requires root or CAP_SETUID)
+    #[serde(default)]
+    pub running_as: Option<String>,
     /// Retry policy for failed executions
     pub retry_policy: RetryPolicy,
+    /// What to do about cron windows missed during downtime
+    #[serde(default)]
+    pub cron_catchup_policy: CatchupPolicy,
+    /// When the job last completed successfully
+    #[serde(default)]
+    pub last_run_at: Option<DateTime<Utc>>,
+    /// Job to run once all retries of this job have failed
+    #[serde(default)]
+    pub on_failure_job: Option<JobId>,
+    /// Jobs that must have completed before this job runs
+    #[serde(default)]
+    pub depends_on: Vec<JobId>,
+    /// Whether failures are posted to the monitor's alert webhook
+    #[serde(default)]
+    pub alert_on_failure: bool,
+    /// Minimum time between two failure alerts; alerts in between are suppressed
+    #[serde(default)]
+    pub notification_throttle: Option<Duration>,
     /// Priority level for execution
     pub priority: Priority,
     /// Resource limits for execution
     pub resource_limits: ResourceLimits,
+    /// Maximum run time, overriding `resource_limits.max_duration`
+    #[serde(default)]
+    pub timeout: Option<Duration>,
+    /// Where standard output is delivered after the job completes
+    #[serde(default)]
+    pub output_destination: OutputDestination,
+    /// Condition that must hold for the job to be scheduled
+    #[serde(default)]
+    pub condition: Option<JobCondition>,
+    /// Expected execution frequency
+    #[serde(default)]
+    pub sla: Option<JobSla>,
+    /// Number of runs after which the job leaves the queue (`None` for no limit)
+    #[serde(default)]
+    pub max_runs: Option<u32>,
+    /// Output-driven choice between a fast and a slow schedule
+    #[serde(default)]
+    pub dynamic_schedule: Option<DynamicSchedule>,
+    /// Cron expression currently chosen by the dynamic schedule
+    #[serde(default)]
+    pub current_schedule_override: Option<String>,
+    /// Whether this job only serves as a template for other jobs
+    #[serde(default)]
+    pub is_template: bool,
+    /// Placeholder names substituted as `{{NAME}}` when instantiating
+    #[serde(default)]
+    pub template_vars: Vec<String>,
+    /// Labels for grouping related jobs
+    #[serde(default)]
+    pub tags: Vec<String>,
     /// Whether the job is enabled
     pub enabled: bool,
     /// Creation timestamp
@@ -218,15 +529,37 @@ impl Job {
             args: Vec::new(),
             working_dir: None,
             env: HashMap::new(),
+            running_as: None,
             retry_policy: RetryPolicy::default(),
+            cron_catchup_policy: CatchupPolicy::default(),
+            last_run_at: None,
+            on_failure_job: None,
+            depends_on: Vec::new(),
+            alert_on_failure: false,
+            notification_throttle: None,
             priority: Priority::default(),
             resource_limits: ResourceLimits::default(),
+            timeout: None,
+            output_destination: OutputDestination::default(),
+            condition: None,
+            sla: None,
+            max_runs: None,
+            dynamic_schedule: None,
+            current_schedule_override: None,
+            is_template: false,
+            template_vars: Vec::new(),
+            tags: Vec::new(),
             enabled: true,
             created_at: now,
             updated_at: now,
         }
     }
     
+    /// Creates a job from a named alias in `~/.rae/aliases.toml`.
+    pub fn from_alias(alias_name: &str) -> Result<Self, AliasError> {
+        AliasFile::load_default()?.job_from_alias(alias_name)
+    }
+    
     /// Creates a job with cron scheduling.
     pub fn with_cron(mut self, cron_expr: String) -> Self {
         self.schedule.cron = Some(cron_expr);
@@ -257,18 +590,168 @@ impl Job {
         self
     }
     
+    /// Sets the tags used to group the job with related jobs.
+    pub fn with_tags(mut self, tags: Vec<String>) -> Self {
+        self.tags = tags;
+        self
+    }
+    
+    /// Checks if the job carries the given tag.
+    pub fn has_tag(&self, tag: &str) -> bool {
+        self.tags.iter().any(|t| t == tag)
+    }
+    
     /// Sets the retry policy.
     pub fn with_retry_policy(mut self, retry_policy: RetryPolicy) -> Self {
         self.retry_policy = retry_policy;
         self
     }
     
+    /// Sets the policy for cron windows missed during downtime.
+    pub fn with_catchup_policy(mut self, policy: CatchupPolicy) -> Self {
+        self.cron_catchup_policy = policy;
+        self
+    }
+    
+    /// Sets the job to run when this job fails after all retries.
+    pub fn with_on_failure_job(mut self, job_id: JobId) -> Self {
+        self.on_failure_job = Some(job_id);
+        self
+    }
+    
+    /// Sets the jobs that must complete before this job runs.
+    pub fn with_depends_on(mut self, job_ids: Vec<JobId>) -> Self {
+        self.depends_on = job_ids;
+        self
+    }
+    
+    /// Posts failures to the alert webhook, at most once per `throttle`.
+    pub fn with_failure_alerts(mut self, throttle: Option<Duration>) -> Self {
+        self.alert_on_failure = true;
+        self.notification_throttle = throttle;
+        self
+    }
+    
+    /// Sets where standard output is delivered after the job completes.
+    pub fn with_output_destination(mut self, destination: OutputDestination) -> Self {
+        self.output_destination = destination;
+        self
+    }
+    
+    /// Sets the condition that must hold for the job to be scheduled.
+    pub fn with_condition(mut self, condition: JobCondition) -> Self {
+        self.condition = Some(condition);
+        self
+    }
+    
+    /// Sets the expected execution frequency of the job.
+    pub fn with_sla(mut self, sla: JobSla) -> Self {
+        self.sla = Some(sla);
+        self
+    }
+    
+    /// Limits how many times the job runs.
+    pub fn with_max_runs(mut self, max_runs: u32) -> Self {
+        self.max_runs = Some(max_runs);
+        self
+    }
+    
+    /// Sets a schedule that adapts to the job's output.
+    pub fn with_dynamic_schedule(mut self, dynamic_schedule: DynamicSchedule) -> Self {
+        self.dynamic_schedule = Some(dynamic_schedule);
+        self
+    }
+    
+    /// Cron expression in effect, taking the dynamic schedule into account.
+    pub fn active_cron(&self) -> Option<&str> {
+        self.current_schedule_override.as_deref().or(self.schedule.cron.as_deref())
+    }
+    
+    /// Updates the schedule override from the output of a run.
+    ///
+    /// Returns whether the active schedule changed.
+    pub fn apply_dynamic_schedule(&mut self, stdout: &str) -> bool {
+        let Some(dynamic_schedule) = &self.dynamic_schedule else {
+            return false;
+        };
+        
+        let selected = dynamic_schedule.select(stdout).to_string();
+        if self.current_schedule_override.as_deref() == Some(selected.as_str()) {
+            return false;
+        }
+        
+        self.current_schedule_override = Some(selected);
+        true
+    }
+    
+    /// Marks the job as a template with the given placeholder names.
+    pub fn as_template(mut self, vars: Vec<String>) -> Self {
+        self.is_template = true;
+        self.template_vars = vars;
+        self
+    }
+    
+    /// Creates a job from this template, substituting `{{NAME}}` placeholders
+    /// in the command, arguments and environment values.
+    pub fn instantiate(&self, vars: &HashMap<String, String>) -> Result<Job, TemplateError> {
+        if !self.is_template {
+            return Err(TemplateError::NotATemplate(self.id.clone()));
+        }
+        
+        let substitute = |text: &str| {
+            self.template_vars.iter().try_fold(text.to_string(), |text, name| {
+                let placeholder = format!("{{{{{}}}}}", name);
+                if !text.contains(&placeholder) {
+                    return Ok(text);
+                }
+                
+                let value = vars.get(name).ok_or_else(|| TemplateError::MissingVariable(name.clone()))?;
+                Ok(text.replace(&placeholder, value))
+            })
+        };
+        
+        let now = Utc::now();
+        let mut job = self.clone();
+        job.id = Uuid::new_v4().to_string();
+        job.command = substitute(&self.command)?;
+        job.args = self.args.iter().map(|arg| substitute(arg)).collect::<Result<_, _>>()?;
+        for value in job.env.values_mut() {
+            *value = substitute(value)?;
+        }
+        job.is_template = false;
+        job.template_vars = Vec::new();
+        job.last_run_at = None;
+        job.created_at = now;
+        job.updated_at = now;
+        
+        Ok(job)
+    }
+    
+    /// Whether the job's condition holds (jobs without one always qualify).
+    pub async fn condition_met(&self) -> bool {
+        match &self.condition {
+            Some(condition) => condition.evaluate().await,
+            None => true,
+        }
+    }
+    
     /// Sets the resource limits.
     pub fn with_resource_limits(mut self, resource_limits: ResourceLimits) -> Self {
         self.resource_limits = resource_limits;
         self
     }
     
+    /// Sets the maximum run time.
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = Some(timeout);
+        self
+    }
+    
+    /// Maximum run time: `timeout`, or else `resource_limits.max_duration`.
+    pub fn effective_timeout(&self) -> Option<Duration> {
+        self.timeout.or(self.resource_limits.max_duration.map(Duration::from_secs))
+    }
+    
     /// Sets the working directory.
     pub fn with_working_dir(mut self, working_dir: String) -> Self {
         self.working_dir = Some(working_dir);
@@ -281,6 +764,25 @@ impl Job {
         self
     }
     
+    /// Inherits the variables of a named environment group from the config.
+    ///
+    /// Groups can be stacked; variables already set on the job (explicitly or by
+    /// an earlier group) take precedence. Unknown groups leave the job unchanged.
+    pub fn with_env_group(mut self, config: &Config, group_name: &str) -> Self {
+        if let Some(vars) = config.env_group(group_name) {
+            for (key, value) in vars {
+                self.env.entry(key.clone()).or_insert_with(|| value.clone());
+            }
+        }
+        self
+    }
+    
+    /// Sets the Unix user to run the job as.
+    pub fn with_running_as(mut self, user: String) -> Self {
+        self.running_as = Some(user);
+        self
+    }
+    
     /// Adds command arguments.
     pub fn with_args(mut self, args: Vec<String>) -> Self {
         self.args = args;
@@ -327,6 +829,64 @@ impl Job {
     }
 }
 
+/// Changes to apply to an existing job; `None` fields are left unchanged.
+#[derive(Debug, Clone, Default)]
+pub struct JobPatch {
+    /// New schedule configuration
+    pub schedule: Option<Schedule>,
+    /// New command to execute
+    pub command: Option<String>,
+    /// New command arguments
+    pub args: Option<Vec<String>>,
+    /// New priority level
+    pub priority: Option<Priority>,
+    /// New retry policy
+    pub retry_policy: Option<RetryPolicy>,
+    /// New resource limits
+    pub resource_limits: Option<ResourceLimits>,
+    /// New description
+    pub description: Option<String>,
+}
+
+impl JobPatch {
+    /// Whether the patch changes nothing.
+    pub fn is_empty(&self) -> bool {
+        self.schedule.is_none()
+            && self.command.is_none()
+            && self.args.is_none()
+            && self.priority.is_none()
+            && self.retry_policy.is_none()
+            && self.resource_limits.is_none()
+            && self.description.is_none()
+    }
+    
+    /// Applies the set fields to a job and updates its modification timestamp.
+    pub fn apply(self, job: &mut Job) {
+        if let Some(schedule) = self.schedule {
+            job.schedule = schedule;
+        }
+        if let Some(command) = self.command {
+            job.command = command;
+        }
+        if let Some(args) = self.args {
+            job.args = args;
+        }
+        if let Some(priority) = self.priority {
+            job.priority = priority;
+        }
+        if let Some(retry_policy) = self.retry_policy {
+            job.retry_policy = retry_policy;
+        }
+        if let Some(resource_limits) = self.resource_limits {
+            job.resource_limits = resource_limits;
+        }
+        if let Some(description) = self.description {
+            job.description = Some(description);
+        }
+        job.touch();
+    }
+}
+
 /// Execution result of a job.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct JobResult {
@@ -342,6 +902,9 @@ pub struct JobResult {
     pub stdout: String,
     /// Standard error
     pub stderr: String,
+    /// Whether stdout or stderr exceeded `max_output_bytes` and was truncated
+    #[serde(default)]
+    pub truncated: bool,
     /// Execution status
     pub status: JobStatus,
     /// Resource usage
@@ -370,4 +933,172 @@ impl Default for ResourceUsage {
             disk_io_mb: 0,
         }
     }
-} 
\ No newline at end of file
+} 
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    fn env_groups_config() -> Config {
+        let mut config = Config::default();
+        config.define_env_group("aws", HashMap::from([
+            ("AWS_REGION".to_string(), "us-east-1".to_string()),
+            ("AWS_PROFILE".to_string(), "rae".to_string()),
+        ]));
+        config.define_env_group("proxy", HashMap::from([
+            ("HTTPS_PROXY".to_string(), "http://proxy:8080".to_string()),
+            ("AWS_REGION".to_string(), "eu-west-1".to_string()),
+        ]));
+        config
+    }
+    
+    #[test]
+    fn test_timeout_falls_back_to_max_duration() {
+        let job = Job::new("job".to_string(), "true".to_string());
+        assert_eq!(job.effective_timeout(), Some(Duration::from_secs(3600)));
+        
+        let job = job.with_timeout(Duration::from_millis(1500));
+        assert_eq!(job.effective_timeout(), Some(Duration::from_millis(1500)));
+    }
+    
+    #[test]
+    fn test_dynamic_schedule_follows_output() {
+        let mut job = Job::new("price-check".to_string(), "check-price".to_string())
+            .with_cron("0 */15 * * * *".to_string())
+            .with_dynamic_schedule(DynamicSchedule {
+                fast_cron: "0 * * * * *".to_string(),
+                slow_cron: "0 */15 * * * *".to_string(),
+                fast_condition: "/volatile".to_string(),
+            });
+        assert_eq!(job.active_cron(), Some("0 */15 * * * *"));
+        
+        // (output of the run, schedule changed, active cron afterwards)
+        let ticks = [
+            (r#"{"price": 101.5, "volatile": true}"#, true, "0 * * * * *"),
+            (r#"{"price": 101.7, "volatile": true}"#, false, "0 * * * * *"),
+            (r#"{"price": 101.6, "volatile": false}"#, true, "0 */15 * * * *"),
+            ("price feed unavailable", false, "0 */15 * * * *"),
+            (r#"{"volatile": true}"#, true, "0 * * * * *"),
+        ];
+        
+        for (stdout, changed, active) in ticks {
+            assert_eq!(job.apply_dynamic_schedule(stdout), changed, "output {}", stdout);
+            assert_eq!(job.active_cron(), Some(active));
+        }
+    }
+    
+    fn test_runner_template() -> Job {
+        let mut job = Job::new("test-runner".to_string(), "cargo".to_string())
+            .with_args(vec!["test".to_string(), "--package".to_string(), "{{MODULE}}".to_string()])
+            .as_template(vec!["MODULE".to_string(), "LEVEL".to_string()]);
+        job.env.insert("RUST_LOG".to_string(), "{{MODULE}}={{LEVEL}}".to_string());
+        job
+    }
+    
+    #[test]
+    fn test_template_substitution() {
+        let template = test_runner_template();
+        let vars = HashMap::from([
+            ("MODULE".to_string(), "rae-agent".to_string()),
+            ("LEVEL".to_string(), "debug".to_string()),
+        ]);
+        
+        let job = template.instantiate(&vars).unwrap();
+        
+        assert_ne!(job.id, template.id);
+        assert!(!job.is_template);
+        assert_eq!(job.args, vec!["test", "--package", "rae-agent"]);
+        assert_eq!(job.env.get("RUST_LOG").map(String::as_str), Some("rae-agent=debug"));
+    }
+    
+    #[test]
+    fn test_template_missing_variable() {
+        let template = test_runner_template();
+        let vars = HashMap::from([("MODULE".to_string(), "rae-agent".to_string())]);
+        
+        assert!(matches!(
+            template.instantiate(&vars),
+            Err(TemplateError::MissingVariable(name)) if name == "LEVEL"
+        ));
+    }
+    
+    #[test]
+    fn test_instantiate_requires_template() {
+        let job = Job::new("plain".to_string(), "true".to_string());
+        assert!(matches!(job.instantiate(&HashMap::new()), Err(TemplateError::NotATemplate(_))));
+    }
+    
+    #[test]
+    fn test_with_stacked_env_groups() {
+        let config = env_groups_config();
+        
+        let job = Job::new("sync".to_string(), "aws".to_string())
+            .with_env_group(&config, "aws")
+            .with_env_group(&config, "proxy");
+        
+        assert_eq!(job.env.get("AWS_PROFILE").map(String::as_str), Some("rae"));
+        assert_eq!(job.env.get("HTTPS_PROXY").map(String::as_str), Some("http://proxy:8080"));
+        // The first group to define a variable wins
+        assert_eq!(job.env.get("AWS_REGION").map(String::as_str), Some("us-east-1"));
+    }
+    
+    #[test]
+    fn test_job_env_overrides_env_group() {
+        let config = env_groups_config();
+        
+        // Job-level value set before the group is kept
+        let job = Job::new("sync".to_string(), "aws".to_string())
+            .with_env("AWS_REGION".to_string(), "ap-south-1".to_string())
+            .with_env_group(&config, "aws");
+        assert_eq!(job.env.get("AWS_REGION").map(String::as_str), Some("ap-south-1"));
+        
+        // Job-level value set after the group replaces it
+        let job = Job::new("sync".to_string(), "aws".to_string())
+            .with_env_group(&config, "aws")
+            .with_env("AWS_REGION".to_string(), "ap-south-1".to_string());
+        assert_eq!(job.env.get("AWS_REGION").map(String::as_str), Some("ap-south-1"));
+    }
+    
+    #[test]
+    fn test_unknown_env_group_is_ignored() {
+        let config = env_groups_config();
+        
+        let job = Job::new("sync".to_string(), "aws".to_string())
+            .with_env_group(&config, "missing");
+        assert!(job.env.is_empty());
+    }
+    
+    #[tokio::test]
+    async fn test_env_var_conditions() {
+        // SAFETY: the variable name is unique to this test
+        unsafe { std::env::set_var("RAE_TEST_CONDITION_NETWORK", "home") };
+        
+        assert!(JobCondition::EnvVarSet("RAE_TEST_CONDITION_NETWORK".to_string()).evaluate().await);
+        assert!(!JobCondition::EnvVarSet("RAE_TEST_CONDITION_UNSET".to_string()).evaluate().await);
+        assert!(JobCondition::EnvVarMatches("RAE_TEST_CONDITION_NETWORK".to_string(), "home".to_string()).evaluate().await);
+        assert!(!JobCondition::EnvVarMatches("RAE_TEST_CONDITION_NETWORK".to_string(), "office".to_string()).evaluate().await);
+        assert!(!JobCondition::EnvVarMatches("RAE_TEST_CONDITION_UNSET".to_string(), "home".to_string()).evaluate().await);
+    }
+    
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_command_condition() {
+        assert!(JobCondition::CommandSucceeds("true".to_string()).evaluate().await);
+        assert!(!JobCondition::CommandSucceeds("exit 3".to_string()).evaluate().await);
+        
+        let job = Job::new("sync".to_string(), "rsync".to_string());
+        assert!(job.condition_met().await);
+        assert!(!job.with_condition(JobCondition::CommandSucceeds("false".to_string())).condition_met().await);
+    }
+    
+    #[test]
+    fn test_priority_parse_display_round_trip() {
+        for priority in [Priority::Low, Priority::Normal, Priority::High, Priority::Critical] {
+            assert_eq!(priority.to_string().parse::<Priority>().unwrap(), priority);
+        }
+        assert_eq!(Priority::High.to_string(), "high");
+        assert_eq!("Critical".parse::<Priority>().unwrap(), Priority::Critical);
+        
+        assert!(matches!("urgent".parse::<Priority>(), Err(SchedulerError::InvalidJob(_))));
+    }
+}
@@ -0,0 +1,139 @@
+//! System metrics sampled for pattern triggers.
+//!
+//! `SystemMetricsCollector` keeps a history of memory usage samples so the
+//! monitor can tell whether usage stayed above a threshold for a whole window.
+
+use chrono::{DateTime, Duration, Utc};
+use std::collections::VecDeque;
+use std::sync::Mutex;
+use sysinfo::System;
+
+/// How long memory samples are kept; longer pattern windows never match.
+pub const SAMPLE_RETENTION: Duration = Duration::days(1);
+
+/// Reads how much of the system memory is in use.
+pub trait MemorySource: Send {
+    /// Percentage of total memory in use, from 0 to 100.
+    fn memory_percent(&mut self) -> f64;
+}
+
+/// Reads memory usage through `sysinfo`.
+pub struct SysinfoMemory {
+    system: System,
+}
+
+impl MemorySource for SysinfoMemory {
+    fn memory_percent(&mut self) -> f64 {
+        self.system.refresh_memory();
+        let total = self.system.total_memory();
+        if total == 0 {
+            return 0.0;
+        }
+        
+        self.system.used_memory() as f64 / total as f64 * 100.0
+    }
+}
+
+/// Samples system memory usage and keeps a sliding history of the samples.
+pub struct SystemMetricsCollector {
+    source: Mutex<Box<dyn MemorySource>>,
+    /// Memory usage samples, oldest first
+    samples: Mutex<VecDeque<(DateTime<Utc>, f64)>>,
+}
+
+impl Default for SystemMetricsCollector {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl SystemMetricsCollector {
+    /// Creates a collector reading the memory of this system.
+    pub fn new() -> Self {
+        Self::with_source(Box::new(SysinfoMemory { system: System::new() }))
+    }
+    
+    /// Creates a collector reading memory usage from `source`.
+    pub fn with_source(source: Box<dyn MemorySource>) -> Self {
+        SystemMetricsCollector {
+            source: Mutex::new(source),
+            samples: Mutex::new(VecDeque::new()),
+        }
+    }
+    
+    /// Percentage of total system memory in use right now, from 0 to 100.
+    pub fn current_memory_percent(&self) -> f64 {
+        self.source.lock().unwrap_or_else(|e| e.into_inner()).memory_percent()
+    }
+    
+    /// Reads the memory usage and records it as the sample taken at `now`.
+    pub fn sample(&self, now: DateTime<Utc>) -> f64 {
+        let percent = self.current_memory_percent();
+        
+        let mut samples = self.samples.lock().unwrap_or_else(|e| e.into_inner());
+        samples.push_back((now, percent));
+        // Keep the newest sample older than the retention, as it covers its start
+        while samples.get(1).is_some_and(|(at, _)| *at <= now - SAMPLE_RETENTION) {
+            samples.pop_front();
+        }
+        
+        percent
+    }
+    
+    /// Whether every sample since `window` before `now` is above `threshold` percent.
+    ///
+    /// The samples must cover the whole window, so this is `false` until
+    /// sampling has been running for at least `window`.
+    pub fn memory_above(&self, threshold: f64, window: Duration, now: DateTime<Utc>) -> bool {
+        let samples = self.samples.lock().unwrap_or_else(|e| e.into_inner());
+        let start = now - window;
+        
+        // The last sample before the window started shows the usage at its start
+        let Some(first) = samples.iter().rposition(|(at, _)| *at <= start) else {
+            return false;
+        };
+        samples.iter().skip(first).all(|(_, percent)| *percent > threshold)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    /// Returns the given readings in order, then 0%.
+    struct Readings(VecDeque<f64>);
+    
+    impl MemorySource for Readings {
+        fn memory_percent(&mut self) -> f64 {
+            self.0.pop_front().unwrap_or(0.0)
+        }
+    }
+    
+    #[test]
+    fn test_memory_above_requires_the_whole_window() {
+        let readings = Readings(VecDeque::from([50.0, 95.0, 95.0, 95.0, 40.0]));
+        let collector = SystemMetricsCollector::with_source(Box::new(readings));
+        let start: DateTime<Utc> = "2024-05-01T00:00:00Z".parse().unwrap();
+        let at = |seconds| start + Duration::seconds(seconds);
+        let window = Duration::seconds(60);
+        
+        // 50% at the start, then 95% from 30s on
+        collector.sample(at(0));
+        collector.sample(at(30));
+        assert!(!collector.memory_above(90.0, window, at(30)));
+        collector.sample(at(60));
+        assert!(!collector.memory_above(90.0, window, at(60)));
+        collector.sample(at(90));
+        assert!(collector.memory_above(90.0, window, at(90)));
+        assert!(!collector.memory_above(95.0, window, at(90)));
+        
+        collector.sample(at(120));
+        assert!(!collector.memory_above(90.0, window, at(120)));
+    }
+    
+    #[test]
+    fn test_current_memory_percent_of_this_system() {
+        let percent = SystemMetricsCollector::new().current_memory_percent();
+        assert!((0.0..=100.0).contains(&percent), "{}", percent);
+    }
+}
@@ -4,21 +4,46 @@
 //! Supports cron-like syntax, timezone-aware scheduling, and platform-appropriate
 //! background process management.
 
+pub mod alert;
+pub mod alias;
+pub mod emergency;
 pub mod job;
 pub mod parser;
 pub mod queue;
 pub mod persistence;
 pub mod executor;
+pub mod file_watch;
+pub mod metrics;
 pub mod monitor;
+pub mod output;
+pub mod results;
+pub mod usage;
 pub mod cli;
 
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
 use std::sync::Arc;
-use tokio::sync::RwLock;
-use crate::scheduler::job::{Job, JobId, JobStatus};
+use chrono::{DateTime, Utc};
+use icalendar::{Calendar, Component, Event, EventLike};
+use tokio::sync::{broadcast, Notify, RwLock};
+use tracing::{info, instrument, warn};
+use crate::config::Config;
+use crate::core::MessageBus;
+use crate::error::RaeError;
+use crate::scheduler::job::{CatchupPolicy, Job, JobCondition, JobId, JobPatch, JobResult, JobStatus, OutputDestination};
 use crate::scheduler::queue::JobQueue;
-use crate::scheduler::persistence::JobPersistence;
+use crate::scheduler::persistence::{JobPersistence, StorageFormat};
 use crate::scheduler::executor::JobExecutor;
-use crate::scheduler::monitor::JobMonitor;
+use crate::scheduler::alert::{AlertThresholds, FileAlerter, WebhookAlerter};
+use crate::scheduler::monitor::{JobHealth, JobMonitor, JobStatusChange, MonitorStats};
+use crate::scheduler::emergency::EmergencyStop;
+use crate::scheduler::results::ResultStore;
+
+/// Number of upcoming runs per job written by `Scheduler::export_schedule`.
+pub const SCHEDULE_EXPORT_RUNS: usize = 30;
+
+/// How long the dispatch loop sleeps when no job has a scheduled time.
+const DISPATCH_IDLE_WAIT: std::time::Duration = std::time::Duration::from_secs(60);
 
 /// Main scheduler that manages all scheduled jobs and automation triggers.
 /// 
@@ -29,6 +54,16 @@ pub struct Scheduler {
     persistence: Arc<JobPersistence>,
     executor: Arc<JobExecutor>,
     monitor: Arc<JobMonitor>,
+    /// Marker file that keeps an emergency stop across restarts
+    emergency_stop_path: PathBuf,
+    /// One-off jobs waiting for their run time; these are never persisted
+    one_off_jobs: Arc<RwLock<HashMap<JobId, Job>>>,
+    /// Wakes the dispatch loop when a job may have become due earlier
+    queue_changed: Arc<Notify>,
+    /// On-disk history of job results
+    result_store: Arc<ResultStore>,
+    /// Bus emergency stops are announced on
+    message_bus: Option<Arc<MessageBus>>,
 }
 
 impl Scheduler {
@@ -38,38 +73,341 @@ impl Scheduler {
         let queue = Arc::new(RwLock::new(JobQueue::new()));
         let executor = Arc::new(JobExecutor::new());
         let monitor = Arc::new(JobMonitor::new());
+        let result_store = Arc::new(ResultStore::new()?);
+        executor.set_result_store(result_store.clone()).await;
+        let emergency_stop_path = EmergencyStop::default_path()
+            .ok_or_else(|| SchedulerError::InvalidJob("Could not determine home directory".to_string()))?;
         
         Ok(Scheduler {
             queue,
             persistence,
             executor,
             monitor,
+            emergency_stop_path,
+            one_off_jobs: Arc::new(RwLock::new(HashMap::new())),
+            queue_changed: Arc::new(Notify::new()),
+            result_store,
+            message_bus: None,
         })
     }
     
+    /// Creates a scheduler that keeps all of its state below `dir`.
+    #[cfg(test)]
+    pub(crate) async fn with_temp_dir(dir: &std::path::Path) -> Self {
+        let mut scheduler = Scheduler::new().await.unwrap();
+        scheduler.persistence = Arc::new(JobPersistence::with_storage_dir(dir.join("jobs")).unwrap());
+        scheduler.result_store = Arc::new(ResultStore::with_dir(dir.join("history")).unwrap());
+        scheduler.executor.set_result_store(scheduler.result_store.clone()).await;
+        scheduler.emergency_stop_path = dir.join("EMERGENCY_STOP");
+        scheduler
+    }
+    
+    /// Sets up monitor alerts from the configuration.
+    ///
+    /// Alerts are logged and appended to `alerts.jsonl` in the data directory;
+    /// they are also posted to `alert_webhook_url` if the privacy level allows it.
+    pub fn with_alerting(self, config: &Config) -> Self {
+        self.monitor.set_alert_thresholds(AlertThresholds::from_config(config));
+        self.monitor.add_alerter(Box::new(FileAlerter::in_data_dir(config)));
+        
+        if let Some(url) = &config.alert_webhook_url {
+            match WebhookAlerter::new(url.clone(), &config.privacy_level) {
+                Ok(alerter) => self.monitor.add_alerter(Box::new(alerter)),
+                Err(e) => warn!("Not sending alerts to webhook: {}", e),
+            }
+        }
+        
+        self
+    }
+    
+    /// Sets the format jobs are stored in.
+    ///
+    /// Jobs stored in another format are migrated when the scheduler starts.
+    pub fn with_storage_format(mut self, format: StorageFormat) -> Result<Self, SchedulerError> {
+        self.persistence = Arc::new(JobPersistence::new()?.with_format(format));
+        Ok(self)
+    }
+    
+    /// Sets the message bus emergency stops are published on, under the
+    /// `emergency_stop` topic.
+    pub fn with_message_bus(mut self, bus: Arc<MessageBus>) -> Self {
+        self.message_bus = Some(bus);
+        self
+    }
+    
+    /// Sets how many results are kept in each job's history.
+    pub fn with_history_limit(self, limit: usize) -> Self {
+        self.result_store.set_max_history(limit);
+        self
+    }
+    
+    /// Halts all jobs: pauses the scheduler and executor, cancels running jobs
+    /// and records the reason so the scheduler stays paused after a restart.
+    ///
+    /// The stop is also published on the message bus, if one is set.
+    pub async fn emergency_stop(&self, reason: &str) -> Result<(), SchedulerError> {
+        self.executor.pause();
+        
+        for job_id in self.executor.running_job_ids().await {
+            self.executor.cancel_job(&job_id).await?;
+            self.monitor.update_job_status(&job_id, JobStatus::Cancelled).await?;
+        }
+        
+        EmergencyStop::new(reason).write(&self.emergency_stop_path)?;
+        if let Some(bus) = &self.message_bus {
+            bus.publish("emergency_stop", serde_json::json!({ "reason": reason }));
+        }
+        
+        warn!("Emergency stop: {}", reason);
+        Ok(())
+    }
+    
+    /// Clears an emergency stop and resumes the scheduler, unless it is also
+    /// paused for maintenance.
+    ///
+    /// Returns whether a stop was in effect.
+    pub async fn clear_emergency_stop(&self) -> Result<bool, SchedulerError> {
+        let cleared = EmergencyStop::clear(&self.emergency_stop_path)?;
+        if self.pause_marker_path().exists() {
+            info!("Scheduler stays paused for maintenance");
+        } else {
+            self.executor.resume();
+        }
+        
+        Ok(cleared)
+    }
+    
+    /// Gets the recorded emergency stop, if one is in effect.
+    pub fn emergency_stop_state(&self) -> Result<Option<EmergencyStop>, SchedulerError> {
+        Ok(EmergencyStop::read(&self.emergency_stop_path)?)
+    }
+    
+    /// Checks if job execution is paused, for maintenance or by an emergency stop.
+    ///
+    /// While paused, jobs that become due (including retries, recovery jobs and
+    /// triggered runs) are held by the executor, and `run_now`, `run_job_now`
+    /// and `run_at` are rejected.
+    pub fn is_paused(&self) -> bool {
+        self.executor.is_paused()
+    }
+    
+    /// Pauses job execution for maintenance.
+    ///
+    /// Jobs that become due while paused are held rather than dropped and run
+    /// once `resume` is called; running jobs are not affected. The pause is
+    /// recorded so the scheduler stays paused after a restart.
+    pub async fn pause(&self) -> Result<(), SchedulerError> {
+        let path = self.pause_marker_path();
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        std::fs::write(&path, Utc::now().to_rfc3339())?;
+        
+        self.executor.pause();
+        info!("Scheduler paused");
+        Ok(())
+    }
+    
+    /// Resumes job execution, starting held jobs up to the concurrency limit.
+    ///
+    /// An emergency stop has to be cleared with `clear_emergency_stop` instead.
+    pub async fn resume(&self) -> Result<(), SchedulerError> {
+        if let Some(stop) = self.emergency_stop_state()? {
+            return Err(SchedulerError::EmergencyStopped(stop.reason));
+        }
+        
+        match std::fs::remove_file(self.pause_marker_path()) {
+            Ok(()) => {}
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => {}
+            Err(e) => return Err(e.into()),
+        }
+        
+        self.executor.resume();
+        info!("Scheduler resumed");
+        Ok(())
+    }
+    
+    /// Marker file that keeps a maintenance pause across restarts.
+    fn pause_marker_path(&self) -> PathBuf {
+        self.emergency_stop_path.with_file_name("PAUSED")
+    }
+    
+    /// Pauses job execution if it was paused before a restart.
+    fn restore_pause(&self) {
+        if self.pause_marker_path().exists() {
+            self.executor.pause();
+            warn!("Scheduler is paused; jobs are held until it is resumed");
+        }
+    }
+    
+    /// Pauses the scheduler if an emergency stop was recorded before a restart.
+    fn restore_emergency_stop(&self) -> Result<(), SchedulerError> {
+        if let Some(stop) = self.emergency_stop_state()? {
+            self.executor.pause();
+            warn!("Scheduler paused by emergency stop at {}: {}", stop.stopped_at, stop.reason);
+        }
+        
+        Ok(())
+    }
+    
     /// Adds a new job to the scheduler.
+    #[instrument(skip_all, fields(job_id = %job.id))]
     pub async fn add_job(&self, job: Job) -> Result<JobId, SchedulerError> {
-        let job_id = job.id.clone();
-        
         // Validate job configuration
         self.validate_job(&job)?;
+        self.check_job_links(&job).await?;
         
         // Store job configuration
         self.persistence.save_job(&job).await?;
         
-        // Add to queue
-        {
-            let mut queue = self.queue.write().await;
-            queue.add_job(job)?;
+        self.schedule_job(job).await
+    }
+    
+    /// Runs a job once at its `schedule.at` time without persisting it.
+    ///
+    /// The job is queued until its run time and removed from the queue once the
+    /// dispatch loop of the started scheduler has handed it to the executor.
+    pub async fn run_at(&self, job: Job) -> Result<JobId, SchedulerError> {
+        if self.is_paused() {
+            return Err(SchedulerError::Paused);
+        }
+        
+        self.validate_job(&job)?;
+        let at = job.schedule.at
+            .ok_or_else(|| SchedulerError::InvalidJob("One-off jobs need a run time".to_string()))?;
+        
+        info!("One-off job {} runs at {}", job.id, at);
+        self.one_off_jobs.write().await.insert(job.id.clone(), job.clone());
+        self.schedule_job(job).await
+    }
+    
+    /// Subscribes to status changes of scheduled jobs.
+    pub fn subscribe_status_changes(&self) -> broadcast::Receiver<JobStatusChange> {
+        self.monitor.subscribe_status_changes()
+    }
+    
+    /// Subscribes to results of jobs that completed successfully.
+    pub fn subscribe_completions(&self) -> broadcast::Receiver<JobResult> {
+        self.executor.subscribe_completions()
+    }
+    
+    /// Subscribes to results of jobs that failed after all retries.
+    pub fn subscribe_failures(&self) -> broadcast::Receiver<JobResult> {
+        self.executor.subscribe_failures()
+    }
+    
+    /// Imports many jobs at once.
+    ///
+    /// Jobs whose ID already exists are skipped and invalid jobs are reported
+    /// in the result. The remaining jobs are persisted as one batch and only
+    /// queued once the whole batch was written.
+    pub async fn bulk_import(&self, jobs: Vec<Job>) -> Result<BulkImportResult, SchedulerError> {
+        let mut result = BulkImportResult::default();
+        
+        let (persisted, _) = self.persistence.list_jobs().await?;
+        let mut known: HashMap<JobId, Job> = persisted
+            .into_iter()
+            .map(|job| (job.id.clone(), job))
+            .collect();
+        
+        let mut accepted = Vec::new();
+        for job in jobs {
+            let exists = known.contains_key(&job.id) || self.queue.read().await.get_job(&job.id).is_some();
+            if exists {
+                result.skipped += 1;
+                continue;
+            }
+            
+            match self.validate_job(&job) {
+                Ok(()) => {
+                    known.insert(job.id.clone(), job.clone());
+                    accepted.push(job);
+                }
+                Err(e) => result.errors.push((job.id.clone(), e)),
+            }
+        }
+        
+        // Failure chains and dependencies may point at other jobs of the same import
+        let (accepted, looping): (Vec<Job>, Vec<Job>) = accepted
+            .into_iter()
+            .partition(|job| find_link_cycle(&job.id, &known).is_ok());
+        for job in looping {
+            if let Err(e) = find_link_cycle(&job.id, &known) {
+                result.errors.push((job.id.clone(), e));
+            }
+        }
+        
+        self.persistence.save_jobs(&accepted).await?;
+        
+        for job in accepted {
+            let job_id = job.id.clone();
+            match self.schedule_job(job).await {
+                Ok(_) => result.added += 1,
+                Err(e) => result.errors.push((job_id, e)),
+            }
         }
         
+        info!("Imported {} jobs ({} skipped, {} failed)", result.added, result.skipped, result.errors.len());
+        Ok(result)
+    }
+    
+    /// Queues and monitors a job that has already been persisted.
+    async fn schedule_job(&self, job: Job) -> Result<JobId, SchedulerError> {
+        let job_id = job.id.clone();
+        let sla = job.sla.clone();
+        let last_run_at = job.last_run_at;
+        
+        // Templates are only stored; their instances get scheduled
+        let is_template = job.is_template;
+        if !is_template && job.enabled {
+            self.watch_job_triggers(&job);
+        }
+        
+        // Add to queue; conditions are evaluated before taking the queue lock
+        let suspended = !is_template && {
+            let condition_met = job.condition_met().await;
+            let mut queue = self.queue.write().await;
+            queue.add_job_with_condition(job, condition_met)?;
+            queue.is_suspended(&job_id)
+        };
+        self.queue_changed.notify_one();
+        
         // Start monitoring
         self.monitor.track_job(job_id.clone()).await?;
+        if suspended {
+            self.monitor.update_job_status(&job_id, JobStatus::Suspended).await?;
+        }
+        if let Some(sla) = sla.filter(|_| !is_template) {
+            self.monitor.set_job_sla(&job_id, sla, last_run_at).await?;
+        }
         
         Ok(job_id)
     }
     
+    /// Runs a job on its file event or pattern trigger, if it has one; failures
+    /// are logged.
+    fn watch_job_triggers(&self, job: &Job) {
+        if let Err(e) = self.executor.start_file_watchers(std::slice::from_ref(job)) {
+            warn!("Failed to watch files for job {}: {}", job.id, e);
+        }
+        self.monitor.watch_pattern(job);
+    }
+    
+    /// Creates and schedules a job from a persisted template.
+    pub async fn instantiate_template(
+        &self,
+        template_id: &JobId,
+        vars: &HashMap<String, String>,
+    ) -> Result<JobId, SchedulerError> {
+        let template = self.persistence.load_job(template_id).await?;
+        let job = template.instantiate(vars)?;
+        
+        info!("Instantiating template {} as job {}", template_id, job.id);
+        self.add_job(job).await
+    }
+    
     /// Removes a job from the scheduler.
+    #[instrument(skip_all, fields(job_id = %job_id))]
     pub async fn remove_job(&self, job_id: &JobId) -> Result<(), SchedulerError> {
         // Remove from queue
         {
@@ -82,18 +420,355 @@ impl Scheduler {
         
         // Stop monitoring
         self.monitor.untrack_job(job_id).await?;
+        self.executor.stop_file_watcher(job_id);
+        self.monitor.unwatch_pattern(job_id);
+        
+        Ok(())
+    }
+    
+    /// Modifies an existing job in place, keeping its ID and history.
+    pub async fn update_job(&self, job_id: &JobId, patch: JobPatch) -> Result<Job, SchedulerError> {
+        let mut job = self.persistence.load_job(job_id).await?;
+        patch.apply(&mut job);
+        
+        self.validate_job(&job)?;
+        self.persistence.save_job(&job).await?;
+        
+        // Re-inserting recalculates the next execution time for the new schedule
+        let suspended = {
+            let condition_met = job.condition_met().await;
+            let mut queue = self.queue.write().await;
+            if queue.get_job(job_id).is_some() {
+                queue.remove_job(job_id)?;
+            }
+            if job.enabled && !job.is_template {
+                queue.add_job_with_condition(job.clone(), condition_met)?;
+            }
+            queue.is_suspended(job_id)
+        };
+        self.queue_changed.notify_one();
+        
+        // Watch the job's current triggers instead of the old ones
+        self.executor.stop_file_watcher(job_id);
+        self.monitor.unwatch_pattern(job_id);
+        if job.enabled && !job.is_template {
+            self.watch_job_triggers(&job);
+        }
+        
+        if suspended {
+            self.monitor.update_job_status(job_id, JobStatus::Suspended).await?;
+        }
+        
+        info!("Updated job {}", job_id);
+        Ok(job)
+    }
+    
+    /// Enables a disabled job and queues it again.
+    pub async fn enable_job(&self, job_id: &JobId) -> Result<(), SchedulerError> {
+        let mut job = self.persistence.load_job(job_id).await?;
+        job.enabled = true;
+        job.touch();
+        self.persistence.save_job(&job).await?;
+        
+        if job.is_template {
+            return Ok(());
+        }
+        self.watch_job_triggers(&job);
+        
+        // Re-queueing recalculates the next execution time
+        let suspended = {
+            let condition_met = job.condition_met().await;
+            let mut queue = self.queue.write().await;
+            if queue.get_job(job_id).is_some() {
+                queue.remove_job(job_id)?;
+            }
+            queue.add_job_with_condition(job, condition_met)?;
+            queue.is_suspended(job_id)
+        };
+        self.queue_changed.notify_one();
+        
+        let status = if suspended { JobStatus::Suspended } else { JobStatus::Scheduled };
+        self.monitor.update_job_status(job_id, status).await?;
+        
+        Ok(())
+    }
+    
+    /// Disables a job: it stays in storage but is removed from the queue.
+    pub async fn disable_job(&self, job_id: &JobId) -> Result<(), SchedulerError> {
+        let mut job = self.persistence.load_job(job_id).await?;
+        job.enabled = false;
+        job.touch();
+        self.persistence.save_job(&job).await?;
+        
+        {
+            let mut queue = self.queue.write().await;
+            if queue.get_job(job_id).is_some() {
+                queue.remove_job(job_id)?;
+            }
+        }
+        self.executor.stop_file_watcher(job_id);
+        self.monitor.unwatch_pattern(job_id);
+        
+        self.monitor.update_job_status(job_id, JobStatus::Disabled).await?;
         
         Ok(())
     }
     
+    /// Re-evaluates job conditions, suspending or resuming jobs as needed, and
+    /// checks job SLAs and health.
+    ///
+    /// Runs every health check interval once the scheduler is started.
+    pub async fn tick(&self) -> Result<(), SchedulerError> {
+        Self::tick_jobs(&self.queue, &self.monitor, &self.queue_changed).await
+    }
+    
+    async fn tick_jobs(queue: &RwLock<JobQueue>, monitor: &JobMonitor, queue_changed: &Notify) -> Result<(), SchedulerError> {
+        // Condition commands may take a while, so they run without the queue lock
+        let conditions = queue.read().await.job_conditions();
+        let conditions = evaluate_conditions(conditions).await;
+        
+        let changed: Vec<(JobId, bool)> = {
+            let mut queue = queue.write().await;
+            queue.tick(&conditions)
+                .into_iter()
+                .map(|job_id| {
+                    let suspended = queue.is_suspended(&job_id);
+                    (job_id, suspended)
+                })
+                .collect()
+        };
+        if !changed.is_empty() {
+            queue_changed.notify_one();
+        }
+        
+        for (job_id, suspended) in changed {
+            let status = if suspended { JobStatus::Suspended } else { JobStatus::Scheduled };
+            info!("Job {} is now {:?}", job_id, status);
+            monitor.update_job_status(&job_id, status).await?;
+        }
+        
+        monitor.check_slas(Utc::now()).await;
+        monitor.check_health(Utc::now()).await;
+        
+        Ok(())
+    }
+    
+    /// Runs a persisted job immediately, adding the given environment variables.
+    pub async fn run_now(&self, job_id: &JobId, env: HashMap<String, String>) -> Result<JobId, SchedulerError> {
+        if self.is_paused() {
+            return Err(SchedulerError::Paused);
+        }
+        
+        Self::run_persisted_job(&self.persistence, &self.executor, job_id, env).await
+    }
+    
+    /// Runs a persisted job immediately, bypassing its schedule.
+    ///
+    /// Returns the ID the run is tracked under. A job limited to one
+    /// concurrent run is rejected while it is running; otherwise the run
+    /// waits for the running one to finish.
+    pub async fn run_job_now(&self, job_id: &JobId) -> Result<JobId, SchedulerError> {
+        if self.is_paused() {
+            return Err(SchedulerError::Paused);
+        }
+        
+        let job = self.persistence.load_job(job_id).await?;
+        if job.resource_limits.max_concurrent == Some(1) && self.executor.running_job_ids().await.contains(job_id) {
+            return Err(SchedulerError::AlreadyRunning(job_id.clone()));
+        }
+        
+        Ok(self.executor.execute_job(job).await?)
+    }
+    
+    /// Loads a job from persistence and hands it to the executor.
+    async fn run_persisted_job(
+        persistence: &JobPersistence,
+        executor: &JobExecutor,
+        job_id: &JobId,
+        env: HashMap<String, String>,
+    ) -> Result<JobId, SchedulerError> {
+        let mut job = persistence.load_job(job_id).await?;
+        job.env.extend(env);
+        
+        Ok(executor.execute_job(job).await?)
+    }
+    
+    /// Alerts on a job whose retries are exhausted and runs its `on_failure_job`.
+    async fn handle_job_failure(
+        persistence: &JobPersistence,
+        executor: &JobExecutor,
+        monitor: &JobMonitor,
+        failed: &JobResult,
+    ) {
+        if let Err(e) = monitor.update_job_status(&failed.job_id, failed.status.clone()).await {
+            warn!("Failed to update status of job {}: {}", failed.job_id, e);
+        }
+        
+        let Ok(job) = persistence.load_job(&failed.job_id).await else {
+            return;
+        };
+        monitor.alert_failure(&job, failed).await;
+        
+        let Some(recovery_id) = &job.on_failure_job else {
+            return;
+        };
+        
+        info!("Job {} failed, running recovery job {}", job.id, recovery_id);
+        if let Err(e) = Self::run_persisted_job(persistence, executor, recovery_id, failure_env(failed)).await {
+            warn!("Failed to run recovery job {} for {}: {}", recovery_id, job.id, e);
+        }
+    }
+    
+    /// Stores when a job last completed and how long it took, for catching up
+    /// on missed windows, measuring SLAs and duration percentiles, and switches
+    /// its dynamic schedule based on the output.
+    async fn record_completion(
+        persistence: &JobPersistence,
+        monitor: &JobMonitor,
+        queue: &RwLock<JobQueue>,
+        completed: &JobResult,
+    ) {
+        if let Err(e) = monitor.record_completion(completed).await {
+            warn!("Failed to update status of job {}: {}", completed.job_id, e);
+        }
+        if let Some(ended_at) = completed.ended_at
+            && let Ok(duration) = (ended_at - completed.started_at).to_std()
+        {
+            monitor.record_duration(duration).await;
+        }
+        
+        let Ok(mut job) = persistence.load_job(&completed.job_id).await else {
+            return;
+        };
+        
+        job.last_run_at = Some(completed.ended_at.unwrap_or(completed.started_at));
+        let rescheduled = job.apply_dynamic_schedule(&completed.stdout);
+        
+        if let Err(e) = persistence.save_job(&job).await {
+            warn!("Failed to record last run of job {}: {}", job.id, e);
+        }
+        
+        if rescheduled {
+            info!("Job {} switched to schedule {}", job.id, job.active_cron().unwrap_or_default());
+            
+            let mut queue = queue.write().await;
+            if queue.get_job(&job.id).is_some()
+                && let Err(e) = queue.update_job(job)
+            {
+                warn!("Failed to reschedule job: {}", e);
+            }
+        }
+    }
+    
+    /// Runs the downstream job of a `PipeToJob` destination with the output as stdin.
+    async fn run_piped_job(persistence: &JobPersistence, executor: &JobExecutor, completed: JobResult) {
+        let Ok(job) = persistence.load_job(&completed.job_id).await else {
+            return;
+        };
+        let OutputDestination::PipeToJob(next_id) = &job.output_destination else {
+            return;
+        };
+        
+        let result = match persistence.load_job(next_id).await {
+            Ok(next) => executor.execute_job_with_input(next, completed.stdout).await.map_err(SchedulerError::from),
+            Err(e) => Err(e.into()),
+        };
+        if let Err(e) = result {
+            warn!("Failed to pipe output of job {} into {}: {}", job.id, next_id, e);
+        }
+    }
+    
+    /// Rejects jobs whose failure, dependency or pipe links would loop back on themselves.
+    async fn check_job_links(&self, job: &Job) -> Result<(), SchedulerError> {
+        let (jobs, _) = self.persistence.list_jobs().await?;
+        let mut jobs: HashMap<JobId, Job> = jobs
+            .into_iter()
+            .map(|job| (job.id.clone(), job))
+            .collect();
+        jobs.insert(job.id.clone(), job.clone());
+        
+        find_link_cycle(&job.id, &jobs)
+    }
+    
     /// Gets the status of a specific job.
     pub async fn get_job_status(&self, job_id: &JobId) -> Result<JobStatus, SchedulerError> {
         self.monitor.get_job_status(job_id).await.map_err(|e| SchedulerError::MonitorError(e))
     }
     
+    /// Gets health information (run counts, last execution) for a job.
+    pub async fn get_job_health(&self, job_id: &JobId) -> Result<JobHealth, SchedulerError> {
+        Ok(self.monitor.get_job_health(job_id).await?)
+    }
+    
+    /// Gets the most recent results of a job, newest first.
+    pub async fn get_job_history(&self, job_id: &JobId, limit: usize) -> Result<Vec<JobResult>, SchedulerError> {
+        Ok(self.result_store.get_history(job_id, limit)?)
+    }
+    
+    /// Gets when the next scheduled job is due, so the dispatch loop can
+    /// `tokio::time::sleep_until` it instead of polling.
+    pub async fn next_wakeup(&self) -> Option<tokio::time::Instant> {
+        Self::wakeup_at(&self.queue).await
+    }
+    
+    async fn wakeup_at(queue: &RwLock<JobQueue>) -> Option<tokio::time::Instant> {
+        queue.read().await
+            .time_until_next()
+            .map(|wait| tokio::time::Instant::now() + wait)
+    }
+    
+    /// Runs the jobs whose scheduled time has come while the monitor is active.
+    ///
+    /// Sleeps until the next job is due, or until the queue changes.
+    async fn dispatch_jobs(
+        queue: Arc<RwLock<JobQueue>>,
+        executor: Arc<JobExecutor>,
+        monitor: Arc<JobMonitor>,
+        one_off_jobs: Arc<RwLock<HashMap<JobId, Job>>>,
+        queue_changed: Arc<Notify>,
+    ) {
+        while monitor.is_active().await {
+            let wakeup = Self::wakeup_at(&queue).await
+                .unwrap_or_else(|| tokio::time::Instant::now() + DISPATCH_IDLE_WAIT);
+            tokio::select! {
+                _ = tokio::time::sleep_until(wakeup) => {}
+                _ = queue_changed.notified() => continue,
+            }
+            
+            let due = queue.write().await.take_due_jobs(Utc::now());
+            for job in due {
+                one_off_jobs.write().await.remove(&job.id);
+                
+                // Held by the executor while the scheduler is paused
+                let job_id = job.id.clone();
+                if let Err(e) = executor.execute_job(job).await {
+                    warn!("Failed to run scheduled job {}: {}", job_id, e);
+                }
+            }
+        }
+    }
+    
+    /// Gets monitoring statistics, including duration percentiles.
+    pub async fn get_stats(&self) -> MonitorStats {
+        let mut stats = self.monitor.get_stats().await;
+        stats.running_jobs = self.executor.running_count().await;
+        stats
+    }
+    
+    /// Sets how many jobs may run at the same time.
+    pub async fn set_concurrency_limit(&self, limit: usize) {
+        self.executor.set_concurrency_limit(limit).await;
+    }
+    
+    /// Gets the next scheduled execution time of a job, if it has one.
+    pub async fn get_next_execution(&self, job_id: &JobId) -> Option<DateTime<Utc>> {
+        let queue = self.queue.read().await;
+        queue.get_next_execution(job_id)
+    }
+    
     /// Lists all jobs with their current status.
     pub async fn list_jobs(&self) -> Result<Vec<JobInfo>, SchedulerError> {
-        let jobs = self.persistence.list_jobs().await?;
+        let (jobs, _) = self.persistence.list_jobs().await?;
         let mut job_infos = Vec::new();
         
         for job in jobs {
@@ -104,9 +779,55 @@ impl Scheduler {
             });
         }
         
+        for job in self.one_off_jobs.read().await.values() {
+            let status = self.monitor.get_job_status(&job.id).await?;
+            job_infos.push(JobInfo {
+                job: job.clone(),
+                status,
+            });
+        }
+        
         Ok(job_infos)
     }
     
+    /// Lists the jobs that carry the given tag.
+    pub async fn list_jobs_by_tag(&self, tag: &str) -> Result<Vec<JobInfo>, SchedulerError> {
+        let mut jobs = self.list_jobs().await?;
+        jobs.retain(|info| info.job.has_tag(tag));
+        Ok(jobs)
+    }
+    
+    /// Writes the next `SCHEDULE_EXPORT_RUNS` runs of every enabled cron job to an
+    /// iCalendar file, one event per run, for import into a calendar app.
+    pub async fn export_schedule(&self, path: &Path) -> Result<(), SchedulerError> {
+        let (mut jobs, _) = self.persistence.list_jobs().await?;
+        jobs.sort_by(|a, b| a.id.cmp(&b.id));
+        
+        let now = Utc::now();
+        let mut calendar = Calendar::new();
+        calendar.name("Rae scheduled jobs");
+        for job in jobs.iter().filter(|job| job.enabled && !job.is_template) {
+            let command = std::iter::once(&job.command).chain(&job.args).cloned().collect::<Vec<_>>().join(" ");
+            for run in JobQueue::upcoming_windows(job, now, SCHEDULE_EXPORT_RUNS) {
+                calendar.push(
+                    Event::new()
+                        .uid(&format!("{}-{}@rae", job.id, run.timestamp()))
+                        .summary(&job.name)
+                        .description(&command)
+                        .starts(run)
+                        .done(),
+                );
+            }
+        }
+        
+        if let Some(parent) = path.parent().filter(|parent| !parent.as_os_str().is_empty()) {
+            tokio::fs::create_dir_all(parent).await?;
+        }
+        tokio::fs::write(path, calendar.done().to_string()).await?;
+        
+        Ok(())
+    }
+    
     /// Validates a job configuration.
     fn validate_job(&self, job: &Job) -> Result<(), SchedulerError> {
         // TODO: Re-enable cron validation once the cron crate issue is resolved
@@ -126,15 +847,95 @@ impl Scheduler {
     
     /// Starts the scheduler background processing.
     pub async fn start(&self) -> Result<(), SchedulerError> {
+        // Stay paused if an emergency stop or pause was never cleared
+        self.restore_emergency_stop()?;
+        self.restore_pause();
+        
         // Start the executor
         self.executor.start().await?;
         
         // Start the monitor
         self.monitor.start().await?;
+        self.monitor.start_pattern_triggers(self.executor.clone());
+        
+        // Record jobs that fail after all retries and chain their recovery jobs
+        let mut failures = self.executor.subscribe_failures();
+        let persistence = self.persistence.clone();
+        let executor = self.executor.clone();
+        let monitor = self.monitor.clone();
+        tokio::spawn(async move {
+            loop {
+                match failures.recv().await {
+                    Ok(failed) => Self::handle_job_failure(&persistence, &executor, &monitor, &failed).await,
+                    Err(broadcast::error::RecvError::Lagged(skipped)) => {
+                        warn!("Missed {} job failures while chaining recovery jobs", skipped);
+                    }
+                    Err(broadcast::error::RecvError::Closed) => break,
+                }
+            }
+        });
+        
+        // Pipe output of completed jobs into their downstream jobs
+        let mut completions = self.executor.subscribe_completions();
+        let persistence = self.persistence.clone();
+        let executor = self.executor.clone();
+        let monitor = self.monitor.clone();
+        let queue = self.queue.clone();
+        tokio::spawn(async move {
+            loop {
+                match completions.recv().await {
+                    Ok(completed) => {
+                        Self::record_completion(&persistence, &monitor, &queue, &completed).await;
+                        Self::run_piped_job(&persistence, &executor, completed).await;
+                    }
+                    Err(broadcast::error::RecvError::Lagged(skipped)) => {
+                        warn!("Missed {} job completions while piping output", skipped);
+                    }
+                    Err(broadcast::error::RecvError::Closed) => break,
+                }
+            }
+        });
+        
+        // Clean up after job writes interrupted by a crash
+        for path in self.persistence.repair_storage().await? {
+            warn!("Removed leftover temporary job file {}", path.display());
+        }
+        
+        // Upgrade job storage written in a different format
+        let migrated = self.persistence.ensure_storage_format().await?;
+        if migrated > 0 {
+            info!("Migrated {} jobs to {:?} storage", migrated, self.persistence.format());
+        }
         
         // Load persisted jobs
         self.load_persisted_jobs().await?;
         
+        // Run jobs as they become due
+        tokio::spawn(Self::dispatch_jobs(
+            self.queue.clone(),
+            self.executor.clone(),
+            self.monitor.clone(),
+            self.one_off_jobs.clone(),
+            self.queue_changed.clone(),
+        ));
+        
+        // Resume suspended jobs and check SLAs and health while the monitor runs
+        let queue = self.queue.clone();
+        let monitor = self.monitor.clone();
+        let queue_changed = self.queue_changed.clone();
+        tokio::spawn(async move {
+            let mut ticks = tokio::time::interval(monitor.get_health_check_interval());
+            loop {
+                ticks.tick().await;
+                if !monitor.is_active().await {
+                    break;
+                }
+                if let Err(e) = Self::tick_jobs(&queue, &monitor, &queue_changed).await {
+                    warn!("Failed to re-evaluate jobs: {}", e);
+                }
+            }
+        });
+        
         Ok(())
     }
     
@@ -143,30 +944,209 @@ impl Scheduler {
         // Stop the executor
         self.executor.stop().await?;
         
-        // Stop the monitor
+        // Stop the monitor, then wake the dispatch loop so it sees the stop
         self.monitor.stop().await?;
+        self.queue_changed.notify_one();
         
         Ok(())
     }
     
     /// Loads persisted jobs from storage.
     async fn load_persisted_jobs(&self) -> Result<(), SchedulerError> {
-        let jobs = self.persistence.list_jobs().await?;
+        let (jobs, errors) = self.persistence.list_jobs().await?;
+        
+        // Skip unreadable job files and move corrupt ones out of the way
+        if !errors.is_empty() {
+            for (path, error) in &errors {
+                warn!("Skipping job file {}: {}", path.display(), error);
+            }
+            
+            for path in self.persistence.quarantine_corrupt_files().await? {
+                warn!("Quarantined corrupt job file to {}", path.display());
+            }
+        }
         
         for job in jobs {
-            let mut queue = self.queue.write().await;
-            queue.add_job(job.clone())?;
+            if job.is_template {
+                self.monitor.track_job(job.id.clone()).await?;
+                continue;
+            }
+            
+            if !job.enabled {
+                self.monitor.track_job(job.id.clone()).await?;
+                self.monitor.update_job_status(&job.id, JobStatus::Disabled).await?;
+                continue;
+            }
+            
+            let suspended = {
+                let condition_met = job.condition_met().await;
+                let mut queue = self.queue.write().await;
+                queue.add_job_with_condition(job.clone(), condition_met)?;
+                queue.is_suspended(&job.id)
+            };
+            self.watch_job_triggers(&job);
             
             // Also track the job in the monitor
             self.monitor.track_job(job.id.clone()).await?;
+            if suspended {
+                self.monitor.update_job_status(&job.id, JobStatus::Suspended).await?;
+            }
+            if let Some(sla) = job.sla.clone() {
+                self.monitor.set_job_sla(&job.id, sla, job.last_run_at).await?;
+            }
+            
+            // Catch up on cron windows missed while the agent was down; the
+            // executor holds the runs while the scheduler is paused
+            let now = Utc::now();
+            let runs = catchup_runs(&job, now);
+            if runs > 0 && !suspended {
+                info!("Job {} missed cron windows, running {} catch-up execution(s)", job.id, runs);
+                let mut submitted = false;
+                for _ in 0..runs {
+                    match self.executor.execute_job(job.clone()).await {
+                        Ok(_) => submitted = true,
+                        Err(e) => {
+                            warn!("Failed to submit catch-up run of job {}: {}", job.id, e);
+                            break;
+                        }
+                    }
+                }
+                
+                // The missed windows are handled once their runs are submitted,
+                // so a restart before they finish does not repeat them
+                if submitted {
+                    let mut job = job.clone();
+                    job.last_run_at = Some(now);
+                    if let Err(e) = self.persistence.save_job(&job).await {
+                        warn!("Failed to record catch-up of job {}: {}", job.id, e);
+                    }
+                }
+            }
         }
         
         Ok(())
     }
 }
 
+/// Environment passed to a recovery job describing the failure that triggered it.
+fn failure_env(failed: &JobResult) -> HashMap<String, String> {
+    let error = match &failed.status {
+        JobStatus::Failed { error } => error.clone(),
+        _ => String::new(),
+    };
+    
+    HashMap::from([
+        ("RAE_TRIGGER_JOB_ID".to_string(), failed.job_id.clone()),
+        ("RAE_TRIGGER_ERROR".to_string(), error),
+    ])
+}
+
+/// Evaluates job conditions one after another, by job ID.
+async fn evaluate_conditions(conditions: Vec<(JobId, JobCondition)>) -> HashMap<JobId, bool> {
+    let mut results = HashMap::with_capacity(conditions.len());
+    for (job_id, condition) in conditions {
+        results.insert(job_id, condition.evaluate().await);
+    }
+    results
+}
+
+/// Number of catch-up executions a job needs for windows missed before `now`.
+fn catchup_runs(job: &Job, now: DateTime<Utc>) -> u32 {
+    let limit = match job.cron_catchup_policy {
+        CatchupPolicy::Skip => return 0,
+        CatchupPolicy::RunOnce => 1,
+        CatchupPolicy::RunAll { max_catchup } => max_catchup,
+    };
+    
+    job.cron_catchup_policy.runs_for(JobQueue::missed_windows(job, now, limit))
+}
+
+/// Follows `on_failure_job` links from `start`, failing if a job is revisited.
+fn find_failure_cycle(start: &JobId, jobs: &HashMap<JobId, Job>) -> Result<(), SchedulerError> {
+    let mut visited = HashSet::new();
+    let mut current = start;
+    
+    while let Some(next) = jobs.get(current).and_then(|job| job.on_failure_job.as_ref()) {
+        if !visited.insert(current.clone()) || next == start {
+            return Err(SchedulerError::InvalidJob(format!(
+                "on_failure_job chain of {} forms a loop", start
+            )));
+        }
+        current = next;
+    }
+    
+    Ok(())
+}
+
+/// Walks `depends_on` links from `start`, failing if they lead back to a job
+/// on the current path.
+fn find_dependency_cycle(start: &JobId, jobs: &HashMap<JobId, Job>) -> Result<(), SchedulerError> {
+    fn visit(
+        job_id: &JobId,
+        jobs: &HashMap<JobId, Job>,
+        path: &mut Vec<JobId>,
+        checked: &mut HashSet<JobId>,
+    ) -> Result<(), SchedulerError> {
+        if let Some(position) = path.iter().position(|id| id == job_id) {
+            let mut cycle = path[position..].to_vec();
+            cycle.push(job_id.clone());
+            return Err(SchedulerError::CyclicDependency(cycle.join(" -> ")));
+        }
+        if checked.contains(job_id) {
+            return Ok(());
+        }
+        
+        path.push(job_id.clone());
+        for dependency in jobs.get(job_id).map(|job| job.depends_on.as_slice()).unwrap_or_default() {
+            visit(dependency, jobs, path, checked)?;
+        }
+        path.pop();
+        checked.insert(job_id.clone());
+        
+        Ok(())
+    }
+    
+    visit(start, jobs, &mut Vec::new(), &mut HashSet::new())
+}
+
+/// Follows `PipeToJob` destinations from `start`, failing if output would be
+/// piped back into a job already in the chain.
+fn find_pipe_cycle(start: &JobId, jobs: &HashMap<JobId, Job>) -> Result<(), SchedulerError> {
+    let mut visited = HashSet::new();
+    let mut current = start;
+    
+    while let Some(OutputDestination::PipeToJob(next)) = jobs.get(current).map(|job| &job.output_destination) {
+        if !visited.insert(current.clone()) || next == start {
+            return Err(SchedulerError::InvalidJob(format!(
+                "output pipe chain of {} forms a loop", start
+            )));
+        }
+        current = next;
+    }
+    
+    Ok(())
+}
+
+/// Checks the failure chain, pipe chain and dependencies of `start` for cycles.
+fn find_link_cycle(start: &JobId, jobs: &HashMap<JobId, Job>) -> Result<(), SchedulerError> {
+    find_failure_cycle(start, jobs)?;
+    find_pipe_cycle(start, jobs)?;
+    find_dependency_cycle(start, jobs)
+}
+
+/// Outcome of `Scheduler::bulk_import`.
+#[derive(Debug, Default)]
+pub struct BulkImportResult {
+    /// Jobs that were persisted and scheduled
+    pub added: usize,
+    /// Jobs skipped because their ID already exists
+    pub skipped: usize,
+    /// Jobs rejected with the reason
+    pub errors: Vec<(JobId, SchedulerError)>,
+}
+
 /// Information about a job including its status.
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, serde::Serialize)]
 pub struct JobInfo {
     pub job: Job,
     pub status: JobStatus,
@@ -181,9 +1161,6 @@ pub enum SchedulerError {
     #[error("Invalid job configuration: {0}")]
     InvalidJob(String),
     
-    #[error("Job not found: {0}")]
-    JobNotFound(String),
-    
     #[error("Persistence error: {0}")]
     PersistenceError(#[from] persistence::PersistenceError),
     
@@ -196,8 +1173,758 @@ pub enum SchedulerError {
     #[error("Monitor error: {0}")]
     MonitorError(#[from] monitor::MonitorError),
     
+    #[error("Scheduler is paused")]
+    Paused,
+    
+    #[error("Scheduler is halted by an emergency stop: {0}")]
+    EmergencyStopped(String),
+    
+    #[error("Job is already running: {0}")]
+    AlreadyRunning(String),
+    
+    #[error("Cyclic job dependency: {0}")]
+    CyclicDependency(String),
+    
+    #[error("Alias error: {0}")]
+    AliasError(#[from] alias::AliasError),
+    
+    #[error("Template error: {0}")]
+    TemplateError(#[from] job::TemplateError),
+    
+    #[error("Result store error: {0}")]
+    ResultStoreError(#[from] results::ResultStoreError),
+    
     #[error("IO error: {0}")]
     IoError(#[from] std::io::Error),
+    
+    #[error(transparent)]
+    Rae(#[from] RaeError),
+}
+
+impl SchedulerError {
+    /// Whether the error is a failed lookup of a job or alias.
+    pub fn is_not_found(&self) -> bool {
+        match self {
+            SchedulerError::Rae(e)
+            | SchedulerError::QueueError(queue::QueueError::Rae(e))
+            | SchedulerError::PersistenceError(persistence::PersistenceError::Rae(e))
+            | SchedulerError::MonitorError(monitor::MonitorError::Rae(e))
+            | SchedulerError::ExecutorError(executor::ExecutorError::Rae(e)) => e.is_not_found(),
+            SchedulerError::AliasError(alias::AliasError::AliasNotFound(_)) => true,
+            _ => false,
+        }
+    }
 }
 
-use std::str::FromStr; 
\ No newline at end of file
+use std::str::FromStr;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::scheduler::alert::AlertType;
+    use crate::scheduler::job::{JobCondition, RetryPolicy};
+    use std::time::Duration;
+    
+    fn jobs_by_id(jobs: Vec<Job>) -> HashMap<JobId, Job> {
+        jobs.into_iter().map(|job| (job.id.clone(), job)).collect()
+    }
+    
+    #[test]
+    fn test_single_level_failure_chain() {
+        let recovery = Job::new("notify".to_string(), "notify-send".to_string());
+        let primary = Job::new("backup".to_string(), "rsync".to_string())
+            .with_on_failure_job(recovery.id.clone());
+        let primary_id = primary.id.clone();
+        let jobs = jobs_by_id(vec![primary, recovery]);
+        
+        assert!(find_failure_cycle(&primary_id, &jobs).is_ok());
+        
+        let failed = JobResult {
+            job_id: primary_id.clone(),
+            started_at: Utc::now(),
+            ended_at: Some(Utc::now()),
+            exit_code: Some(23),
+            stdout: String::new(),
+            stderr: String::new(),
+            truncated: false,
+            status: JobStatus::Failed { error: "Process exited with code 23".to_string() },
+            resource_usage: None,
+        };
+        let env = failure_env(&failed);
+        assert_eq!(env.get("RAE_TRIGGER_JOB_ID"), Some(&primary_id));
+        assert_eq!(env.get("RAE_TRIGGER_ERROR").map(String::as_str), Some("Process exited with code 23"));
+    }
+    
+    fn downtime_job(policy: CatchupPolicy) -> (Job, DateTime<Utc>) {
+        // Hourly job last run at midnight; the agent comes back at 03:30,
+        // so the 01:00, 02:00 and 03:00 windows were missed
+        let mut job = Job::new("hourly".to_string(), "true".to_string())
+            .with_cron("0 0 * * * *".to_string())
+            .with_catchup_policy(policy);
+        job.last_run_at = Some("2024-05-01T00:00:00Z".parse().unwrap());
+        
+        (job, "2024-05-01T03:30:00Z".parse().unwrap())
+    }
+    
+    #[test]
+    fn test_catchup_skip() {
+        let (job, now) = downtime_job(CatchupPolicy::Skip);
+        assert_eq!(JobQueue::missed_windows(&job, now, 10), 3);
+        assert_eq!(catchup_runs(&job, now), 0);
+    }
+    
+    #[test]
+    fn test_catchup_run_once() {
+        let (job, now) = downtime_job(CatchupPolicy::RunOnce);
+        assert_eq!(catchup_runs(&job, now), 1);
+    }
+    
+    #[test]
+    fn test_catchup_run_all() {
+        let (job, now) = downtime_job(CatchupPolicy::RunAll { max_catchup: 10 });
+        assert_eq!(catchup_runs(&job, now), 3);
+        
+        let (job, now) = downtime_job(CatchupPolicy::RunAll { max_catchup: 2 });
+        assert_eq!(catchup_runs(&job, now), 2);
+        
+        // Never-run jobs have nothing to catch up on
+        let (mut job, now) = downtime_job(CatchupPolicy::RunAll { max_catchup: 10 });
+        job.last_run_at = None;
+        assert_eq!(catchup_runs(&job, now), 0);
+    }
+    
+    /// Creates a scheduler that stores jobs in a temporary directory.
+    async fn temp_scheduler(temp_dir: &tempfile::TempDir) -> Scheduler {
+        Scheduler::with_temp_dir(temp_dir.path()).await
+    }
+    
+    #[tokio::test]
+    async fn test_submitted_catchup_marks_windows_handled() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let scheduler = temp_scheduler(&temp_dir).await;
+        
+        let missed_at = Utc::now() - chrono::Duration::hours(3);
+        let mut job = Job::new("hourly".to_string(), "true".to_string())
+            .with_cron("0 0 * * * *".to_string())
+            .with_catchup_policy(CatchupPolicy::RunOnce);
+        job.last_run_at = Some(missed_at);
+        scheduler.persistence.save_job(&job).await.unwrap();
+        
+        // Paused, so the catch-up run is held and cannot record its own completion
+        scheduler.pause().await.unwrap();
+        scheduler.load_persisted_jobs().await.unwrap();
+        
+        let reloaded = scheduler.persistence.load_job(&job.id).await.unwrap();
+        assert!(reloaded.last_run_at.unwrap() > missed_at);
+        assert_eq!(catchup_runs(&reloaded, Utc::now()), 0);
+    }
+    
+    #[tokio::test]
+    async fn test_bulk_import_all_success() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let scheduler = temp_scheduler(&temp_dir).await;
+        
+        let jobs: Vec<Job> = (0..3)
+            .map(|i| Job::new(format!("job-{}", i), "echo".to_string()))
+            .collect();
+        let result = scheduler.bulk_import(jobs.clone()).await.unwrap();
+        
+        assert_eq!((result.added, result.skipped, result.errors.len()), (3, 0, 0));
+        for job in &jobs {
+            assert!(scheduler.persistence.job_exists(&job.id));
+            assert!(scheduler.queue.read().await.get_job(&job.id).is_some());
+        }
+    }
+    
+    #[tokio::test]
+    async fn test_bulk_import_skips_existing_jobs() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let scheduler = temp_scheduler(&temp_dir).await;
+        
+        let existing = Job::new("existing".to_string(), "echo".to_string());
+        scheduler.add_job(existing.clone()).await.unwrap();
+        
+        let new = Job::new("new".to_string(), "echo".to_string());
+        let result = scheduler.bulk_import(vec![existing, new.clone(), new]).await.unwrap();
+        
+        assert_eq!((result.added, result.skipped, result.errors.len()), (1, 2, 0));
+    }
+    
+    #[tokio::test]
+    async fn test_bulk_import_reports_invalid_jobs() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let scheduler = temp_scheduler(&temp_dir).await;
+        
+        let valid = Job::new("valid".to_string(), "echo".to_string());
+        let invalid = Job::new("invalid".to_string(), String::new());
+        let result = scheduler.bulk_import(vec![valid.clone(), invalid.clone()]).await.unwrap();
+        
+        assert_eq!((result.added, result.skipped), (1, 0));
+        assert_eq!(result.errors.len(), 1);
+        assert_eq!(result.errors[0].0, invalid.id);
+        assert!(scheduler.persistence.job_exists(&valid.id));
+        assert!(!scheduler.persistence.job_exists(&invalid.id));
+    }
+    
+    #[tokio::test]
+    async fn test_disable_and_enable_job() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let scheduler = temp_scheduler(&temp_dir).await;
+        
+        let job_id = scheduler.add_job(Job::new("sync".to_string(), "echo".to_string())).await.unwrap();
+        let is_due = |queue: &JobQueue| queue.get_due_jobs().iter().any(|job| job.id == job_id);
+        assert!(is_due(&*scheduler.queue.read().await));
+        
+        scheduler.disable_job(&job_id).await.unwrap();
+        assert!(!is_due(&*scheduler.queue.read().await));
+        assert!(!scheduler.persistence.load_job(&job_id).await.unwrap().enabled);
+        assert_eq!(scheduler.monitor.get_job_status(&job_id).await.unwrap(), JobStatus::Disabled);
+        
+        scheduler.enable_job(&job_id).await.unwrap();
+        assert!(is_due(&*scheduler.queue.read().await));
+        assert!(scheduler.persistence.load_job(&job_id).await.unwrap().enabled);
+        assert_eq!(scheduler.monitor.get_job_status(&job_id).await.unwrap(), JobStatus::Scheduled);
+        
+        assert!(matches!(
+            scheduler.enable_job(&"missing".to_string()).await,
+            Err(SchedulerError::PersistenceError(_))
+        ));
+    }
+    
+    #[tokio::test]
+    async fn test_started_scheduler_resumes_suspended_job() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let mut scheduler = temp_scheduler(&temp_dir).await;
+        Arc::get_mut(&mut scheduler.monitor).unwrap().set_health_check_interval(Duration::from_millis(20));
+        scheduler.start().await.unwrap();
+        
+        let job = Job::new("sync".to_string(), "echo".to_string())
+            .with_cron("0 0 18 * * *".to_string())
+            .with_condition(JobCondition::EnvVarSet("RAE_TEST_SCHEDULER_TICK_LOOP".to_string()));
+        let job_id = scheduler.add_job(job).await.unwrap();
+        assert_eq!(scheduler.get_job_status(&job_id).await.unwrap(), JobStatus::Suspended);
+        
+        // SAFETY: the variable name is unique to this test
+        unsafe { std::env::set_var("RAE_TEST_SCHEDULER_TICK_LOOP", "1") };
+        
+        let mut status = JobStatus::Suspended;
+        for _ in 0..100 {
+            status = scheduler.get_job_status(&job_id).await.unwrap();
+            if status != JobStatus::Suspended {
+                break;
+            }
+            tokio::time::sleep(Duration::from_millis(20)).await;
+        }
+        assert_eq!(status, JobStatus::Scheduled);
+        assert!(!scheduler.queue.read().await.is_suspended(&job_id));
+        
+        scheduler.stop().await.unwrap();
+    }
+    
+    #[tokio::test]
+    async fn test_started_scheduler_runs_cron_jobs() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let scheduler = temp_scheduler(&temp_dir).await;
+        scheduler.start().await.unwrap();
+        let mut completions = scheduler.subscribe_completions();
+        
+        let job = Job::new("tick".to_string(), "echo".to_string())
+            .with_args(vec!["tock".to_string()])
+            .with_cron("* * * * * *".to_string());
+        let job_id = scheduler.add_job(job).await.unwrap();
+        
+        for _ in 0..2 {
+            let result = tokio::time::timeout(Duration::from_secs(5), completions.recv())
+                .await
+                .unwrap()
+                .unwrap();
+            assert_eq!(result.job_id, job_id);
+            assert_eq!(result.stdout.trim(), "tock");
+        }
+        assert!(scheduler.get_next_execution(&job_id).await.is_some());
+        
+        scheduler.stop().await.unwrap();
+    }
+    
+    #[tokio::test]
+    async fn test_failing_job_raises_high_failure_rate_alert() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let scheduler = temp_scheduler(&temp_dir).await;
+        scheduler.start().await.unwrap();
+        let mut failures = scheduler.subscribe_failures();
+        
+        let job = Job::new("broken".to_string(), "false".to_string())
+            .with_retry_policy(RetryPolicy { max_attempts: 1, ..RetryPolicy::fixed(0) });
+        let job_id = scheduler.add_job(job).await.unwrap();
+        scheduler.run_job_now(&job_id).await.unwrap();
+        
+        let failed = tokio::time::timeout(Duration::from_secs(5), failures.recv())
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(failed.job_id, job_id);
+        
+        // The failure is recorded by a subscriber, so wait for the monitor to see it
+        let alerts = tokio::time::timeout(Duration::from_secs(5), async {
+            loop {
+                let alerts = scheduler.monitor.check_health(Utc::now()).await;
+                if !alerts.is_empty() {
+                    return alerts;
+                }
+                tokio::time::sleep(Duration::from_millis(10)).await;
+            }
+        }).await.unwrap();
+        
+        assert_eq!(alerts.len(), 1);
+        assert_eq!(alerts[0].alert_type, AlertType::HighFailureRate);
+        assert_eq!(alerts[0].job_id, job_id);
+        
+        scheduler.stop().await.unwrap();
+    }
+    
+    #[tokio::test]
+    async fn test_completed_jobs_feed_duration_percentiles() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let scheduler = temp_scheduler(&temp_dir).await;
+        scheduler.start().await.unwrap();
+        assert_eq!(scheduler.get_stats().await.duration_p50, 0.0);
+        
+        let job_id = scheduler.add_job(Job::new("quick".to_string(), "true".to_string())).await.unwrap();
+        scheduler.run_job_now(&job_id).await.unwrap();
+        
+        // Completions are recorded by a subscriber, so wait for the stats to show it
+        tokio::time::timeout(Duration::from_secs(5), async {
+            while scheduler.get_stats().await.duration_p50 == 0.0 {
+                tokio::time::sleep(Duration::from_millis(10)).await;
+            }
+        }).await.unwrap();
+        
+        scheduler.stop().await.unwrap();
+    }
+    
+    #[tokio::test]
+    async fn test_next_wakeup_follows_earliest_job() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let scheduler = temp_scheduler(&temp_dir).await;
+        assert!(scheduler.next_wakeup().await.is_none());
+        
+        let at = Utc::now() + chrono::Duration::minutes(30);
+        scheduler.add_job(Job::new("report".to_string(), "echo".to_string()).with_time(at)).await.unwrap();
+        
+        let wakeup = scheduler.next_wakeup().await.unwrap();
+        let wait = wakeup - tokio::time::Instant::now();
+        assert!(wait <= std::time::Duration::from_secs(30 * 60));
+        assert!(wait > std::time::Duration::from_secs(29 * 60));
+    }
+    
+    #[tokio::test]
+    async fn test_run_job_now_rejects_second_run_of_single_run_job() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let scheduler = temp_scheduler(&temp_dir).await;
+        
+        let mut job = Job::new("slow".to_string(), "sleep".to_string()).with_args(vec!["2".to_string()]);
+        job.resource_limits.max_concurrent = Some(1);
+        let job_id = scheduler.add_job(job).await.unwrap();
+        
+        assert_eq!(scheduler.run_job_now(&job_id).await.unwrap(), job_id);
+        tokio::time::timeout(std::time::Duration::from_secs(5), async {
+            while !scheduler.executor.running_job_ids().await.contains(&job_id) {
+                tokio::time::sleep(std::time::Duration::from_millis(10)).await;
+            }
+        }).await.unwrap();
+        
+        assert!(matches!(scheduler.run_job_now(&job_id).await, Err(SchedulerError::AlreadyRunning(_))));
+        assert!(scheduler.run_job_now(&"missing".to_string()).await.unwrap_err().is_not_found());
+        scheduler.executor.cancel_job(&job_id).await.unwrap();
+    }
+    
+    #[tokio::test]
+    async fn test_update_job_keeps_identity() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let scheduler = temp_scheduler(&temp_dir).await;
+        
+        let original = Job::new("backup".to_string(), "echo".to_string())
+            .with_description("nightly backup".to_string());
+        let job_id = scheduler.add_job(original.clone()).await.unwrap();
+        
+        let patch = JobPatch {
+            command: Some("rsync".to_string()),
+            args: Some(vec!["-a".to_string()]),
+            schedule: Some(job::Schedule {
+                cron: Some("0 0 3 * * *".to_string()),
+                ..Default::default()
+            }),
+            ..Default::default()
+        };
+        let updated = scheduler.update_job(&job_id, patch).await.unwrap();
+        
+        assert_eq!(updated.id, job_id);
+        assert_eq!(updated.created_at, original.created_at);
+        assert_eq!(updated.description.as_deref(), Some("nightly backup"));
+        
+        let stored = scheduler.persistence.load_job(&job_id).await.unwrap();
+        assert_eq!((stored.command.as_str(), stored.args.clone()), ("rsync", vec!["-a".to_string()]));
+        
+        let queue = scheduler.queue.read().await;
+        assert_eq!(queue.get_job(&job_id).unwrap().command, "rsync");
+        assert!(queue.get_next_execution(&job_id).is_some());
+        drop(queue);
+        
+        // Invalid changes are rejected before anything is saved
+        let patch = JobPatch { command: Some(String::new()), ..Default::default() };
+        assert!(matches!(scheduler.update_job(&job_id, patch).await, Err(SchedulerError::InvalidJob(_))));
+        assert_eq!(scheduler.persistence.load_job(&job_id).await.unwrap().command, "rsync");
+    }
+    
+    #[tokio::test]
+    async fn test_update_job_watches_new_trigger_path() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let scheduler = temp_scheduler(&temp_dir).await;
+        let mut completions = scheduler.subscribe_completions();
+        let old_path = temp_dir.path().join("old.txt");
+        let new_path = temp_dir.path().join("new.txt");
+        let on_created = |path: &std::path::Path| job::EventTrigger {
+            event_type: job::EventType::FileCreated,
+            path: Some(path.to_string_lossy().into_owned()),
+            filter: None,
+        };
+        
+        let job = Job::new("on-file".to_string(), "echo".to_string()).with_event(on_created(&old_path));
+        let job_id = scheduler.add_job(job).await.unwrap();
+        
+        let patch = JobPatch {
+            schedule: Some(job::Schedule { event: Some(on_created(&new_path)), ..Default::default() }),
+            ..Default::default()
+        };
+        scheduler.update_job(&job_id, patch).await.unwrap();
+        
+        // The old path no longer runs the job
+        std::fs::write(&old_path, "x").unwrap();
+        assert!(tokio::time::timeout(Duration::from_millis(700), completions.recv()).await.is_err());
+        
+        std::fs::write(&new_path, "x").unwrap();
+        let result = tokio::time::timeout(Duration::from_secs(5), completions.recv())
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(result.job_id, job_id);
+    }
+    
+    #[tokio::test]
+    async fn test_update_job_drops_replaced_pattern_trigger() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let scheduler = temp_scheduler(&temp_dir).await;
+        
+        let job = Job::new("cleanup".to_string(), "echo".to_string()).with_pattern(job::PatternTrigger {
+            pattern_type: job::PatternType::HighMemoryUsage,
+            threshold: 90.0,
+            window: 60,
+        });
+        let job_id = scheduler.add_job(job).await.unwrap();
+        
+        let patch = JobPatch {
+            schedule: Some(job::Schedule { cron: Some("0 0 3 * * *".to_string()), ..Default::default() }),
+            ..Default::default()
+        };
+        scheduler.update_job(&job_id, patch).await.unwrap();
+        
+        assert!(!scheduler.monitor.unwatch_pattern(&job_id));
+    }
+    
+    #[tokio::test]
+    async fn test_run_at_runs_once_without_persisting() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let scheduler = temp_scheduler(&temp_dir).await;
+        scheduler.start().await.unwrap();
+        let mut completions = scheduler.subscribe_completions();
+        
+        let mut job = Job::new("one-off".to_string(), "echo".to_string())
+            .with_args(vec!["once".to_string()])
+            .with_max_runs(1);
+        job.schedule.at = Some(Utc::now() + chrono::Duration::milliseconds(200));
+        
+        let job_id = scheduler.run_at(job).await.unwrap();
+        assert!(!scheduler.persistence.job_exists(&job_id));
+        let listed = scheduler.list_jobs().await.unwrap();
+        assert!(listed.iter().any(|info| info.job.id == job_id));
+        
+        let result = tokio::time::timeout(std::time::Duration::from_secs(5), completions.recv())
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(result.job_id, job_id);
+        assert_eq!(result.stdout.trim(), "once");
+        
+        let listed = scheduler.list_jobs().await.unwrap();
+        assert!(listed.iter().all(|info| info.job.id != job_id));
+        assert!(scheduler.queue.read().await.get_job(&job_id).is_none());
+    }
+    
+    #[tokio::test]
+    async fn test_job_history_is_persisted() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let scheduler = temp_scheduler(&temp_dir).await;
+        let mut completions = scheduler.subscribe_completions();
+        
+        let job = Job::new("greet".to_string(), "echo".to_string())
+            .with_args(vec!["hello".to_string()]);
+        let job_id = scheduler.add_job(job).await.unwrap();
+        
+        for _ in 0..2 {
+            scheduler.run_now(&job_id, HashMap::new()).await.unwrap();
+            tokio::time::timeout(std::time::Duration::from_secs(5), completions.recv())
+                .await
+                .unwrap()
+                .unwrap();
+        }
+        
+        let history = scheduler.get_job_history(&job_id, 10).await.unwrap();
+        assert_eq!(history.len(), 2);
+        assert!(history[0].started_at >= history[1].started_at);
+        
+        // A fresh store over the same directory sees the same history
+        let reopened = ResultStore::with_dir(temp_dir.path().join("history")).unwrap();
+        assert_eq!(reopened.get_history(&job_id, 1).unwrap().len(), 1);
+    }
+    
+    #[tokio::test]
+    async fn test_run_at_requires_run_time() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let scheduler = temp_scheduler(&temp_dir).await;
+        
+        let job = Job::new("one-off".to_string(), "echo".to_string());
+        assert!(matches!(scheduler.run_at(job).await, Err(SchedulerError::InvalidJob(_))));
+    }
+    
+    #[tokio::test]
+    async fn test_emergency_stop_persists_across_restart() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let path = temp_dir.path().join("scheduler").join("EMERGENCY_STOP");
+        
+        let bus = Arc::new(MessageBus::new());
+        let mut stops = bus.subscribe("emergency_stop");
+        let mut scheduler = Scheduler::new().await.unwrap().with_message_bus(bus);
+        scheduler.emergency_stop_path = path.clone();
+        
+        scheduler.emergency_stop("runaway job").await.unwrap();
+        assert!(scheduler.is_paused());
+        assert!(path.is_file());
+        assert_eq!(stops.try_recv().unwrap(), serde_json::json!({ "reason": "runaway job" }));
+        assert!(matches!(scheduler.run_now(&"any".to_string(), HashMap::new()).await, Err(SchedulerError::Paused)));
+        
+        // A fresh scheduler picks the stop up again on start
+        let mut restarted = Scheduler::new().await.unwrap();
+        restarted.emergency_stop_path = path.clone();
+        assert!(!restarted.is_paused());
+        restarted.restore_emergency_stop().unwrap();
+        assert!(restarted.is_paused());
+        assert_eq!(restarted.emergency_stop_state().unwrap().unwrap().reason, "runaway job");
+        
+        // Only clearing the stop resumes the scheduler
+        assert!(matches!(restarted.resume().await, Err(SchedulerError::EmergencyStopped(_))));
+        assert!(restarted.is_paused());
+        assert!(restarted.clear_emergency_stop().await.unwrap());
+        assert!(!restarted.is_paused());
+        assert!(!path.exists());
+    }
+    
+    #[tokio::test]
+    async fn test_list_jobs_by_tag() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let scheduler = Scheduler::with_temp_dir(temp_dir.path()).await;
+        
+        let tagged = |name: &str, tags: &[&str]| {
+            Job::new(name.to_string(), "true".to_string())
+                .with_cron("0 3 * * *".to_string())
+                .with_tags(tags.iter().map(|tag| tag.to_string()).collect())
+        };
+        let backup = scheduler.add_job(tagged("backup", &["nightly"])).await.unwrap();
+        let cleanup = scheduler.add_job(tagged("cleanup", &["nightly", "disk"])).await.unwrap();
+        let report = scheduler.add_job(tagged("report", &["disk"])).await.unwrap();
+        
+        let ids = |jobs: Vec<JobInfo>| {
+            let mut ids: Vec<JobId> = jobs.into_iter().map(|info| info.job.id).collect();
+            ids.sort();
+            ids
+        };
+        let mut nightly = vec![backup, cleanup.clone()];
+        nightly.sort();
+        let mut disk = vec![cleanup, report];
+        disk.sort();
+        
+        assert_eq!(ids(scheduler.list_jobs_by_tag("nightly").await.unwrap()), nightly);
+        assert_eq!(ids(scheduler.list_jobs_by_tag("disk").await.unwrap()), disk);
+        assert!(scheduler.list_jobs_by_tag("weekly").await.unwrap().is_empty());
+        
+        // Tags are persisted with the job
+        let restarted = Scheduler::with_temp_dir(temp_dir.path()).await;
+        restarted.load_persisted_jobs().await.unwrap();
+        assert_eq!(ids(restarted.list_jobs_by_tag("nightly").await.unwrap()), nightly);
+    }
+    
+    #[tokio::test]
+    async fn test_pause_persists_across_restart() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let scheduler = Scheduler::with_temp_dir(temp_dir.path()).await;
+        
+        scheduler.pause().await.unwrap();
+        assert!(scheduler.is_paused());
+        assert!(matches!(scheduler.run_now(&"any".to_string(), HashMap::new()).await, Err(SchedulerError::Paused)));
+        
+        let restarted = Scheduler::with_temp_dir(temp_dir.path()).await;
+        assert!(!restarted.is_paused());
+        restarted.restore_pause();
+        assert!(restarted.is_paused());
+        
+        // Clearing an emergency stop keeps a maintenance pause
+        restarted.emergency_stop("incident").await.unwrap();
+        restarted.clear_emergency_stop().await.unwrap();
+        assert!(restarted.is_paused());
+        
+        restarted.resume().await.unwrap();
+        assert!(!restarted.is_paused());
+        assert!(!temp_dir.path().join("PAUSED").exists());
+        
+        // Resuming when not paused is fine
+        restarted.resume().await.unwrap();
+    }
+    
+    #[test]
+    fn test_circular_failure_chain_is_rejected() {
+        let mut first = Job::new("first".to_string(), "true".to_string());
+        let mut second = Job::new("second".to_string(), "true".to_string());
+        first.on_failure_job = Some(second.id.clone());
+        second.on_failure_job = Some(first.id.clone());
+        let first_id = first.id.clone();
+        
+        let jobs = jobs_by_id(vec![first, second]);
+        assert!(matches!(find_failure_cycle(&first_id, &jobs), Err(SchedulerError::InvalidJob(_))));
+        
+        let mut own = Job::new("own".to_string(), "true".to_string());
+        own.on_failure_job = Some(own.id.clone());
+        let own_id = own.id.clone();
+        assert!(find_failure_cycle(&own_id, &jobs_by_id(vec![own])).is_err());
+    }
+    
+    #[test]
+    fn test_circular_pipe_chain_is_rejected() {
+        let mut own = Job::new("own".to_string(), "cat".to_string());
+        own.output_destination = OutputDestination::PipeToJob(own.id.clone());
+        let own_id = own.id.clone();
+        assert!(matches!(find_link_cycle(&own_id, &jobs_by_id(vec![own])), Err(SchedulerError::InvalidJob(_))));
+        
+        let consumer = Job::new("consumer".to_string(), "cat".to_string());
+        let producer = Job::new("producer".to_string(), "echo".to_string())
+            .with_output_destination(OutputDestination::PipeToJob(consumer.id.clone()));
+        let producer_id = producer.id.clone();
+        let mut jobs = jobs_by_id(vec![producer.clone(), consumer.clone()]);
+        assert!(find_link_cycle(&producer_id, &jobs).is_ok());
+        
+        let consumer = consumer.with_output_destination(OutputDestination::PipeToJob(producer_id.clone()));
+        jobs.insert(consumer.id.clone(), consumer);
+        assert!(matches!(find_link_cycle(&producer_id, &jobs), Err(SchedulerError::InvalidJob(_))));
+    }
+    
+    #[tokio::test]
+    async fn test_add_job_rejects_pipe_to_itself() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let scheduler = temp_scheduler(&temp_dir).await;
+        
+        let mut job = Job::new("loop".to_string(), "cat".to_string());
+        job.output_destination = OutputDestination::PipeToJob(job.id.clone());
+        assert!(matches!(scheduler.add_job(job).await, Err(SchedulerError::InvalidJob(_))));
+    }
+    
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_completed_producer_runs_piped_job() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let scheduler = temp_scheduler(&temp_dir).await;
+        scheduler.start().await.unwrap();
+        let mut completions = scheduler.subscribe_completions();
+        
+        let upper = Job::new("upper".to_string(), "tr".to_string())
+            .with_args(vec!["a-z".to_string(), "A-Z".to_string()]);
+        let upper_id = scheduler.add_job(upper).await.unwrap();
+        let producer = Job::new("producer".to_string(), "echo".to_string())
+            .with_args(vec!["hello pipeline".to_string()])
+            .with_output_destination(OutputDestination::PipeToJob(upper_id.clone()));
+        let producer_id = scheduler.add_job(producer).await.unwrap();
+        
+        scheduler.run_now(&producer_id, HashMap::new()).await.unwrap();
+        let produced = tokio::time::timeout(Duration::from_secs(5), completions.recv())
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(produced.job_id, producer_id);
+        assert_eq!(produced.stdout, "hello pipeline\n");
+        
+        let piped = tokio::time::timeout(Duration::from_secs(5), completions.recv())
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(piped.job_id, upper_id);
+        assert_eq!(piped.stdout, "HELLO PIPELINE\n");
+        
+        scheduler.stop().await.unwrap();
+    }
+    
+    #[test]
+    fn test_dependency_cycle_detection() {
+        let extract = Job::new("extract".to_string(), "true".to_string());
+        let transform = Job::new("transform".to_string(), "true".to_string())
+            .with_depends_on(vec![extract.id.clone()]);
+        let load = Job::new("load".to_string(), "true".to_string())
+            .with_depends_on(vec![extract.id.clone(), transform.id.clone()]);
+        let load_id = load.id.clone();
+        
+        // Shared dependencies are not cycles
+        let mut jobs = jobs_by_id(vec![extract.clone(), transform, load]);
+        assert!(find_dependency_cycle(&load_id, &jobs).is_ok());
+        
+        let extract = extract.with_depends_on(vec![load_id.clone()]);
+        jobs.insert(extract.id.clone(), extract);
+        assert!(matches!(find_dependency_cycle(&load_id, &jobs), Err(SchedulerError::CyclicDependency(_))));
+    }
+    
+    #[tokio::test]
+    async fn test_add_job_rejects_cyclic_dependency() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let scheduler = temp_scheduler(&temp_dir).await;
+        
+        let first = Job::new("first".to_string(), "true".to_string());
+        let second = Job::new("second".to_string(), "true".to_string())
+            .with_depends_on(vec![first.id.clone()]);
+        scheduler.add_job(second.clone()).await.unwrap();
+        
+        let first = first.with_depends_on(vec![second.id.clone()]);
+        assert!(matches!(scheduler.add_job(first).await, Err(SchedulerError::CyclicDependency(_))));
+    }
+    
+    #[tokio::test]
+    async fn test_export_schedule_writes_upcoming_runs() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let scheduler = temp_scheduler(&temp_dir).await;
+        
+        // Every Monday at 09:00; cron expressions here start with a seconds field
+        let weekly = Job::new("weekly-report".to_string(), "report".to_string())
+            .with_cron("0 0 9 * * Mon".to_string())
+            .with_args(vec!["--weekly".to_string()]);
+        scheduler.add_job(weekly).await.unwrap();
+        let mut disabled = Job::new("disabled".to_string(), "true".to_string())
+            .with_cron("0 0 * * * *".to_string());
+        disabled.enabled = false;
+        scheduler.add_job(disabled).await.unwrap();
+        
+        let path = temp_dir.path().join("export").join("schedule.ics");
+        scheduler.export_schedule(&path).await.unwrap();
+        
+        let ics = std::fs::read_to_string(&path).unwrap();
+        assert!(ics.starts_with("BEGIN:VCALENDAR"));
+        assert_eq!(ics.matches("BEGIN:VEVENT").count(), SCHEDULE_EXPORT_RUNS);
+        assert_eq!(ics.matches("SUMMARY:weekly-report").count(), SCHEDULE_EXPORT_RUNS);
+        assert!(ics.contains("DESCRIPTION:report --weekly"));
+        assert!(ics.lines().filter(|line| line.starts_with("DTSTART:")).all(|line| line.ends_with("T090000Z")));
+        assert!(!ics.contains("SUMMARY:disabled"));
+    }
+}
@@ -3,30 +3,36 @@
 //! Provides cross-platform job status monitoring with platform-appropriate
 //! health checks, notifications, and metrics collection.
 
-use crate::scheduler::job::{JobId, JobStatus};
+use crate::error::RaeError;
+use crate::scheduler::alert::{Alert, AlertSink, AlertThresholds, AlertType, LogAlerter};
+use crate::scheduler::executor::JobExecutor;
+use crate::scheduler::job::{Job, JobId, JobResult, JobSla, JobStatus, PatternType};
+use crate::scheduler::metrics::SystemMetricsCollector;
 use chrono::{DateTime, Utc};
-use std::collections::HashMap;
-use std::sync::Arc;
-use tokio::sync::RwLock;
+use serde::Serialize;
+use std::collections::{BTreeMap, HashMap};
+use std::sync::{Arc, Mutex};
+use tokio::sync::{broadcast, RwLock};
+use tokio::task::JoinHandle;
 use tokio::time::{interval, Duration};
 use thiserror::Error;
-use tracing::{debug, info, warn};
+use tracing::{debug, info, instrument, warn};
 
 /// Errors that can occur in the job monitor.
 #[derive(Debug, Error)]
 pub enum MonitorError {
-    #[error("Job not found: {0}")]
-    JobNotFound(String),
-    
     #[error("Monitoring failed: {0}")]
     MonitoringFailed(String),
     
     #[error("Health check failed: {0}")]
     HealthCheckFailed(String),
+    
+    #[error(transparent)]
+    Rae(#[from] RaeError),
 }
 
 /// Job monitoring statistics.
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize)]
 pub struct MonitorStats {
     pub total_jobs: usize,
     pub running_jobs: usize,
@@ -34,7 +40,15 @@ pub struct MonitorStats {
     pub failed_jobs: usize,
     pub cancelled_jobs: usize,
     pub average_execution_time: f64,
+    /// Median of the per-job average execution times, in seconds
+    pub p50_execution_time: f64,
+    /// 95th percentile of the per-job average execution times, in seconds
+    pub p95_execution_time: f64,
     pub success_rate: f64,
+    pub duration_p50: f64,
+    pub duration_p95: f64,
+    pub duration_p99: f64,
+    pub sla_violations: u32,
 }
 
 impl Default for MonitorStats {
@@ -46,11 +60,37 @@ impl Default for MonitorStats {
             failed_jobs: 0,
             cancelled_jobs: 0,
             average_execution_time: 0.0,
+            p50_execution_time: 0.0,
+            p95_execution_time: 0.0,
             success_rate: 0.0,
+            duration_p50: 0.0,
+            duration_p95: 0.0,
+            duration_p99: 0.0,
+            sla_violations: 0,
         }
     }
 }
 
+impl MonitorStats {
+    /// Formats the duration percentiles as a one-line report.
+    pub fn format_latency_report(&self) -> String {
+        format!(
+            "Latency: p50 {:.2}s, p95 {:.2}s, p99 {:.2}s (avg {:.2}s)",
+            self.duration_p50, self.duration_p95, self.duration_p99, self.average_execution_time
+        )
+    }
+}
+
+/// Width of a duration histogram bucket in milliseconds.
+const DURATION_BUCKET_MS: u64 = 100;
+
+/// A change in the status of a tracked job.
+#[derive(Debug, Clone, Serialize)]
+pub struct JobStatusChange {
+    pub job_id: JobId,
+    pub status: JobStatus,
+}
+
 /// Job health information.
 #[derive(Debug, Clone)]
 pub struct JobHealth {
@@ -61,6 +101,21 @@ pub struct JobHealth {
     pub failure_count: u32,
     pub average_duration: f64,
     pub last_execution: Option<DateTime<Utc>>,
+    pub sla: Option<JobSla>,
+    pub sla_violation: bool,
+    pub last_notified: Option<DateTime<Utc>>,
+    pub suppressed_notifications: u32,
+    /// Whether a stuck job alert was raised for the current run
+    pub stuck_alerted: bool,
+    /// Whether a high failure rate alert was raised and the rate is still high
+    pub failure_rate_alerted: bool,
+}
+
+/// A job run when its pattern trigger matches.
+struct PatternWatch {
+    job: Job,
+    /// Whether the job ran since the pattern started matching
+    triggered: bool,
 }
 
 /// Job monitor for tracking status and health.
@@ -69,10 +124,22 @@ pub struct JobMonitor {
     tracked_jobs: Arc<RwLock<HashMap<JobId, JobHealth>>>,
     /// Monitoring statistics
     stats: Arc<RwLock<MonitorStats>>,
+    /// Execution duration samples, keyed by 100 ms bucket
+    duration_histogram: Arc<RwLock<BTreeMap<u64, u32>>>,
     /// Health check interval
     health_check_interval: Duration,
     /// Whether monitoring is active
     is_active: Arc<RwLock<bool>>,
+    /// Sender for status changes of tracked jobs
+    status_sender: broadcast::Sender<JobStatusChange>,
+    /// Sinks every alert is delivered to
+    alerters: Mutex<Vec<Box<dyn AlertSink>>>,
+    /// When health checks raise alerts
+    alert_thresholds: Mutex<AlertThresholds>,
+    /// System metrics that pattern triggers are matched against
+    metrics: Arc<SystemMetricsCollector>,
+    /// Jobs with a pattern trigger, by ID
+    pattern_jobs: Mutex<HashMap<JobId, PatternWatch>>,
 }
 
 impl JobMonitor {
@@ -81,34 +148,56 @@ impl JobMonitor {
         JobMonitor {
             tracked_jobs: Arc::new(RwLock::new(HashMap::new())),
             stats: Arc::new(RwLock::new(MonitorStats::default())),
+            duration_histogram: Arc::new(RwLock::new(BTreeMap::new())),
             health_check_interval: Duration::from_secs(30),
             is_active: Arc::new(RwLock::new(false)),
+            status_sender: broadcast::channel(100).0,
+            alerters: Mutex::new(vec![Box::new(LogAlerter)]),
+            alert_thresholds: Mutex::new(AlertThresholds::default()),
+            metrics: Arc::new(SystemMetricsCollector::new()),
+            pattern_jobs: Mutex::new(HashMap::new()),
         }
     }
     
+    /// Registers a sink that receives every alert, in addition to the log.
+    pub fn add_alerter(&self, alerter: Box<dyn AlertSink>) {
+        self.alerters.lock().unwrap_or_else(|e| e.into_inner()).push(alerter);
+    }
+    
+    /// Sets when health checks raise alerts.
+    pub fn set_alert_thresholds(&self, thresholds: AlertThresholds) {
+        *self.alert_thresholds.lock().unwrap_or_else(|e| e.into_inner()) = thresholds;
+    }
+    
+    /// Delivers an alert to every registered sink.
+    ///
+    /// A failing sink is logged and does not keep the others from the alert.
+    pub fn alert(&self, alert: Alert) {
+        for alerter in self.alerters.lock().unwrap_or_else(|e| e.into_inner()).iter() {
+            if let Err(e) = alerter.alert(alert.clone()) {
+                warn!("Failed to deliver {:?} alert for job {}: {}", alert.alert_type, alert.job_id, e);
+            }
+        }
+    }
+    
+    /// Subscribes to status changes of tracked jobs.
+    pub fn subscribe_status_changes(&self) -> broadcast::Receiver<JobStatusChange> {
+        self.status_sender.subscribe()
+    }
+    
+    /// Matches pattern triggers against the metrics of `collector` instead of
+    /// this system.
+    pub fn with_metrics_collector(mut self, collector: Arc<SystemMetricsCollector>) -> Self {
+        self.metrics = collector;
+        self
+    }
+    
     /// Starts the job monitor.
     pub async fn start(&self) -> Result<(), MonitorError> {
         let mut is_active = self.is_active.write().await;
         *is_active = true;
         
-        // Temporarily disable background monitoring to avoid deadlock
-        // let tracked_jobs = self.tracked_jobs.clone();
-        // let stats = self.stats.clone();
-        // let is_active_clone = self.is_active.clone();
-        // let interval_duration = self.health_check_interval;
-        // 
-        // // Start monitoring loop
-        // tokio::spawn(async move {
-        //     let mut interval = interval(interval_duration);
-        //     
-        //     while *is_active_clone.read().await {
-        //         interval.tick().await;
-        //         
-        //         // Perform health checks
-        //         Self::perform_health_checks(&tracked_jobs, &stats).await;
-        //     }
-        // });
-        
+        // Health checks run from the scheduler's tick loop while the monitor is active
         info!("Job monitor started");
         Ok(())
     }
@@ -136,6 +225,12 @@ impl JobMonitor {
                 failure_count: 0,
                 average_duration: 0.0,
                 last_execution: None,
+                sla: None,
+                sla_violation: false,
+                last_notified: None,
+                suppressed_notifications: 0,
+                stuck_alerted: false,
+                failure_rate_alerted: false,
             };
             
             tracked_jobs.insert(job_id.clone(), health);
@@ -172,6 +267,31 @@ impl JobMonitor {
     
     /// Updates the status of a tracked job.
     pub async fn update_job_status(&self, job_id: &JobId, status: JobStatus) -> Result<(), MonitorError> {
+        self.set_job_status(job_id, status, None).await
+    }
+    
+    /// Marks a tracked job as completed and folds the run's duration into its average.
+    ///
+    /// The duration is read from the result's resource usage, falling back to
+    /// its start and end times.
+    pub async fn record_completion(&self, result: &JobResult) -> Result<(), MonitorError> {
+        let duration = result.resource_usage.as_ref()
+            .map(|usage| usage.duration_seconds as f64)
+            .or_else(|| {
+                result.ended_at
+                    .and_then(|ended_at| (ended_at - result.started_at).to_std().ok())
+                    .map(|duration| duration.as_secs_f64())
+            });
+        
+        self.set_job_status(&result.job_id, JobStatus::Completed, duration).await
+    }
+    
+    async fn set_job_status(
+        &self,
+        job_id: &JobId,
+        status: JobStatus,
+        duration: Option<f64>,
+    ) -> Result<(), MonitorError> {
         let mut tracked_jobs = self.tracked_jobs.write().await;
         
         if let Some(health) = tracked_jobs.get_mut(job_id) {
@@ -183,6 +303,11 @@ impl JobMonitor {
                 JobStatus::Completed => {
                     health.execution_count += 1;
                     health.last_execution = Some(Utc::now());
+                    if let Some(duration) = duration {
+                        // Welford's online mean
+                        health.average_duration +=
+                            (duration - health.average_duration) / health.execution_count as f64;
+                    }
                 }
                 JobStatus::Failed { .. } => {
                     health.failure_count += 1;
@@ -194,6 +319,10 @@ impl JobMonitor {
             // self.update_stats().await;
             
             debug!("Updated job {} status to {:?}", job_id, status);
+            let _ = self.status_sender.send(JobStatusChange {
+                job_id: job_id.clone(),
+                status,
+            });
         }
         
         Ok(())
@@ -206,7 +335,7 @@ impl JobMonitor {
         if let Some(health) = tracked_jobs.get(job_id) {
             Ok(health.status.clone())
         } else {
-            Err(MonitorError::JobNotFound(job_id.clone()))
+            Err(RaeError::not_found("job", job_id).into())
         }
     }
     
@@ -217,7 +346,7 @@ impl JobMonitor {
         if let Some(health) = tracked_jobs.get(job_id) {
             Ok(health.clone())
         } else {
-            Err(MonitorError::JobNotFound(job_id.clone()))
+            Err(RaeError::not_found("job", job_id).into())
         }
     }
     
@@ -227,47 +356,238 @@ impl JobMonitor {
         tracked_jobs.values().cloned().collect()
     }
     
-    /// Gets monitoring statistics.
-    pub async fn get_stats(&self) -> MonitorStats {
-        let stats = self.stats.read().await;
-        stats.clone()
+    /// Sets the SLA of a tracked job, seeding its last successful run.
+    pub async fn set_job_sla(
+        &self,
+        job_id: &JobId,
+        sla: JobSla,
+        last_execution: Option<DateTime<Utc>>,
+    ) -> Result<(), MonitorError> {
+        let mut tracked_jobs = self.tracked_jobs.write().await;
+        let health = tracked_jobs.get_mut(job_id)
+            .ok_or_else(|| MonitorError::from(RaeError::not_found("job", job_id)))?;
+        
+        health.sla = Some(sla);
+        if health.last_execution.is_none() {
+            health.last_execution = last_execution;
+        }
+        
+        Ok(())
     }
     
-    /// Performs health checks on tracked jobs.
-    async fn perform_health_checks(
-        tracked_jobs: &Arc<RwLock<HashMap<JobId, JobHealth>>>,
-        stats: &Arc<RwLock<MonitorStats>>,
-    ) {
-        let mut jobs = tracked_jobs.write().await;
-        let now = Utc::now();
+    /// Checks SLA-enabled jobs against their expected frequency.
+    ///
+    /// Returns the jobs that newly violated their SLA and alerts on those that
+    /// ask for it.
+    pub async fn check_slas(&self, now: DateTime<Utc>) -> Vec<JobId> {
+        let mut newly_violated = Vec::new();
+        let mut violations = Vec::new();
         
-        for (job_id, health) in jobs.iter_mut() {
-            // Update last check time
-            health.last_check = now;
+        {
+            let mut tracked_jobs = self.tracked_jobs.write().await;
             
-            // Check for stuck jobs (running for too long)
-            if let JobStatus::Running = health.status {
-                if let Some(last_execution) = health.last_execution {
-                    let duration = now.signed_duration_since(last_execution);
-                    if duration.num_minutes() > 60 {
-                        warn!("Job {} has been running for {} minutes", 
-                              job_id, duration.num_minutes());
+            for (job_id, health) in tracked_jobs.iter_mut() {
+                let Some(sla) = &health.sla else {
+                    continue;
+                };
+                
+                // Jobs that have never run have no interval to measure yet
+                let violated = health.last_execution.is_some_and(|last| {
+                    now.signed_duration_since(last).num_seconds() > sla.max_interval_seconds as i64
+                });
+                
+                if violated && !health.sla_violation {
+                    newly_violated.push(job_id.clone());
+                    if sla.alert_on_violation {
+                        violations.push(Alert::new(
+                            job_id.clone(),
+                            AlertType::SlaViolation,
+                            format!("Job {} has not run in the last {} seconds", job_id, sla.max_interval_seconds),
+                        ));
                     }
                 }
+                health.sla_violation = violated;
+            }
+        } // tracked_jobs lock is released before sending alerts
+        
+        for violation in violations {
+            self.alert(violation);
+        }
+        
+        newly_violated
+    }
+    
+    /// Decides whether a notification for a job may be sent now.
+    ///
+    /// Returns the number of notifications suppressed since the last one that
+    /// was sent, or `None` if this one falls within the throttle window.
+    pub async fn throttle_notification(
+        &self,
+        job_id: &JobId,
+        throttle: Option<Duration>,
+        now: DateTime<Utc>,
+    ) -> Result<Option<u32>, MonitorError> {
+        let mut tracked_jobs = self.tracked_jobs.write().await;
+        let health = tracked_jobs.get_mut(job_id)
+            .ok_or_else(|| MonitorError::from(RaeError::not_found("job", job_id)))?;
+        
+        let throttled = match (throttle, health.last_notified) {
+            (Some(throttle), Some(last)) => {
+                let throttle = chrono::Duration::from_std(throttle).unwrap_or(chrono::Duration::MAX);
+                now.signed_duration_since(last) < throttle
+            }
+            _ => false,
+        };
+        
+        if throttled {
+            health.suppressed_notifications += 1;
+            return Ok(None);
+        }
+        
+        health.last_notified = Some(now);
+        Ok(Some(std::mem::take(&mut health.suppressed_notifications)))
+    }
+    
+    /// Alerts on a failed job if the job asks for it and the alert is not
+    /// throttled.
+    pub async fn alert_failure(&self, job: &Job, result: &JobResult) {
+        if !job.alert_on_failure {
+            return;
+        }
+        
+        let suppressed = match self.throttle_notification(&job.id, job.notification_throttle, Utc::now()).await {
+            Ok(Some(suppressed)) => suppressed,
+            Ok(None) => {
+                debug!("Suppressed failure alert for job {}", job.id);
+                return;
+            }
+            Err(e) => {
+                warn!("Failed to check alert throttle for job {}: {}", job.id, e);
+                return;
+            }
+        };
+        
+        let message = failure_alert_message(job, result, suppressed);
+        self.alert(Alert::new(job.id.clone(), AlertType::JobFailed, message));
+    }
+    
+    /// Records the execution duration of a completed job.
+    pub async fn record_duration(&self, duration: Duration) {
+        let bucket = duration.as_millis() as u64 / DURATION_BUCKET_MS;
+        
+        let mut histogram = self.duration_histogram.write().await;
+        *histogram.entry(bucket).or_insert(0) += 1;
+    }
+    
+    /// Gets monitoring statistics.
+    pub async fn get_stats(&self) -> MonitorStats {
+        let mut stats = self.stats.read().await.clone();
+        
+        let histogram = self.duration_histogram.read().await;
+        stats.duration_p50 = Self::histogram_percentile(&histogram, 0.50);
+        stats.duration_p95 = Self::histogram_percentile(&histogram, 0.95);
+        stats.duration_p99 = Self::histogram_percentile(&histogram, 0.99);
+        
+        let tracked_jobs = self.tracked_jobs.read().await;
+        stats.sla_violations = tracked_jobs.values().filter(|health| health.sla_violation).count() as u32;
+        
+        let mut averages: Vec<f64> = tracked_jobs.values()
+            .filter(|health| health.execution_count > 0)
+            .map(|health| health.average_duration)
+            .collect();
+        averages.sort_by(f64::total_cmp);
+        if !averages.is_empty() {
+            stats.average_execution_time = averages.iter().sum::<f64>() / averages.len() as f64;
+        }
+        stats.p50_execution_time = Self::sorted_percentile(&averages, 0.50);
+        stats.p95_execution_time = Self::sorted_percentile(&averages, 0.95);
+        
+        stats
+    }
+    
+    /// Nearest-rank percentile of sorted values; 0.0 when there are none.
+    fn sorted_percentile(sorted: &[f64], percentile: f64) -> f64 {
+        if sorted.is_empty() {
+            return 0.0;
+        }
+        
+        let rank = ((percentile * sorted.len() as f64).ceil() as usize).max(1);
+        sorted[rank - 1]
+    }
+    
+    /// Approximates a percentile (in seconds) by walking the duration histogram.
+    ///
+    /// Returns the midpoint of the bucket containing the requested rank.
+    fn histogram_percentile(histogram: &BTreeMap<u64, u32>, percentile: f64) -> f64 {
+        let total: u64 = histogram.values().map(|&count| count as u64).sum();
+        if total == 0 {
+            return 0.0;
+        }
+        
+        let rank = ((percentile * total as f64).ceil() as u64).max(1);
+        let mut seen = 0;
+        
+        for (&bucket, &count) in histogram {
+            seen += count as u64;
+            if seen >= rank {
+                return (bucket * DURATION_BUCKET_MS + DURATION_BUCKET_MS / 2) as f64 / 1000.0;
             }
+        }
+        
+        0.0
+    }
+    
+    /// Checks tracked jobs for stuck runs and high failure rates.
+    ///
+    /// Each problem is alerted once, when it starts; the alerts raised are
+    /// returned.
+    #[instrument(skip_all)]
+    pub async fn check_health(&self, now: DateTime<Utc>) -> Vec<Alert> {
+        let thresholds = *self.alert_thresholds.lock().unwrap_or_else(|e| e.into_inner());
+        let mut alerts = Vec::new();
+        
+        {
+            let mut jobs = self.tracked_jobs.write().await;
             
-            // Check for jobs with high failure rates
-            if health.execution_count > 0 {
-                let failure_rate = health.failure_count as f64 / health.execution_count as f64;
-                if failure_rate > 0.5 {
-                    warn!("Job {} has high failure rate: {:.1}%", 
-                          job_id, failure_rate * 100.0);
+            for (job_id, health) in jobs.iter_mut() {
+                health.last_check = now;
+                
+                // Check for stuck jobs (running for too long)
+                let running_minutes = match (&health.status, health.last_execution) {
+                    (JobStatus::Running, Some(last_execution)) => Some(now.signed_duration_since(last_execution).num_minutes()),
+                    _ => None,
+                };
+                let stuck = running_minutes.is_some_and(|minutes| minutes > thresholds.stuck_minutes as i64);
+                if stuck && !health.stuck_alerted {
+                    alerts.push(Alert::new(
+                        job_id.clone(),
+                        AlertType::StuckJob,
+                        format!("Job {} has been running for {} minutes", job_id, running_minutes.unwrap_or_default()),
+                    ));
                 }
+                health.stuck_alerted = stuck;
+                
+                // Check for jobs with high failure rates
+                let runs = health.execution_count + health.failure_count;
+                let failure_rate = if runs > 0 { health.failure_count as f64 / runs as f64 } else { 0.0 };
+                let failing = failure_rate > thresholds.failure_rate;
+                if failing && !health.failure_rate_alerted {
+                    alerts.push(Alert::new(
+                        job_id.clone(),
+                        AlertType::HighFailureRate,
+                        format!("Job {} has high failure rate: {:.1}%", job_id, failure_rate * 100.0),
+                    ));
+                }
+                health.failure_rate_alerted = failing;
             }
+        } // tracked_jobs lock is released before updating stats and alerting
+        
+        self.update_stats().await;
+        for alert in &alerts {
+            self.alert(alert.clone());
         }
         
-        // Update statistics
-        Self::update_stats_internal(tracked_jobs, stats).await;
+        alerts
     }
     
     /// Updates monitoring statistics.
@@ -283,7 +603,6 @@ impl JobMonitor {
         let jobs = tracked_jobs.read().await;
         let mut new_stats = MonitorStats::default();
         
-        let mut total_duration = 0.0;
         let mut total_executions = 0;
         let mut total_failures = 0;
         
@@ -303,15 +622,9 @@ impl JobMonitor {
                 JobStatus::Cancelled => new_stats.cancelled_jobs += 1,
                 _ => {}
             }
-            
-            total_duration += health.average_duration;
-        }
-        
-        // Calculate averages
-        if new_stats.total_jobs > 0 {
-            new_stats.average_execution_time = total_duration / new_stats.total_jobs as f64;
         }
         
+        // Average execution times are computed from the tracked jobs in `get_stats`
         if total_executions > 0 {
             new_stats.success_rate = (total_executions - total_failures) as f64 / total_executions as f64;
         }
@@ -321,6 +634,84 @@ impl JobMonitor {
         *stats_write = new_stats;
     }
     
+    /// Runs a job when its `HighMemoryUsage` pattern trigger matches; returns
+    /// whether the job has such a trigger.
+    ///
+    /// A job that is already watched is watched again with its new trigger.
+    pub fn watch_pattern(&self, job: &Job) -> bool {
+        let is_memory_pattern = job.schedule.pattern.as_ref()
+            .is_some_and(|pattern| matches!(pattern.pattern_type, PatternType::HighMemoryUsage));
+        if !is_memory_pattern {
+            return false;
+        }
+        
+        let watch = PatternWatch { job: job.clone(), triggered: false };
+        self.pattern_jobs.lock().unwrap_or_else(|e| e.into_inner()).insert(job.id.clone(), watch);
+        true
+    }
+    
+    /// Stops running a job on its pattern trigger; returns whether it was watched.
+    pub fn unwatch_pattern(&self, job_id: &JobId) -> bool {
+        self.pattern_jobs.lock().unwrap_or_else(|e| e.into_inner()).remove(job_id).is_some()
+    }
+    
+    /// Samples system memory and returns the jobs whose pattern started matching.
+    ///
+    /// A `HighMemoryUsage` pattern matches while memory usage stays above its
+    /// `threshold` percent for its whole `window`. A job is returned once per
+    /// stretch of matching.
+    pub fn check_patterns(&self, now: DateTime<Utc>) -> Vec<Job> {
+        let mut pattern_jobs = self.pattern_jobs.lock().unwrap_or_else(|e| e.into_inner());
+        if pattern_jobs.is_empty() {
+            return Vec::new();
+        }
+        
+        self.metrics.sample(now);
+        
+        let mut matched = Vec::new();
+        for watch in pattern_jobs.values_mut() {
+            let Some(pattern) = &watch.job.schedule.pattern else {
+                continue;
+            };
+            let window = chrono::Duration::seconds(pattern.window as i64);
+            let matching = self.metrics.memory_above(pattern.threshold, window, now);
+            if matching && !watch.triggered {
+                debug!("Memory usage above {}% for {}s triggered job {}", pattern.threshold, pattern.window, watch.job.id);
+                matched.push(watch.job.clone());
+            }
+            watch.triggered = matching;
+        }
+        
+        matched
+    }
+    
+    /// Checks pattern triggers every health check interval while the monitor
+    /// is active, running the matching jobs on `executor`.
+    ///
+    /// Patterns are still sampled while the executor is paused, but no jobs run.
+    pub fn start_pattern_triggers(self: &Arc<Self>, executor: Arc<JobExecutor>) -> JoinHandle<()> {
+        let monitor = self.clone();
+        tokio::spawn(async move {
+            let mut ticks = interval(monitor.health_check_interval);
+            loop {
+                ticks.tick().await;
+                if !monitor.is_active().await {
+                    break;
+                }
+                
+                for job in monitor.check_patterns(Utc::now()) {
+                    if executor.is_paused() {
+                        continue;
+                    }
+                    let job_id = job.id.clone();
+                    if let Err(e) = executor.execute_job(job).await {
+                        warn!("Failed to run job {} on its pattern trigger: {}", job_id, e);
+                    }
+                }
+            }
+        })
+    }
+    
     /// Sets the health check interval.
     pub fn set_health_check_interval(&mut self, interval: Duration) {
         self.health_check_interval = interval;
@@ -338,10 +729,25 @@ impl JobMonitor {
     }
 }
 
+/// Builds the body of a failure alert.
+fn failure_alert_message(job: &Job, result: &JobResult, suppressed: u32) -> String {
+    let error = match &result.status {
+        JobStatus::Failed { error } => error.clone(),
+        status => format!("{:?}", status),
+    };
+    
+    let mut message = format!("Job '{}' failed: {}", job.name, error);
+    if suppressed > 0 {
+        message.push_str(&format!(" ({} previous notifications suppressed)", suppressed));
+    }
+    message
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::scheduler::job::JobStatus;
+    use crate::scheduler::job::{JobStatus, PatternTrigger, ResourceUsage};
+    use crate::scheduler::metrics::MemorySource;
     
     #[tokio::test]
     async fn test_track_and_untrack_job() {
@@ -363,6 +769,44 @@ mod tests {
         // Check if job is no longer tracked
         let tracked_jobs = monitor.get_tracked_jobs().await;
         assert_eq!(tracked_jobs.len(), 0);
+        assert!(matches!(
+            monitor.get_job_status(&job_id).await,
+            Err(MonitorError::Rae(RaeError::NotFound { kind: "job", .. }))
+        ));
+    }
+    
+    /// Collects the alerts delivered to it.
+    #[derive(Clone, Default)]
+    struct RecordingAlerter(Arc<Mutex<Vec<Alert>>>);
+    
+    impl AlertSink for RecordingAlerter {
+        fn alert(&self, alert: Alert) -> crate::error::Result<()> {
+            self.0.lock().unwrap().push(alert);
+            Ok(())
+        }
+    }
+    
+    #[tokio::test]
+    async fn test_high_failure_rate_is_alerted_once() {
+        let monitor = JobMonitor::new();
+        let recorder = RecordingAlerter::default();
+        monitor.add_alerter(Box::new(recorder.clone()));
+        monitor.set_alert_thresholds(AlertThresholds { failure_rate: 0.25, stuck_minutes: 60 });
+        
+        let job_id = "flaky".to_string();
+        monitor.track_job(job_id.clone()).await.unwrap();
+        monitor.update_job_status(&job_id, JobStatus::Completed).await.unwrap();
+        monitor.update_job_status(&job_id, JobStatus::Completed).await.unwrap();
+        monitor.update_job_status(&job_id, JobStatus::Failed { error: "exit 1".to_string() }).await.unwrap();
+        
+        let alerts = monitor.check_health(Utc::now()).await;
+        assert_eq!(alerts.len(), 1);
+        assert_eq!(alerts[0].alert_type, AlertType::HighFailureRate);
+        assert_eq!(alerts[0].job_id, job_id);
+        
+        // The rate is still high, but the job was already alerted
+        assert!(monitor.check_health(Utc::now()).await.is_empty());
+        assert_eq!(recorder.0.lock().unwrap().clone(), alerts);
     }
     
     #[tokio::test]
@@ -373,6 +817,7 @@ mod tests {
         
         // Track job
         monitor.track_job(job_id.clone()).await.unwrap();
+        let mut changes = monitor.subscribe_status_changes();
         
         // Update status
         let new_status = JobStatus::Running;
@@ -381,6 +826,10 @@ mod tests {
         // Check status
         let status = monitor.get_job_status(&job_id).await.unwrap();
         assert!(matches!(status, JobStatus::Running));
+        
+        let change = changes.try_recv().unwrap();
+        assert_eq!(change.job_id, job_id);
+        assert!(matches!(change.status, JobStatus::Running));
     }
     
     #[tokio::test]
@@ -414,6 +863,120 @@ mod tests {
         assert_eq!(stats.running_jobs, 0);
     }
     
+    #[tokio::test]
+    async fn test_execution_time_averages_and_percentiles() {
+        let monitor = JobMonitor::new();
+        
+        for i in 0..10u64 {
+            let job_id = format!("job-{}", i);
+            monitor.track_job(job_id.clone()).await.unwrap();
+            // Runs of i + 1 and 3 * (i + 1) seconds average to 2 * (i + 1)
+            for duration_seconds in [i + 1, 3 * (i + 1)] {
+                let result = JobResult {
+                    job_id: job_id.clone(),
+                    started_at: Utc::now(),
+                    ended_at: Some(Utc::now()),
+                    exit_code: Some(0),
+                    stdout: String::new(),
+                    stderr: String::new(),
+                    truncated: false,
+                    status: JobStatus::Completed,
+                    resource_usage: Some(ResourceUsage { duration_seconds, ..ResourceUsage::default() }),
+                };
+                monitor.record_completion(&result).await.unwrap();
+            }
+        }
+        
+        let health = monitor.get_job_health(&"job-4".to_string()).await.unwrap();
+        assert_eq!(health.execution_count, 2);
+        assert_eq!(health.average_duration, 10.0);
+        
+        let stats = monitor.get_stats().await;
+        assert_eq!(stats.average_execution_time, 11.0);
+        assert_eq!(stats.p50_execution_time, 10.0);
+        assert_eq!(stats.p95_execution_time, 20.0);
+        assert!(stats.p50_execution_time <= stats.p95_execution_time);
+    }
+    
+    #[tokio::test]
+    async fn test_duration_percentiles() {
+        let monitor = JobMonitor::new();
+        
+        // 1.0s, 1.5s, ..., 50.5s
+        let samples: Vec<f64> = (0..100).map(|i| 1.0 + i as f64 * 0.5).collect();
+        for sample in &samples {
+            monitor.record_duration(Duration::from_secs_f64(*sample)).await;
+        }
+        
+        let exact = |p: f64| samples[(p * samples.len() as f64).ceil() as usize - 1];
+        let stats = monitor.get_stats().await;
+        
+        for (actual, p) in [(stats.duration_p50, 0.50), (stats.duration_p95, 0.95), (stats.duration_p99, 0.99)] {
+            let expected = exact(p);
+            assert!(
+                (actual - expected).abs() / expected <= 0.05,
+                "p{} was {} but expected about {}", p * 100.0, actual, expected
+            );
+        }
+    }
+    
+    #[tokio::test]
+    async fn test_percentiles_without_samples() {
+        let monitor = JobMonitor::new();
+        
+        let stats = monitor.get_stats().await;
+        assert_eq!(stats.duration_p50, 0.0);
+        assert_eq!(stats.duration_p99, 0.0);
+        assert_eq!(
+            stats.format_latency_report(),
+            "Latency: p50 0.00s, p95 0.00s, p99 0.00s (avg 0.00s)"
+        );
+    }
+    
+    #[tokio::test]
+    async fn test_sla_violation() {
+        let monitor = JobMonitor::new();
+        let recorder = RecordingAlerter::default();
+        monitor.add_alerter(Box::new(recorder.clone()));
+        let now = Utc::now();
+        let sla = JobSla { max_interval_seconds: 25 * 3600, alert_on_violation: true };
+        
+        let late = "late-job".to_string();
+        monitor.track_job(late.clone()).await.unwrap();
+        monitor.set_job_sla(&late, sla.clone(), Some(now - chrono::Duration::hours(26))).await.unwrap();
+        
+        let on_time = "on-time-job".to_string();
+        monitor.track_job(on_time.clone()).await.unwrap();
+        monitor.set_job_sla(&on_time, sla, Some(now - chrono::Duration::hours(24))).await.unwrap();
+        
+        let quiet = "quiet-job".to_string();
+        let quiet_sla = JobSla { max_interval_seconds: 25 * 3600, alert_on_violation: false };
+        monitor.track_job(quiet.clone()).await.unwrap();
+        monitor.set_job_sla(&quiet, quiet_sla, Some(now - chrono::Duration::hours(26))).await.unwrap();
+        
+        let mut violated = monitor.check_slas(now).await;
+        violated.sort();
+        assert_eq!(violated, vec![late.clone(), quiet.clone()]);
+        
+        // Only the job that asks for it is alerted
+        let alerts = recorder.0.lock().unwrap().clone();
+        assert_eq!(alerts.len(), 1);
+        assert_eq!(alerts[0].alert_type, AlertType::SlaViolation);
+        assert_eq!(alerts[0].job_id, late);
+        assert!(monitor.get_job_health(&late).await.unwrap().sla_violation);
+        assert!(!monitor.get_job_health(&on_time).await.unwrap().sla_violation);
+        assert_eq!(monitor.get_stats().await.sla_violations, 2);
+        
+        // A violation is only reported once
+        assert!(monitor.check_slas(now).await.is_empty());
+        assert_eq!(recorder.0.lock().unwrap().len(), 1);
+        
+        // A successful run clears it
+        monitor.update_job_status(&late, JobStatus::Completed).await.unwrap();
+        monitor.check_slas(Utc::now()).await;
+        assert_eq!(monitor.get_stats().await.sla_violations, 1);
+    }
+    
     #[tokio::test]
     async fn test_start_and_stop_monitor() {
         let monitor = JobMonitor::new();
@@ -432,4 +995,134 @@ mod tests {
         tokio::time::sleep(Duration::from_millis(10)).await;
         assert!(!monitor.is_active().await);
     }
+    
+    /// Reports the memory usage stored in it.
+    #[derive(Clone)]
+    struct MockMemory(Arc<Mutex<f64>>);
+    
+    impl MemorySource for MockMemory {
+        fn memory_percent(&mut self) -> f64 {
+            *self.0.lock().unwrap()
+        }
+    }
+    
+    fn memory_job(threshold: f64, window: u64) -> Job {
+        Job::new("free-memory".to_string(), "echo".to_string())
+            .with_args(vec!["cleaned".to_string()])
+            .with_pattern(PatternTrigger { pattern_type: PatternType::HighMemoryUsage, threshold, window })
+    }
+    
+    #[test]
+    fn test_high_memory_pattern_matches_once_per_stretch() {
+        let memory = MockMemory(Arc::new(Mutex::new(95.0)));
+        let collector = Arc::new(SystemMetricsCollector::with_source(Box::new(memory.clone())));
+        let monitor = JobMonitor::new().with_metrics_collector(collector);
+        
+        let job = memory_job(90.0, 60);
+        let relaxed = memory_job(99.0, 60);
+        assert!(monitor.watch_pattern(&job));
+        assert!(monitor.watch_pattern(&relaxed));
+        assert!(!monitor.watch_pattern(&Job::new("plain".to_string(), "true".to_string())));
+        
+        let start: DateTime<Utc> = "2024-05-01T00:00:00Z".parse().unwrap();
+        let at = |seconds| start + chrono::Duration::seconds(seconds);
+        
+        // Not high for the whole window yet
+        assert!(monitor.check_patterns(at(0)).is_empty());
+        assert!(monitor.check_patterns(at(30)).is_empty());
+        
+        let matched = monitor.check_patterns(at(60));
+        assert_eq!(matched.iter().map(|job| &job.id).collect::<Vec<_>>(), vec![&job.id]);
+        // Still high, but the job already ran
+        assert!(monitor.check_patterns(at(90)).is_empty());
+        
+        // Dropping below the threshold rearms the trigger
+        *memory.0.lock().unwrap() = 50.0;
+        assert!(monitor.check_patterns(at(120)).is_empty());
+        *memory.0.lock().unwrap() = 95.0;
+        assert!(monitor.check_patterns(at(150)).is_empty());
+        assert!(monitor.check_patterns(at(180)).is_empty());
+        assert_eq!(monitor.check_patterns(at(210)).len(), 1);
+        
+        assert!(monitor.unwatch_pattern(&job.id));
+        *memory.0.lock().unwrap() = 50.0;
+        monitor.check_patterns(at(240));
+        *memory.0.lock().unwrap() = 95.0;
+        assert!(monitor.check_patterns(at(300)).is_empty());
+        assert!(monitor.check_patterns(at(360)).is_empty());
+    }
+    
+    #[tokio::test]
+    async fn test_high_memory_triggers_job_on_executor() {
+        let memory = MockMemory(Arc::new(Mutex::new(97.5)));
+        let collector = Arc::new(SystemMetricsCollector::with_source(Box::new(memory)));
+        let mut monitor = JobMonitor::new().with_metrics_collector(collector);
+        monitor.set_health_check_interval(Duration::from_millis(50));
+        let monitor = Arc::new(monitor);
+        
+        let executor = Arc::new(JobExecutor::new());
+        let mut completions = executor.subscribe_completions();
+        let job = memory_job(90.0, 1);
+        monitor.watch_pattern(&job);
+        monitor.start().await.unwrap();
+        let triggers = monitor.start_pattern_triggers(executor);
+        
+        let result = tokio::time::timeout(Duration::from_secs(5), completions.recv())
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(result.job_id, job.id);
+        assert_eq!(result.stdout.trim(), "cleaned");
+        
+        monitor.stop().await.unwrap();
+        tokio::time::timeout(Duration::from_secs(1), triggers).await.unwrap().unwrap();
+    }
+    
+    #[tokio::test]
+    async fn test_failure_notifications_are_throttled() {
+        let monitor = JobMonitor::new();
+        let job_id = "flaky-job".to_string();
+        monitor.track_job(job_id.clone()).await.unwrap();
+        
+        // Fails every 5 minutes with a one-hour throttle
+        let start: DateTime<Utc> = "2024-05-01T00:00:00Z".parse().unwrap();
+        let mut sent = Vec::new();
+        for i in 0..10 {
+            let now = start + chrono::Duration::minutes(5 * i);
+            if let Some(suppressed) = monitor.throttle_notification(&job_id, Some(Duration::from_secs(3600)), now).await.unwrap() {
+                sent.push(suppressed);
+            }
+        }
+        assert_eq!(sent, vec![0]);
+        
+        // The next notification after the window reports what was suppressed
+        let later = start + chrono::Duration::hours(1);
+        assert_eq!(monitor.throttle_notification(&job_id, Some(Duration::from_secs(3600)), later).await.unwrap(), Some(9));
+        assert_eq!(monitor.throttle_notification(&job_id, Some(Duration::from_secs(3600)), later).await.unwrap(), None);
+        
+        // Without a throttle every notification is sent
+        assert_eq!(monitor.throttle_notification(&job_id, None, later).await.unwrap(), Some(1));
+    }
+    
+    #[test]
+    fn test_failure_alert_message_mentions_suppressed() {
+        let job = Job::new("backup".to_string(), "rsync".to_string());
+        let result = JobResult {
+            job_id: job.id.clone(),
+            started_at: Utc::now(),
+            ended_at: Some(Utc::now()),
+            exit_code: Some(1),
+            stdout: String::new(),
+            stderr: String::new(),
+            truncated: false,
+            status: JobStatus::Failed { error: "Process exited with code 1".to_string() },
+            resource_usage: None,
+        };
+        
+        assert_eq!(failure_alert_message(&job, &result, 0), "Job 'backup' failed: Process exited with code 1");
+        assert_eq!(
+            failure_alert_message(&job, &result, 4),
+            "Job 'backup' failed: Process exited with code 1 (4 previous notifications suppressed)"
+        );
+    }
 } 
\ No newline at end of file
@@ -0,0 +1,116 @@
+//! Delivery of job output to its configured destination.
+//!
+//! Handles file and S3-compatible destinations. Piping output into another
+//! job is handled by the scheduler, which can look up the downstream job.
+
+use crate::api::NetworkGuard;
+use crate::config::Config;
+use crate::scheduler::job::OutputDestination;
+use std::path::Path;
+use thiserror::Error;
+use tokio::fs::OpenOptions;
+use tokio::io::AsyncWriteExt;
+use tracing::warn;
+
+/// Errors that can occur while delivering job output.
+#[derive(Debug, Error)]
+pub enum OutputError {
+    #[error("IO error: {0}")]
+    IoError(#[from] std::io::Error),
+    
+    #[error("Upload failed: {0}")]
+    UploadFailed(#[from] reqwest::Error),
+    
+    #[error("Destination blocked by privacy level: {0}")]
+    PrivacyBlocked(String),
+}
+
+/// Delivers job output to a file or S3-compatible destination.
+///
+/// `Stdout` and `PipeToJob` destinations are left to the caller.
+pub async fn deliver(destination: &OutputDestination, output: &str, config: &Config) -> Result<(), OutputError> {
+    match destination {
+        OutputDestination::File { path, append } => {
+            write_file(Path::new(path), *append, output, &config.expanded_data_dir()).await
+        }
+        OutputDestination::S3Compatible { endpoint, bucket, key } => {
+            let url = format!("{}/{}/{}", endpoint.trim_end_matches('/'), bucket, key);
+            NetworkGuard::new(config)
+                .check(&url)
+                .map_err(|e| OutputError::PrivacyBlocked(e.to_string()))?;
+            
+            reqwest::Client::new()
+                .put(&url)
+                .body(output.to_string())
+                .send()
+                .await?
+                .error_for_status()?;
+            
+            Ok(())
+        }
+        OutputDestination::Stdout | OutputDestination::PipeToJob(_) => Ok(()),
+    }
+}
+
+/// Writes output to a file, replacing or appending to its contents.
+async fn write_file(path: &Path, append: bool, output: &str, data_dir: &Path) -> Result<(), OutputError> {
+    if path.is_absolute() && !path.starts_with(data_dir) {
+        warn!("Job output file {} is outside the data directory {}", path.display(), data_dir.display());
+    }
+    
+    let mut file = OpenOptions::new()
+        .create(true)
+        .write(true)
+        .append(append)
+        .truncate(!append)
+        .open(path)
+        .await?;
+    
+    file.write_all(output.as_bytes()).await?;
+    file.flush().await?;
+    
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+    
+    #[tokio::test]
+    async fn test_file_destination_overwrite_and_append() {
+        let temp_dir = tempdir().unwrap();
+        let path = temp_dir.path().join("output.log");
+        let config = Config::default();
+        
+        let overwrite = OutputDestination::File {
+            path: path.to_string_lossy().into_owned(),
+            append: false,
+        };
+        deliver(&overwrite, "first\n", &config).await.unwrap();
+        deliver(&overwrite, "second\n", &config).await.unwrap();
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), "second\n");
+        
+        let append = OutputDestination::File {
+            path: path.to_string_lossy().into_owned(),
+            append: true,
+        };
+        deliver(&append, "third\n", &config).await.unwrap();
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), "second\nthird\n");
+    }
+    
+    #[tokio::test]
+    async fn test_s3_destination_blocked_in_strict_mode() {
+        let config = Config::default();
+        let destination = OutputDestination::S3Compatible {
+            endpoint: "https://s3.example.com".to_string(),
+            bucket: "backups".to_string(),
+            key: "job.log".to_string(),
+        };
+        
+        assert!(matches!(
+            deliver(&destination, "output", &config).await,
+            Err(OutputError::PrivacyBlocked(_))
+        ));
+    }
+}
@@ -4,7 +4,7 @@
 //! platform-appropriate trigger validation.
 
 use crate::scheduler::job::{Schedule, EventTrigger, PatternTrigger, EventType, PatternType};
-use chrono::{DateTime, Utc, TimeZone};
+use chrono::{DateTime, Datelike, NaiveDate, Timelike, Utc, TimeZone, Weekday};
 use std::str::FromStr;
 use thiserror::Error;
 
@@ -27,6 +27,15 @@ pub enum ParserError {
     InvalidPatternTrigger(String),
 }
 
+/// Result of parsing a human-readable time.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ParsedTime {
+    /// A single point in time
+    Absolute(DateTime<Utc>),
+    /// A recurring time, as a cron expression accepted by [`Parser::parse_cron`]
+    Recurring(String),
+}
+
 /// Parser for job scheduling and triggers.
 pub struct Parser;
 
@@ -158,7 +167,10 @@ impl Parser {
     }
     
     /// Parses a human-readable time string.
-    pub fn parse_human_time(time_str: &str) -> Result<DateTime<Utc>, ParserError> {
+    ///
+    /// "every {weekday} at HH:MM" yields a recurring cron expression; every
+    /// other supported form yields a single point in time.
+    pub fn parse_human_time(time_str: &str) -> Result<ParsedTime, ParserError> {
         // Try common formats
         let formats = [
             "%Y-%m-%d %H:%M:%S",
@@ -170,13 +182,18 @@ impl Parser {
         
         for format in &formats {
             if let Ok(dt) = chrono::NaiveDateTime::parse_from_str(time_str, format) {
-                return Ok(dt.and_utc());
+                return Ok(ParsedTime::Absolute(dt.and_utc()));
             }
         }
         
+        // Try recurring expressions
+        if let Some(cron_expr) = Self::parse_recurring_time(time_str) {
+            return Ok(ParsedTime::Recurring(cron_expr));
+        }
+        
         // Try relative time expressions
         if let Some(dt) = Self::parse_relative_time(time_str) {
-            return Ok(dt);
+            return Ok(ParsedTime::Absolute(dt));
         }
         
         Err(ParserError::InvalidTimeFormat(format!(
@@ -184,6 +201,37 @@ impl Parser {
         )))
     }
     
+    /// Parses recurring expressions like "every friday at 17:00" into a cron expression.
+    ///
+    /// The cron crate expects a leading seconds field and numbers weekdays from
+    /// Sunday = 1, so the weekday is emitted by name: "0 0 17 * * Fri".
+    fn parse_recurring_time(time_str: &str) -> Option<String> {
+        let time_str = time_str.to_lowercase();
+        let (weekday, naive_time) = Self::parse_weekday_at(time_str.strip_prefix("every ")?)?;
+        
+        Some(format!("0 {} {} * * {}", naive_time.minute(), naive_time.hour(), weekday))
+    }
+    
+    /// Parses "{weekday} at HH:MM".
+    fn parse_weekday_at(expr: &str) -> Option<(Weekday, chrono::NaiveTime)> {
+        let (weekday, time_part) = expr.split_once(" at ")?;
+        let weekday = weekday.trim().parse::<Weekday>().ok()?;
+        let naive_time = chrono::NaiveTime::parse_from_str(time_part.trim(), "%H:%M").ok()?;
+        
+        Some((weekday, naive_time))
+    }
+    
+    /// Gets the first date after `from` that falls on `weekday`.
+    ///
+    /// "Next Monday" said on a Monday means a week later, never today.
+    fn next_weekday(from: NaiveDate, weekday: Weekday) -> NaiveDate {
+        let days_ahead = (7 + weekday.num_days_from_monday() as i64
+            - from.weekday().num_days_from_monday() as i64) % 7;
+        let days_ahead = if days_ahead == 0 { 7 } else { days_ahead };
+        
+        from + chrono::Duration::days(days_ahead)
+    }
+    
     /// Parses relative time expressions like "in 5 minutes", "tomorrow at 6pm".
     fn parse_relative_time(time_str: &str) -> Option<DateTime<Utc>> {
         let now = Utc::now();
@@ -224,6 +272,12 @@ impl Parser {
             }
         }
         
+        // Handle "next {weekday} at X"
+        if let Some((weekday, naive_time)) = time_str.strip_prefix("next ").and_then(Self::parse_weekday_at) {
+            let date = Self::next_weekday(now.date_naive(), weekday);
+            return Some(chrono::NaiveDateTime::new(date, naive_time).and_utc());
+        }
+        
         None
     }
 }
@@ -262,6 +316,42 @@ mod tests {
         assert!(result.is_err());
     }
     
+    #[test]
+    fn test_parse_next_weekday() {
+        let result = Parser::parse_human_time("next Monday at 09:00").unwrap();
+        let ParsedTime::Absolute(dt) = result else {
+            panic!("expected an absolute time, got {:?}", result);
+        };
+        assert_eq!(dt.weekday(), Weekday::Mon);
+        assert_eq!((dt.hour(), dt.minute()), (9, 0));
+        assert!(dt > Utc::now());
+        assert!(dt - Utc::now() <= chrono::Duration::days(7));
+        
+        // 2024-01-05 is a Friday
+        let friday = NaiveDate::from_ymd_opt(2024, 1, 5).unwrap();
+        assert_eq!(Parser::next_weekday(friday, Weekday::Mon), NaiveDate::from_ymd_opt(2024, 1, 8).unwrap());
+        assert_eq!(Parser::next_weekday(friday, Weekday::Sun), NaiveDate::from_ymd_opt(2024, 1, 7).unwrap());
+        assert_eq!(Parser::next_weekday(friday, Weekday::Fri), NaiveDate::from_ymd_opt(2024, 1, 12).unwrap());
+        
+        assert!(Parser::parse_human_time("next someday at 09:00").is_err());
+    }
+    
+    #[test]
+    fn test_parse_every_weekday() {
+        let result = Parser::parse_human_time("every Friday at 17:00").unwrap();
+        assert_eq!(result, ParsedTime::Recurring("0 0 17 * * Fri".to_string()));
+        
+        let result = Parser::parse_human_time("every sunday at 08:30").unwrap();
+        let ParsedTime::Recurring(cron_expr) = result else {
+            panic!("expected a recurring time, got {:?}", result);
+        };
+        let next = Parser::parse_cron(&cron_expr).unwrap().upcoming(Utc).next().unwrap();
+        assert_eq!(next.weekday(), Weekday::Sun);
+        assert_eq!((next.hour(), next.minute()), (8, 30));
+        
+        assert!(Parser::parse_human_time("every monday at 25:00").is_err());
+    }
+    
     #[test]
     fn test_validate_event_trigger() {
         let event = EventTrigger {
@@ -1,17 +1,33 @@
 //! Job persistence layer for storing scheduled tasks.
 //! 
-//! Provides cross-platform JSON-based job storage with platform-appropriate
-//! file system operations and error handling.
+//! Provides cross-platform job storage with platform-appropriate file system
+//! operations and error handling. Jobs are stored as one JSON file each or in
+//! a single SQLite database, and can be migrated between the two formats.
 
+use crate::error::RaeError;
 use crate::scheduler::job::Job;
 use crate::scheduler::job::JobId;
+use rusqlite::{params, Connection, OptionalExtension};
+use serde::{Deserialize, Serialize};
 use serde_json;
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::fs;
+use std::io::Write;
 use std::path::{Path, PathBuf};
+use std::sync::Mutex;
 use thiserror::Error;
 use tokio::fs as tokio_fs;
 use tokio::io::AsyncWriteExt;
+use tracing::warn;
+use uuid::Uuid;
+
+/// File name of the SQLite job database inside the storage directory.
+const DATABASE_FILE: &str = "jobs.db";
+
+/// File name of the job archive inside the storage directory.
+///
+/// It has no `.json` extension, so it is never mistaken for a job file.
+const ARCHIVE_FILE: &str = "jobs.archive";
 
 /// Errors that can occur in the persistence layer.
 #[derive(Debug, Error)]
@@ -22,20 +38,49 @@ pub enum PersistenceError {
     #[error("Serialization error: {0}")]
     SerializationError(#[from] serde_json::Error),
     
-    #[error("Job not found: {0}")]
-    JobNotFound(String),
-    
     #[error("Invalid job data: {0}")]
     InvalidJobData(String),
     
     #[error("Storage directory error: {0}")]
     StorageDirectoryError(String),
+    
+    #[error("Database error: {0}")]
+    DatabaseError(#[from] rusqlite::Error),
+    
+    #[error("Migration failed: {0}")]
+    MigrationFailed(String),
+    
+    #[error(transparent)]
+    Rae(#[from] RaeError),
+}
+
+/// Jobs read from storage, with the sources that could not be read.
+pub type JobListing = (Vec<Job>, Vec<(PathBuf, PersistenceError)>);
+
+/// Format jobs are stored in.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum StorageFormat {
+    /// One JSON file per job
+    #[default]
+    JsonFiles,
+    /// A single SQLite database
+    Sqlite,
+    /// A single JSON file holding an array of all jobs
+    Archive,
 }
 
 /// Job persistence manager for storing and retrieving jobs.
 pub struct JobPersistence {
     /// Directory where job files are stored
     storage_dir: PathBuf,
+    /// Directory corrupt job files are moved to
+    quarantine_dir: PathBuf,
+    /// Format jobs are stored in
+    format: StorageFormat,
+    /// Archive file used by `StorageFormat::Archive`
+    archive_path: PathBuf,
+    /// Serializes read-modify-write cycles of the archive
+    archive_lock: Mutex<()>,
     /// Cache of loaded jobs
     job_cache: HashMap<JobId, Job>,
 }
@@ -51,11 +96,74 @@ impl JobPersistence {
         }
         
         Ok(JobPersistence {
+            archive_path: storage_dir.join(ARCHIVE_FILE),
+            storage_dir,
+            quarantine_dir: Self::get_quarantine_dir()?,
+            format: StorageFormat::default(),
+            archive_lock: Mutex::new(()),
+            job_cache: HashMap::new(),
+        })
+    }
+    
+    /// Creates a persistence manager storing jobs in the given directory.
+    ///
+    /// Corrupt files are quarantined to a `quarantine` directory next to it.
+    pub fn with_storage_dir(storage_dir: PathBuf) -> Result<Self, PersistenceError> {
+        fs::create_dir_all(&storage_dir)?;
+        
+        let quarantine_dir = storage_dir
+            .parent()
+            .map(|parent| parent.join("quarantine"))
+            .ok_or_else(|| PersistenceError::StorageDirectoryError(format!(
+                "{} has no parent directory", storage_dir.display()
+            )))?;
+        
+        Ok(JobPersistence {
+            archive_path: storage_dir.join(ARCHIVE_FILE),
             storage_dir,
+            quarantine_dir,
+            format: StorageFormat::default(),
+            archive_lock: Mutex::new(()),
             job_cache: HashMap::new(),
         })
     }
     
+    /// Opens a job archive written by `compact`, loading its jobs into the cache.
+    ///
+    /// The returned manager stores jobs in the archive, with the archive's
+    /// directory as storage directory.
+    pub fn from_archive(path: &Path) -> Result<Self, PersistenceError> {
+        if !path.is_file() {
+            return Err(PersistenceError::StorageDirectoryError(format!(
+                "Archive {} does not exist", path.display()
+            )));
+        }
+        
+        let storage_dir = path.parent()
+            .filter(|parent| !parent.as_os_str().is_empty())
+            .unwrap_or(Path::new("."))
+            .to_path_buf();
+        
+        let mut persistence = Self::with_storage_dir(storage_dir)?.with_format(StorageFormat::Archive);
+        persistence.archive_path = path.to_path_buf();
+        for job in Self::read_archive(path)? {
+            persistence.job_cache.insert(job.id.clone(), job);
+        }
+        
+        Ok(persistence)
+    }
+    
+    /// Sets the format jobs are stored in.
+    pub fn with_format(mut self, format: StorageFormat) -> Self {
+        self.format = format;
+        self
+    }
+    
+    /// Gets the format jobs are stored in.
+    pub fn format(&self) -> StorageFormat {
+        self.format
+    }
+    
     /// Gets the storage directory for jobs.
     fn get_storage_dir() -> Result<PathBuf, PersistenceError> {
         let mut path = dirs::data_local_dir()
@@ -70,32 +178,229 @@ impl JobPersistence {
         Ok(path)
     }
     
+    /// Gets the directory corrupt job files are moved to.
+    fn get_quarantine_dir() -> Result<PathBuf, PersistenceError> {
+        let home_dir = dirs::home_dir()
+            .ok_or_else(|| PersistenceError::StorageDirectoryError(
+                "Could not determine home directory".to_string()
+            ))?;
+        
+        Ok(home_dir.join(".rae").join("scheduler").join("quarantine"))
+    }
+    
     /// Gets the file path for a job.
     fn get_job_file_path(&self, job_id: &JobId) -> PathBuf {
         self.storage_dir.join(format!("{}.json", job_id))
     }
     
+    /// Gets the path of the SQLite job database.
+    fn get_database_path(&self) -> PathBuf {
+        self.storage_dir.join(DATABASE_FILE)
+    }
+    
+    /// Opens the SQLite job database, creating the table if needed.
+    fn open_database(&self) -> Result<Connection, PersistenceError> {
+        let conn = Connection::open(self.get_database_path())?;
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS jobs (id TEXT PRIMARY KEY, data TEXT NOT NULL)",
+            [],
+        )?;
+        Ok(conn)
+    }
+    
     /// Saves a job to storage.
     pub async fn save_job(&self, job: &Job) -> Result<(), PersistenceError> {
+        match self.format {
+            StorageFormat::Sqlite => return self.save_sqlite_jobs(std::slice::from_ref(job)),
+            StorageFormat::Archive => return self.save_archive_jobs(std::slice::from_ref(job)),
+            StorageFormat::JsonFiles => {}
+        }
+        
         let file_path = self.get_job_file_path(&job.id);
+        let temp_path = file_path.with_extension("json.tmp");
         
         // Serialize job to JSON
         let json_data = serde_json::to_string_pretty(job)?;
         
-        // Write to file
-        let mut file = tokio_fs::File::create(&file_path).await?;
-        file.write_all(json_data.as_bytes()).await?;
+        // Write next to the job file and rename over it, so a crash mid-write
+        // never leaves a truncated job file behind
+        if let Err(e) = Self::write_temp_file(&temp_path, &json_data).await {
+            let _ = tokio_fs::remove_file(&temp_path).await;
+            return Err(e.into());
+        }
+        tokio_fs::rename(&temp_path, &file_path).await?;
+        
+        Ok(())
+    }
+    
+    /// Writes and syncs a temporary file.
+    ///
+    /// Temporary files live in the storage directory itself, so renaming them
+    /// over the job file stays on one filesystem on every platform.
+    async fn write_temp_file(path: &Path, contents: &str) -> std::io::Result<()> {
+        let mut file = tokio_fs::File::create(path).await?;
+        file.write_all(contents.as_bytes()).await?;
         file.flush().await?;
+        file.sync_all().await
+    }
+    
+    /// Removes temporary files left behind by writes that were interrupted.
+    ///
+    /// Returns the paths of the removed files.
+    pub async fn repair_storage(&self) -> Result<Vec<PathBuf>, PersistenceError> {
+        let mut removed = Vec::new();
+        let mut entries = tokio_fs::read_dir(&self.storage_dir).await?;
+        
+        while let Some(entry) = entries.next_entry().await? {
+            let path = entry.path();
+            if path.extension().and_then(|s| s.to_str()) == Some("tmp") {
+                tokio_fs::remove_file(&path).await?;
+                removed.push(path);
+            }
+        }
+        
+        Ok(removed)
+    }
+    
+    /// Saves several jobs as one batch.
+    ///
+    /// A failed batch leaves storage unchanged.
+    pub async fn save_jobs(&self, jobs: &[Job]) -> Result<(), PersistenceError> {
+        self.save_jobs_as(self.format, jobs).await
+    }
+    
+    /// Saves several jobs as one batch in the given format.
+    async fn save_jobs_as(&self, format: StorageFormat, jobs: &[Job]) -> Result<(), PersistenceError> {
+        match format {
+            StorageFormat::JsonFiles => self.save_json_jobs(jobs).await,
+            StorageFormat::Sqlite => self.save_sqlite_jobs(jobs),
+            StorageFormat::Archive => self.save_archive_jobs(jobs),
+        }
+    }
+    
+    /// Writes every job to a temporary file first; job files are only
+    /// replaced once all writes succeeded.
+    async fn save_json_jobs(&self, jobs: &[Job]) -> Result<(), PersistenceError> {
+        let mut staged = Vec::new();
+        
+        for job in jobs {
+            let file_path = self.get_job_file_path(&job.id);
+            let temp_path = file_path.with_extension("json.tmp");
+            
+            let written = match serde_json::to_string_pretty(job) {
+                Ok(json_data) => Self::write_temp_file(&temp_path, &json_data).await.map_err(PersistenceError::from),
+                Err(e) => Err(e.into()),
+            };
+            
+            if let Err(e) = written {
+                for (temp_path, _) in &staged {
+                    let _ = tokio_fs::remove_file(temp_path).await;
+                }
+                let _ = tokio_fs::remove_file(&temp_path).await;
+                return Err(e);
+            }
+            
+            staged.push((temp_path, file_path));
+        }
+        
+        for (temp_path, file_path) in staged {
+            tokio_fs::rename(&temp_path, &file_path).await?;
+        }
+        
+        Ok(())
+    }
+    
+    /// Inserts or replaces jobs in the database within one transaction.
+    fn save_sqlite_jobs(&self, jobs: &[Job]) -> Result<(), PersistenceError> {
+        let mut conn = self.open_database()?;
+        let tx = conn.transaction()?;
+        
+        for job in jobs {
+            let json_data = serde_json::to_string(job)?;
+            tx.execute(
+                "INSERT OR REPLACE INTO jobs (id, data) VALUES (?1, ?2)",
+                params![job.id, json_data],
+            )?;
+        }
+        
+        tx.commit()?;
+        Ok(())
+    }
+    
+    /// Inserts or replaces jobs in the archive, rewriting it once.
+    fn save_archive_jobs(&self, jobs: &[Job]) -> Result<(), PersistenceError> {
+        let _guard = self.archive_lock.lock().unwrap_or_else(|e| e.into_inner());
+        
+        let mut stored = self.read_archive_if_exists()?;
+        stored.retain(|stored| !jobs.iter().any(|job| job.id == stored.id));
+        stored.extend(jobs.iter().cloned());
+        
+        Self::write_archive(&self.archive_path, stored)
+    }
+    
+    /// Reads the jobs in the archive; a missing archive holds no jobs.
+    fn read_archive_if_exists(&self) -> Result<Vec<Job>, PersistenceError> {
+        if !self.archive_path.exists() {
+            return Ok(Vec::new());
+        }
+        Self::read_archive(&self.archive_path)
+    }
+    
+    /// Reads the jobs in a JSON array file.
+    fn read_archive(path: &Path) -> Result<Vec<Job>, PersistenceError> {
+        let content = fs::read_to_string(path)?;
+        Ok(serde_json::from_str(&content)?)
+    }
+    
+    /// Writes jobs to `path` as a JSON array, sorted by ID.
+    ///
+    /// The array is written to a temporary file next to `path` first and
+    /// renamed over it, like job files.
+    fn write_archive(path: &Path, mut jobs: Vec<Job>) -> Result<(), PersistenceError> {
+        jobs.sort_by(|a, b| a.id.cmp(&b.id));
+        
+        if let Some(parent) = path.parent().filter(|parent| !parent.as_os_str().is_empty()) {
+            fs::create_dir_all(parent)?;
+        }
+        
+        let json_data = serde_json::to_string_pretty(&jobs)?;
+        let mut temp_name = path.file_name().unwrap_or_default().to_os_string();
+        temp_name.push(".tmp");
+        let temp_path = path.with_file_name(temp_name);
+        let written = fs::File::create(&temp_path).and_then(|mut file| {
+            file.write_all(json_data.as_bytes())?;
+            file.sync_all()
+        });
+        if let Err(e) = written {
+            let _ = fs::remove_file(&temp_path);
+            return Err(e.into());
+        }
+        fs::rename(&temp_path, path)?;
         
         Ok(())
     }
     
+    /// Checks whether a job is stored.
+    pub fn job_exists(&self, job_id: &JobId) -> bool {
+        match self.format {
+            StorageFormat::JsonFiles => self.get_job_file_path(job_id).exists(),
+            StorageFormat::Sqlite => self.load_sqlite_job(job_id).is_ok(),
+            StorageFormat::Archive => self.load_archive_job(job_id).is_ok(),
+        }
+    }
+    
     /// Loads a job from storage.
     pub async fn load_job(&self, job_id: &JobId) -> Result<Job, PersistenceError> {
+        match self.format {
+            StorageFormat::Sqlite => return self.load_sqlite_job(job_id),
+            StorageFormat::Archive => return self.load_archive_job(job_id),
+            StorageFormat::JsonFiles => {}
+        }
+        
         let file_path = self.get_job_file_path(job_id);
         
         if !file_path.exists() {
-            return Err(PersistenceError::JobNotFound(job_id.clone()));
+            return Err(RaeError::not_found("job", job_id).into());
         }
         
         // Read file content
@@ -107,8 +412,47 @@ impl JobPersistence {
         Ok(job)
     }
     
+    /// Loads a job from the database.
+    fn load_sqlite_job(&self, job_id: &JobId) -> Result<Job, PersistenceError> {
+        let data: Option<String> = self
+            .open_database()?
+            .query_row("SELECT data FROM jobs WHERE id = ?1", params![job_id], |row| row.get(0))
+            .optional()?;
+        
+        match data {
+            Some(data) => Ok(serde_json::from_str(&data)?),
+            None => Err(RaeError::not_found("job", job_id).into()),
+        }
+    }
+    
+    /// Loads a job from the archive.
+    fn load_archive_job(&self, job_id: &JobId) -> Result<Job, PersistenceError> {
+        self.read_archive_if_exists()?
+            .into_iter()
+            .find(|job| &job.id == job_id)
+            .ok_or_else(|| RaeError::not_found("job", job_id).into())
+    }
+    
     /// Deletes a job from storage.
     pub async fn delete_job(&self, job_id: &JobId) -> Result<(), PersistenceError> {
+        match self.format {
+            StorageFormat::Sqlite => {
+                self.open_database()?.execute("DELETE FROM jobs WHERE id = ?1", params![job_id])?;
+                return Ok(());
+            }
+            StorageFormat::Archive => {
+                let _guard = self.archive_lock.lock().unwrap_or_else(|e| e.into_inner());
+                let mut jobs = self.read_archive_if_exists()?;
+                let count = jobs.len();
+                jobs.retain(|job| &job.id != job_id);
+                if jobs.len() != count {
+                    Self::write_archive(&self.archive_path, jobs)?;
+                }
+                return Ok(());
+            }
+            StorageFormat::JsonFiles => {}
+        }
+        
         let file_path = self.get_job_file_path(job_id);
         
         if file_path.exists() {
@@ -119,8 +463,26 @@ impl JobPersistence {
     }
     
     /// Lists all jobs in storage.
-    pub async fn list_jobs(&self) -> Result<Vec<Job>, PersistenceError> {
+    ///
+    /// Files that cannot be read or parsed are skipped and returned alongside
+    /// the valid jobs instead of failing the whole load.
+    pub async fn list_jobs(&self) -> Result<JobListing, PersistenceError> {
+        self.list_jobs_as(self.format).await
+    }
+    
+    /// Lists all jobs stored in the given format.
+    async fn list_jobs_as(&self, format: StorageFormat) -> Result<JobListing, PersistenceError> {
+        match format {
+            StorageFormat::JsonFiles => self.list_json_jobs().await,
+            StorageFormat::Sqlite => self.list_sqlite_jobs(),
+            StorageFormat::Archive => Ok(self.list_archive_jobs()),
+        }
+    }
+    
+    /// Lists the jobs stored as JSON files.
+    async fn list_json_jobs(&self) -> Result<JobListing, PersistenceError> {
         let mut jobs = Vec::new();
+        let mut errors = Vec::new();
         
         // Read all JSON files in the storage directory
         let mut entries = tokio_fs::read_dir(&self.storage_dir).await?;
@@ -129,23 +491,193 @@ impl JobPersistence {
             let path = entry.path();
             
             // Only process JSON files
-            if path.extension().and_then(|s| s.to_str()) == Some("json") {
-                if let Ok(content) = tokio_fs::read_to_string(&path).await {
-                    if let Ok(job) = serde_json::from_str::<Job>(&content) {
-                        jobs.push(job);
+            if path.extension().and_then(|s| s.to_str()) != Some("json") {
+                continue;
+            }
+            
+            let content = match tokio_fs::read_to_string(&path).await {
+                Ok(content) => content,
+                Err(e) => {
+                    errors.push((path, e.into()));
+                    continue;
+                }
+            };
+            
+            match serde_json::from_str::<Job>(&content) {
+                Ok(job) => jobs.push(job),
+                Err(e) => errors.push((path, e.into())),
+            }
+        }
+        
+        Ok((jobs, errors))
+    }
+    
+    /// Lists the jobs stored in the database.
+    ///
+    /// Rows that fail to parse are reported against the database path.
+    fn list_sqlite_jobs(&self) -> Result<JobListing, PersistenceError> {
+        let conn = self.open_database()?;
+        let mut stmt = conn.prepare("SELECT data FROM jobs")?;
+        let rows = stmt.query_map([], |row| row.get::<_, String>(0))?;
+        
+        let mut jobs = Vec::new();
+        let mut errors = Vec::new();
+        
+        for data in rows {
+            match serde_json::from_str::<Job>(&data?) {
+                Ok(job) => jobs.push(job),
+                Err(e) => errors.push((self.get_database_path(), e.into())),
+            }
+        }
+        
+        Ok((jobs, errors))
+    }
+    
+    /// Lists the jobs in the archive.
+    ///
+    /// An unreadable archive is reported as a whole, as no job in it can be
+    /// trusted.
+    fn list_archive_jobs(&self) -> JobListing {
+        match self.read_archive_if_exists() {
+            Ok(jobs) => (jobs, Vec::new()),
+            Err(e) => (Vec::new(), vec![(self.archive_path.clone(), e)]),
+        }
+    }
+    
+    /// Detects the format of the jobs already in the storage directory.
+    ///
+    /// Returns `None` if no jobs have been stored yet.
+    pub fn detect_storage_format(&self) -> Result<Option<StorageFormat>, PersistenceError> {
+        if self.get_database_path().exists() {
+            return Ok(Some(StorageFormat::Sqlite));
+        }
+        if self.archive_path.exists() {
+            return Ok(Some(StorageFormat::Archive));
+        }
+        
+        for entry in fs::read_dir(&self.storage_dir)? {
+            if entry?.path().extension().and_then(|s| s.to_str()) == Some("json") {
+                return Ok(Some(StorageFormat::JsonFiles));
+            }
+        }
+        
+        Ok(None)
+    }
+    
+    /// Migrates existing jobs to the configured format if they are stored in
+    /// another one.
+    ///
+    /// Returns the number of migrated jobs.
+    pub async fn ensure_storage_format(&self) -> Result<usize, PersistenceError> {
+        match self.detect_storage_format()? {
+            Some(found) if found != self.format => self.migrate_storage_format(found, self.format).await,
+            _ => Ok(0),
+        }
+    }
+    
+    /// Copies all jobs from one storage format to another.
+    ///
+    /// Once every job is readable in the destination format, the source files
+    /// are moved to a `jobs.bak` directory next to the storage directory.
+    /// Returns the number of migrated jobs.
+    pub async fn migrate_storage_format(&self, from: StorageFormat, to: StorageFormat) -> Result<usize, PersistenceError> {
+        if from == to {
+            return Ok(0);
+        }
+        
+        let (jobs, errors) = self.list_jobs_as(from).await?;
+        for (path, error) in &errors {
+            warn!("Not migrating unreadable job data in {}: {}", path.display(), error);
+        }
+        
+        self.save_jobs_as(to, &jobs).await?;
+        
+        // Verify every job arrived before touching the source
+        let (migrated, _) = self.list_jobs_as(to).await?;
+        let migrated: HashSet<&JobId> = migrated.iter().map(|job| &job.id).collect();
+        let verified = jobs.iter().filter(|job| migrated.contains(&job.id)).count();
+        if verified != jobs.len() {
+            return Err(PersistenceError::MigrationFailed(format!(
+                "only {} of {} jobs were found after migrating to {:?}", verified, jobs.len(), to
+            )));
+        }
+        
+        self.move_to_backup(from).await?;
+        
+        Ok(jobs.len())
+    }
+    
+    /// Moves the files of a storage format into the `jobs.bak` directory.
+    async fn move_to_backup(&self, format: StorageFormat) -> Result<(), PersistenceError> {
+        let backup_dir = self.storage_dir.with_extension("bak");
+        tokio_fs::create_dir_all(&backup_dir).await?;
+        
+        let mut sources = Vec::new();
+        match format {
+            StorageFormat::Sqlite => sources.push(self.get_database_path()),
+            StorageFormat::Archive => sources.push(self.archive_path.clone()),
+            StorageFormat::JsonFiles => {
+                let mut entries = tokio_fs::read_dir(&self.storage_dir).await?;
+                while let Some(entry) = entries.next_entry().await? {
+                    let path = entry.path();
+                    if path.extension().and_then(|s| s.to_str()) == Some("json") {
+                        sources.push(path);
                     }
                 }
             }
         }
         
-        Ok(jobs)
+        for source in sources {
+            if let Some(file_name) = source.file_name() {
+                tokio_fs::rename(&source, backup_dir.join(file_name)).await?;
+            }
+        }
+        
+        Ok(())
+    }
+    
+    /// Moves job files that fail to deserialize into the quarantine directory.
+    ///
+    /// Returns the new paths of the quarantined files. Only JSON files are
+    /// quarantined; the database is never moved.
+    pub async fn quarantine_corrupt_files(&self) -> Result<Vec<PathBuf>, PersistenceError> {
+        if self.format != StorageFormat::JsonFiles {
+            return Ok(Vec::new());
+        }
+        
+        let (_, errors) = self.list_jobs().await?;
+        let mut quarantined = Vec::new();
+        
+        for (path, error) in errors {
+            if !matches!(error, PersistenceError::SerializationError(_)) {
+                continue;
+            }
+            
+            let Some(file_name) = path.file_name() else {
+                continue;
+            };
+            
+            tokio_fs::create_dir_all(&self.quarantine_dir).await?;
+            
+            // Keep earlier quarantined copies of the same job
+            let mut target = self.quarantine_dir.join(file_name);
+            if target.exists() {
+                let stamp = chrono::Utc::now().format("%Y%m%d%H%M%S");
+                target = self.quarantine_dir.join(format!("{}.{}", file_name.to_string_lossy(), stamp));
+            }
+            
+            tokio_fs::rename(&path, &target).await?;
+            quarantined.push(target);
+        }
+        
+        Ok(quarantined)
     }
     
     /// Loads all jobs into cache.
     pub async fn load_all_jobs(&mut self) -> Result<(), PersistenceError> {
         self.job_cache.clear();
         
-        let jobs = self.list_jobs().await?;
+        let (jobs, _) = self.list_jobs().await?;
         for job in jobs {
             self.job_cache.insert(job.id.clone(), job);
         }
@@ -182,6 +714,19 @@ impl JobPersistence {
     pub async fn get_storage_stats(&self) -> Result<StorageStats, PersistenceError> {
         let mut stats = StorageStats::default();
         
+        let single_file = match self.format {
+            StorageFormat::Sqlite => Some(self.get_database_path()),
+            StorageFormat::Archive => Some(self.archive_path.clone()),
+            StorageFormat::JsonFiles => None,
+        };
+        if let Some(path) = single_file {
+            if let Ok(metadata) = tokio_fs::metadata(path).await {
+                stats.total_files = 1;
+                stats.total_size = metadata.len();
+            }
+            return Ok(stats);
+        }
+        
         let mut entries = tokio_fs::read_dir(&self.storage_dir).await?;
         
         while let Some(entry) = entries.next_entry().await? {
@@ -201,9 +746,7 @@ impl JobPersistence {
     
     /// Validates job data integrity.
     pub async fn validate_job_data(&self, job_id: &JobId) -> Result<bool, PersistenceError> {
-        let file_path = self.get_job_file_path(job_id);
-        
-        if !file_path.exists() {
+        if !self.job_exists(job_id) {
             return Ok(false);
         }
         
@@ -227,7 +770,7 @@ impl JobPersistence {
             tokio_fs::create_dir_all(backup_dir).await?;
         }
         
-        let jobs = self.list_jobs().await?;
+        let (jobs, _) = self.list_jobs().await?;
         
         for job in jobs {
             let backup_file = backup_dir.join(format!("{}.json", job.id));
@@ -265,6 +808,108 @@ impl JobPersistence {
         
         Ok(())
     }
+    
+    /// Exports all jobs to `path` as a single JSON array.
+    ///
+    /// Jobs that cannot be read are left out, as in `list_jobs`.
+    pub async fn export_jobs(&self, path: &Path) -> Result<(), PersistenceError> {
+        let (jobs, _) = self.list_jobs().await?;
+        Self::write_archive(path, jobs)
+    }
+    
+    /// Merges all stored jobs into one archive at `output_path`.
+    ///
+    /// The archive is a JSON array of jobs that `from_archive` can open.
+    /// Compacting into this manager's own archive path replaces the individual
+    /// job files (or database), which are moved to `jobs.bak`.
+    pub async fn compact(&self, output_path: &Path) -> Result<(), PersistenceError> {
+        let (jobs, errors) = self.list_jobs().await?;
+        for (path, error) in &errors {
+            warn!("Not compacting unreadable job data in {}: {}", path.display(), error);
+        }
+        
+        Self::write_archive(output_path, jobs)?;
+        
+        if output_path == self.archive_path && self.format != StorageFormat::Archive {
+            self.move_to_backup(self.format).await?;
+        }
+        
+        Ok(())
+    }
+    
+    /// Imports jobs from a JSON array written by `export_jobs`.
+    ///
+    /// Jobs whose ID is already stored are handled according to `merge_strategy`.
+    pub async fn import_jobs(&self, path: &Path, merge_strategy: MergeStrategy) -> Result<ImportReport, PersistenceError> {
+        let content = tokio_fs::read_to_string(path).await?;
+        let jobs: Vec<Job> = serde_json::from_str(&content)?;
+        
+        let mut report = ImportReport::default();
+        for mut job in jobs {
+            if !self.job_exists(&job.id) {
+                self.save_job(&job).await?;
+                report.created += 1;
+                continue;
+            }
+            
+            let mut conflict = ImportConflict {
+                job_id: job.id.clone(),
+                name: job.name.clone(),
+                renamed_to: None,
+            };
+            match merge_strategy {
+                MergeStrategy::Skip => report.skipped += 1,
+                MergeStrategy::Overwrite => {
+                    self.save_job(&job).await?;
+                    report.overwritten += 1;
+                }
+                MergeStrategy::RenameConflict => {
+                    job.id = Uuid::new_v4().to_string();
+                    self.save_job(&job).await?;
+                    conflict.renamed_to = Some(job.id);
+                    report.renamed += 1;
+                }
+            }
+            report.conflicts.push(conflict);
+        }
+        
+        Ok(report)
+    }
+}
+
+/// How `import_jobs` handles a job whose ID is already stored.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum MergeStrategy {
+    /// Keep the stored job and drop the imported one
+    #[default]
+    Skip,
+    /// Replace the stored job with the imported one
+    Overwrite,
+    /// Import the job under a new ID
+    RenameConflict,
+}
+
+/// An imported job whose ID was already stored.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ImportConflict {
+    pub job_id: JobId,
+    pub name: String,
+    /// New ID the job was imported under, for `MergeStrategy::RenameConflict`
+    pub renamed_to: Option<JobId>,
+}
+
+/// Outcome of `JobPersistence::import_jobs`.
+#[derive(Debug, Clone, Default)]
+pub struct ImportReport {
+    /// Jobs that did not exist yet
+    pub created: usize,
+    /// Conflicting jobs that were left alone
+    pub skipped: usize,
+    /// Conflicting jobs that replaced the stored job
+    pub overwritten: usize,
+    /// Conflicting jobs imported under a new ID
+    pub renamed: usize,
+    pub conflicts: Vec<ImportConflict>,
 }
 
 /// Statistics about job storage.
@@ -297,6 +942,10 @@ mod tests {
         
         let mut persistence = JobPersistence {
             storage_dir,
+            quarantine_dir: temp_dir.path().join("quarantine"),
+            format: StorageFormat::JsonFiles,
+            archive_path: temp_dir.path().join("jobs").join(ARCHIVE_FILE),
+            archive_lock: Mutex::new(()),
             job_cache: HashMap::new(),
         };
         
@@ -321,6 +970,10 @@ mod tests {
         
         let mut persistence = JobPersistence {
             storage_dir,
+            quarantine_dir: temp_dir.path().join("quarantine"),
+            format: StorageFormat::JsonFiles,
+            archive_path: temp_dir.path().join("jobs").join(ARCHIVE_FILE),
+            archive_lock: Mutex::new(()),
             job_cache: HashMap::new(),
         };
         
@@ -336,7 +989,10 @@ mod tests {
         assert!(persistence.delete_job(&job.id).await.is_ok());
         
         // Verify job is deleted
-        assert!(persistence.load_job(&job.id).await.is_err());
+        assert!(matches!(
+            persistence.load_job(&job.id).await,
+            Err(PersistenceError::Rae(ref e)) if e.is_not_found()
+        ));
     }
     
     #[tokio::test]
@@ -347,6 +1003,10 @@ mod tests {
         
         let mut persistence = JobPersistence {
             storage_dir,
+            quarantine_dir: temp_dir.path().join("quarantine"),
+            format: StorageFormat::JsonFiles,
+            archive_path: temp_dir.path().join("jobs").join(ARCHIVE_FILE),
+            archive_lock: Mutex::new(()),
             job_cache: HashMap::new(),
         };
         
@@ -358,11 +1018,298 @@ mod tests {
         persistence.save_job(&job2).await.unwrap();
         
         // List jobs
-        let jobs = persistence.list_jobs().await.unwrap();
+        let (jobs, errors) = persistence.list_jobs().await.unwrap();
         assert_eq!(jobs.len(), 2);
+        assert!(errors.is_empty());
         
         let job_ids: Vec<String> = jobs.iter().map(|j| j.id.clone()).collect();
         assert!(job_ids.contains(&job1.id));
         assert!(job_ids.contains(&job2.id));
     }
-} 
\ No newline at end of file
+    
+    #[tokio::test]
+    async fn test_corrupt_files_are_skipped_and_quarantined() {
+        let temp_dir = tempdir().unwrap();
+        let storage_dir = temp_dir.path().join("jobs");
+        let quarantine_dir = temp_dir.path().join("quarantine");
+        tokio_fs::create_dir_all(&storage_dir).await.unwrap();
+        
+        let persistence = JobPersistence {
+            storage_dir: storage_dir.clone(),
+            quarantine_dir: quarantine_dir.clone(),
+            format: StorageFormat::JsonFiles,
+            archive_path: temp_dir.path().join("jobs").join(ARCHIVE_FILE),
+            archive_lock: Mutex::new(()),
+            job_cache: HashMap::new(),
+        };
+        
+        let job1 = Job::new("job1".to_string(), "echo".to_string());
+        let job2 = Job::new("job2".to_string(), "ls".to_string());
+        persistence.save_job(&job1).await.unwrap();
+        persistence.save_job(&job2).await.unwrap();
+        
+        let truncated = storage_dir.join("truncated.json");
+        let wrong_shape = storage_dir.join("wrong-shape.json");
+        fs::write(&truncated, "{\"id\": \"abc\", \"name\":").unwrap();
+        fs::write(&wrong_shape, "[1, 2, 3]").unwrap();
+        
+        // Valid jobs still load; each corrupt file is reported
+        let (jobs, errors) = persistence.list_jobs().await.unwrap();
+        assert_eq!(jobs.len(), 2);
+        assert_eq!(errors.len(), 2);
+        assert!(errors.iter().all(|(_, e)| matches!(e, PersistenceError::SerializationError(_))));
+        
+        let mut quarantined = persistence.quarantine_corrupt_files().await.unwrap();
+        quarantined.sort();
+        assert_eq!(quarantined, vec![quarantine_dir.join("truncated.json"), quarantine_dir.join("wrong-shape.json")]);
+        assert!(!truncated.exists());
+        assert!(!wrong_shape.exists());
+        
+        let (jobs, errors) = persistence.list_jobs().await.unwrap();
+        assert_eq!(jobs.len(), 2);
+        assert!(errors.is_empty());
+    }
+    
+    #[tokio::test]
+    async fn test_save_job_replaces_file_atomically() {
+        let temp_dir = tempdir().unwrap();
+        let storage_dir = temp_dir.path().join("jobs");
+        let persistence = JobPersistence::with_storage_dir(storage_dir.clone()).unwrap();
+        
+        let mut job = Job::new("backup".to_string(), "rsync".to_string());
+        persistence.save_job(&job).await.unwrap();
+        job.command = "restic".to_string();
+        persistence.save_job(&job).await.unwrap();
+        
+        assert_eq!(persistence.load_job(&job.id).await.unwrap().command, "restic");
+        let files: Vec<PathBuf> = fs::read_dir(&storage_dir).unwrap().map(|entry| entry.unwrap().path()).collect();
+        assert_eq!(files, vec![storage_dir.join(format!("{}.json", job.id))]);
+    }
+    
+    #[tokio::test]
+    async fn test_repair_storage_removes_leftover_temp_files() {
+        let temp_dir = tempdir().unwrap();
+        let storage_dir = temp_dir.path().join("jobs");
+        let persistence = JobPersistence::with_storage_dir(storage_dir.clone()).unwrap();
+        
+        let job = Job::new("backup".to_string(), "rsync".to_string());
+        persistence.save_job(&job).await.unwrap();
+        
+        // A write interrupted before the rename
+        let leftover = storage_dir.join(format!("{}.json.tmp", job.id));
+        fs::write(&leftover, "{\"id\": \"").unwrap();
+        
+        assert_eq!(persistence.repair_storage().await.unwrap(), vec![leftover.clone()]);
+        assert!(!leftover.exists());
+        assert_eq!(persistence.load_job(&job.id).await.unwrap().command, "rsync");
+        assert!(persistence.repair_storage().await.unwrap().is_empty());
+    }
+    
+    async fn stored_ids(persistence: &JobPersistence) -> Vec<JobId> {
+        let (jobs, errors) = persistence.list_jobs().await.unwrap();
+        assert!(errors.is_empty());
+        
+        let mut ids: Vec<JobId> = jobs.into_iter().map(|job| job.id).collect();
+        ids.sort();
+        ids
+    }
+    
+    #[tokio::test]
+    async fn test_migrate_json_files_to_sqlite() {
+        let temp_dir = tempdir().unwrap();
+        let storage_dir = temp_dir.path().join("jobs");
+        let json = JobPersistence::with_storage_dir(storage_dir.clone()).unwrap();
+        
+        let jobs = vec![
+            Job::new("job1".to_string(), "echo".to_string()),
+            Job::new("job2".to_string(), "ls".to_string()),
+        ];
+        json.save_jobs(&jobs).await.unwrap();
+        let mut expected: Vec<JobId> = jobs.iter().map(|job| job.id.clone()).collect();
+        expected.sort();
+        
+        let sqlite = JobPersistence::with_storage_dir(storage_dir.clone()).unwrap()
+            .with_format(StorageFormat::Sqlite);
+        assert_eq!(sqlite.detect_storage_format().unwrap(), Some(StorageFormat::JsonFiles));
+        assert_eq!(sqlite.ensure_storage_format().await.unwrap(), 2);
+        
+        assert_eq!(sqlite.detect_storage_format().unwrap(), Some(StorageFormat::Sqlite));
+        assert_eq!(stored_ids(&sqlite).await, expected);
+        assert_eq!(sqlite.load_job(&jobs[0].id).await.unwrap().command, "echo");
+        
+        // Source files are kept in the backup directory
+        let backup_dir = temp_dir.path().join("jobs.bak");
+        assert!(backup_dir.join(format!("{}.json", jobs[0].id)).is_file());
+        assert!(!storage_dir.join(format!("{}.json", jobs[0].id)).exists());
+        
+        // Nothing left to migrate on the next start
+        assert_eq!(sqlite.ensure_storage_format().await.unwrap(), 0);
+    }
+    
+    #[tokio::test]
+    async fn test_migrate_sqlite_to_json_files() {
+        let temp_dir = tempdir().unwrap();
+        let storage_dir = temp_dir.path().join("jobs");
+        let sqlite = JobPersistence::with_storage_dir(storage_dir.clone()).unwrap()
+            .with_format(StorageFormat::Sqlite);
+        
+        let job1 = Job::new("job1".to_string(), "echo".to_string());
+        let job2 = Job::new("job2".to_string(), "ls".to_string());
+        sqlite.save_job(&job1).await.unwrap();
+        sqlite.save_job(&job2).await.unwrap();
+        sqlite.delete_job(&job2.id).await.unwrap();
+        assert!(sqlite.job_exists(&job1.id));
+        assert!(!sqlite.job_exists(&job2.id));
+        
+        let json = JobPersistence::with_storage_dir(storage_dir.clone()).unwrap();
+        assert_eq!(json.migrate_storage_format(StorageFormat::Sqlite, StorageFormat::JsonFiles).await.unwrap(), 1);
+        
+        assert_eq!(stored_ids(&json).await, vec![job1.id.clone()]);
+        assert!(storage_dir.join(format!("{}.json", job1.id)).is_file());
+        assert!(!storage_dir.join(DATABASE_FILE).exists());
+        assert!(temp_dir.path().join("jobs.bak").join(DATABASE_FILE).is_file());
+        assert_eq!(json.detect_storage_format().unwrap(), Some(StorageFormat::JsonFiles));
+    }
+    
+    #[tokio::test]
+    async fn test_export_and_import_jobs_round_trip() {
+        let temp_dir = tempdir().unwrap();
+        let persistence = JobPersistence::with_storage_dir(temp_dir.path().join("jobs")).unwrap();
+        let export_path = temp_dir.path().join("export").join("jobs.json");
+        
+        let jobs = vec![
+            Job::new("job1".to_string(), "echo".to_string()),
+            Job::new("job2".to_string(), "ls".to_string()),
+        ];
+        persistence.save_jobs(&jobs).await.unwrap();
+        let expected = stored_ids(&persistence).await;
+        
+        persistence.export_jobs(&export_path).await.unwrap();
+        let exported: Vec<Job> = serde_json::from_str(&fs::read_to_string(&export_path).unwrap()).unwrap();
+        assert_eq!(exported.len(), 2);
+        
+        for job in &jobs {
+            persistence.delete_job(&job.id).await.unwrap();
+        }
+        assert!(stored_ids(&persistence).await.is_empty());
+        
+        let report = persistence.import_jobs(&export_path, MergeStrategy::Skip).await.unwrap();
+        assert_eq!(report.created, 2);
+        assert!(report.conflicts.is_empty());
+        assert_eq!(stored_ids(&persistence).await, expected);
+    }
+    
+    #[tokio::test]
+    async fn test_import_jobs_merge_strategies() {
+        let temp_dir = tempdir().unwrap();
+        let persistence = JobPersistence::with_storage_dir(temp_dir.path().join("jobs")).unwrap();
+        let export_path = temp_dir.path().join("jobs.json");
+        
+        let job = Job::new("backup".to_string(), "rsync".to_string());
+        persistence.save_job(&job).await.unwrap();
+        persistence.export_jobs(&export_path).await.unwrap();
+        
+        let mut changed = job.clone();
+        changed.command = "cp".to_string();
+        persistence.save_job(&changed).await.unwrap();
+        
+        let report = persistence.import_jobs(&export_path, MergeStrategy::Skip).await.unwrap();
+        assert_eq!((report.created, report.skipped), (0, 1));
+        assert_eq!(report.conflicts[0].job_id, job.id);
+        assert_eq!(persistence.load_job(&job.id).await.unwrap().command, "cp");
+        
+        let report = persistence.import_jobs(&export_path, MergeStrategy::Overwrite).await.unwrap();
+        assert_eq!(report.overwritten, 1);
+        assert_eq!(persistence.load_job(&job.id).await.unwrap().command, "rsync");
+        
+        let report = persistence.import_jobs(&export_path, MergeStrategy::RenameConflict).await.unwrap();
+        assert_eq!(report.renamed, 1);
+        let new_id = report.conflicts[0].renamed_to.clone().unwrap();
+        assert_ne!(new_id, job.id);
+        assert_eq!(persistence.load_job(&new_id).await.unwrap().name, "backup");
+        assert_eq!(stored_ids(&persistence).await.len(), 2);
+    }
+    
+    #[tokio::test]
+    async fn test_compact_replaces_job_files_with_archive() {
+        let temp_dir = tempdir().unwrap();
+        let storage_dir = temp_dir.path().join("jobs");
+        let persistence = JobPersistence::with_storage_dir(storage_dir.clone()).unwrap();
+        
+        let jobs = vec![
+            Job::new("job1".to_string(), "echo".to_string()),
+            Job::new("job2".to_string(), "ls".to_string()),
+        ];
+        persistence.save_jobs(&jobs).await.unwrap();
+        let expected = stored_ids(&persistence).await;
+        
+        // Compacting elsewhere keeps the job files
+        let copy_path = temp_dir.path().join("copy").join("jobs.archive");
+        persistence.compact(&copy_path).await.unwrap();
+        assert!(storage_dir.join(format!("{}.json", jobs[0].id)).is_file());
+        let copy = JobPersistence::from_archive(&copy_path).unwrap();
+        let mut cached: Vec<JobId> = copy.get_all_cached_jobs().iter().map(|job| job.id.clone()).collect();
+        cached.sort();
+        assert_eq!(cached, expected);
+        
+        let archive_path = storage_dir.join(ARCHIVE_FILE);
+        persistence.compact(&archive_path).await.unwrap();
+        assert!(!storage_dir.join(format!("{}.json", jobs[0].id)).exists());
+        assert!(temp_dir.path().join("jobs.bak").join(format!("{}.json", jobs[0].id)).is_file());
+        
+        let archive = JobPersistence::from_archive(&archive_path).unwrap();
+        assert_eq!(archive.format(), StorageFormat::Archive);
+        assert_eq!(archive.detect_storage_format().unwrap(), Some(StorageFormat::Archive));
+        assert_eq!(stored_ids(&archive).await, expected);
+        assert_eq!(archive.get_cached_job(&jobs[1].id).unwrap().command, "ls");
+        
+        assert!(JobPersistence::from_archive(&temp_dir.path().join("missing.archive")).is_err());
+        let corrupt_path = temp_dir.path().join("corrupt.archive");
+        std::fs::write(&corrupt_path, "not json").unwrap();
+        assert!(JobPersistence::from_archive(&corrupt_path).is_err());
+    }
+    
+    #[tokio::test]
+    async fn test_archive_save_load_and_delete() {
+        let temp_dir = tempdir().unwrap();
+        let persistence = JobPersistence::with_storage_dir(temp_dir.path().join("jobs")).unwrap()
+            .with_format(StorageFormat::Archive);
+        
+        let job1 = Job::new("job1".to_string(), "echo".to_string());
+        let mut job2 = Job::new("job2".to_string(), "ls".to_string());
+        assert!(stored_ids(&persistence).await.is_empty());
+        persistence.save_job(&job1).await.unwrap();
+        persistence.save_job(&job2).await.unwrap();
+        
+        job2.command = "pwd".to_string();
+        persistence.save_job(&job2).await.unwrap();
+        assert_eq!(persistence.load_job(&job2.id).await.unwrap().command, "pwd");
+        assert_eq!(stored_ids(&persistence).await.len(), 2);
+        
+        persistence.delete_job(&job1.id).await.unwrap();
+        assert!(!persistence.job_exists(&job1.id));
+        assert!(persistence.load_job(&job1.id).await.is_err());
+        assert_eq!(stored_ids(&persistence).await, vec![job2.id.clone()]);
+        
+        let stats = persistence.get_storage_stats().await.unwrap();
+        assert_eq!(stats.total_files, 1);
+    }
+    
+    #[tokio::test]
+    async fn test_migrate_json_files_to_archive() {
+        let temp_dir = tempdir().unwrap();
+        let storage_dir = temp_dir.path().join("jobs");
+        let json = JobPersistence::with_storage_dir(storage_dir.clone()).unwrap();
+        let job = Job::new("job1".to_string(), "echo".to_string());
+        json.save_job(&job).await.unwrap();
+        
+        let archive = JobPersistence::with_storage_dir(storage_dir.clone()).unwrap()
+            .with_format(StorageFormat::Archive);
+        assert_eq!(archive.ensure_storage_format().await.unwrap(), 1);
+        
+        assert!(storage_dir.join(ARCHIVE_FILE).is_file());
+        assert!(!storage_dir.join(format!("{}.json", job.id)).exists());
+        assert_eq!(stored_ids(&archive).await, vec![job.id.clone()]);
+        assert_eq!(archive.ensure_storage_format().await.unwrap(), 0);
+    }
+}
@@ -3,8 +3,10 @@
 //! Provides cross-platform job queuing with priority management,
 //! time-based scheduling, and platform-appropriate resource limits.
 
-use crate::scheduler::job::{Job, JobId, Priority};
-use chrono::{DateTime, Utc};
+use crate::error::RaeError;
+use crate::scheduler::job::{Job, JobCondition, JobId, Priority};
+use chrono::{DateTime, Duration, LocalResult, NaiveDateTime, TimeZone, Timelike, Utc};
+use chrono_tz::Tz;
 use std::collections::{BinaryHeap, HashMap};
 use std::cmp::Ordering;
 use std::str::FromStr;
@@ -16,11 +18,11 @@ pub enum QueueError {
     #[error("Job already exists: {0}")]
     JobAlreadyExists(String),
     
-    #[error("Job not found: {0}")]
-    JobNotFound(String),
-    
     #[error("Invalid job configuration: {0}")]
     InvalidJob(String),
+    
+    #[error(transparent)]
+    Rae(#[from] RaeError),
 }
 
 /// A job entry in the queue with scheduling information.
@@ -30,6 +32,40 @@ pub struct QueuedJob {
     pub next_execution: Option<DateTime<Utc>>,
     pub priority: Priority,
     pub added_at: DateTime<Utc>,
+    /// Scheduling rounds this job was due but another job was picked
+    pub wait_ticks: u64,
+}
+
+impl QueuedJob {
+    /// Priority after ageing the job by the ticks it has waited.
+    pub fn effective_priority(&self, fairness: &FairnessPolicy) -> Priority {
+        let boost = self.wait_ticks / fairness.ticks_per_boost.max(1);
+        self.priority.boosted(boost.min(fairness.max_priority_boost as u64) as u8)
+    }
+    
+    /// Whether the job should run at `now`.
+    fn is_due(&self, now: DateTime<Utc>) -> bool {
+        // Jobs without a next execution time (event/pattern based) are always due
+        self.next_execution.is_none_or(|next_execution| next_execution <= now)
+    }
+}
+
+/// Ageing policy that keeps low priority jobs from starving.
+#[derive(Debug, Clone)]
+pub struct FairnessPolicy {
+    /// Ticks a job must wait to gain one priority level
+    pub ticks_per_boost: u64,
+    /// Most levels a job can gain by waiting
+    pub max_priority_boost: u8,
+}
+
+impl Default for FairnessPolicy {
+    fn default() -> Self {
+        FairnessPolicy {
+            ticks_per_boost: 10,
+            max_priority_boost: 2,
+        }
+    }
 }
 
 impl PartialEq for QueuedJob {
@@ -70,6 +106,10 @@ pub struct JobQueue {
     jobs: BinaryHeap<QueuedJob>,
     /// Index of jobs by ID for fast lookup
     job_index: HashMap<JobId, QueuedJob>,
+    /// Jobs held back because their condition is not met
+    suspended: HashMap<JobId, Job>,
+    /// Ageing policy applied when picking the next job
+    fairness: FairnessPolicy,
     /// Statistics about the queue
     stats: QueueStats,
 }
@@ -104,17 +144,53 @@ impl JobQueue {
         JobQueue {
             jobs: BinaryHeap::new(),
             job_index: HashMap::new(),
+            suspended: HashMap::new(),
+            fairness: FairnessPolicy::default(),
             stats: QueueStats::default(),
         }
     }
     
+    /// Sets the ageing policy used to prevent starvation.
+    pub fn with_fairness(mut self, fairness: FairnessPolicy) -> Self {
+        self.fairness = fairness;
+        self
+    }
+    
     /// Adds a job to the queue.
+    ///
+    /// A job with a condition is suspended until a `tick` reports the
+    /// condition as met; use `add_job_with_condition` if it was evaluated.
     pub fn add_job(&mut self, job: Job) -> Result<(), QueueError> {
+        let condition_met = job.condition.is_none();
+        self.add_job_with_condition(job, condition_met)
+    }
+    
+    /// Adds a job whose condition was evaluated beforehand, suspending it if
+    /// the condition does not hold.
+    pub fn add_job_with_condition(&mut self, job: Job, condition_met: bool) -> Result<(), QueueError> {
         // Check if job already exists
-        if self.job_index.contains_key(&job.id) {
+        if self.job_index.contains_key(&job.id) || self.suspended.contains_key(&job.id) {
             return Err(QueueError::JobAlreadyExists(job.id.clone()));
         }
         
+        // Hold the job back until its condition is met
+        if !condition_met {
+            self.suspended.insert(job.id.clone(), job);
+            self.stats.total_jobs += 1;
+            return Ok(());
+        }
+        
+        self.enqueue(job);
+        
+        // Update statistics
+        self.stats.total_jobs += 1;
+        self.stats.scheduled_jobs += 1;
+        
+        Ok(())
+    }
+    
+    /// Adds a job to the priority queue and index.
+    fn enqueue(&mut self, job: Job) {
         // Calculate next execution time
         let next_execution = self.calculate_next_execution(&job);
         
@@ -124,23 +200,77 @@ impl JobQueue {
             next_execution,
             priority: job.priority,
             added_at: Utc::now(),
+            wait_ticks: 0,
         };
         
         // Add to queue and index
         self.jobs.push(queued_job.clone());
-        self.job_index.insert(job.id.clone(), queued_job);
+        self.job_index.insert(job.id, queued_job);
+    }
+    
+    /// Gets the conditions of all queued and suspended jobs that have one, so
+    /// they can be evaluated without holding the queue.
+    pub fn job_conditions(&self) -> Vec<(JobId, JobCondition)> {
+        self.job_index
+            .values()
+            .map(|qj| &qj.job)
+            .chain(self.suspended.values())
+            .filter_map(|job| job.condition.clone().map(|condition| (job.id.clone(), condition)))
+            .collect()
+    }
+    
+    /// Applies evaluated job conditions.
+    ///
+    /// Suspended jobs whose condition now holds are queued, and queued jobs
+    /// whose condition no longer holds are suspended; jobs missing from
+    /// `conditions` keep their state. Returns the IDs of the jobs that
+    /// changed state.
+    pub fn tick(&mut self, conditions: &HashMap<JobId, bool>) -> Vec<JobId> {
+        let lapsed: Vec<JobId> = self.job_index
+            .keys()
+            .filter(|job_id| conditions.get(*job_id) == Some(&false))
+            .cloned()
+            .collect();
+        let resumed: Vec<JobId> = self.suspended
+            .keys()
+            .filter(|job_id| conditions.get(*job_id) == Some(&true))
+            .cloned()
+            .collect();
         
-        // Update statistics
-        self.stats.total_jobs += 1;
-        self.stats.scheduled_jobs += 1;
+        for job_id in &lapsed {
+            if let Some(queued_job) = self.job_index.remove(job_id) {
+                self.suspended.insert(job_id.clone(), queued_job.job);
+            }
+        }
+        if !lapsed.is_empty() {
+            self.rebuild_queue();
+        }
         
-        Ok(())
+        for job_id in &resumed {
+            if let Some(job) = self.suspended.remove(job_id) {
+                self.enqueue(job);
+            }
+        }
+        
+        self.stats.scheduled_jobs = self.jobs.len();
+        
+        lapsed.into_iter().chain(resumed).collect()
+    }
+    
+    /// Checks if a job is suspended waiting for its condition.
+    pub fn is_suspended(&self, job_id: &JobId) -> bool {
+        self.suspended.contains_key(job_id)
     }
     
     /// Removes a job from the queue.
     pub fn remove_job(&mut self, job_id: &JobId) -> Result<(), QueueError> {
+        if self.suspended.remove(job_id).is_some() {
+            self.stats.total_jobs = self.len();
+            return Ok(());
+        }
+        
         if !self.job_index.contains_key(job_id) {
-            return Err(QueueError::JobNotFound(job_id.clone()));
+            return Err(RaeError::not_found("job", job_id).into());
         }
         
         // Remove from index
@@ -150,61 +280,125 @@ impl JobQueue {
         self.rebuild_queue();
         
         // Update statistics
-        self.stats.total_jobs = self.job_index.len();
+        self.stats.total_jobs = self.len();
         self.stats.scheduled_jobs = self.jobs.len();
         
         Ok(())
     }
     
     /// Gets the next job to execute.
+    ///
+    /// Picks the due job with the highest effective priority; every other due
+    /// job ages by one tick so low priority jobs cannot starve.
     pub fn get_next_job(&mut self) -> Option<Job> {
         let now = Utc::now();
         
-        // Find the next job that should be executed
-        while let Some(queued_job) = self.jobs.peek() {
-            if let Some(next_execution) = queued_job.next_execution {
-                if next_execution <= now {
-                    // This job should be executed now
-                    let job = queued_job.job.clone();
-                    
-                    // Remove from queue
-                    self.jobs.pop();
-                    self.job_index.remove(&job.id);
-                    
-                    // Update statistics
-                    self.stats.scheduled_jobs = self.jobs.len();
-                    
-                    return Some(job);
-                } else {
-                    // Job is scheduled for the future
-                    break;
-                }
-            } else {
-                // Job has no next execution time (event/pattern based)
-                let job = queued_job.job.clone();
-                
-                // Remove from queue
-                self.jobs.pop();
-                self.job_index.remove(&job.id);
-                
-                // Update statistics
-                self.stats.scheduled_jobs = self.jobs.len();
-                
-                return Some(job);
-            }
+        let next_id = self.job_index
+            .values()
+            .filter(|qj| qj.is_due(now))
+            .max_by(|a, b| {
+                a.effective_priority(&self.fairness)
+                    .cmp(&b.effective_priority(&self.fairness))
+                    // Among equals, the longest-waiting and then oldest job wins
+                    .then(a.wait_ticks.cmp(&b.wait_ticks))
+                    .then(b.added_at.cmp(&a.added_at))
+            })
+            .map(|qj| qj.job.id.clone())?;
+        
+        let queued_job = self.job_index.remove(&next_id)?;
+        
+        for waiting in self.job_index.values_mut().filter(|qj| qj.is_due(now)) {
+            waiting.wait_ticks += 1;
         }
         
-        None
+        self.rebuild_queue();
+        
+        // Update statistics
+        self.stats.scheduled_jobs = self.jobs.len();
+        
+        Some(queued_job.job)
+    }
+    
+    /// Takes the jobs whose scheduled execution time has come, highest effective
+    /// priority first.
+    ///
+    /// Cron jobs are queued again for their next window; other jobs leave the
+    /// queue. Event and pattern based jobs have no execution time and stay.
+    pub fn take_due_jobs(&mut self, now: DateTime<Utc>) -> Vec<Job> {
+        let mut due: Vec<QueuedJob> = self.job_index
+            .values()
+            .filter(|qj| qj.next_execution.is_some_and(|next_execution| next_execution <= now))
+            .cloned()
+            .collect();
+        if due.is_empty() {
+            return Vec::new();
+        }
+        due.sort_by(|a, b| {
+            b.effective_priority(&self.fairness)
+                .cmp(&a.effective_priority(&self.fairness))
+                .then(a.added_at.cmp(&b.added_at))
+        });
+        
+        for queued_job in &due {
+            self.job_index.remove(&queued_job.job.id);
+        }
+        self.rebuild_queue();
+        
+        let jobs: Vec<Job> = due.into_iter().map(|qj| qj.job).collect();
+        for job in jobs.iter().filter(|job| job.active_cron().is_some()) {
+            self.enqueue(job.clone());
+        }
+        
+        self.stats.total_jobs = self.len();
+        self.stats.scheduled_jobs = self.jobs.len();
+        
+        jobs
+    }
+    
+    /// Gets the job with the earliest scheduled execution without removing it.
+    ///
+    /// Event and pattern based jobs have no execution time and are skipped.
+    pub fn peek_next_due(&self) -> Option<(JobId, DateTime<Utc>)> {
+        self.job_index
+            .values()
+            .filter_map(|qj| qj.next_execution.map(|next_execution| (qj.job.id.clone(), next_execution)))
+            .min_by_key(|(_, next_execution)| *next_execution)
+    }
+    
+    /// Gets the time until the earliest scheduled execution.
+    ///
+    /// Returns zero if that execution is already overdue.
+    pub fn time_until_next(&self) -> Option<std::time::Duration> {
+        self.peek_next_due()
+            .map(|(_, next_execution)| (next_execution - Utc::now()).to_std().unwrap_or_default())
     }
     
     /// Gets a job by ID.
     pub fn get_job(&self, job_id: &JobId) -> Option<&Job> {
-        self.job_index.get(job_id).map(|qj| &qj.job)
+        self.job_index.get(job_id)
+            .map(|qj| &qj.job)
+            .or_else(|| self.suspended.get(job_id))
+    }
+    
+    /// Gets the next scheduled execution time of a job.
+    pub fn get_next_execution(&self, job_id: &JobId) -> Option<DateTime<Utc>> {
+        self.job_index.get(job_id).and_then(|qj| qj.next_execution)
     }
     
     /// Lists all jobs in the queue.
     pub fn list_jobs(&self) -> Vec<&Job> {
-        self.job_index.values().map(|qj| &qj.job).collect()
+        self.job_index.values()
+            .map(|qj| &qj.job)
+            .chain(self.suspended.values())
+            .collect()
+    }
+    
+    /// Lists the jobs in the queue that carry the given tag.
+    pub fn get_jobs_by_tag(&self, tag: &str) -> Vec<&Job> {
+        self.list_jobs()
+            .into_iter()
+            .filter(|job| job.has_tag(tag))
+            .collect()
     }
     
     /// Gets jobs that should be executed now.
@@ -226,11 +420,14 @@ impl JobQueue {
     
     /// Updates a job in the queue.
     pub fn update_job(&mut self, job: Job) -> Result<(), QueueError> {
+        // Keep the job suspended or queued as it was
+        let condition_met = !self.suspended.contains_key(&job.id);
+        
         // Remove existing job
         self.remove_job(&job.id)?;
         
         // Add updated job
-        self.add_job(job)
+        self.add_job_with_condition(job, condition_met)
     }
     
     /// Gets queue statistics.
@@ -247,9 +444,12 @@ impl JobQueue {
         let now = Utc::now();
         
         // Check cron schedule
-        if let Some(cron_expr) = &job.schedule.cron {
+        if let Some(cron_expr) = job.active_cron() {
             if let Ok(schedule) = cron::Schedule::from_str(cron_expr) {
-                return schedule.after(&now).next();
+                let tz = job.schedule.timezone.as_deref()
+                    .and_then(|tz| Tz::from_str(tz).ok())
+                    .unwrap_or(Tz::UTC);
+                return Self::next_cron_execution(&schedule, tz, now);
             }
         }
         
@@ -264,6 +464,97 @@ impl JobQueue {
         None
     }
     
+    /// Counts cron windows of a job that passed between its last run and `now`,
+    /// stopping at `limit`.
+    pub fn missed_windows(job: &Job, now: DateTime<Utc>, limit: u32) -> u32 {
+        let (Some(cron_expr), Some(last_run)) = (job.active_cron(), job.last_run_at) else {
+            return 0;
+        };
+        let Ok(schedule) = cron::Schedule::from_str(cron_expr) else {
+            return 0;
+        };
+        let tz = job.schedule.timezone.as_deref()
+            .and_then(|tz| Tz::from_str(tz).ok())
+            .unwrap_or(Tz::UTC);
+        
+        let mut missed = 0;
+        let mut after = last_run;
+        while missed < limit {
+            match Self::next_cron_execution(&schedule, tz, after) {
+                Some(window) if window <= now => {
+                    missed += 1;
+                    after = window;
+                }
+                _ => break,
+            }
+        }
+        
+        missed
+    }
+    
+    /// Lists the next `count` cron windows of a job after `after`.
+    ///
+    /// Jobs without a valid cron schedule have none.
+    pub fn upcoming_windows(job: &Job, after: DateTime<Utc>, count: usize) -> Vec<DateTime<Utc>> {
+        let Some(Ok(schedule)) = job.active_cron().map(cron::Schedule::from_str) else {
+            return Vec::new();
+        };
+        let tz = job.schedule.timezone.as_deref()
+            .and_then(|tz| Tz::from_str(tz).ok())
+            .unwrap_or(Tz::UTC);
+        
+        let mut windows = Vec::with_capacity(count);
+        let mut after = after;
+        while windows.len() < count {
+            match Self::next_cron_execution(&schedule, tz, after) {
+                Some(window) => {
+                    windows.push(window);
+                    after = window;
+                }
+                None => break,
+            }
+        }
+        
+        windows
+    }
+    
+    /// Finds the next time a cron schedule fires after `after`, following the
+    /// wall clock of `tz`.
+    ///
+    /// When clocks fall back, a repeated wall-clock time fires on its first
+    /// occurrence only. When clocks spring forward, a skipped time fires at the
+    /// first minute after the gap.
+    fn next_cron_execution(schedule: &cron::Schedule, tz: Tz, after: DateTime<Utc>) -> Option<DateTime<Utc>> {
+        // Evaluate the cron fields against naive wall-clock time
+        let local_after = tz.from_utc_datetime(&after.naive_utc()).naive_local();
+        
+        schedule
+            .after(&Utc.from_utc_datetime(&local_after))
+            .take(1000)
+            .filter_map(|candidate| Self::resolve_local_time(tz, candidate.naive_utc()))
+            .find(|candidate| *candidate > after)
+    }
+    
+    /// Maps a wall-clock time in `tz` to UTC, resolving DST ambiguities.
+    fn resolve_local_time(tz: Tz, local: NaiveDateTime) -> Option<DateTime<Utc>> {
+        let mut minute = local;
+        
+        // A DST gap is at most a few hours; walk forward to its end
+        for _ in 0..(24 * 60) {
+            match minute.and_local_timezone(tz) {
+                LocalResult::Single(time) => return Some(time.with_timezone(&Utc)),
+                LocalResult::Ambiguous(first, _) => return Some(first.with_timezone(&Utc)),
+                LocalResult::None => {
+                    minute = (minute + Duration::minutes(1))
+                        .with_second(0)?
+                        .with_nanosecond(0)?;
+                }
+            }
+        }
+        
+        None
+    }
+    
     /// Rebuilds the queue after modifications.
     fn rebuild_queue(&mut self) {
         self.jobs.clear();
@@ -276,24 +567,25 @@ impl JobQueue {
     pub fn clear(&mut self) {
         self.jobs.clear();
         self.job_index.clear();
+        self.suspended.clear();
         self.stats = QueueStats::default();
     }
     
     /// Gets the number of jobs in the queue.
     pub fn len(&self) -> usize {
-        self.job_index.len()
+        self.job_index.len() + self.suspended.len()
     }
     
     /// Checks if the queue is empty.
     pub fn is_empty(&self) -> bool {
-        self.job_index.is_empty()
+        self.job_index.is_empty() && self.suspended.is_empty()
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::scheduler::job::{Job, Schedule, Priority};
+    use crate::scheduler::job::{Job, JobCondition, Priority};
     
     fn create_test_job(id: &str, priority: Priority) -> Job {
         Job::new(id.to_string(), "echo".to_string())
@@ -332,7 +624,13 @@ mod tests {
     #[test]
     fn test_remove_nonexistent_job() {
         let mut queue = JobQueue::new();
-        assert!(queue.remove_job(&"nonexistent".to_string()).is_err());
+        match queue.remove_job(&"nonexistent".to_string()) {
+            Err(QueueError::Rae(RaeError::NotFound { kind, id })) => {
+                assert_eq!(kind, "job");
+                assert_eq!(id, "nonexistent");
+            }
+            other => panic!("expected a not found error, got {:?}", other),
+        }
     }
     
     #[test]
@@ -353,6 +651,54 @@ mod tests {
         assert_eq!(next_job.unwrap().priority, Priority::High);
     }
     
+    #[test]
+    fn test_effective_priority_ages_with_wait() {
+        let fairness = FairnessPolicy { ticks_per_boost: 3, max_priority_boost: 2 };
+        let mut queued_job = QueuedJob {
+            job: create_test_job("low", Priority::Low),
+            next_execution: None,
+            priority: Priority::Low,
+            added_at: Utc::now(),
+            wait_ticks: 0,
+        };
+        
+        assert_eq!(queued_job.effective_priority(&fairness), Priority::Low);
+        queued_job.wait_ticks = 3;
+        assert_eq!(queued_job.effective_priority(&fairness), Priority::Normal);
+        queued_job.wait_ticks = 100;
+        assert_eq!(queued_job.effective_priority(&fairness), Priority::High);
+    }
+    
+    /// Runs one job per tick while 5 `Low` and 1 `High` job arrive each tick,
+    /// returning the tick at which the first `Low` job ran.
+    fn first_low_job_tick(fairness: FairnessPolicy, max_ticks: u64) -> Option<u64> {
+        let mut queue = JobQueue::new().with_fairness(fairness);
+        
+        for tick in 0..max_ticks {
+            for priority in [Priority::Low; 5].into_iter().chain([Priority::High]) {
+                queue.add_job(Job::new(format!("{:?}-{}", priority, tick), "echo".to_string())
+                    .with_priority(priority)).unwrap();
+            }
+            
+            if queue.get_next_job().unwrap().priority == Priority::Low {
+                return Some(tick);
+            }
+        }
+        
+        None
+    }
+    
+    #[test]
+    fn test_low_priority_jobs_do_not_starve() {
+        let fairness = FairnessPolicy { ticks_per_boost: 3, max_priority_boost: 2 };
+        let tick = first_low_job_tick(fairness, 50).expect("low priority job never ran");
+        assert!(tick <= 7, "first low priority job ran at tick {}", tick);
+        
+        // Without ageing the stream of High jobs starves them
+        let no_boost = FairnessPolicy { ticks_per_boost: 3, max_priority_boost: 0 };
+        assert_eq!(first_low_job_tick(no_boost, 50), None);
+    }
+    
     #[test]
     fn test_get_job() {
         let mut queue = JobQueue::new();
@@ -365,6 +711,87 @@ mod tests {
         assert_eq!(retrieved_job.unwrap().id, job.id);
     }
     
+    fn utc(rfc3339: &str) -> DateTime<Utc> {
+        DateTime::parse_from_rfc3339(rfc3339).unwrap().with_timezone(&Utc)
+    }
+    
+    #[test]
+    fn test_cron_keeps_wall_clock_across_dst() {
+        // 6pm daily in New York is 23:00 UTC in winter and 22:00 UTC in summer
+        let schedule = cron::Schedule::from_str("0 0 18 * * *").unwrap();
+        let tz = chrono_tz::America::New_York;
+        
+        let next = JobQueue::next_cron_execution(&schedule, tz, utc("2024-03-09T23:30:00Z"));
+        assert_eq!(next, Some(utc("2024-03-10T22:00:00Z")));
+        
+        let next = JobQueue::next_cron_execution(&schedule, tz, utc("2024-11-02T22:30:00Z"));
+        assert_eq!(next, Some(utc("2024-11-03T23:00:00Z")));
+    }
+    
+    #[test]
+    fn test_cron_in_spring_forward_gap() {
+        // 2:30am does not exist on 2024-03-10; run at 3:00am EDT instead
+        let schedule = cron::Schedule::from_str("0 30 2 * * *").unwrap();
+        let tz = chrono_tz::America::New_York;
+        
+        let next = JobQueue::next_cron_execution(&schedule, tz, utc("2024-03-10T06:00:00Z"));
+        assert_eq!(next, Some(utc("2024-03-10T07:00:00Z")));
+    }
+    
+    #[test]
+    fn test_cron_in_fall_back_repeat() {
+        // 1:30am happens twice on 2024-11-03; only the first (EDT) one fires
+        let schedule = cron::Schedule::from_str("0 30 1 * * *").unwrap();
+        let tz = chrono_tz::America::New_York;
+        
+        let next = JobQueue::next_cron_execution(&schedule, tz, utc("2024-11-03T04:00:00Z"));
+        assert_eq!(next, Some(utc("2024-11-03T05:30:00Z")));
+        
+        let next = JobQueue::next_cron_execution(&schedule, tz, utc("2024-11-03T05:30:00Z"));
+        assert_eq!(next, Some(utc("2024-11-04T06:30:00Z")));
+    }
+    
+    #[test]
+    fn test_unmet_condition_suspends_job() {
+        let mut queue = JobQueue::new();
+        let job = create_test_job("sync", Priority::Normal)
+            .with_condition(JobCondition::EnvVarSet("RAE_TEST_QUEUE_NEVER_SET".to_string()));
+        
+        queue.add_job(job.clone()).unwrap();
+        
+        assert!(queue.is_suspended(&job.id));
+        assert_eq!(queue.len(), 1);
+        assert!(queue.get_job(&job.id).is_some());
+        assert!(queue.get_next_job().is_none());
+        assert!(queue.tick(&HashMap::new()).is_empty());
+        assert!(queue.tick(&HashMap::from([(job.id.clone(), false)])).is_empty());
+        
+        queue.remove_job(&job.id).unwrap();
+        assert!(queue.is_empty());
+    }
+    
+    #[test]
+    fn test_condition_change_mid_run() {
+        let mut queue = JobQueue::new();
+        let condition = JobCondition::EnvVarMatches("RAE_TEST_QUEUE_NETWORK".to_string(), "home".to_string());
+        let job = create_test_job("sync", Priority::Normal).with_condition(condition.clone());
+        
+        queue.add_job_with_condition(job.clone(), false).unwrap();
+        assert!(queue.is_suspended(&job.id));
+        assert_eq!(queue.job_conditions(), vec![(job.id.clone(), condition)]);
+        
+        assert_eq!(queue.tick(&HashMap::from([(job.id.clone(), true)])), vec![job.id.clone()]);
+        assert!(!queue.is_suspended(&job.id));
+        
+        // Rescheduling a queued job keeps it queued
+        queue.update_job(job.clone()).unwrap();
+        assert!(!queue.is_suspended(&job.id));
+        
+        assert_eq!(queue.tick(&HashMap::from([(job.id.clone(), false)])), vec![job.id.clone()]);
+        assert!(queue.is_suspended(&job.id));
+        assert_eq!(queue.len(), 1);
+    }
+    
     #[test]
     fn test_clear_queue() {
         let mut queue = JobQueue::new();
@@ -379,4 +806,80 @@ mod tests {
         assert_eq!(queue.len(), 0);
         assert!(queue.is_empty());
     }
+    
+    #[test]
+    fn test_peek_next_due_does_not_dequeue() {
+        let mut queue = JobQueue::new();
+        assert_eq!(queue.peek_next_due(), None);
+        assert_eq!(queue.time_until_next(), None);
+        
+        let now = Utc::now();
+        let later = Job::new("later".to_string(), "echo".to_string())
+            .with_time(now + Duration::minutes(10));
+        let sooner = Job::new("sooner".to_string(), "echo".to_string())
+            .with_time(now + Duration::minutes(5))
+            .with_priority(Priority::Low);
+        queue.add_job(later).unwrap();
+        queue.add_job(sooner.clone()).unwrap();
+        
+        // The earliest execution wins regardless of priority
+        assert_eq!(queue.peek_next_due(), Some((sooner.id.clone(), now + Duration::minutes(5))));
+        assert_eq!(queue.peek_next_due(), Some((sooner.id, now + Duration::minutes(5))));
+        assert_eq!(queue.len(), 2);
+        
+        let wait = queue.time_until_next().unwrap();
+        assert!(wait <= std::time::Duration::from_secs(5 * 60));
+        assert!(wait > std::time::Duration::from_secs(4 * 60));
+    }
+    
+    #[test]
+    fn test_take_due_jobs_requeues_cron_jobs() {
+        let mut queue = JobQueue::new();
+        let now = Utc::now();
+        let cron = Job::new("cron".to_string(), "echo".to_string()).with_cron("* * * * * *".to_string());
+        let once = Job::new("once".to_string(), "echo".to_string())
+            .with_time(now + Duration::milliseconds(10))
+            .with_priority(Priority::High);
+        let later = Job::new("later".to_string(), "echo".to_string()).with_time(now + Duration::minutes(5));
+        let event = Job::new("event".to_string(), "echo".to_string());
+        for job in [&cron, &once, &later, &event] {
+            queue.add_job(job.clone()).unwrap();
+        }
+        
+        assert!(queue.take_due_jobs(now).is_empty());
+        
+        let due: Vec<JobId> = queue.take_due_jobs(now + Duration::seconds(2)).into_iter().map(|job| job.id).collect();
+        assert_eq!(due, vec![once.id.clone(), cron.id.clone()]);
+        
+        // The cron job waits for its next window; the one-time job is gone
+        assert!(queue.get_next_execution(&cron.id).is_some());
+        assert!(queue.get_job(&once.id).is_none());
+        assert!(queue.get_job(&later.id).is_some());
+        assert!(queue.get_job(&event.id).is_some());
+        assert_eq!(queue.len(), 3);
+    }
+    
+    #[test]
+    fn test_get_jobs_by_tag() {
+        let mut queue = JobQueue::new();
+        let backup = create_test_job("backup", Priority::Normal)
+            .with_tags(vec!["nightly".to_string(), "storage".to_string()]);
+        let cleanup = create_test_job("cleanup", Priority::Normal)
+            .with_tags(vec!["nightly".to_string()]);
+        let report = create_test_job("report", Priority::Normal)
+            .with_tags(vec!["reports".to_string()]);
+        for job in [&backup, &cleanup, &report] {
+            queue.add_job(job.clone()).unwrap();
+        }
+        
+        let mut nightly: Vec<&str> = queue.get_jobs_by_tag("nightly").iter().map(|job| job.id.as_str()).collect();
+        nightly.sort();
+        let mut expected = vec![backup.id.as_str(), cleanup.id.as_str()];
+        expected.sort();
+        assert_eq!(nightly, expected);
+        
+        let reports: Vec<&str> = queue.get_jobs_by_tag("reports").iter().map(|job| job.id.as_str()).collect();
+        assert_eq!(reports, vec![report.id.as_str()]);
+        assert!(queue.get_jobs_by_tag("weekly").is_empty());
+    }
 } 
\ No newline at end of file
@@ -0,0 +1,205 @@
+//! On-disk history of job results.
+//!
+//! Each result is written to `{job_id}/results/{timestamp}.json` under the
+//! history directory, keeping at most `max_history` results per job.
+
+use crate::scheduler::job::{JobId, JobResult};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use thiserror::Error;
+use tracing::warn;
+
+/// Number of results kept per job unless configured otherwise.
+pub const DEFAULT_MAX_HISTORY: usize = 50;
+
+/// Errors that can occur while storing job results.
+#[derive(Debug, Error)]
+pub enum ResultStoreError {
+    #[error("IO error: {0}")]
+    IoError(#[from] std::io::Error),
+    
+    #[error("Serialization error: {0}")]
+    SerializationError(#[from] serde_json::Error),
+    
+    #[error("Storage directory error: {0}")]
+    StorageDirectoryError(String),
+}
+
+/// Stores the most recent results of each job on disk.
+#[derive(Debug)]
+pub struct ResultStore {
+    /// Directory holding one subdirectory per job
+    history_dir: PathBuf,
+    /// Number of results kept per job
+    max_history: AtomicUsize,
+}
+
+impl ResultStore {
+    /// Creates a result store in the local data directory.
+    pub fn new() -> Result<Self, ResultStoreError> {
+        let mut path = dirs::data_local_dir()
+            .ok_or_else(|| ResultStoreError::StorageDirectoryError(
+                "Could not determine local data directory".to_string()
+            ))?;
+        
+        path.push("rae");
+        path.push("scheduler");
+        path.push("history");
+        
+        Self::with_dir(path)
+    }
+    
+    /// Creates a result store in the given directory.
+    pub fn with_dir(history_dir: PathBuf) -> Result<Self, ResultStoreError> {
+        fs::create_dir_all(&history_dir)?;
+        
+        Ok(ResultStore {
+            history_dir,
+            max_history: AtomicUsize::new(DEFAULT_MAX_HISTORY),
+        })
+    }
+    
+    /// Sets how many results are kept per job; older ones are pruned on the next save.
+    pub fn set_max_history(&self, max_history: usize) {
+        self.max_history.store(max_history, Ordering::Relaxed);
+    }
+    
+    /// Gets how many results are kept per job.
+    pub fn max_history(&self) -> usize {
+        self.max_history.load(Ordering::Relaxed)
+    }
+    
+    /// Gets the directory holding the results of a job.
+    fn results_dir(&self, job_id: &JobId) -> PathBuf {
+        self.history_dir.join(job_id).join("results")
+    }
+    
+    /// Writes a result and prunes the oldest results beyond `max_history`.
+    pub fn save(&self, result: &JobResult) -> Result<PathBuf, ResultStoreError> {
+        let results_dir = self.results_dir(&result.job_id);
+        fs::create_dir_all(&results_dir)?;
+        
+        // Fixed-width timestamps sort chronologically by file name
+        let file_name = format!("{}.json", result.started_at.format("%Y%m%dT%H%M%S%.9fZ"));
+        let path = results_dir.join(file_name);
+        fs::write(&path, serde_json::to_string_pretty(result)?)?;
+        
+        self.prune(&results_dir)?;
+        Ok(path)
+    }
+    
+    /// Removes the oldest result files so at most `max_history` remain.
+    fn prune(&self, results_dir: &Path) -> Result<(), ResultStoreError> {
+        let mut files = Self::result_files(results_dir)?;
+        let excess = files.len().saturating_sub(self.max_history());
+        
+        files.sort();
+        for path in files.into_iter().take(excess) {
+            fs::remove_file(path)?;
+        }
+        
+        Ok(())
+    }
+    
+    /// Gets the most recent results of a job, newest first.
+    ///
+    /// Unreadable result files are skipped.
+    pub fn get_history(&self, job_id: &JobId, limit: usize) -> Result<Vec<JobResult>, ResultStoreError> {
+        let results_dir = self.results_dir(job_id);
+        if !results_dir.exists() {
+            return Ok(Vec::new());
+        }
+        
+        let mut results: Vec<JobResult> = Self::result_files(&results_dir)?
+            .into_iter()
+            .filter_map(|path| {
+                match fs::read_to_string(&path).map_err(ResultStoreError::from)
+                    .and_then(|content| Ok(serde_json::from_str(&content)?))
+                {
+                    Ok(result) => Some(result),
+                    Err(e) => {
+                        warn!("Skipping unreadable job result {}: {}", path.display(), e);
+                        None
+                    }
+                }
+            })
+            .collect();
+        
+        results.sort_by_key(|result| std::cmp::Reverse(result.started_at));
+        results.truncate(limit);
+        
+        Ok(results)
+    }
+    
+    /// Lists the result files in a job's results directory.
+    fn result_files(results_dir: &Path) -> Result<Vec<PathBuf>, ResultStoreError> {
+        let mut files = Vec::new();
+        for entry in fs::read_dir(results_dir)? {
+            let path = entry?.path();
+            if path.extension().is_some_and(|ext| ext == "json") {
+                files.push(path);
+            }
+        }
+        
+        Ok(files)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::scheduler::job::JobStatus;
+    use chrono::{Duration, Utc};
+    use tempfile::tempdir;
+    
+    fn result(job_id: &str, minutes_ago: i64) -> JobResult {
+        let started_at = Utc::now() - Duration::minutes(minutes_ago);
+        JobResult {
+            job_id: job_id.to_string(),
+            started_at,
+            ended_at: Some(started_at),
+            exit_code: Some(0),
+            stdout: format!("run {}", minutes_ago),
+            stderr: String::new(),
+            truncated: false,
+            status: JobStatus::Completed,
+            resource_usage: None,
+        }
+    }
+    
+    #[test]
+    fn test_history_is_newest_first() {
+        let temp_dir = tempdir().unwrap();
+        let store = ResultStore::with_dir(temp_dir.path().to_path_buf()).unwrap();
+        
+        for minutes_ago in [20, 5, 10] {
+            store.save(&result("backup", minutes_ago)).unwrap();
+        }
+        store.save(&result("other", 1)).unwrap();
+        
+        let history = store.get_history(&"backup".to_string(), 2).unwrap();
+        let stdout: Vec<&str> = history.iter().map(|result| result.stdout.as_str()).collect();
+        assert_eq!(stdout, vec!["run 5", "run 10"]);
+        
+        assert!(store.get_history(&"missing".to_string(), 10).unwrap().is_empty());
+    }
+    
+    #[test]
+    fn test_old_results_are_pruned() {
+        let temp_dir = tempdir().unwrap();
+        let store = ResultStore::with_dir(temp_dir.path().to_path_buf()).unwrap();
+        store.set_max_history(3);
+        
+        for minutes_ago in (0..5).rev() {
+            store.save(&result("backup", minutes_ago)).unwrap();
+        }
+        
+        let files = ResultStore::result_files(&temp_dir.path().join("backup").join("results")).unwrap();
+        assert_eq!(files.len(), 3);
+        
+        let history = store.get_history(&"backup".to_string(), 10).unwrap();
+        let stdout: Vec<&str> = history.iter().map(|result| result.stdout.as_str()).collect();
+        assert_eq!(stdout, vec!["run 0", "run 1", "run 2"]);
+    }
+}
@@ -0,0 +1,153 @@
+//! Resource usage sampling for running jobs.
+//!
+//! Samples a child process while it runs and keeps the peak memory and the
+//! latest CPU time and disk I/O. Sampling reads `/proc` and is implemented for
+//! Linux; on other platforms only the duration of a job is reported.
+
+use crate::scheduler::job::ResourceUsage;
+
+/// Clock ticks per second used by `/proc/{pid}/stat` (`USER_HZ`).
+const CLOCK_TICKS_PER_SECOND: f64 = 100.0;
+
+/// One reading of a process's resource counters.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct ProcessSample {
+    /// User and system CPU time (seconds)
+    pub cpu_seconds: f64,
+    /// Resident memory (kB)
+    pub memory_kb: u64,
+    /// Bytes read from and written to storage
+    pub disk_io_bytes: u64,
+}
+
+/// Accumulates samples of a single process.
+#[derive(Debug, Clone, Default)]
+pub struct UsageTracker {
+    peak: ProcessSample,
+}
+
+impl UsageTracker {
+    /// Records a sample, keeping the highest value of each counter.
+    ///
+    /// CPU time and I/O only grow, and an exited process reports no memory,
+    /// so the maximum is the most complete reading.
+    pub fn record(&mut self, sample: Option<ProcessSample>) {
+        if let Some(sample) = sample {
+            self.peak.cpu_seconds = self.peak.cpu_seconds.max(sample.cpu_seconds);
+            self.peak.memory_kb = self.peak.memory_kb.max(sample.memory_kb);
+            self.peak.disk_io_bytes = self.peak.disk_io_bytes.max(sample.disk_io_bytes);
+        }
+    }
+    
+    /// Converts the samples into the usage reported for a job run.
+    pub fn into_resource_usage(self, duration: chrono::Duration) -> ResourceUsage {
+        let seconds = duration.num_milliseconds() as f64 / 1000.0;
+        let cpu_percent = if seconds > 0.0 {
+            self.peak.cpu_seconds / seconds * 100.0
+        } else {
+            0.0
+        };
+        
+        ResourceUsage {
+            cpu_percent,
+            memory_mb: self.peak.memory_kb.div_ceil(1024),
+            duration_seconds: duration.num_seconds().max(0) as u64,
+            disk_io_mb: self.peak.disk_io_bytes / (1024 * 1024),
+        }
+    }
+}
+
+/// Reads the current resource counters of a process.
+#[cfg(target_os = "linux")]
+pub fn sample(pid: u32) -> Option<ProcessSample> {
+    let proc_dir = std::path::Path::new("/proc").join(pid.to_string());
+    
+    let cpu_seconds = parse_cpu_seconds(&std::fs::read_to_string(proc_dir.join("stat")).ok()?)?;
+    let memory_kb = std::fs::read_to_string(proc_dir.join("status"))
+        .ok()
+        .and_then(|status| parse_memory_kb(&status))
+        .unwrap_or(0);
+    // `io` is only readable for our own processes
+    let disk_io_bytes = std::fs::read_to_string(proc_dir.join("io"))
+        .ok()
+        .map(|io| parse_disk_io_bytes(&io))
+        .unwrap_or(0);
+    
+    Some(ProcessSample {
+        cpu_seconds,
+        memory_kb,
+        disk_io_bytes,
+    })
+}
+
+/// Reads the current resource counters of a process.
+#[cfg(not(target_os = "linux"))]
+pub fn sample(_pid: u32) -> Option<ProcessSample> {
+    None
+}
+
+/// Parses user plus system CPU time from `/proc/{pid}/stat`.
+#[cfg_attr(not(target_os = "linux"), allow(dead_code))]
+fn parse_cpu_seconds(stat: &str) -> Option<f64> {
+    // The command name may contain spaces, so count fields after its closing paren;
+    // the state is field 3, utime field 14 and stime field 15
+    let fields: Vec<&str> = stat.rsplit_once(')')?.1.split_whitespace().collect();
+    let utime: u64 = fields.get(11)?.parse().ok()?;
+    let stime: u64 = fields.get(12)?.parse().ok()?;
+    
+    Some((utime + stime) as f64 / CLOCK_TICKS_PER_SECOND)
+}
+
+/// Parses peak resident memory (`VmHWM`, else `VmRSS`) from `/proc/{pid}/status`.
+#[cfg_attr(not(target_os = "linux"), allow(dead_code))]
+fn parse_memory_kb(status: &str) -> Option<u64> {
+    let field = |name: &str| {
+        status.lines()
+            .find_map(|line| line.strip_prefix(name))
+            .and_then(|value| value.trim().trim_end_matches("kB").trim().parse::<u64>().ok())
+    };
+    
+    field("VmHWM:").or_else(|| field("VmRSS:"))
+}
+
+/// Parses bytes read and written from `/proc/{pid}/io`.
+#[cfg_attr(not(target_os = "linux"), allow(dead_code))]
+fn parse_disk_io_bytes(io: &str) -> u64 {
+    io.lines()
+        .filter_map(|line| line.strip_prefix("read_bytes:").or_else(|| line.strip_prefix("write_bytes:")))
+        .filter_map(|value| value.trim().parse::<u64>().ok())
+        .sum()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    #[test]
+    fn test_parse_proc_files() {
+        let stat = "4242 (my job (v2)) S 1 4242 4242 0 -1 4194560 120 0 0 0 250 50 0 0 20 0 1 0 123 4096 300";
+        assert_eq!(parse_cpu_seconds(stat), Some(3.0));
+        
+        let status = "Name:\tsleep\nVmPeak:\t    8000 kB\nVmHWM:\t    1536 kB\nVmRSS:\t    1024 kB\n";
+        assert_eq!(parse_memory_kb(status), Some(1536));
+        assert_eq!(parse_memory_kb("Name:\tzombie\nState:\tZ (zombie)\n"), None);
+        
+        let io = "rchar: 9000\nwchar: 100\nread_bytes: 2097152\nwrite_bytes: 1048576\ncancelled_write_bytes: 0\n";
+        assert_eq!(parse_disk_io_bytes(io), 3 * 1024 * 1024);
+    }
+    
+    #[test]
+    fn test_tracker_keeps_peaks() {
+        let mut tracker = UsageTracker::default();
+        tracker.record(Some(ProcessSample { cpu_seconds: 0.5, memory_kb: 4096, disk_io_bytes: 0 }));
+        tracker.record(None);
+        // An exited process still reports its CPU time but no memory
+        tracker.record(Some(ProcessSample { cpu_seconds: 1.0, memory_kb: 0, disk_io_bytes: 2 * 1024 * 1024 }));
+        
+        let usage = tracker.into_resource_usage(chrono::Duration::seconds(2));
+        assert_eq!(usage.cpu_percent, 50.0);
+        assert_eq!(usage.memory_mb, 4);
+        assert_eq!(usage.duration_seconds, 2);
+        assert_eq!(usage.disk_io_mb, 2);
+    }
+}
@@ -0,0 +1,143 @@
+//! Schema version migrations.
+//!
+//! Migrations are registered as edges between schema versions; data is moved
+//! between two versions along the shortest chain of registered migrations.
+
+use crate::error::{RaeError, Result};
+use serde_json::Value;
+use std::collections::{HashMap, VecDeque};
+
+/// Transforms data from one schema version to another.
+pub type Migration = Box<dyn Fn(Value) -> Result<Value> + Send + Sync>;
+
+/// Registry of migrations between schema versions.
+#[derive(Default)]
+pub struct SchemaEvolution {
+    /// Migrations by source version, then target version
+    migrations: HashMap<u32, HashMap<u32, Migration>>,
+}
+
+impl SchemaEvolution {
+    /// Creates an empty migration registry.
+    pub fn new() -> Self {
+        Self::default()
+    }
+    
+    /// Registers a migration, replacing any existing one between the same versions.
+    ///
+    /// Register a migration in each direction to support both upgrades and
+    /// downgrades.
+    pub fn register_migration(&mut self, from_version: u32, to_version: u32, transform: Migration) {
+        self.migrations
+            .entry(from_version)
+            .or_default()
+            .insert(to_version, transform);
+    }
+    
+    /// Migrates data between versions using the shortest chain of migrations.
+    pub fn migrate(&self, data: Value, from: u32, to: u32) -> Result<Value> {
+        let path = self.find_path(from, to).ok_or_else(|| RaeError::Schema(format!(
+            "No migration path from schema version {} to {}", from, to
+        )))?;
+        
+        path.windows(2).try_fold(data, |data, step| {
+            self.migrations[&step[0]][&step[1]](data).map_err(|e| RaeError::Schema(format!(
+                "Migration from schema version {} to {} failed: {}", step[0], step[1], e
+            )))
+        })
+    }
+    
+    /// Finds the shortest chain of versions from `from` to `to` (breadth-first).
+    fn find_path(&self, from: u32, to: u32) -> Option<Vec<u32>> {
+        let mut previous: HashMap<u32, u32> = HashMap::new();
+        let mut queue = VecDeque::from([from]);
+        
+        while let Some(version) = queue.pop_front() {
+            if version == to {
+                let mut path = vec![to];
+                while let Some(&prev) = previous.get(path.last()?) {
+                    path.push(prev);
+                }
+                path.reverse();
+                return Some(path);
+            }
+            
+            // Visit targets in order so the chosen path is deterministic
+            let mut targets: Vec<u32> = self.migrations
+                .get(&version)
+                .map(|targets| targets.keys().copied().collect())
+                .unwrap_or_default();
+            targets.sort_unstable();
+            
+            for target in targets {
+                if target != from && !previous.contains_key(&target) {
+                    previous.insert(target, version);
+                    queue.push_back(target);
+                }
+            }
+        }
+        
+        None
+    }
+}
+
+impl std::fmt::Debug for SchemaEvolution {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let mut edges: Vec<(u32, u32)> = self.migrations
+            .iter()
+            .flat_map(|(from, targets)| targets.keys().map(move |to| (*from, *to)))
+            .collect();
+        edges.sort_unstable();
+        f.debug_struct("SchemaEvolution").field("migrations", &edges).finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+    
+    fn rename(mut data: Value, from: &str, to: &str) -> Result<Value> {
+        let object = data.as_object_mut().ok_or_else(|| RaeError::Schema("expected an object".to_string()))?;
+        if let Some(value) = object.remove(from) {
+            object.insert(to.to_string(), value);
+        }
+        Ok(data)
+    }
+    
+    /// Registers v1 -> v2 (rename `secs` to `seconds`), v2 -> v3 (add `tags`)
+    /// and v2 -> v1.
+    fn evolution() -> SchemaEvolution {
+        let mut evolution = SchemaEvolution::new();
+        evolution.register_migration(1, 2, Box::new(|data| rename(data, "secs", "seconds")));
+        evolution.register_migration(2, 3, Box::new(|mut data| {
+            data["tags"] = json!([]);
+            Ok(data)
+        }));
+        evolution.register_migration(2, 1, Box::new(|data| rename(data, "seconds", "secs")));
+        evolution
+    }
+    
+    #[test]
+    fn test_forward_migration_chain() {
+        let migrated = evolution().migrate(json!({ "secs": 30 }), 1, 3).unwrap();
+        assert_eq!(migrated, json!({ "seconds": 30, "tags": [] }));
+    }
+    
+    #[test]
+    fn test_backward_migration() {
+        let migrated = evolution().migrate(json!({ "seconds": 30 }), 2, 1).unwrap();
+        assert_eq!(migrated, json!({ "secs": 30 }));
+        
+        // Same version is a no-op
+        assert_eq!(evolution().migrate(json!({ "a": 1 }), 3, 3).unwrap(), json!({ "a": 1 }));
+    }
+    
+    #[test]
+    fn test_missing_path_and_failing_step() {
+        assert!(matches!(evolution().migrate(json!({}), 3, 1), Err(RaeError::Schema(_))));
+        
+        let err = evolution().migrate(json!("not an object"), 1, 2).unwrap_err();
+        assert!(err.to_string().contains("from schema version 1 to 2 failed"), "{}", err);
+    }
+}
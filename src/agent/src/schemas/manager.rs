@@ -0,0 +1,58 @@
+//! Registry of named schemas.
+
+use crate::error::{RaeError, Result};
+use crate::schemas::validator::SchemaValidator;
+use serde_json::Value;
+use std::collections::HashMap;
+
+/// Holds compiled schemas by name.
+#[derive(Debug, Default)]
+pub struct SchemaManager {
+    schemas: HashMap<String, SchemaValidator>,
+}
+
+impl SchemaManager {
+    /// Creates an empty schema registry.
+    pub fn new() -> Self {
+        Self::default()
+    }
+    
+    /// Compiles and registers a schema, replacing any schema with the same name.
+    pub fn register(&mut self, name: &str, schema: Value) -> Result<()> {
+        let validator = SchemaValidator::new(schema)?;
+        self.schemas.insert(name.to_string(), validator);
+        Ok(())
+    }
+    
+    /// Whether a schema is registered under the name.
+    pub fn contains(&self, name: &str) -> bool {
+        self.schemas.contains_key(name)
+    }
+    
+    /// Validates data against a registered schema.
+    pub fn validate_named(&self, name: &str, data: &Value) -> Result<()> {
+        self.schemas
+            .get(name)
+            .ok_or_else(|| RaeError::Schema(format!("Unknown schema: {}", name)))?
+            .validate(data)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+    
+    #[test]
+    fn test_register_and_validate_named() {
+        let mut manager = SchemaManager::new();
+        manager.register("focus", json!({ "type": "object", "required": ["minutes"] })).unwrap();
+        assert!(manager.contains("focus"));
+        
+        assert!(manager.validate_named("focus", &json!({ "minutes": 25 })).is_ok());
+        assert!(matches!(manager.validate_named("focus", &json!({})), Err(RaeError::Schema(_))));
+        
+        let err = manager.validate_named("missing", &json!({})).unwrap_err();
+        assert_eq!(err.to_string(), "Schema validation error: Unknown schema: missing");
+    }
+}
@@ -10,4 +10,4 @@ pub mod evolution;
 // Re-export main types
 pub use validator::SchemaValidator;
 pub use manager::SchemaManager;
-pub use evolution::SchemaEvolution; 
\ No newline at end of file
+pub use evolution::SchemaEvolution;
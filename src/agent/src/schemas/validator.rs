@@ -0,0 +1,112 @@
+//! JSON Schema validation.
+//!
+//! Schemas are compiled once as JSON Schema draft 7 and reused for every
+//! validation.
+
+use crate::error::{RaeError, Result};
+use jsonschema::{Draft, JSONSchema};
+use serde_json::Value;
+
+/// A compiled JSON Schema.
+#[derive(Debug)]
+pub struct SchemaValidator {
+    schema: JSONSchema,
+}
+
+impl SchemaValidator {
+    /// Compiles a draft 7 schema.
+    pub fn new(schema: Value) -> Result<Self> {
+        let schema = JSONSchema::options()
+            .with_draft(Draft::Draft7)
+            .compile(&schema)
+            .map_err(|e| RaeError::Schema(format!("Invalid schema: {}", e)))?;
+        
+        Ok(SchemaValidator { schema })
+    }
+    
+    /// Validates data against the schema.
+    ///
+    /// On failure, every validation error is reported, each prefixed with the
+    /// path of the offending value.
+    pub fn validate(&self, data: &Value) -> Result<()> {
+        self.schema.validate(data).map_err(|errors| {
+            let messages: Vec<String> = errors
+                .map(|error| {
+                    let path = error.instance_path.to_string();
+                    if path.is_empty() {
+                        error.to_string()
+                    } else {
+                        format!("{}: {}", path, error)
+                    }
+                })
+                .collect();
+            RaeError::Schema(messages.join("; "))
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+    
+    fn activity_schema() -> Value {
+        json!({
+            "type": "object",
+            "required": ["source", "payload"],
+            "properties": {
+                "source": { "type": "string" },
+                "payload": {
+                    "type": "object",
+                    "required": ["duration"],
+                    "properties": {
+                        "duration": { "type": "integer", "minimum": 0 }
+                    }
+                }
+            }
+        })
+    }
+    
+    fn schema_error(result: Result<()>) -> String {
+        match result {
+            Err(RaeError::Schema(message)) => message,
+            other => panic!("expected a schema error, got {:?}", other),
+        }
+    }
+    
+    #[test]
+    fn test_valid_data_passes() {
+        let validator = SchemaValidator::new(activity_schema()).unwrap();
+        assert!(validator.validate(&json!({ "source": "editor", "payload": { "duration": 30 } })).is_ok());
+    }
+    
+    #[test]
+    fn test_missing_required_field() {
+        let validator = SchemaValidator::new(activity_schema()).unwrap();
+        let message = schema_error(validator.validate(&json!({ "source": "editor" })));
+        assert!(message.contains("\"payload\" is a required property"), "{}", message);
+    }
+    
+    #[test]
+    fn test_type_mismatch_reports_all_errors() {
+        let validator = SchemaValidator::new(activity_schema()).unwrap();
+        let message = schema_error(validator.validate(&json!({ "source": 42 })));
+        assert!(message.contains("/source: 42 is not of type \"string\""), "{}", message);
+        assert!(message.contains("\"payload\" is a required property"), "{}", message);
+    }
+    
+    #[test]
+    fn test_nested_object_validation() {
+        let validator = SchemaValidator::new(activity_schema()).unwrap();
+        let message = schema_error(validator.validate(&json!({ "source": "editor", "payload": { "duration": -5 } })));
+        assert!(message.starts_with("/payload/duration: "), "{}", message);
+        
+        let message = schema_error(validator.validate(&json!({ "source": "editor", "payload": {} })));
+        assert!(message.contains("\"duration\" is a required property"), "{}", message);
+    }
+    
+    #[test]
+    fn test_invalid_schema_is_rejected() {
+        assert!(matches!(SchemaValidator::new(json!({ "type": "no-such-type" })), Err(RaeError::Schema(_))));
+    }
+}
@@ -7,10 +7,15 @@ use std::process::Command;
 use std::thread;
 use tracing::{error, info};
 
+/// Path of today's summary file
+pub fn todays_summary_path() -> Result<PathBuf, Box<dyn std::error::Error>> {
+    let home_dir = dirs::home_dir().ok_or("Could not find home directory")?;
+    Ok(home_dir.join("Documents").join("rae").join("today.md"))
+}
+
 /// Opens today's summary file
 pub fn open_todays_summary() -> Result<(), Box<dyn std::error::Error>> {
-    let home_dir = dirs::home_dir().ok_or("Could not find home directory")?;
-    let summary_path = home_dir.join("Documents").join("rae").join("today.md");
+    let summary_path = todays_summary_path()?;
     
     // Create directory if it doesn't exist
     if let Some(parent) = summary_path.parent() {
@@ -0,0 +1,135 @@
+//! Handlebars templates rendering the native Web Components.
+//!
+//! Each component renders to its custom element (`<rae-job-list>`, ...).
+//! Values are HTML-escaped, and registered components can be used as
+//! partials by other components (`{{> status-badge}}`).
+
+use crate::error::{RaeError, Result};
+use handlebars::Handlebars;
+use serde_json::Value;
+
+/// Scheduled jobs: `{ "jobs": [{ "id", "name", "status", "next_run"? }] }`.
+const JOB_LIST: &str = r#"<rae-job-list>
+  <ul class="rae-job-list">
+  {{#each jobs}}
+    <li data-job-id="{{id}}"><span class="rae-job-name">{{name}}</span> {{> status-badge}}{{#if next_run}} <time datetime="{{next_run}}">{{next_run}}</time>{{/if}}</li>
+  {{else}}
+    <li class="rae-empty">No jobs scheduled</li>
+  {{/each}}
+  </ul>
+</rae-job-list>
+"#;
+
+/// A status: `{ "status", "label"? }`; the label defaults to the status.
+const STATUS_BADGE: &str = r#"<rae-status-badge class="rae-status rae-status-{{status}}">{{#if label}}{{label}}{{else}}{{status}}{{/if}}</rae-status-badge>"#;
+
+/// A digest, as serialized by `core::Digest`.
+const DIGEST_CARD: &str = r#"<rae-digest-card period="{{period}}">
+  <h2>{{period}} digest</h2>
+  <p class="rae-digest-summary">{{summary}}</p>
+  <ul class="rae-digest-modules">
+  {{#each module_counts}}
+    <li><span class="rae-module">{{@key}}</span> {{this}}</li>
+  {{/each}}
+  </ul>
+</rae-digest-card>
+"#;
+
+/// Registry of component templates, starting with the built-in `job-list`,
+/// `status-badge` and `digest-card` components.
+pub struct ComponentRegistry {
+    handlebars: Handlebars<'static>,
+}
+
+impl Default for ComponentRegistry {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ComponentRegistry {
+    /// Creates a registry with the built-in components.
+    pub fn new() -> Self {
+        let mut registry = ComponentRegistry { handlebars: Handlebars::new() };
+        for (name, template) in [("job-list", JOB_LIST), ("status-badge", STATUS_BADGE), ("digest-card", DIGEST_CARD)] {
+            registry.register(name, template).expect("built-in component templates are valid");
+        }
+        registry
+    }
+    
+    /// Adds a component, replacing any component with the same name.
+    ///
+    /// Returns `RaeError::Schema` if the template does not parse.
+    pub fn register(&mut self, name: &str, template: &str) -> Result<()> {
+        self.handlebars.register_template_string(name, template)
+            .map_err(|e| RaeError::Schema(format!("Invalid template for component {}: {}", name, e)))
+    }
+    
+    /// Renders a component with `data`.
+    pub fn render(&self, name: &str, data: &Value) -> Result<String> {
+        if !self.handlebars.has_template(name) {
+            return Err(RaeError::not_found("component", name));
+        }
+        
+        self.handlebars.render(name, data)
+            .map_err(|e| RaeError::Schema(format!("Failed to render component {}: {}", name, e)))
+    }
+    
+    /// Names of the registered components, in alphabetical order.
+    pub fn list(&self) -> Vec<String> {
+        let mut names: Vec<String> = self.handlebars.get_templates().keys().cloned().collect();
+        names.sort();
+        names
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+    
+    #[test]
+    fn test_render_builtin_components() {
+        let registry = ComponentRegistry::new();
+        assert_eq!(registry.list(), vec!["digest-card", "job-list", "status-badge"]);
+        
+        let badge = registry.render("status-badge", &json!({ "status": "failed" })).unwrap();
+        assert_eq!(badge, r#"<rae-status-badge class="rae-status rae-status-failed">failed</rae-status-badge>"#);
+        
+        let jobs = registry.render("job-list", &json!({
+            "jobs": [
+                { "id": "backup", "name": "Nightly <backup>", "status": "scheduled", "next_run": "2026-03-02T03:00:00Z" },
+                { "id": "sync", "name": "Sync", "status": "running" },
+            ]
+        })).unwrap();
+        assert!(jobs.starts_with("<rae-job-list>"));
+        assert!(jobs.contains(r#"<li data-job-id="backup"><span class="rae-job-name">Nightly &lt;backup&gt;</span>"#));
+        assert!(jobs.contains(r#"rae-status-running">running</rae-status-badge>"#));
+        assert!(jobs.contains(r#"<time datetime="2026-03-02T03:00:00Z">"#));
+        assert!(registry.render("job-list", &json!({ "jobs": [] })).unwrap().contains("No jobs scheduled"));
+        
+        let digest = registry.render("digest-card", &json!({
+            "period": "weekly",
+            "summary": "15 activities across 2 modules: git (8), calendar (7)",
+            "module_counts": { "calendar": 7, "git": 8 },
+        })).unwrap();
+        assert!(digest.contains(r#"<rae-digest-card period="weekly">"#));
+        assert!(digest.contains("<p class=\"rae-digest-summary\">15 activities across 2 modules: git (8), calendar (7)</p>"));
+        assert!(digest.contains(r#"<li><span class="rae-module">git</span> 8</li>"#));
+    }
+    
+    #[test]
+    fn test_register_and_render_custom_component() {
+        let mut registry = ComponentRegistry::new();
+        
+        assert!(matches!(registry.register("broken", "{{#each items}}"), Err(RaeError::Schema(_))));
+        assert!(!registry.list().contains(&"broken".to_string()));
+        
+        registry.register("greeting", "<rae-greeting>Hello, {{name}}!</rae-greeting>").unwrap();
+        assert_eq!(
+            registry.render("greeting", &json!({ "name": "Ada" })).unwrap(),
+            "<rae-greeting>Hello, Ada!</rae-greeting>"
+        );
+        assert!(registry.render("missing", &json!({})).unwrap_err().is_not_found());
+    }
+}
@@ -2,12 +2,14 @@
 //!
 //! This module provides native Web Components for the user interface,
 //! following the UI philosophy defined in the functional specification.
+//! - Components: Handlebars templates rendering the custom elements
+//! - Themes: named color themes exposed as CSS custom properties
+//!
+//! The layout manager is not implemented yet.
 
 pub mod components;
-pub mod layout;
 pub mod themes;
 
 // Re-export main types
 pub use components::ComponentRegistry;
-pub use layout::LayoutManager;
-pub use themes::ThemeManager; 
\ No newline at end of file
+pub use themes::{Theme, ThemeManager};
@@ -0,0 +1,199 @@
+//! Color themes for the native Web Components.
+//!
+//! Components read their colors from `--rae-*` CSS custom properties; applying
+//! a theme produces the declarations to set on `:root` (or a component host).
+
+use crate::error::{RaeError, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::Path;
+
+/// Theme used when none is selected.
+pub const DEFAULT_THEME: &str = "default";
+
+/// A named set of colors, each a hex color such as `#1f2937`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Theme {
+    pub primary: String,
+    pub secondary: String,
+    pub background: String,
+    pub foreground: String,
+    pub accent: String,
+}
+
+impl Theme {
+    fn builtin(primary: &str, secondary: &str, background: &str, foreground: &str, accent: &str) -> Self {
+        Theme {
+            primary: primary.to_string(),
+            secondary: secondary.to_string(),
+            background: background.to_string(),
+            foreground: foreground.to_string(),
+            accent: accent.to_string(),
+        }
+    }
+    
+    /// CSS variable names (without the `--rae-` prefix) and their values.
+    pub fn variables(&self) -> [(&'static str, &str); 5] {
+        [
+            ("primary", &self.primary),
+            ("secondary", &self.secondary),
+            ("background", &self.background),
+            ("foreground", &self.foreground),
+            ("accent", &self.accent),
+        ]
+    }
+    
+    /// Checks that every color is a hex color.
+    ///
+    /// Colors end up in CSS, so anything else could break out of the declaration.
+    pub fn validate(&self) -> Result<()> {
+        for (name, value) in self.variables() {
+            if !is_hex_color(value) {
+                return Err(RaeError::Config(format!(
+                    "Theme color {} must be a hex color like #1f2937, got {:?}", name, value
+                )));
+            }
+        }
+        Ok(())
+    }
+}
+
+/// `#` followed by 3, 4, 6 or 8 hex digits.
+fn is_hex_color(value: &str) -> bool {
+    match value.strip_prefix('#') {
+        Some(digits) => {
+            matches!(digits.len(), 3 | 4 | 6 | 8) && digits.chars().all(|c| c.is_ascii_hexdigit())
+        }
+        None => false,
+    }
+}
+
+/// Registry of named themes, starting with the built-in `default`, `dark` and
+/// `high-contrast` themes.
+#[derive(Debug, Clone)]
+pub struct ThemeManager {
+    themes: BTreeMap<String, Theme>,
+}
+
+impl Default for ThemeManager {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ThemeManager {
+    /// Creates a manager with the built-in themes.
+    pub fn new() -> Self {
+        let themes = BTreeMap::from([
+            (DEFAULT_THEME.to_string(), Theme::builtin("#2563eb", "#64748b", "#ffffff", "#1f2937", "#f59e0b")),
+            ("dark".to_string(), Theme::builtin("#60a5fa", "#94a3b8", "#111827", "#f3f4f6", "#fbbf24")),
+            ("high-contrast".to_string(), Theme::builtin("#ffff00", "#00ffff", "#000000", "#ffffff", "#ff00ff")),
+        ]);
+        
+        ThemeManager { themes }
+    }
+    
+    /// Adds a theme, replacing any theme with the same name.
+    pub fn register(&mut self, name: &str, theme: Theme) -> Result<()> {
+        theme.validate()?;
+        self.themes.insert(name.to_string(), theme);
+        Ok(())
+    }
+    
+    /// Gets a theme by name.
+    pub fn get(&self, name: &str) -> Option<&Theme> {
+        self.themes.get(name)
+    }
+    
+    /// Names of the registered themes, in alphabetical order.
+    pub fn names(&self) -> impl Iterator<Item = &str> {
+        self.themes.keys().map(String::as_str)
+    }
+    
+    /// CSS custom property declarations for a theme, one per line.
+    pub fn apply(&self, name: &str) -> Result<String> {
+        let theme = self.get(name).ok_or_else(|| RaeError::not_found("theme", name))?;
+        
+        Ok(theme.variables()
+            .iter()
+            .map(|(variable, value)| format!("--rae-{}: {};\n", variable, value))
+            .collect())
+    }
+    
+    /// Registers the themes defined in a TOML file, one table per theme:
+    ///
+    /// ```toml
+    /// [solarized]
+    /// primary = "#268bd2"
+    /// secondary = "#586e75"
+    /// background = "#fdf6e3"
+    /// foreground = "#657b83"
+    /// accent = "#b58900"
+    /// ```
+    ///
+    /// Nothing is registered if any theme is invalid. Returns the number of
+    /// themes loaded.
+    pub fn load_from_file(&mut self, path: &Path) -> Result<usize> {
+        let content = std::fs::read_to_string(path)?;
+        let themes: BTreeMap<String, Theme> = toml::from_str(&content)
+            .map_err(|e| RaeError::Config(format!("Invalid {}: {}", path.display(), e)))?;
+        
+        for (name, theme) in &themes {
+            theme.validate()
+                .map_err(|e| RaeError::Config(format!("Invalid theme {} in {}: {}", name, path.display(), e)))?;
+        }
+        
+        let count = themes.len();
+        self.themes.extend(themes);
+        Ok(count)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+    
+    #[test]
+    fn test_apply_builtin_themes() {
+        let manager = ThemeManager::new();
+        assert_eq!(manager.names().collect::<Vec<_>>(), vec!["dark", "default", "high-contrast"]);
+        
+        let css = manager.apply("dark").unwrap();
+        assert!(css.contains("--rae-background: #111827;"));
+        assert_eq!(css.lines().count(), 5);
+        assert!(css.lines().all(|line| line.starts_with("--rae-") && line.ends_with(';')));
+        
+        assert!(manager.apply("solarized").unwrap_err().is_not_found());
+    }
+    
+    #[test]
+    fn test_load_themes_from_file() {
+        let temp_dir = tempdir().unwrap();
+        let path = temp_dir.path().join("themes.toml");
+        std::fs::write(&path, r##"
+[solarized]
+primary = "#268bd2"
+secondary = "#586e75"
+background = "#fdf6e3"
+foreground = "#657b83"
+accent = "#b58900"
+"##).unwrap();
+        
+        let mut manager = ThemeManager::new();
+        assert_eq!(manager.load_from_file(&path).unwrap(), 1);
+        assert!(manager.apply("solarized").unwrap().contains("--rae-primary: #268bd2;"));
+        
+        // A color that would escape the declaration is rejected
+        std::fs::write(&path, r##"
+[broken]
+primary = "red; } body { display: none"
+secondary = "#586e75"
+background = "#fdf6e3"
+foreground = "#657b83"
+accent = "#b58900"
+"##).unwrap();
+        assert!(matches!(manager.load_from_file(&path), Err(RaeError::Config(_))));
+        assert!(manager.get("broken").is_none());
+    }
+}